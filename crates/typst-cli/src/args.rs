@@ -56,6 +56,9 @@ pub enum Command {
     /// Processes an input file to extract provided metadata
     Query(QueryCommand),
 
+    /// Runs the tests defined in an input file
+    Test(TestCommand),
+
     /// Lists all discovered fonts in system and custom font paths
     Fonts(FontsCommand),
 
@@ -71,7 +74,7 @@ pub struct CompileCommand {
     #[clap(flatten)]
     pub common: SharedArgs,
 
-    /// Path to output file (PDF, PNG or SVG). Use `-` to write output to stdout.
+    /// Path to output file (PDF, PNG, SVG or TXT). Use `-` to write output to stdout.
     ///
     /// For output formats emitting one file per page (PNG & SVG), a page number template
     /// must be present if the source document renders to multiple pages. Use `{p}` for page
@@ -92,6 +95,22 @@ pub struct CompileCommand {
     #[arg(long = "pages", value_delimiter = ',')]
     pub pages: Option<Vec<PageRangeArgument>>,
 
+    /// Additional output files to export the same compiled document to.
+    ///
+    /// The document is only compiled once and then exported to the main output
+    /// and each additional one. The format of an additional output is inferred
+    /// from its extension. Options for just this output can be given before
+    /// the path, separated from it by a colon: `format`, `ppi` and `pages`
+    /// (a single page range) override the respective arguments. For example,
+    /// `--also ppi=20,pages=1:thumbnail.png` additionally exports a small
+    /// image of the first page.
+    #[arg(
+        long = "also",
+        value_name = "OUTPUT",
+        value_parser = ValueParser::new(extra_output_value_parser)
+    )]
+    pub also: Vec<ExtraOutput>,
+
     /// Output a Makefile rule describing the current compilation
     #[clap(long = "make-deps", value_name = "PATH")]
     pub make_deps: Option<PathBuf>,
@@ -164,6 +183,27 @@ pub struct QueryCommand {
     pub pretty: bool,
 }
 
+/// Runs the tests defined in an input file
+#[derive(Debug, Clone, Parser)]
+pub struct TestCommand {
+    /// Shared arguments
+    #[clap(flatten)]
+    pub common: SharedArgs,
+
+    /// Directory with PNG snapshots to compare the frames of tests that
+    /// request layout against
+    #[clap(long = "snapshots", value_name = "DIR")]
+    pub snapshots: Option<PathBuf>,
+
+    /// Overwrites snapshots that differ instead of failing the test
+    #[clap(long = "update", requires = "snapshots")]
+    pub update: bool,
+
+    /// The PPI (pixels per inch) to use for snapshots
+    #[arg(long = "ppi", default_value_t = 144.0)]
+    pub ppi: f32,
+}
+
 // Output file format for query command
 #[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
 pub enum SerializationFormat {
@@ -171,7 +211,7 @@ pub enum SerializationFormat {
     Yaml,
 }
 
-/// Common arguments of compile, watch, and query.
+/// Common arguments of compile, watch, query, and test.
 #[derive(Debug, Clone, Args)]
 pub struct SharedArgs {
     /// Path to input Typst file. Use `-` to read input from stdin
@@ -191,6 +231,28 @@ pub struct SharedArgs {
     )]
     pub inputs: Vec<(String, String)>,
 
+    /// Disallows loading WebAssembly plugins. Documents can detect this
+    /// through `sys.capabilities.plugins`
+    #[clap(long = "no-plugins")]
+    pub no_plugins: bool,
+
+    /// Restricts reading files to a directory of the project, given relative
+    /// to the project root. Can be repeated to allow several directories. The
+    /// main file can always be read. Documents can detect this through
+    /// `sys.capabilities.read-roots`
+    #[clap(long = "read-root", value_name = "DIR", action = ArgAction::Append)]
+    pub read_roots: Vec<String>,
+
+    /// Disallows accessing the network, e.g. to download packages. Documents
+    /// can detect this through `sys.capabilities.network`
+    #[clap(long = "no-network")]
+    pub no_network: bool,
+
+    /// Draws an overlay onto the pages that shows the areas used by the
+    /// layout, like margins, regions, the baseline grid, and floats
+    #[clap(long = "debug-layout")]
+    pub debug_layout: bool,
+
     /// Common font arguments
     #[clap(flatten)]
     pub font_args: FontArgs,
@@ -299,6 +361,58 @@ fn output_value_parser(value: &str) -> Result<Output, clap::error::Error> {
     }
 }
 
+/// An additional output of a compilation with its own export options.
+#[derive(Debug, Clone)]
+pub struct ExtraOutput {
+    /// The path to write to.
+    pub path: PathBuf,
+    /// The format to use, inferred from the extension if not given.
+    pub format: Option<OutputFormat>,
+    /// The PPI to use for PNG export, if different from the main output's.
+    pub ppi: Option<f32>,
+    /// The pages to export, if different from the main output's.
+    pub pages: Option<PageRangeArgument>,
+}
+
+/// The clap value parser used by `CompileCommand.also`
+///
+/// Options are only split off if the part before the first colon contains an
+/// equal sign, so that Windows paths with drive letters stay intact.
+fn extra_output_value_parser(value: &str) -> Result<ExtraOutput, String> {
+    let (options, path) = match value.split_once(':') {
+        Some((options, path)) if options.contains('=') => (options, path),
+        _ => ("", value),
+    };
+
+    if path.is_empty() {
+        return Err("the path was missing or empty".to_owned());
+    } else if path == "-" {
+        return Err("additional outputs cannot be written to stdout".to_owned());
+    }
+
+    let mut output = ExtraOutput {
+        path: path.into(),
+        format: None,
+        ppi: None,
+        pages: None,
+    };
+
+    for option in options.split(',').filter(|option| !option.is_empty()) {
+        let (key, val) = parse_input_pair(option)?;
+        match key.as_str() {
+            "format" => output.format = Some(OutputFormat::from_str(&val, true)?),
+            "ppi" => {
+                output.ppi =
+                    Some(val.parse().map_err(|err| format!("invalid ppi ({err})"))?)
+            }
+            "pages" => output.pages = Some(val.parse()?),
+            _ => return Err(format!("unknown output option `{key}`")),
+        }
+    }
+
+    Ok(output)
+}
+
 /// Parses key/value pairs split by the first equal sign.
 ///
 /// This function will return an error if the argument contains no equals sign
@@ -442,6 +556,7 @@ pub enum OutputFormat {
     Pdf,
     Png,
     Svg,
+    Txt,
 }
 
 impl Display for OutputFormat {
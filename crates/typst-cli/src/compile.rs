@@ -9,12 +9,12 @@ use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 use parking_lot::RwLock;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use typst::diag::{bail, FileError, Severity, SourceDiagnostic, StrResult, Warned};
-use typst::foundations::{Datetime, Smart};
+use typst::foundations::Datetime;
 use typst::layout::{Frame, PageRanges};
 use typst::model::Document;
 use typst::syntax::{FileId, Source, Span};
-use typst::visualize::Color;
 use typst::{World, WorldExt};
+use typst_export::{ExportFormat, ExportTarget};
 
 use crate::args::{
     CompileCommand, DiagnosticFormat, Input, Output, OutputFormat, PageRangeArgument,
@@ -39,6 +39,7 @@ impl CompileCommand {
                     OutputFormat::Pdf => "pdf",
                     OutputFormat::Png => "png",
                     OutputFormat::Svg => "svg",
+                    OutputFormat::Txt => "txt",
                 },
             ))
         })
@@ -51,17 +52,64 @@ impl CompileCommand {
         Ok(if let Some(specified) = self.format {
             specified
         } else if let Some(Output::Path(output)) = &self.output {
-            match output.extension() {
-                Some(ext) if ext.eq_ignore_ascii_case("pdf") => OutputFormat::Pdf,
-                Some(ext) if ext.eq_ignore_ascii_case("png") => OutputFormat::Png,
-                Some(ext) if ext.eq_ignore_ascii_case("svg") => OutputFormat::Svg,
-                _ => bail!("could not infer output format for path {}.\nconsider providing the format manually with `--format/-f`", output.display()),
-            }
+            infer_format(output).ok_or_else(|| {
+                eco_format!(
+                    "could not infer output format for path {}.\n\
+                     consider providing the format manually with `--format/-f`",
+                    output.display()
+                )
+            })?
         } else {
             OutputFormat::Pdf
         })
     }
 
+    /// All outputs to export the compiled document to, along with the options
+    /// to export them with: The main output followed by the additional ones.
+    pub fn targets(&self) -> StrResult<Vec<(Output, ExportTarget)>> {
+        let timestamp = convert_datetime(
+            self.common.creation_timestamp.unwrap_or_else(chrono::Utc::now),
+        );
+
+        let mut targets = vec![(
+            self.output(),
+            ExportTarget {
+                format: self.output_format()?.into(),
+                pages: self.exported_page_ranges(),
+                ppi: self.ppi,
+                timestamp,
+            },
+        )];
+
+        for extra in &self.also {
+            let format = match extra.format {
+                Some(format) => format,
+                None => infer_format(&extra.path).ok_or_else(|| {
+                    eco_format!(
+                        "could not infer output format for path {0}.\n\
+                         consider providing the format with `format=..:{0}`",
+                        extra.path.display()
+                    )
+                })?,
+            };
+
+            targets.push((
+                Output::Path(extra.path.clone()),
+                ExportTarget {
+                    format: format.into(),
+                    pages: match &extra.pages {
+                        Some(range) => Some(PageRanges::new(vec![range.to_range()])),
+                        None => self.exported_page_ranges(),
+                    },
+                    ppi: extra.ppi.unwrap_or(self.ppi),
+                    timestamp,
+                },
+            ));
+        }
+
+        Ok(targets)
+    }
+
     /// The ranges of the pages to be exported as specified by the user.
     ///
     /// This returns `None` if all pages should be exported.
@@ -74,6 +122,28 @@ impl CompileCommand {
     }
 }
 
+/// Infers the output format from a path's extension.
+fn infer_format(path: &Path) -> Option<OutputFormat> {
+    match path.extension() {
+        Some(ext) if ext.eq_ignore_ascii_case("pdf") => Some(OutputFormat::Pdf),
+        Some(ext) if ext.eq_ignore_ascii_case("png") => Some(OutputFormat::Png),
+        Some(ext) if ext.eq_ignore_ascii_case("svg") => Some(OutputFormat::Svg),
+        Some(ext) if ext.eq_ignore_ascii_case("txt") => Some(OutputFormat::Txt),
+        _ => None,
+    }
+}
+
+impl From<OutputFormat> for ExportFormat {
+    fn from(format: OutputFormat) -> Self {
+        match format {
+            OutputFormat::Pdf => Self::Pdf,
+            OutputFormat::Png => Self::Png,
+            OutputFormat::Svg => Self::Svg,
+            OutputFormat::Txt => Self::Txt,
+        }
+    }
+}
+
 /// Execute a compilation command.
 pub fn compile(mut timer: Timer, mut command: CompileCommand) -> StrResult<()> {
     let mut world =
@@ -111,10 +181,15 @@ pub fn compile_once(
         return Ok(());
     }
 
-    let Warned { output, warnings } = typst::compile(world);
+    let Warned { output, warnings, logs } = typst::compile(world);
+
+    // Print the document's own messages first, independently of whether
+    // compilation succeeded.
+    print_diagnostics(world, &[], &logs, command.common.diagnostic_format)
+        .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
 
     match output {
-        // Export the PDF / PNG.
+        // Export the PDF / PNG / SVG / TXT.
         Ok(document) => {
             export(world, &document, command, watching)?;
             let duration = start.elapsed();
@@ -131,6 +206,7 @@ pub fn compile_once(
                 .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
 
             write_make_deps(world, command)?;
+            world.save_package_lock().map_err(|err| eco_format!("{err}"))?;
 
             if let Some(open) = command.open.take() {
                 if let Output::Path(file) = command.output() {
@@ -160,35 +236,28 @@ pub fn compile_once(
     Ok(())
 }
 
-/// Export into the target format.
+/// Export the document to all targets.
+///
+/// All targets share the same compiled document, so layout only runs once no
+/// matter how many formats are exported.
 fn export(
     world: &mut SystemWorld,
     document: &Document,
     command: &CompileCommand,
     watching: bool,
 ) -> StrResult<()> {
-    match command.output_format()? {
-        OutputFormat::Png => {
-            export_image(world, document, command, watching, ImageExportFormat::Png)
-        }
-        OutputFormat::Svg => {
-            export_image(world, document, command, watching, ImageExportFormat::Svg)
+    for (i, (output, target)) in command.targets()?.iter().enumerate() {
+        if target.format.is_paged() {
+            // The export cache only tracks the frames of the main output.
+            let cached = watching && i == 0;
+            export_pages(world, document, output, target, cached)?;
+        } else {
+            let buffer = target.export_document(document)?;
+            output.write(&buffer).map_err(|err| {
+                eco_format!("failed to write {} file ({err})", target.format.name())
+            })?;
         }
-        OutputFormat::Pdf => export_pdf(document, command),
     }
-}
-
-/// Export to a PDF.
-fn export_pdf(document: &Document, command: &CompileCommand) -> StrResult<()> {
-    let timestamp = convert_datetime(
-        command.common.creation_timestamp.unwrap_or_else(chrono::Utc::now),
-    );
-    let exported_page_ranges = command.exported_page_ranges();
-    let buffer = typst_pdf::pdf(document, Smart::Auto, timestamp, exported_page_ranges);
-    command
-        .output()
-        .write(&buffer)
-        .map_err(|err| eco_format!("failed to write PDF file ({err})"))?;
     Ok(())
 }
 
@@ -204,42 +273,23 @@ fn convert_datetime(date_time: chrono::DateTime<chrono::Utc>) -> Option<Datetime
     )
 }
 
-/// An image format to export in.
-#[derive(Clone, Copy)]
-enum ImageExportFormat {
-    Png,
-    Svg,
-}
-
-/// Export to one or multiple images.
-fn export_image(
+/// Export each page into its own file.
+fn export_pages(
     world: &mut SystemWorld,
     document: &Document,
-    command: &CompileCommand,
-    watching: bool,
-    fmt: ImageExportFormat,
+    output: &Output,
+    target: &ExportTarget,
+    cached: bool,
 ) -> StrResult<()> {
-    let output = command.output();
     // Determine whether we have indexable templates in output
     let can_handle_multiple = match output {
         Output::Stdout => false,
-        Output::Path(ref output) => {
+        Output::Path(output) => {
             output_template::has_indexable_template(output.to_str().unwrap_or_default())
         }
     };
 
-    let exported_page_ranges = command.exported_page_ranges();
-
-    let exported_pages = document
-        .pages
-        .iter()
-        .enumerate()
-        .filter(|(i, _)| {
-            exported_page_ranges.as_ref().map_or(true, |exported_page_ranges| {
-                exported_page_ranges.includes_page_index(*i)
-            })
-        })
-        .collect::<Vec<_>>();
+    let exported_pages = target.pages(document).collect::<Vec<_>>();
 
     if !can_handle_multiple && exported_pages.len() > 1 {
         let err = match output {
@@ -259,7 +309,7 @@ fn export_image(
         .map(|(i, page)| {
             // Use output with converted path.
             let output = match output {
-                Output::Path(ref path) => {
+                Output::Path(path) => {
                     let storage;
                     let path = if can_handle_multiple {
                         storage = output_template::format(
@@ -275,7 +325,7 @@ fn export_image(
                     // If we are not watching, don't use the cache.
                     // If the frame is in the cache, skip it.
                     // If the file does not exist, always create it.
-                    if watching && cache.is_cached(*i, &page.frame) && path.exists() {
+                    if cached && cache.is_cached(*i, &page.frame) && path.exists() {
                         return Ok(());
                     }
 
@@ -284,8 +334,10 @@ fn export_image(
                 Output::Stdout => Output::Stdout,
             };
 
-            export_image_page(command, &page.frame, &output, fmt)?;
-            Ok(())
+            let buffer = target.export_page(&page.frame)?;
+            output.write(&buffer).map_err(|err| {
+                eco_format!("failed to write {} file ({err})", target.format.name())
+            })
         })
         .collect::<Result<Vec<()>, EcoString>>()?;
 
@@ -321,33 +373,6 @@ mod output_template {
     }
 }
 
-/// Export single image.
-fn export_image_page(
-    command: &CompileCommand,
-    frame: &Frame,
-    output: &Output,
-    fmt: ImageExportFormat,
-) -> StrResult<()> {
-    match fmt {
-        ImageExportFormat::Png => {
-            let pixmap = typst_render::render(frame, command.ppi / 72.0, Color::WHITE);
-            let buf = pixmap
-                .encode_png()
-                .map_err(|err| eco_format!("failed to encode PNG file ({err})"))?;
-            output
-                .write(&buf)
-                .map_err(|err| eco_format!("failed to write PNG file ({err})"))?;
-        }
-        ImageExportFormat::Svg => {
-            let svg = typst_svg::svg(frame);
-            output
-                .write(svg.as_bytes())
-                .map_err(|err| eco_format!("failed to write SVG file ({err})"))?;
-        }
-    }
-    Ok(())
-}
-
 impl Output {
     fn write(&self, buffer: &[u8]) -> StrResult<()> {
         match self {
@@ -544,6 +569,7 @@ pub fn print_diagnostics(
         let diag = match diagnostic.severity {
             Severity::Error => Diagnostic::error(),
             Severity::Warning => Diagnostic::warning(),
+            Severity::Info => Diagnostic::note(),
         }
         .with_message(diagnostic.message.clone())
         .with_notes(
@@ -639,3 +665,87 @@ impl<'a> codespan_reporting::files::Files<'a> for SystemWorld {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::args::{CliArguments, Command};
+
+    /// Parse the arguments of a compile command.
+    fn parse(args: &[&str]) -> Result<CompileCommand, clap::Error> {
+        let args = ["typst", "compile", "main.typ"].iter().chain(args);
+        match CliArguments::try_parse_from(args)?.command {
+            Command::Compile(command) => Ok(command),
+            _ => unreachable!(),
+        }
+    }
+
+    /// The path of a target's output.
+    fn path((output, _): &(Output, ExportTarget)) -> &Path {
+        match output {
+            Output::Path(path) => path,
+            Output::Stdout => panic!("unexpected stdout output"),
+        }
+    }
+
+    #[test]
+    fn test_targets_inherit_main_options() {
+        let command = parse(&[
+            "out.pdf",
+            "--ppi=300",
+            "--pages=2-3",
+            "--also=ppi=20,pages=1:thumbnail.png",
+            "--also=out.svg",
+        ])
+        .unwrap();
+
+        let targets = command.targets().unwrap();
+        assert_eq!(targets.len(), 3);
+        assert_eq!(path(&targets[0]), Path::new("out.pdf"));
+        assert_eq!(targets[0].1.format, ExportFormat::Pdf);
+
+        assert_eq!(path(&targets[1]), Path::new("thumbnail.png"));
+        assert_eq!(targets[1].1.format, ExportFormat::Png);
+        assert_eq!(targets[1].1.ppi, 20.0);
+        assert!(targets[1].1.includes_page_index(0));
+        assert!(!targets[1].1.includes_page_index(1));
+
+        assert_eq!(path(&targets[2]), Path::new("out.svg"));
+        assert_eq!(targets[2].1.format, ExportFormat::Svg);
+        assert_eq!(targets[2].1.ppi, 300.0);
+        assert!(!targets[2].1.includes_page_index(0));
+        assert!(targets[2].1.includes_page_index(1));
+    }
+
+    #[test]
+    fn test_targets_text() {
+        let command = parse(&["out.pdf", "--also=out.txt"]).unwrap();
+        let targets = command.targets().unwrap();
+        assert_eq!(path(&targets[1]), Path::new("out.txt"));
+        assert_eq!(targets[1].1.format, ExportFormat::Txt);
+        assert!(!targets[1].1.format.is_paged());
+    }
+
+    #[test]
+    fn test_targets_explicit_format() {
+        let command = parse(&["out.pdf", "--also=format=svg:C:\\out"]).unwrap();
+        let targets = command.targets().unwrap();
+        assert_eq!(path(&targets[1]), Path::new("C:\\out"));
+        assert_eq!(targets[1].1.format, ExportFormat::Svg);
+
+        // Without options, a drive letter stays part of the path.
+        let command = parse(&["out.pdf", "--also=C:\\out.png"]).unwrap();
+        let targets = command.targets().unwrap();
+        assert_eq!(path(&targets[1]), Path::new("C:\\out.png"));
+    }
+
+    #[test]
+    fn test_targets_errors() {
+        assert!(parse(&["out.pdf", "--also=-"]).is_err());
+        assert!(parse(&["out.pdf", "--also=ppi=20:"]).is_err());
+        assert!(parse(&["out.pdf", "--also=size=20:out.png"]).is_err());
+        assert!(parse(&["out.pdf", "--also=out.doc"]).unwrap().targets().is_err());
+    }
+}
@@ -9,7 +9,7 @@ use once_cell::sync::OnceCell;
 use termcolor::WriteColor;
 use typst::diag::{bail, PackageError, PackageResult, StrResult};
 use typst::syntax::package::{
-    PackageInfo, PackageSpec, PackageVersion, VersionlessPackageSpec,
+    BoundedPackageSpec, PackageInfo, PackageSpec, PackageVersion, VersionlessPackageSpec,
 };
 
 use crate::download::{download, download_with_progress};
@@ -17,11 +17,17 @@ use crate::terminal;
 
 const HOST: &str = "https://packages.typst.org";
 const DEFAULT_PACKAGES_SUBDIR: &str = "typst/packages";
+const PROJECT_PACKAGES_SUBDIR: &str = "packages";
 
 /// Holds information about where packages should be stored.
 pub struct PackageStorage {
     pub package_cache_path: Option<PathBuf>,
     pub package_path: Option<PathBuf>,
+    /// Packages vendored into the project, which take precedence over all
+    /// other locations.
+    pub project_package_path: Option<PathBuf>,
+    /// Whether packages and the package index may be downloaded.
+    pub network: bool,
     index: OnceCell<Vec<PackageInfo>>,
 }
 
@@ -36,15 +42,29 @@ impl PackageStorage {
         Self {
             package_cache_path,
             package_path,
+            project_package_path: None,
+            network: true,
             index: OnceCell::new(),
         }
     }
 
+    /// Also search for packages in the `packages` directory of the project.
+    pub fn with_project_root(mut self, root: &Path) -> Self {
+        self.project_package_path = Some(root.join(PROJECT_PACKAGES_SUBDIR));
+        self
+    }
+
+    /// Allow or forbid downloading packages and the package index.
+    pub fn with_network(mut self, network: bool) -> Self {
+        self.network = network;
+        self
+    }
+
     /// Make a package available in the on-disk cache.
     pub fn prepare_package(&self, spec: &PackageSpec) -> PackageResult<PathBuf> {
         let subdir = format!("{}/{}/{}", spec.namespace, spec.name, spec.version);
 
-        if let Some(packages_dir) = &self.package_path {
+        for packages_dir in self.local_paths() {
             let dir = packages_dir.join(&subdir);
             if dir.exists() {
                 return Ok(dir);
@@ -84,24 +104,66 @@ impl PackageStorage {
                 .max()
                 .ok_or_else(|| eco_format!("failed to find package {spec}"))
         } else {
-            // For other namespaces, search locally. We only search in the data
-            // directory and not the cache directory, because the latter is not
-            // intended for storage of local packages.
-            let subdir = format!("{}/{}", spec.namespace, spec.name);
-            self.package_path
-                .iter()
-                .flat_map(|dir| std::fs::read_dir(dir.join(&subdir)).ok())
-                .flatten()
-                .filter_map(|entry| entry.ok())
-                .map(|entry| entry.path())
-                .filter_map(|path| path.file_name()?.to_string_lossy().parse().ok())
+            // For other namespaces, search locally.
+            self.local_versions(spec)
                 .max()
                 .ok_or_else(|| eco_format!("please specify the desired version"))
         }
     }
+
+    /// Determine the highest available version of a package that matches the
+    /// specification's version bound.
+    pub fn resolve_version(
+        &self,
+        spec: &BoundedPackageSpec,
+    ) -> PackageResult<PackageVersion> {
+        let versionless = spec.versionless();
+        let mut versions: Vec<PackageVersion> =
+            self.local_versions(&versionless).collect();
+
+        // Versions in the `@preview` namespace need not be downloaded yet.
+        if spec.namespace == "preview" {
+            let index = self
+                .download_index()
+                .map_err(|err| PackageError::NetworkFailed(Some(eco_format!("{err}"))))?;
+            versions.extend(
+                index
+                    .iter()
+                    .filter(|package| package.name == spec.name)
+                    .map(|package| package.version),
+            );
+        }
+
+        versions
+            .into_iter()
+            .filter(|version| spec.matches(version))
+            .max()
+            .ok_or_else(|| PackageError::NoMatchingVersion(spec.clone()))
+    }
 }
 
 impl PackageStorage {
+    /// The directories with locally stored packages, in order of precedence.
+    fn local_paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.project_package_path.iter().chain(&self.package_path)
+    }
+
+    /// All versions of a package available in local directories. We don't
+    /// search the cache directory because it is not intended for storage of
+    /// local packages.
+    fn local_versions<'a>(
+        &'a self,
+        spec: &VersionlessPackageSpec,
+    ) -> impl Iterator<Item = PackageVersion> + 'a {
+        let subdir = format!("{}/{}", spec.namespace, spec.name);
+        self.local_paths()
+            .flat_map(move |dir| fs::read_dir(dir.join(&subdir)).ok())
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter_map(|path| path.file_name()?.to_string_lossy().parse().ok())
+    }
+
     /// Download a package over the network.
     fn download_package(
         &self,
@@ -112,6 +174,12 @@ impl PackageStorage {
         // fetching.
         assert_eq!(spec.namespace, "preview");
 
+        if !self.network {
+            return Err(PackageError::NetworkFailed(Some(
+                "network access is disabled".into(),
+            )));
+        }
+
         let url = format!("{HOST}/preview/{}-{}.tar.gz", spec.name, spec.version);
 
         print_downloading(spec).unwrap();
@@ -142,6 +210,10 @@ impl PackageStorage {
     /// To avoid downloading the index multiple times, the result is cached.
     fn download_index(&self) -> StrResult<&Vec<PackageInfo>> {
         self.index.get_or_try_init(|| {
+            if !self.network {
+                bail!("failed to fetch package index (network access is disabled)");
+            }
+
             let url = format!("{HOST}/preview/index.json");
             match download(&url) {
                 Ok(response) => response
@@ -21,7 +21,9 @@ pub fn query(command: &QueryCommand) -> HintedStrResult<()> {
     world.reset();
     world.source(world.main()).map_err(|err| err.to_string())?;
 
-    let Warned { output, warnings } = typst::compile(&world);
+    let Warned { output, warnings, logs } = typst::compile(&world);
+    print_diagnostics(&world, &[], &logs, command.common.diagnostic_format)
+        .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
 
     match output {
         // Retrieve and print query results.
@@ -0,0 +1,218 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use codespan_reporting::term::termcolor::{Color, ColorSpec, WriteColor};
+use ecow::{eco_format, eco_vec, EcoString};
+use tiny_skia as sk;
+use typst::diag::{HintedStrResult, SourceDiagnostic, SourceResult, StrResult, Warned};
+use typst::layout::Frame;
+use typst::visualize::Color as FillColor;
+use typst::{TestResult, World};
+
+use crate::args::TestCommand;
+use crate::compile::print_diagnostics;
+use crate::world::SystemWorld;
+use crate::{set_failed, terminal};
+
+/// Execute a test command.
+pub fn test(command: &TestCommand) -> HintedStrResult<()> {
+    let mut world = SystemWorld::new(&command.common)?;
+
+    // Reset everything and ensure that the main file is present.
+    world.reset();
+    world.source(world.main()).map_err(|err| err.to_string())?;
+
+    let Warned { output, warnings, logs } = typst::test(&world);
+    print_diagnostics(&world, &[], &logs, command.common.diagnostic_format)
+        .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
+
+    let results = match output {
+        Ok(results) => results,
+        Err(errors) => {
+            set_failed();
+            print_diagnostics(
+                &world,
+                &errors,
+                &warnings,
+                command.common.diagnostic_format,
+            )
+            .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
+            return Ok(());
+        }
+    };
+
+    let mut failed = 0;
+    for result in &results {
+        let errors = match check(command, result) {
+            Ok(()) => eco_vec![],
+            Err(errors) => errors,
+        };
+
+        print_outcome(&result.name, errors.is_empty())
+            .map_err(|err| eco_format!("failed to print test outcome ({err})"))?;
+        print_diagnostics(&world, &errors, &[], command.common.diagnostic_format)
+            .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
+
+        if !errors.is_empty() {
+            failed += 1;
+        }
+    }
+
+    print_diagnostics(&world, &[], &warnings, command.common.diagnostic_format)
+        .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
+
+    let passed = results.len() - failed;
+    writeln!(terminal::out(), "{passed} passed, {failed} failed")
+        .map_err(|err| eco_format!("failed to print test summary ({err})"))?;
+
+    if failed > 0 {
+        set_failed();
+    }
+
+    Ok(())
+}
+
+/// Check a test's result, comparing its frame against the snapshot if there
+/// is one.
+fn check(command: &TestCommand, result: &TestResult) -> SourceResult<()> {
+    let Some(frame) = result.output.clone()? else { return Ok(()) };
+    let Some(dir) = &command.snapshots else { return Ok(()) };
+
+    let path = dir.join(format!("{}.png", file_name(&result.name)));
+    let message = match compare(command, &path, &frame) {
+        Ok(true) => return Ok(()),
+        Ok(false) => EcoString::from("rendered frame differs from the snapshot"),
+        Err(err) => err,
+    };
+
+    Err(eco_vec![SourceDiagnostic::error(result.span, message).with_hint(eco_format!(
+        "run with `--update` to accept the new rendering as {}",
+        path.display()
+    ))])
+}
+
+/// Render the frame and compare it against the snapshot at the path, creating
+/// or updating the snapshot if allowed.
+fn compare(command: &TestCommand, path: &Path, frame: &Frame) -> StrResult<bool> {
+    let pixmap = typst_render::render(frame, command.ppi / 72.0, FillColor::WHITE);
+
+    match fs::read(path) {
+        Ok(existing) if approx_equal(&pixmap, &existing) => return Ok(true),
+        Ok(_) if !command.update => return Ok(false),
+        Ok(_) => {}
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => return Err(eco_format!("failed to read snapshot ({err})")),
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| eco_format!("failed to create snapshot directory ({err})"))?;
+    }
+
+    let png = pixmap
+        .encode_png()
+        .map_err(|err| eco_format!("failed to encode PNG file ({err})"))?;
+
+    fs::write(path, png)
+        .map_err(|err| eco_format!("failed to write snapshot ({err})"))?;
+
+    Ok(true)
+}
+
+/// Whether a snapshot's PNG data decodes to approximately the same pixels as
+/// the rendered frame. Rasterization can differ slightly between platforms, so
+/// each channel may be off by one.
+fn approx_equal(pixmap: &sk::Pixmap, png: &[u8]) -> bool {
+    let Ok(snapshot) = sk::Pixmap::decode_png(png) else { return false };
+    pixmap.width() == snapshot.width()
+        && pixmap.height() == snapshot.height()
+        && pixmap
+            .data()
+            .iter()
+            .zip(snapshot.data())
+            .all(|(&a, &b)| a.abs_diff(b) <= 1)
+}
+
+/// Turn a test's name into a file name.
+fn file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect()
+}
+
+/// Print whether a test passed.
+fn print_outcome(name: &str, passed: bool) -> io::Result<()> {
+    let mut out = terminal::out();
+    write!(out, "test {name} ... ")?;
+
+    let (color, word) =
+        if passed { (Color::Green, "ok") } else { (Color::Red, "FAILED") };
+    out.set_color(ColorSpec::new().set_fg(Some(color)).set_bold(true))?;
+    write!(out, "{word}")?;
+    out.reset()?;
+    writeln!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::args::{CliArguments, Command};
+
+    /// Parse the arguments of a test command.
+    fn command(args: &[&str]) -> TestCommand {
+        let args = ["typst", "test"].iter().chain(args);
+        let Command::Test(command) = CliArguments::parse_from(args).command else {
+            unreachable!()
+        };
+        command
+    }
+
+    /// Run the tests of the command's input file and check the first one.
+    fn check_first(command: &TestCommand) -> SourceResult<()> {
+        let world = SystemWorld::new(&command.common).unwrap();
+        let results = typst::test(&world).output.unwrap();
+        check(command, &results[0])
+    }
+
+    #[test]
+    fn test_snapshots() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("main.typ");
+        let snapshots = dir.path().join("snapshots");
+        let (input_arg, snapshots_arg) =
+            (input.to_str().unwrap(), snapshots.to_str().unwrap());
+        let source = |width: &str| {
+            format!("#test-case(\"a box\", layout: true, () => rect(width: {width}))")
+        };
+
+        let plain = command(&["--snapshots", snapshots_arg, input_arg]);
+        let update = command(&["--snapshots", snapshots_arg, "--update", input_arg]);
+
+        // The first run creates the snapshot.
+        fs::write(&input, source("10pt")).unwrap();
+        assert!(check_first(&plain).is_ok());
+        assert!(snapshots.join("a-box.png").exists());
+        assert!(check_first(&plain).is_ok());
+
+        // Tiny differences in the pixels are tolerated, but the snapshot must
+        // still be a valid image.
+        let path = snapshots.join("a-box.png");
+        let mut pixmap = sk::Pixmap::decode_png(&fs::read(&path).unwrap()).unwrap();
+        let channel = &mut pixmap.data_mut()[0];
+        *channel = if *channel > 0 { *channel - 1 } else { 1 };
+        fs::write(&path, pixmap.encode_png().unwrap()).unwrap();
+        assert!(check_first(&plain).is_ok());
+        fs::write(&path, b"not an image").unwrap();
+        assert!(check_first(&plain).is_err());
+        assert!(check_first(&update).is_ok());
+
+        // A different rendering fails unless the snapshot may be updated.
+        fs::write(&input, source("20pt")).unwrap();
+        assert!(check_first(&plain).is_err());
+        assert!(check_first(&update).is_ok());
+        assert!(check_first(&plain).is_ok());
+    }
+}
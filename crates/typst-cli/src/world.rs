@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::OnceLock;
 use std::{fmt, fs, io, mem};
 
@@ -8,8 +9,10 @@ use chrono::{DateTime, Datelike, FixedOffset, Local, Utc};
 use ecow::{eco_format, EcoString};
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
-use typst::diag::{FileError, FileResult};
+use typst::diag::{FileError, FileResult, PackageError, PackageResult};
+use typst::foundations::sys::Capabilities;
 use typst::foundations::{Bytes, Datetime, Dict, IntoValue};
+use typst::syntax::package::{BoundedPackageSpec, PackageLock, PackageVersion};
 use typst::syntax::{FileId, Source, VirtualPath};
 use typst::text::{Font, FontBook};
 use typst::utils::LazyHash;
@@ -26,6 +29,9 @@ use crate::package::PackageStorage;
 static STDIN_ID: Lazy<FileId> =
     Lazy::new(|| FileId::new_fake(VirtualPath::new("<stdin>")));
 
+/// The name of the package lockfile in the project root.
+const LOCKFILE: &str = "typst.lock";
+
 /// A world that provides access to the operating system.
 pub struct SystemWorld {
     /// The working directory.
@@ -44,6 +50,10 @@ pub struct SystemWorld {
     slots: Mutex<HashMap<FileId, FileSlot>>,
     /// Holds information about where packages are stored.
     package_storage: PackageStorage,
+    /// The project's package lockfile. Loaded on first use.
+    package_lock: Mutex<Option<PackageLock>>,
+    /// Whether versions were added to the lockfile since it was last saved.
+    package_lock_changed: AtomicBool,
     /// The current datetime if requested. This is stored here to ensure it is
     /// always the same within one compilation.
     /// Reset between compilations if not [`Now::Fixed`].
@@ -107,7 +117,21 @@ impl SystemWorld {
                 .map(|(k, v)| (k.as_str().into(), v.as_str().into_value()))
                 .collect();
 
-            Library::builder().with_inputs(inputs).build()
+            let mut capabilities = Capabilities {
+                plugins: !command.no_plugins,
+                network: !command.no_network,
+                ..Default::default()
+            };
+            if !command.read_roots.is_empty() {
+                capabilities.read_roots =
+                    command.read_roots.iter().map(|root| root.as_str().into()).collect();
+            }
+
+            Library::builder()
+                .with_inputs(inputs)
+                .with_capabilities(capabilities)
+                .with_layout_debug(command.debug_layout)
+                .build()
         };
 
         let mut searcher = FontSearcher::new();
@@ -119,7 +143,9 @@ impl SystemWorld {
             None => Now::System(OnceLock::new()),
         };
 
-        let package_storage = PackageStorage::from_args(&command.package_storage_args);
+        let package_storage = PackageStorage::from_args(&command.package_storage_args)
+            .with_project_root(&root)
+            .with_network(library.capabilities.network);
 
         Ok(Self {
             workdir: std::env::current_dir().ok(),
@@ -130,6 +156,8 @@ impl SystemWorld {
             fonts: searcher.fonts,
             slots: Mutex::new(HashMap::new()),
             package_storage,
+            package_lock: Mutex::new(None),
+            package_lock_changed: AtomicBool::new(false),
             now,
             export_cache: ExportCache::new(),
         })
@@ -181,6 +209,22 @@ impl SystemWorld {
     pub fn export_cache(&self) -> &ExportCache {
         &self.export_cache
     }
+
+    /// Writes the versions that were resolved during compilation to the
+    /// project's package lockfile.
+    ///
+    /// This is not done during compilation itself, so that a compilation
+    /// that fails doesn't pin the versions it happened to resolve.
+    pub fn save_package_lock(&self) -> PackageResult<()> {
+        if !self.package_lock_changed.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        match &*self.package_lock.lock() {
+            Some(lock) => write_package_lock(&self.root, lock),
+            None => Ok(()),
+        }
+    }
 }
 
 impl World for SystemWorld {
@@ -197,10 +241,12 @@ impl World for SystemWorld {
     }
 
     fn source(&self, id: FileId) -> FileResult<Source> {
+        self.check_read_roots(id)?;
         self.slot(id, |slot| slot.source(&self.root, &self.package_storage))
     }
 
     fn file(&self, id: FileId) -> FileResult<Bytes> {
+        self.check_read_roots(id)?;
         self.slot(id, |slot| slot.file(&self.root, &self.package_storage))
     }
 
@@ -229,9 +275,50 @@ impl World for SystemWorld {
             with_offset.day().try_into().ok()?,
         )
     }
+
+    fn resolve_package(
+        &self,
+        spec: &BoundedPackageSpec,
+    ) -> PackageResult<PackageVersion> {
+        let mut lock = self.package_lock.lock();
+        if lock.is_none() {
+            *lock = Some(read_package_lock(&self.root)?);
+        }
+
+        let lock = lock.as_mut().unwrap();
+        if let Some(version) = lock.get(spec) {
+            return Ok(version);
+        }
+
+        let version = self.package_storage.resolve_version(spec)?;
+        if lock.insert(spec, version) {
+            self.package_lock_changed.store(true, Ordering::Relaxed);
+        }
+
+        Ok(version)
+    }
 }
 
 impl SystemWorld {
+    /// Denies access to project files outside of the read roots granted by the
+    /// capabilities. The main file and package files can always be read.
+    fn check_read_roots(&self, id: FileId) -> FileResult<()> {
+        if id == self.main || id.package().is_some() {
+            return Ok(());
+        }
+
+        let path = id.vpath().as_rooted_path();
+        let allowed = self.library.capabilities.read_roots.iter().any(|root| {
+            path.starts_with(VirtualPath::new(root.as_str()).as_rooted_path())
+        });
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(FileError::AccessDenied)
+        }
+    }
+
     /// Access the canonical slot for the given file id.
     fn slot<F, T>(&self, id: FileId, f: F) -> T
     where
@@ -366,6 +453,45 @@ impl<T: Clone> SlotCell<T> {
     }
 }
 
+/// Reads the project's package lockfile or starts a new one if there is none.
+fn read_package_lock(root: &Path) -> PackageResult<PackageLock> {
+    let path = root.join(LOCKFILE);
+    let string = match fs::read_to_string(&path) {
+        Ok(string) => string,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return Ok(PackageLock::default())
+        }
+        Err(err) => {
+            return Err(PackageError::Other(Some(eco_format!(
+                "failed to read {LOCKFILE} ({})",
+                FileError::from_io(err, &path)
+            ))))
+        }
+    };
+
+    toml::from_str(&string).map_err(|err| {
+        PackageError::Other(Some(eco_format!(
+            "{LOCKFILE} is malformed ({})",
+            err.message()
+        )))
+    })
+}
+
+/// Writes the project's package lockfile.
+fn write_package_lock(root: &Path, lock: &PackageLock) -> PackageResult<()> {
+    let path = root.join(LOCKFILE);
+    let string = toml::to_string(lock).map_err(|err| {
+        PackageError::Other(Some(eco_format!("failed to serialize {LOCKFILE} ({err})")))
+    })?;
+
+    fs::write(&path, string).map_err(|err| {
+        PackageError::Other(Some(eco_format!(
+            "failed to write {LOCKFILE} ({})",
+            FileError::from_io(err, &path)
+        )))
+    })
+}
+
 /// Resolves the path of a file id on the system, downloading a package if
 /// necessary.
 fn system_path(
@@ -475,3 +601,27 @@ impl From<WorldCreationError> for EcoString {
         eco_format!("{err}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::args::{CliArguments, Command};
+
+    /// Create a world from the arguments of a compile command that reads its
+    /// input from stdin.
+    fn world(args: &[&str]) -> SystemWorld {
+        let args = ["typst", "compile"].iter().chain(args).chain(&["-", "out.pdf"]);
+        let Command::Compile(command) = CliArguments::parse_from(args).command else {
+            unreachable!()
+        };
+        SystemWorld::new(&command.common).unwrap()
+    }
+
+    #[test]
+    fn test_debug_layout_flag() {
+        assert!(!world(&[]).library().layout_debug);
+        assert!(world(&["--debug-layout"]).library().layout_debug);
+    }
+}
@@ -0,0 +1,116 @@
+//! Exporting of compiled Typst documents.
+//!
+//! A document is laid out only once and can then be exported to any number of
+//! [targets](ExportTarget), each with its own format and options. This crate
+//! dispatches to the respective exporter for each target's format.
+
+use ecow::eco_format;
+use typst::diag::{bail, StrResult};
+use typst::foundations::{Datetime, Smart};
+use typst::layout::{Frame, Page, PageRanges};
+use typst::model::Document;
+use typst::visualize::Color;
+
+/// A format to export a document in.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ExportFormat {
+    /// A single PDF file containing all exported pages.
+    Pdf,
+    /// One PNG image per exported page.
+    Png,
+    /// One SVG image per exported page.
+    Svg,
+    /// A single text file with the plain text of all exported pages.
+    Txt,
+}
+
+impl ExportFormat {
+    /// The name of the format.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Pdf => "PDF",
+            Self::Png => "PNG",
+            Self::Svg => "SVG",
+            Self::Txt => "text",
+        }
+    }
+
+    /// Whether the format produces one file per page instead of one file for
+    /// the whole document.
+    pub fn is_paged(self) -> bool {
+        matches!(self, Self::Png | Self::Svg)
+    }
+}
+
+/// An export of a document into one format, along with the options specific
+/// to it.
+#[derive(Debug, Clone)]
+pub struct ExportTarget {
+    /// The format to export in.
+    pub format: ExportFormat,
+    /// The pages to export, or `None` for all pages.
+    pub pages: Option<PageRanges>,
+    /// The pixels per inch to use for PNG export.
+    pub ppi: f32,
+    /// The creation timestamp to use for PDF export.
+    pub timestamp: Option<Datetime>,
+}
+
+impl ExportTarget {
+    /// Create a target that exports all pages in the given format.
+    pub fn new(format: ExportFormat) -> Self {
+        Self { format, pages: None, ppi: 144.0, timestamp: None }
+    }
+
+    /// Whether the page with the given zero-based index is exported.
+    pub fn includes_page_index(&self, i: usize) -> bool {
+        self.pages.as_ref().map_or(true, |pages| pages.includes_page_index(i))
+    }
+
+    /// The exported pages of the document along with their zero-based index.
+    pub fn pages<'a>(
+        &'a self,
+        document: &'a Document,
+    ) -> impl Iterator<Item = (usize, &'a Page)> + 'a {
+        document
+            .pages
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.includes_page_index(*i))
+    }
+
+    /// Export the exported pages of the document into a single file.
+    ///
+    /// Fails for [paged](ExportFormat::is_paged) formats, whose pages must be
+    /// exported one by one with [`export_page`](Self::export_page).
+    pub fn export_document(&self, document: &Document) -> StrResult<Vec<u8>> {
+        match self.format {
+            ExportFormat::Pdf => Ok(typst_pdf::pdf(
+                document,
+                Smart::Auto,
+                self.timestamp,
+                self.pages.clone(),
+            )),
+            ExportFormat::Txt => {
+                Ok(document.plain_text(self.pages.as_ref()).as_bytes().to_vec())
+            }
+            format => bail!("cannot export a whole document to {}", format.name()),
+        }
+    }
+
+    /// Export a single page's frame into a file.
+    ///
+    /// Fails for formats that aren't [paged](ExportFormat::is_paged).
+    pub fn export_page(&self, frame: &Frame) -> StrResult<Vec<u8>> {
+        match self.format {
+            ExportFormat::Png => {
+                let pixmap = typst_render::render(frame, self.ppi / 72.0, Color::WHITE);
+                pixmap
+                    .encode_png()
+                    .map_err(|err| eco_format!("failed to encode PNG file ({err})"))
+            }
+            ExportFormat::Svg => Ok(typst_svg::svg(frame).into_bytes()),
+            format => bail!("cannot export a single page to {}", format.name()),
+        }
+    }
+}
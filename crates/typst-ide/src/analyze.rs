@@ -1,6 +1,6 @@
 use comemo::Track;
 use ecow::{eco_vec, EcoString, EcoVec};
-use typst::engine::{Engine, Route, Sink, Traced};
+use typst::engine::{Budget, Engine, Route, Sink, Traced};
 use typst::eval::Vm;
 use typst::foundations::{Context, Label, Scopes, Styles, Value};
 use typst::introspection::Introspector;
@@ -57,11 +57,13 @@ pub fn analyze_import(world: &dyn World, source: &LinkedNode) -> Option<Value> {
 
     let introspector = Introspector::default();
     let traced = Traced::default();
+    let budget = Budget::default();
     let mut sink = Sink::new();
     let engine = Engine {
         world: world.track(),
         introspector: introspector.track(),
         traced: traced.track(),
+        budget: budget.track(),
         sink: sink.track_mut(),
         route: Route::default(),
     };
@@ -7,8 +7,9 @@
 use ecow::eco_format;
 use pdf_writer::{
     types::{ColorSpaceOperand, LineCapStyle, LineJoinStyle, TextRenderingMode},
-    Content, Finish, Name, Rect, Str,
+    Content, Finish, Name, Rect, Str, TextStr,
 };
+use ttf_parser::GlyphId;
 use typst::layout::{
     Abs, Em, Frame, FrameItem, GroupItem, Point, Ratio, Size, Transform,
 };
@@ -385,6 +386,42 @@ fn write_group(ctx: &mut Builder, pos: Point, group: &GroupItem) {
 
 /// Encode a text run into the content stream.
 fn write_text(ctx: &mut Builder, pos: Point, text: &TextItem) {
+    // If the `/ToUnicode` maps can't reproduce the run's text, the viewer gets
+    // it as `/ActualText` instead, so that copying and searching work.
+    let actual = needs_actual_text(text);
+    if actual {
+        let mut span = ctx.content.begin_marked_content_with_properties(Name(b"Span"));
+        let mut properties = span.properties();
+        properties.actual_text(TextStr(&text.text));
+        properties.finish();
+        span.finish();
+    }
+
+    write_glyph_runs(ctx, pos, text);
+
+    if actual {
+        ctx.content.end_marked_content();
+    }
+}
+
+/// Whether the glyphs of a text run don't map back to its text one-to-one.
+///
+/// This is the case for ligatures, alternate glyphs like old-style figures and
+/// clusters made of multiple glyphs. Since the `/ToUnicode` maps hold only one
+/// string per glyph, the text of such runs might not survive extraction.
+fn needs_actual_text(text: &TextItem) -> bool {
+    let ttf = text.font.ttf();
+    text.glyphs.iter().any(|glyph| {
+        let mut chars = text.text[glyph.range()].chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => ttf.glyph_index(c) != Some(GlyphId(glyph.id)),
+            _ => true,
+        }
+    })
+}
+
+/// Encode the glyphs of a text run into the content stream.
+fn write_glyph_runs(ctx: &mut Builder, pos: Point, text: &TextItem) {
     let ttf = text.font.ttf();
     let tables = ttf.tables();
 
@@ -8,7 +8,7 @@ use pdf_writer::{
 };
 use typst::foundations::Label;
 use typst::introspection::Location;
-use typst::layout::{Abs, Frame};
+use typst::layout::{Abs, Page};
 use typst::model::{Destination, Numbering};
 use typst::text::Case;
 
@@ -33,7 +33,7 @@ pub fn traverse_pages(
             pages.push(None);
             skipped_pages += 1;
         } else {
-            let mut encoded = construct_page(&mut resources, &page.frame);
+            let mut encoded = construct_page(&mut resources, page);
             encoded.label = page
                 .numbering
                 .as_ref()
@@ -60,10 +60,15 @@ pub fn traverse_pages(
 
 /// Construct a page object.
 #[typst_macros::time(name = "construct page")]
-fn construct_page(out: &mut Resources<()>, frame: &Frame) -> EncodedPage {
-    let content = content::build(out, frame, None);
-
-    EncodedPage { content, label: None }
+fn construct_page(out: &mut Resources<()>, page: &Page) -> EncodedPage {
+    let content = content::build(out, &page.frame, None);
+
+    EncodedPage {
+        content,
+        label: None,
+        trim: page.trim,
+        bleed: page.bleed,
+    }
 }
 
 /// Allocate a reference for each exported page.
@@ -170,6 +175,17 @@ fn write_page(
     let w = page.content.size.x.to_f32();
     let h = page.content.size.y.to_f32();
     page_writer.media_box(Rect::new(0.0, 0.0, w, h));
+
+    // Pages with a bleed or printer's marks are larger than their trimmed
+    // size.
+    if page.trim > Abs::zero() {
+        let box_at = |inset: Abs| {
+            let inset = inset.to_f32();
+            Rect::new(inset, inset, w - inset, h - inset)
+        };
+        page_writer.trim_box(box_at(page.trim));
+        page_writer.bleed_box(box_at(page.trim - page.bleed));
+    }
     page_writer.contents(content_id);
     page_writer.pair(Name(b"Resources"), ctx.resources.reference);
 
@@ -295,4 +311,8 @@ impl PdfPageLabelStyle {
 pub struct EncodedPage {
     pub content: content::Encoded,
     pub label: Option<PdfPageLabel>,
+    /// The distance from the edges of the page to its trim box.
+    pub trim: Abs,
+    /// How far the bleed box extends beyond the trim box.
+    pub bleed: Abs,
 }
@@ -5,6 +5,11 @@ mod paint;
 mod shape;
 mod text;
 
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use pixglyph::Bitmap;
 use tiny_skia as sk;
 use typst::layout::{
     Abs, Axes, Frame, FrameItem, FrameKind, GroupItem, Point, Size, Transform,
@@ -18,6 +23,52 @@ use typst::visualize::Color;
 /// the resulting `tiny-skia` pixel buffer.
 #[typst_macros::time(name = "render")]
 pub fn render(frame: &Frame, pixel_per_pt: f32, fill: Color) -> sk::Pixmap {
+    render_impl(frame, pixel_per_pt, fill, false)
+}
+
+/// Export a frame into a low-resolution thumbnail.
+///
+/// The frame is scaled to fit into a square of `max_size` pixels, see
+/// [`thumbnail_pixel_per_pt`]. Thumbnails trade accuracy for speed: Text that
+/// is too small to be legible is drawn as bars instead of glyphs and all other
+/// glyphs are snapped to whole pixels, so that their rasterizations can be
+/// reused across the page and across thumbnails.
+#[typst_macros::time(name = "render thumbnail")]
+pub fn render_thumbnail(frame: &Frame, max_size: u32, fill: Color) -> sk::Pixmap {
+    let pixel_per_pt = thumbnail_pixel_per_pt(frame.size(), max_size);
+    render_impl(frame, pixel_per_pt, fill, true)
+}
+
+/// Export all pages of a document into low-resolution thumbnails.
+///
+/// See [`render_thumbnail`] for details.
+pub fn render_thumbnails(
+    document: &Document,
+    max_size: u32,
+    fill: Color,
+) -> Vec<sk::Pixmap> {
+    document
+        .pages
+        .iter()
+        .map(|page| render_thumbnail(&page.frame, max_size, fill))
+        .collect()
+}
+
+/// The number of pixels per point at which a frame of the given size fits into
+/// a square of `max_size` pixels.
+///
+/// This can be used to lay out previews before their thumbnails are rendered.
+pub fn thumbnail_pixel_per_pt(size: Size, max_size: u32) -> f32 {
+    let extent = size.x.max(size.y).to_f32();
+    if extent.is_finite() && extent > 0.0 {
+        max_size as f32 / extent
+    } else {
+        1.0
+    }
+}
+
+/// Export a frame into a raster image, optionally in draft quality.
+fn render_impl(frame: &Frame, pixel_per_pt: f32, fill: Color, draft: bool) -> sk::Pixmap {
     let size = frame.size();
     let pxw = (pixel_per_pt * size.x.to_f32()).round().max(1.0) as u32;
     let pxh = (pixel_per_pt * size.y.to_f32()).round().max(1.0) as u32;
@@ -26,7 +77,160 @@ pub fn render(frame: &Frame, pixel_per_pt: f32, fill: Color) -> sk::Pixmap {
     canvas.fill(paint::to_sk_color(fill));
 
     let ts = sk::Transform::from_scale(pixel_per_pt, pixel_per_pt);
-    render_frame(&mut canvas, State::new(size, ts, pixel_per_pt), frame);
+    let state = State { draft, ..State::new(size, ts, pixel_per_pt) };
+    render_frame(&mut canvas, state, frame);
+
+    canvas
+}
+
+/// Caches rasterized glyphs and shapes across renders.
+///
+/// When the same content is rasterized repeatedly at the same scale, e.g.
+/// while scrolling through a live preview, most glyphs and shapes end up with
+/// the same pixels as before. [`render_viewport`] reuses their rasterizations
+/// from this cache, so that only content that wasn't visible before needs to
+/// be rasterized. The cache is owned by the caller, who would typically keep
+/// one per preview and [evict](Self::evict) old entries after each render.
+#[derive(Default)]
+pub struct RenderCache {
+    /// Rasterized glyph outlines.
+    glyphs: Entries<Bitmap>,
+    /// Rasterized shapes.
+    shapes: Entries<Raster>,
+    /// The number of renders done with this cache.
+    age: usize,
+}
+
+impl RenderCache {
+    /// Create a new, empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Evict all rasterizations that weren't used in any of the last
+    /// `max_age` renders.
+    pub fn evict(&mut self, max_age: usize) {
+        let age = self.age;
+        self.glyphs.get_mut().retain(|_, (_, used)| age - *used < max_age);
+        self.shapes.get_mut().retain(|_, (_, used)| age - *used < max_age);
+    }
+
+    /// Fetch a glyph rasterization from the cache or create and cache it.
+    fn glyph(
+        &self,
+        key: u128,
+        rasterize: impl FnOnce() -> Option<Bitmap>,
+    ) -> Option<Arc<Bitmap>> {
+        fetch(&self.glyphs, self.age, key, || rasterize().map(Arc::new))
+    }
+
+    /// Fetch a shape rasterization from the cache or create and cache it.
+    fn shape(
+        &self,
+        key: u128,
+        rasterize: impl FnOnce() -> Option<Raster>,
+    ) -> Option<Arc<Raster>> {
+        fetch(&self.shapes, self.age, key, || rasterize().map(Arc::new))
+    }
+}
+
+/// Cached rasterizations by their keys, along with the render they were last
+/// used in.
+type Entries<T> = RefCell<HashMap<u128, (Option<Arc<T>>, usize)>>;
+
+/// Fetch a value from one of the cache's maps or create and insert it.
+fn fetch<T: Clone>(
+    map: &RefCell<HashMap<u128, (T, usize)>>,
+    age: usize,
+    key: u128,
+    create: impl FnOnce() -> T,
+) -> T {
+    if let Some((value, used)) = map.borrow_mut().get_mut(&key) {
+        *used = age;
+        return value.clone();
+    }
+
+    let value = create();
+    map.borrow_mut().insert(key, (value.clone(), age));
+    value
+}
+
+/// A shape rasterized into its own pixmap.
+struct Raster {
+    /// The rasterized shape.
+    pixmap: sk::Pixmap,
+    /// The horizontal offset of the pixmap from the whole pixel the shape is
+    /// placed at.
+    left: i32,
+    /// The vertical offset of the pixmap from the whole pixel the shape is
+    /// placed at.
+    top: i32,
+}
+
+/// The number of distinct subpixel positions per pixel at which cached
+/// rasterizations are placed.
+const SUBPIXELS: f32 = 4.0;
+
+/// Split a pixel coordinate into a whole pixel and an offset within it that
+/// is rounded to one of the subpixel positions. This way, rasterizations at
+/// the offset can be reused for all pixels.
+fn split_subpixel(v: f32) -> (i32, f32) {
+    let rounded = (v * SUBPIXELS).round() / SUBPIXELS;
+    let pixel = rounded.floor();
+    (pixel as i32, rounded - pixel)
+}
+
+/// Export the part of a frame that is visible in a viewport into a raster
+/// image.
+///
+/// The viewport is given in the pixel coordinates of the full image that
+/// [`render`] would produce with the same number of pixels per point. Only
+/// content that is visible in the viewport is rasterized, and glyphs and
+/// shapes are taken from the cache if they were rasterized before. To make
+/// that possible, they are placed at quarter-pixel precision, so the result
+/// can differ slightly from the one of [`render`]. Parts of the viewport
+/// outside of the frame are filled with the fill color.
+#[typst_macros::time(name = "render viewport")]
+pub fn render_viewport(
+    cache: &mut RenderCache,
+    frame: &Frame,
+    pixel_per_pt: f32,
+    fill: Color,
+    viewport: sk::IntRect,
+) -> sk::Pixmap {
+    cache.age += 1;
+
+    let mut canvas = sk::Pixmap::new(viewport.width(), viewport.height()).unwrap();
+    canvas.fill(paint::to_sk_color(fill));
+
+    let ts =
+        sk::Transform::from_translate(-viewport.left() as f32, -viewport.top() as f32)
+            .pre_scale(pixel_per_pt, pixel_per_pt);
+    let state = State {
+        cache: Some(cache),
+        ..State::new(frame.size(), ts, pixel_per_pt)
+    };
+    render_frame(&mut canvas, state, frame);
+
+    // Like the full image, only show the content within the frame's bounds.
+    let size = frame.size();
+    let pxw = (pixel_per_pt * size.x.to_f32()).round().max(1.0) as i32;
+    let pxh = (pixel_per_pt * size.y.to_f32()).round().max(1.0) as i32;
+    let (w, h) = (viewport.width() as i32, viewport.height() as i32);
+    let (right, bottom) = (pxw - viewport.left(), pxh - viewport.top());
+    let mut paint = sk::Paint::default();
+    paint.set_color(paint::to_sk_color(fill));
+    for rect in [
+        sk::IntRect::from_ltrb(0, 0, w, -viewport.top()),
+        sk::IntRect::from_ltrb(0, bottom, w, h),
+        sk::IntRect::from_ltrb(0, 0, -viewport.left(), h),
+        sk::IntRect::from_ltrb(right, 0, w, h),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        canvas.fill_rect(rect.to_rect(), &paint, sk::Transform::identity(), None);
+    }
 
     canvas
 }
@@ -85,6 +289,10 @@ struct State<'a> {
     pixel_per_pt: f32,
     /// The size of the first hard frame in the hierarchy.
     size: Size,
+    /// Whether to trade accuracy for speed, as for thumbnails.
+    draft: bool,
+    /// The cache to take rasterizations from, if any.
+    cache: Option<&'a RenderCache>,
 }
 
 impl<'a> State<'a> {
@@ -122,7 +330,10 @@ impl<'a> State<'a> {
     }
 
     /// Sets the current mask.
-    fn with_mask(self, mask: Option<&sk::Mask>) -> State<'_> {
+    fn with_mask<'b>(self, mask: Option<&'b sk::Mask>) -> State<'b>
+    where
+        'a: 'b,
+    {
         // Ensure that we're using the parent's mask if we don't have one.
         if mask.is_some() {
             State { mask, ..self }
@@ -248,3 +459,202 @@ impl AbsExt for Abs {
         self.to_pt() as f32
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use typst::foundations::Bytes;
+    use typst::layout::{Abs, Em, Point, Size};
+    use typst::syntax::Span;
+    use typst::text::{Font, Glyph, Lang, TextItem};
+    use typst::visualize::Geometry;
+
+    use super::*;
+
+    /// A frame with squares of different colors scattered across it.
+    fn frame() -> Frame {
+        let mut frame = Frame::soft(Size::new(Abs::pt(600.0), Abs::pt(400.0)));
+        let colors = [Color::RED, Color::GREEN, Color::BLUE, Color::BLACK];
+        for (i, color) in colors.into_iter().enumerate() {
+            let square = Geometry::Rect(Size::splat(Abs::pt(150.0))).filled(color.into());
+            let pos = Point::new(Abs::pt(140.0 * i as f64), Abs::pt(70.0 * i as f64));
+            frame.push(pos, FrameItem::Shape(square, Span::detached()));
+        }
+        frame
+    }
+
+    /// The pixels of a pixmap within the rectangle.
+    fn crop(pixmap: &sk::Pixmap, rect: sk::IntRect) -> Vec<sk::PremultipliedColorU8> {
+        (rect.top()..rect.bottom())
+            .flat_map(|y| {
+                (rect.left()..rect.right())
+                    .map(move |x| pixmap.pixel(x as u32, y as u32).unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_render_viewport_matches_render() {
+        let frame = frame();
+        let full = render(&frame, 2.0, Color::WHITE);
+        let mut cache = RenderCache::new();
+        for rect in [
+            sk::IntRect::from_xywh(0, 0, 1200, 800).unwrap(),
+            sk::IntRect::from_xywh(200, 250, 500, 300).unwrap(),
+            sk::IntRect::from_xywh(511, 0, 2, 800).unwrap(),
+        ] {
+            let viewport = render_viewport(&mut cache, &frame, 2.0, Color::WHITE, rect);
+            let all = sk::IntRect::from_xywh(0, 0, rect.width(), rect.height()).unwrap();
+            assert_eq!(crop(&viewport, all), crop(&full, rect));
+        }
+    }
+
+    #[test]
+    fn test_render_viewport_fills_outside_of_frame() {
+        let color = |color: Color| paint::to_sk_color(color).premultiply().to_color_u8();
+        let frame = frame();
+        let mut cache = RenderCache::new();
+
+        // Overlapping the red square at the top-left corner.
+        let rect = sk::IntRect::from_xywh(-50, -50, 100, 100).unwrap();
+        let viewport = render_viewport(&mut cache, &frame, 2.0, Color::WHITE, rect);
+        assert_eq!(viewport.pixel(10, 10), Some(color(Color::WHITE)));
+        assert_eq!(viewport.pixel(60, 60), Some(color(Color::RED)));
+
+        // Overlapping the black square at the bottom-right corner, which
+        // extends beyond the frame.
+        let rect = sk::IntRect::from_xywh(1100, 700, 200, 200).unwrap();
+        let viewport = render_viewport(&mut cache, &frame, 2.0, Color::WHITE, rect);
+        assert_eq!(viewport.pixel(10, 10), Some(color(Color::BLACK)));
+        assert_eq!(viewport.pixel(110, 50), Some(color(Color::WHITE)));
+        assert_eq!(viewport.pixel(150, 150), Some(color(Color::WHITE)));
+    }
+
+    #[test]
+    fn test_render_viewport_reuses_rasterizations() {
+        let mut frame = text_frame(Abs::pt(20.0));
+        frame.push_frame(Point::zero(), self::frame());
+        let mut cache = RenderCache::new();
+
+        // Only the visible squares are rasterized.
+        let rect = sk::IntRect::from_xywh(0, 0, 200, 200).unwrap();
+        render_viewport(&mut cache, &frame, 2.0, Color::WHITE, rect);
+        assert_eq!(cache.shapes.borrow().len(), 1);
+
+        // All glyphs are the same and lie at the same offsets within pixels,
+        // so they share a single rasterization. Scrolling by whole pixels
+        // reuses it.
+        let rect = sk::IntRect::from_xywh(0, 0, 1200, 800).unwrap();
+        render_viewport(&mut cache, &frame, 2.0, Color::WHITE, rect);
+        assert_eq!(cache.glyphs.borrow().len(), 1);
+        assert_eq!(cache.shapes.borrow().len(), 4);
+        let rect = sk::IntRect::from_xywh(-7, 3, 1200, 800).unwrap();
+        render_viewport(&mut cache, &frame, 2.0, Color::WHITE, rect);
+        assert_eq!(cache.glyphs.borrow().len(), 1);
+        assert_eq!(cache.shapes.borrow().len(), 4);
+
+        // Cached glyphs look like directly rendered ones.
+        let full = render(&frame, 2.0, Color::WHITE);
+        let rect = sk::IntRect::from_xywh(0, 0, 1200, 800).unwrap();
+        let viewport = render_viewport(&mut cache, &frame, 2.0, Color::WHITE, rect);
+        let max_diff = full
+            .pixels()
+            .iter()
+            .zip(viewport.pixels())
+            .flat_map(|(a, b)| {
+                [
+                    a.red().abs_diff(b.red()),
+                    a.green().abs_diff(b.green()),
+                    a.blue().abs_diff(b.blue()),
+                ]
+            })
+            .max();
+        assert!(max_diff <= Some(2), "{max_diff:?}");
+    }
+
+    #[test]
+    fn test_render_cache_evicts_unused_rasterizations() {
+        let frame = frame();
+        let mut cache = RenderCache::new();
+        let left = sk::IntRect::from_xywh(0, 0, 200, 200).unwrap();
+        let right = sk::IntRect::from_xywh(1000, 600, 200, 200).unwrap();
+        render_viewport(&mut cache, &frame, 2.0, Color::WHITE, left);
+        render_viewport(&mut cache, &frame, 2.0, Color::WHITE, right);
+        assert_eq!(cache.shapes.borrow().len(), 2);
+
+        // The square on the left wasn't used in the last render.
+        cache.evict(1);
+        assert_eq!(cache.shapes.borrow().len(), 1);
+        cache.evict(0);
+        assert!(cache.shapes.borrow().is_empty());
+    }
+
+    /// A frame with a line of text at the given font size. The glyphs are one
+    /// em wide.
+    fn text_frame(size: Abs) -> Frame {
+        let data = typst_assets::fonts().next().unwrap();
+        let font = Font::iter(Bytes::from_static(data)).next().unwrap();
+        let id = font.ttf().glyph_index('a').unwrap().0;
+        let glyphs = (0..20)
+            .map(|i| Glyph {
+                id,
+                x_advance: Em::one(),
+                x_offset: Em::zero(),
+                range: i..i + 1,
+                span: (Span::detached(), 0),
+            })
+            .collect();
+
+        let text = TextItem {
+            font,
+            size,
+            fill: Color::BLACK.into(),
+            stroke: None,
+            lang: Lang::ENGLISH,
+            region: None,
+            text: "a".repeat(20).into(),
+            glyphs,
+        };
+
+        let mut frame = Frame::soft(Size::new(Abs::pt(600.0), Abs::pt(400.0)));
+        frame.push(Point::new(Abs::pt(20.0), Abs::pt(21.25)), FrameItem::Text(text));
+        frame
+    }
+
+    /// The distinct colors of a pixmap's pixels.
+    fn colors(pixmap: &sk::Pixmap) -> Vec<sk::PremultipliedColorU8> {
+        let mut colors = pixmap.pixels().to_vec();
+        colors.sort_by_key(|c| (c.red(), c.green(), c.blue(), c.alpha()));
+        colors.dedup();
+        colors
+    }
+
+    #[test]
+    fn test_render_thumbnail_fits_max_size() {
+        let wide = frame();
+        let thumbnail = render_thumbnail(&wide, 120, Color::WHITE);
+        assert_eq!((thumbnail.width(), thumbnail.height()), (120, 80));
+
+        let tall = Frame::soft(Size::new(Abs::pt(100.0), Abs::pt(500.0)));
+        let thumbnail = render_thumbnail(&tall, 120, Color::WHITE);
+        assert_eq!((thumbnail.width(), thumbnail.height()), (24, 120));
+
+        assert_eq!(thumbnail_pixel_per_pt(Size::zero(), 120), 1.0);
+    }
+
+    #[test]
+    fn test_render_thumbnail_greeks_small_text() {
+        // At 0.2 pixels per point, 12.5pt text has 2.5 pixels per em and is
+        // drawn as a bar, which is aligned to whole pixels here.
+        let frame = text_frame(Abs::pt(12.5));
+        let thumbnail = render_thumbnail(&frame, 120, Color::WHITE);
+        let white = paint::to_sk_color(Color::WHITE).premultiply().to_color_u8();
+        assert_eq!(colors(&thumbnail).len(), 2);
+        assert_ne!(thumbnail.pixel(10, 3), Some(white));
+        assert_eq!(thumbnail.pixel(10, 4), Some(white));
+
+        // Larger text is still drawn glyph by glyph, with anti-aliased edges.
+        let frame = text_frame(Abs::pt(40.0));
+        let thumbnail = render_thumbnail(&frame, 120, Color::WHITE);
+        assert!(colors(&thumbnail).len() > 2);
+    }
+}
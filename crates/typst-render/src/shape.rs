@@ -1,39 +1,101 @@
 use tiny_skia as sk;
 use typst::layout::{Abs, Axes, Point, Ratio, Size};
+use typst::utils::hash128;
 use typst::visualize::{
-    DashPattern, FixedStroke, Geometry, LineCap, LineJoin, Path, PathItem, Shape,
+    DashPattern, FixedStroke, Geometry, LineCap, LineJoin, Paint, Path, PathItem, Shape,
 };
 
-use crate::{paint, AbsExt, State};
+use crate::{paint, split_subpixel, AbsExt, Raster, RenderCache, State};
 
 /// Render a geometrical shape into the canvas.
+///
+/// With a render cache, shapes with solid paints that aren't clipped are
+/// rasterized separately, so that the rasterization can be reused.
 pub fn render_shape(canvas: &mut sk::Pixmap, state: State, shape: &Shape) -> Option<()> {
-    let ts = state.transform;
-    let path = match shape.geometry {
-        Geometry::Line(target) => {
-            let mut builder = sk::PathBuilder::new();
-            builder.line_to(target.x.to_f32(), target.y.to_f32());
-            builder.finish()?
+    let solid =
+        |paint: Option<&Paint>| paint.map_or(true, |p| matches!(p, Paint::Solid(_)));
+    match state.cache {
+        Some(cache)
+            if state.mask.is_none()
+                && solid(shape.fill.as_ref())
+                && solid(shape.stroke.as_ref().map(|stroke| &stroke.paint)) =>
+        {
+            render_cached_shape(canvas, state, shape, cache)
         }
-        Geometry::Rect(size) => {
-            let w = size.x.to_f32();
-            let h = size.y.to_f32();
-            let rect = if w < 0.0 || h < 0.0 {
-                // Skia doesn't normally allow for negative dimensions, but
-                // Typst supports them, so we apply a transform if needed
-                // Because this operation is expensive according to tiny-skia's
-                // docs, we prefer to not apply it if not needed
-                let transform = sk::Transform::from_scale(w.signum(), h.signum());
-                let rect = sk::Rect::from_xywh(0.0, 0.0, w.abs(), h.abs())?;
-                rect.transform(transform)?
-            } else {
-                sk::Rect::from_xywh(0.0, 0.0, w, h)?
-            };
+        _ => draw_shape(canvas, state, shape),
+    }
+}
 
-            sk::PathBuilder::from_rect(rect)
-        }
-        Geometry::Path(ref path) => convert_path(path)?,
-    };
+/// Render a shape by drawing a cached rasterization of it.
+fn render_cached_shape(
+    canvas: &mut sk::Pixmap,
+    state: State,
+    shape: &Shape,
+    cache: &RenderCache,
+) -> Option<()> {
+    // Rasterize the shape at its offset within a pixel and move it to the
+    // whole pixel afterwards.
+    let ts = state.transform;
+    let (x, dx) = split_subpixel(ts.tx);
+    let (y, dy) = split_subpixel(ts.ty);
+    let local = sk::Transform::from_row(ts.sx, ts.ky, ts.kx, ts.sy, dx, dy);
+
+    // Skip shapes that are outside of the canvas.
+    let bounds = bounds(shape, local)?;
+    let visible = sk::IntRect::from_xywh(0, 0, canvas.width(), canvas.height())?;
+    if bounds.translate(x, y).and_then(|b| b.intersect(&visible)).is_none() {
+        return Some(());
+    }
+
+    let transform = [local.sx, local.ky, local.kx, local.sy, dx, dy].map(f32::to_bits);
+    let raster = cache.shape(hash128(&(shape, transform)), || {
+        let mut pixmap = sk::Pixmap::new(bounds.width(), bounds.height())?;
+        let ts = local.post_translate(-bounds.left() as f32, -bounds.top() as f32);
+        draw_shape(&mut pixmap, State { transform: ts, ..state }, shape)?;
+        Some(Raster { pixmap, left: bounds.left(), top: bounds.top() })
+    })?;
+
+    canvas.draw_pixmap(
+        x + raster.left,
+        y + raster.top,
+        raster.pixmap.as_ref(),
+        &sk::PixmapPaint::default(),
+        sk::Transform::identity(),
+        None,
+    );
+
+    Some(())
+}
+
+/// The pixels that a shape may cover when drawn with the given transform.
+fn bounds(shape: &Shape, ts: sk::Transform) -> Option<sk::IntRect> {
+    let rect = convert_geometry(&shape.geometry)?.bounds();
+
+    // Leave room for the stroke, including its caps and miter joins, and for
+    // anti-aliasing.
+    let pad = shape.stroke.as_ref().map_or(0.0, |stroke| {
+        stroke.thickness.to_f32() * stroke.miter_limit.get().max(2.0) as f32 / 2.0
+    });
+    let rect = sk::Rect::from_ltrb(
+        rect.left() - pad,
+        rect.top() - pad,
+        rect.right() + pad,
+        rect.bottom() + pad,
+    )?
+    .transform(ts)?;
+
+    sk::IntRect::from_ltrb(
+        rect.left().floor() as i32 - 1,
+        rect.top().floor() as i32 - 1,
+        rect.right().ceil() as i32 + 1,
+        rect.bottom().ceil() as i32 + 1,
+    )
+}
+
+/// Draw a geometrical shape directly into the canvas.
+fn draw_shape(canvas: &mut sk::Pixmap, state: State, shape: &Shape) -> Option<()> {
+    let ts = state.transform;
+    let path = convert_geometry(&shape.geometry)?;
 
     if let Some(fill) = &shape.fill {
         let mut pixmap = None;
@@ -115,6 +177,35 @@ pub fn render_shape(canvas: &mut sk::Pixmap, state: State, shape: &Shape) -> Opt
     Some(())
 }
 
+/// Convert a Typst geometry into a tiny-skia path.
+fn convert_geometry(geometry: &Geometry) -> Option<sk::Path> {
+    Some(match *geometry {
+        Geometry::Line(target) => {
+            let mut builder = sk::PathBuilder::new();
+            builder.line_to(target.x.to_f32(), target.y.to_f32());
+            builder.finish()?
+        }
+        Geometry::Rect(size) => {
+            let w = size.x.to_f32();
+            let h = size.y.to_f32();
+            let rect = if w < 0.0 || h < 0.0 {
+                // Skia doesn't normally allow for negative dimensions, but
+                // Typst supports them, so we apply a transform if needed
+                // Because this operation is expensive according to tiny-skia's
+                // docs, we prefer to not apply it if not needed
+                let transform = sk::Transform::from_scale(w.signum(), h.signum());
+                let rect = sk::Rect::from_xywh(0.0, 0.0, w.abs(), h.abs())?;
+                rect.transform(transform)?
+            } else {
+                sk::Rect::from_xywh(0.0, 0.0, w, h)?
+            };
+
+            sk::PathBuilder::from_rect(rect)
+        }
+        Geometry::Path(ref path) => convert_path(path)?,
+    })
+}
+
 /// Convert a Typst path into a tiny-skia path.
 pub fn convert_path(path: &Path) -> Option<sk::Path> {
     let mut builder = sk::PathBuilder::new();
@@ -6,13 +6,26 @@ use ttf_parser::{GlyphId, OutlineBuilder};
 use typst::layout::{Abs, Axes, Point, Size};
 use typst::text::color::{frame_for_glyph, is_color_glyph};
 use typst::text::{Font, TextItem};
+use typst::utils::hash128;
 use typst::visualize::{FixedStroke, Paint};
 
 use crate::paint::{self, GradientSampler, PaintSampler, PatternSampler};
 use crate::{shape, AbsExt, State};
 
+/// Text with fewer pixels per em than this is drawn as a bar in draft mode.
+const GREEKING_PPEM: f32 = 4.0;
+
 /// Render a text run into the canvas.
 pub fn render_text(canvas: &mut sk::Pixmap, state: State, text: &TextItem) {
+    if state.draft {
+        let ts = &state.transform;
+        let ppem = text.size.to_f32() * (ts.kx * ts.kx + ts.sy * ts.sy).sqrt();
+        if ppem < GREEKING_PPEM {
+            render_greeked(canvas, state, text);
+            return;
+        }
+    }
+
     let mut x = 0.0;
     for glyph in &text.glyphs {
         let id = GlyphId(glyph.id);
@@ -38,6 +51,25 @@ pub fn render_text(canvas: &mut sk::Pixmap, state: State, text: &TextItem) {
     }
 }
 
+/// Render a text run as a bar that covers roughly where its lowercase letters
+/// would be. This is much faster than rasterizing glyphs that wouldn't be
+/// legible anyway.
+fn render_greeked(canvas: &mut sk::Pixmap, state: State, text: &TextItem) -> Option<()> {
+    let size = text.size.to_f32();
+    let rect = sk::Rect::from_xywh(0.0, -0.5 * size, text.width().to_f32(), 0.4 * size)?;
+
+    let mut color = match &text.fill {
+        Paint::Solid(color) => paint::to_sk_color(*color),
+        _ => sk::Color::BLACK,
+    };
+    color.apply_opacity(0.5);
+
+    let mut paint = sk::Paint::default();
+    paint.set_color(color);
+    canvas.fill_rect(rect, &paint, state.transform, state.mask);
+    Some(())
+}
+
 /// Render an outline glyph into the canvas. This is the "normal" case.
 fn render_outline_glyph(
     canvas: &mut sk::Pixmap,
@@ -45,8 +77,16 @@ fn render_outline_glyph(
     text: &TextItem,
     id: GlyphId,
 ) -> Option<()> {
-    let ts = &state.transform;
-    let ppem = text.size.to_f32() * ts.sy;
+    let mut ts = state.transform;
+    let mut ppem = text.size.to_f32() * ts.sy;
+
+    // In draft mode, snap the glyph to whole pixels so that its rasterization
+    // is likely to be reused.
+    if state.draft {
+        ts.tx = ts.tx.round();
+        ts.ty = ts.ty.round();
+        ppem = ppem.round();
+    }
 
     // Render a glyph directly as a path. This only happens when the fast glyph
     // rasterization can't be used due to very large text size or weird
@@ -131,18 +171,48 @@ fn render_outline_glyph(
     }
 
     // Try to retrieve a prepared glyph or prepare it from scratch if it
-    // doesn't exist, yet.
-    let bitmap =
-        rasterize(&text.font, id, ts.tx.to_bits(), ts.ty.to_bits(), ppem.to_bits())?;
+    // doesn't exist, yet. With a render cache, the glyph is rasterized at its
+    // offset within a pixel and then moved to the whole pixel, so that the
+    // rasterization can be reused wherever the glyph lands at that offset.
+    let (bitmap, origin) = match state.cache {
+        Some(cache) => {
+            let (x, dx) = crate::split_subpixel(ts.tx);
+            let (y, dy) = crate::split_subpixel(ts.ty);
+
+            // Skip glyphs that are certainly outside of the canvas.
+            let margin = 2 * ppem.ceil() as i32;
+            if x + margin < 0
+                || y + margin < 0
+                || x - margin > canvas.width() as i32
+                || y - margin > canvas.height() as i32
+            {
+                return Some(());
+            }
+
+            let key =
+                hash128(&(&text.font, id, dx.to_bits(), dy.to_bits(), ppem.to_bits()));
+            let bitmap = cache.glyph(key, || {
+                let glyph = pixglyph::Glyph::load(text.font.ttf(), id)?;
+                Some(glyph.rasterize(dx, dy, ppem))
+            })?;
+            (bitmap, (x, y))
+        }
+        None => (
+            rasterize(&text.font, id, ts.tx.to_bits(), ts.ty.to_bits(), ppem.to_bits())?,
+            (0, 0),
+        ),
+    };
+
     match &text.fill {
         Paint::Gradient(gradient) => {
             let sampler = GradientSampler::new(gradient, &state, Size::zero(), true);
-            write_bitmap(canvas, &bitmap, &state, sampler)?;
+            write_bitmap(canvas, &bitmap, origin, &state, sampler)?;
         }
         Paint::Solid(color) => {
             write_bitmap(
                 canvas,
                 &bitmap,
+                origin,
                 &state,
                 paint::to_sk_color_u8(*color).premultiply(),
             )?;
@@ -150,16 +220,18 @@ fn render_outline_glyph(
         Paint::Pattern(pattern) => {
             let pixmap = paint::render_pattern_frame(&state, pattern);
             let sampler = PatternSampler::new(pattern, &pixmap, &state, true);
-            write_bitmap(canvas, &bitmap, &state, sampler)?;
+            write_bitmap(canvas, &bitmap, origin, &state, sampler)?;
         }
     }
 
     Some(())
 }
 
+/// Write a glyph bitmap into the canvas, moved by the given number of pixels.
 fn write_bitmap<S: PaintSampler>(
     canvas: &mut sk::Pixmap,
     bitmap: &Bitmap,
+    origin: (i32, i32),
     state: &State,
     sampler: S,
 ) -> Option<()> {
@@ -187,8 +259,8 @@ fn write_bitmap<S: PaintSampler>(
             }
         }
 
-        let left = bitmap.left;
-        let top = bitmap.top;
+        let left = bitmap.left + origin.0;
+        let top = bitmap.top + origin.1;
 
         canvas.draw_pixmap(
             left - 1,
@@ -205,9 +277,9 @@ fn write_bitmap<S: PaintSampler>(
         let mh = bitmap.height as i32;
 
         // Determine the pixel bounding box that we actually need to draw.
-        let left = bitmap.left;
+        let left = bitmap.left + origin.0;
         let right = left + mw;
-        let top = bitmap.top;
+        let top = bitmap.top + origin.1;
         let bottom = top + mh;
 
         // Blend the glyph bitmap with the existing pixels on the canvas.
@@ -34,31 +34,32 @@ pub fn svg(frame: &Frame) -> String {
 
 /// Export a document with potentially multiple pages into a single SVG file.
 ///
-/// The padding will be added around and between the individual frames.
+/// The padding will be added around and between the individual frames. If the
+/// document is meant to be viewed as spreads, facing pages are placed side by
+/// side.
 pub fn svg_merged(document: &Document, padding: Abs) -> String {
+    let imposed;
+    let frames: Vec<&Frame> = if document.spreads {
+        imposed = document.impose();
+        imposed.iter().collect()
+    } else {
+        document.pages.iter().map(|page| &page.frame).collect()
+    };
+
     let width = 2.0 * padding
-        + document
-            .pages
-            .iter()
-            .map(|page| page.frame.width())
-            .max()
-            .unwrap_or_default();
-    let height = padding
-        + document
-            .pages
-            .iter()
-            .map(|page| page.frame.height() + padding)
-            .sum::<Abs>();
+        + frames.iter().map(|frame| frame.width()).max().unwrap_or_default();
+    let height =
+        padding + frames.iter().map(|frame| frame.height() + padding).sum::<Abs>();
 
     let mut renderer = SVGRenderer::new();
     renderer.write_header(Size::new(width, height));
 
     let [x, mut y] = [padding; 2];
-    for page in &document.pages {
+    for frame in frames {
         let ts = Transform::translate(x, y);
-        let state = State::new(page.frame.size(), Transform::identity());
-        renderer.render_frame(state, ts, &page.frame);
-        y += page.frame.height() + padding;
+        let state = State::new(frame.size(), Transform::identity());
+        renderer.render_frame(state, ts, frame);
+        y += frame.height() + padding;
     }
 
     renderer.finalize()
@@ -1,5 +1,6 @@
 //! Package manifest parsing.
 
+use std::collections::BTreeMap;
 use std::fmt::{self, Debug, Display, Formatter};
 use std::str::FromStr;
 
@@ -41,6 +42,13 @@ pub struct PackageInfo {
     pub entrypoint: EcoString,
     /// The minimum required compiler version for the package.
     pub compiler: Option<VersionBound>,
+    /// The bindings that make up the package's public API.
+    ///
+    /// Each entry is the name of a top-level binding of the entrypoint or a
+    /// dotted path to a binding in one of its submodules (e.g.
+    /// `"themes.dark"`), which is then exported under its last component. If
+    /// this is `None`, all top-level bindings of the entrypoint are exported.
+    pub exports: Option<Vec<EcoString>>,
 }
 
 impl PackageManifest {
@@ -164,6 +172,100 @@ impl Display for VersionlessPackageSpec {
     }
 }
 
+/// Identifies a package by a version bound rather than an exact version.
+///
+/// An import of `@local/letter:1.2` refers to the highest available version
+/// `1.2.x` of the package. Which exact version that is has to be determined by
+/// the environment the compiler runs in.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct BoundedPackageSpec {
+    /// The namespace the package lives in.
+    pub namespace: EcoString,
+    /// The name of the package within its namespace.
+    pub name: EcoString,
+    /// The bound the package's version must match.
+    pub bound: VersionBound,
+}
+
+impl BoundedPackageSpec {
+    pub fn versionless(&self) -> VersionlessPackageSpec {
+        VersionlessPackageSpec {
+            namespace: self.namespace.clone(),
+            name: self.name.clone(),
+        }
+    }
+
+    /// Whether the given version satisfies this specification's bound.
+    pub fn matches(&self, version: &PackageVersion) -> bool {
+        version.matches_eq(&self.bound)
+    }
+
+    /// Fill in a concrete `version` to get a complete [`PackageSpec`].
+    pub fn at(self, version: PackageVersion) -> PackageSpec {
+        PackageSpec {
+            namespace: self.namespace,
+            name: self.name,
+            version,
+        }
+    }
+}
+
+impl FromStr for BoundedPackageSpec {
+    type Err = EcoString;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut s = unscanny::Scanner::new(s);
+        let namespace = parse_namespace(&mut s)?.into();
+        let name = parse_name(&mut s)?.into();
+        s.eat_if(':');
+        let bound = s.after();
+        if bound.is_empty() {
+            Err("package specification is missing version")?;
+        }
+        Ok(Self { namespace, name, bound: bound.parse()? })
+    }
+}
+
+impl Debug for BoundedPackageSpec {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Display for BoundedPackageSpec {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "@{}/{}:{}", self.namespace, self.name, self.bound)
+    }
+}
+
+/// A parsed package lockfile.
+///
+/// Records which exact version a bounded package specification was resolved
+/// to, so that repeated compilations of a project use the same packages even
+/// if newer matching versions become available.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct PackageLock {
+    /// Maps bounded specifications (e.g. `@local/letter:1`) to the versions
+    /// they were resolved to.
+    #[serde(default)]
+    pub packages: BTreeMap<EcoString, PackageVersion>,
+}
+
+impl PackageLock {
+    /// The locked version for the given specification, if it still matches.
+    pub fn get(&self, spec: &BoundedPackageSpec) -> Option<PackageVersion> {
+        let version = self.packages.get(spec.to_string().as_str())?;
+        spec.matches(version).then_some(*version)
+    }
+
+    /// Lock the given specification to a version.
+    ///
+    /// Returns whether the lock changed.
+    pub fn insert(&mut self, spec: &BoundedPackageSpec, version: PackageVersion) -> bool {
+        self.packages.insert(eco_format!("{spec}"), version) != Some(version)
+    }
+}
+
 fn parse_namespace<'s>(s: &mut Scanner<'s>) -> Result<&'s str, EcoString> {
     if !s.eat_if('@') {
         Err("package specification must start with '@'")?;
@@ -423,4 +525,14 @@ mod tests {
         assert!(!v1_1_1.matches_lt(&VersionBound::from_str("1.1").unwrap()));
         assert!(v1_1_1.matches_lt(&VersionBound::from_str("1.2").unwrap()));
     }
+
+    #[test]
+    fn bounded_spec_parse() {
+        let spec = BoundedPackageSpec::from_str("@local/letter:1.2").unwrap();
+        assert_eq!(spec.to_string(), "@local/letter:1.2");
+        assert!(spec.matches(&PackageVersion::from_str("1.2.7").unwrap()));
+        assert!(!spec.matches(&PackageVersion::from_str("1.3.0").unwrap()));
+        assert!(BoundedPackageSpec::from_str("@local/letter:").is_err());
+        assert!(BoundedPackageSpec::from_str("@local/letter:1.2.").is_err());
+    }
 }
@@ -0,0 +1,282 @@
+//! Compilation of documents.
+
+use std::collections::HashSet;
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
+use comemo::{Track, Tracked, Validate};
+use ecow::{eco_format, EcoVec};
+use typst_timing::{timed, TimingScope};
+
+use crate::diag::{warning, SourceDiagnostic, SourceResult, Warned};
+use crate::engine::{Budget, Engine, Route, Sink, Traced};
+use crate::foundations::{Content, Label, Repr, StyleChain, Styles, Value};
+use crate::introspection::{divergences, Divergence, Introspector};
+use crate::model::{Document, Incomplete};
+use crate::partial::extract_partial;
+use crate::syntax::Span;
+use crate::World;
+
+/// Compile sources into a fully layouted document.
+///
+/// - Returns `Ok(document)` if there were no fatal errors.
+/// - Returns `Err(errors)` if there were fatal errors.
+#[typst_macros::time]
+pub fn compile(world: &dyn World) -> Warned<SourceResult<Document>> {
+    compile_with(world, Budget::default(), None)
+}
+
+/// Compile sources into a document, stopping early once the given time
+/// `budget` is spent.
+///
+/// This is meant for interactive previews, which would rather show something
+/// right away than wait for a pathological document. If the budget runs out,
+/// the document's [`incomplete`](Document::incomplete) marker says what is
+/// missing: Either the pages after those finished so far or the convergence of
+/// introspections. Errors that would only be reported at the end of the
+/// introspection loop are not reported for an incomplete document.
+///
+/// On WebAssembly, there is no clock to measure the budget with, so this
+/// behaves like [`compile`].
+#[typst_macros::time]
+pub fn compile_within(
+    world: &dyn World,
+    budget: Duration,
+) -> Warned<SourceResult<Document>> {
+    compile_with(world, Budget::new(budget), None)
+}
+
+/// Compile sources with the given budget, optionally only the part marked
+/// with a label.
+pub(crate) fn compile_with(
+    world: &dyn World,
+    budget: Budget,
+    partial: Option<Label>,
+) -> Warned<SourceResult<Document>> {
+    let mut sink = Sink::new();
+    let output = compile_inner(
+        world.track(),
+        Traced::default().track(),
+        &budget,
+        partial,
+        &mut sink,
+    )
+    .map_err(deduplicate)
+    .map_err(sort_by_span);
+    let logs = sort_by_span(sink.logs());
+    Warned {
+        output,
+        warnings: sort_by_span(sink.warnings()),
+        logs,
+    }
+}
+
+/// Compiles sources and returns all values and styles observed at the given
+/// `span` during compilation.
+#[typst_macros::time]
+pub fn trace(world: &dyn World, span: Span) -> EcoVec<(Value, Option<Styles>)> {
+    let mut sink = Sink::new();
+    let traced = Traced::new(span);
+    compile_inner(world.track(), traced.track(), &Budget::default(), None, &mut sink)
+        .ok();
+    sink.values()
+}
+
+/// Relayout until introspection converges.
+fn compile_inner(
+    world: Tracked<dyn World + '_>,
+    traced: Tracked<Traced>,
+    budget: &Budget,
+    partial: Option<Label>,
+    sink: &mut Sink,
+) -> SourceResult<Document> {
+    let library = world.library();
+    let styles = StyleChain::new(&library.styles);
+
+    // First evaluate the main source file into a module.
+    let mut content = crate::eval::eval(
+        world,
+        traced,
+        sink.track_mut(),
+        Route::default().track(),
+        &world.main(),
+    )?
+    .content();
+
+    // Keep only the part of the content that is to be compiled.
+    if let Some(label) = partial {
+        content = extract_partial(&content, label, styles)?;
+    }
+
+    layout_converged(
+        world,
+        traced,
+        budget,
+        &content,
+        styles,
+        None,
+        Introspector::default(),
+        sink,
+    )
+}
+
+/// Relayout the content until introspection converges, stopping once the given
+/// number of pages is finished.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn layout_converged(
+    world: Tracked<dyn World + '_>,
+    traced: Tracked<Traced>,
+    budget: &Budget,
+    content: &Content,
+    styles: StyleChain,
+    pages: Option<usize>,
+    introspector: Introspector,
+    sink: &mut Sink,
+) -> SourceResult<Document> {
+    // Messages logged before layout are kept, while those logged during
+    // layout are only kept from the last attempt.
+    let logged = sink.logged();
+    let mut iter = 0;
+    let mut document = Document { introspector, ..Document::default() };
+
+    // Relayout until all introspections stabilize.
+    // If that doesn't happen within the configured number of attempts, we give
+    // up.
+    loop {
+        // The name of the iterations for timing scopes.
+        const ITER_NAMES: &[&str] =
+            &["layout (1)", "layout (2)", "layout (3)", "layout (4)", "layout (5)"];
+        let name = ITER_NAMES.get(iter).copied().unwrap_or("layout (n)");
+        let _scope = TimingScope::new(name, None);
+
+        // Clear delayed errors and messages logged by the previous attempt.
+        sink.delayed();
+        sink.truncate_logs(logged);
+
+        let constraint = <Introspector as Validate>::Constraint::new();
+        let mut engine = Engine {
+            world,
+            introspector: document.introspector.track_with(&constraint),
+            traced,
+            budget: budget.track(),
+            sink: sink.track_mut(),
+            route: Route::default(),
+        };
+
+        // Layout!
+        let mut next = content.layout_document_pages(&mut engine, styles, pages)?;
+        let max = next.iterations.map_or(DEFAULT_ITERATIONS, NonZeroUsize::get);
+
+        // Keep the previous introspector around if this is the last attempt,
+        // to find out what didn't converge.
+        let previous = (iter + 1 >= max).then(|| document.introspector.clone());
+
+        // Update the previous introspector such that only the changed pages
+        // need to be introspected again.
+        next.introspector = std::mem::take(&mut document.introspector);
+        next.introspector.rebuild(&next.pages);
+        document = next;
+        iter += 1;
+
+        // Relayouting a document that was truncated because the budget ran
+        // out would only truncate it again.
+        if document.incomplete.is_some() && budget.is_spent() {
+            break;
+        }
+
+        if timed!("check stabilized", document.introspector.validate(&constraint)) {
+            break;
+        }
+
+        if budget.is_spent() {
+            document.incomplete = Some(Incomplete::Unstable);
+            break;
+        }
+
+        if let Some(previous) = previous {
+            document.divergences =
+                warn_divergences(world, sink, max, &previous, &document.introspector);
+            break;
+        }
+    }
+
+    sink.deduplicate_logs(logged);
+
+    // Promote delayed errors. In an incomplete document, they may just be due
+    // to the unfinished introspection.
+    let delayed = sink.delayed();
+    if !delayed.is_empty() && document.incomplete.is_none() {
+        return Err(delayed);
+    }
+
+    Ok(document)
+}
+
+/// How many times a document is laid out at most by default until its
+/// introspections converge.
+pub(crate) const DEFAULT_ITERATIONS: usize = 5;
+
+/// Warns that layout did not converge within `max` attempts, pointing to the
+/// counters and states that changed in the last attempt.
+pub(crate) fn warn_divergences(
+    world: Tracked<dyn World + '_>,
+    sink: &mut Sink,
+    max: usize,
+    previous: &Introspector,
+    current: &Introspector,
+) -> Vec<Divergence> {
+    sink.warn(warning!(
+        Span::detached(), "layout did not converge within {max} attempts";
+        hint: "check if any states or queries are updating themselves"
+    ));
+
+    let divergences = divergences(world, previous, current);
+    for divergence in &divergences {
+        let hint = match (&divergence.previous, &divergence.current) {
+            (Some(previous), Some(current)) => eco_format!(
+                "its value changed from {} to {} in the last attempt",
+                previous.repr(),
+                current.repr(),
+            ),
+            (None, _) => "this update appeared in the last attempt".into(),
+            (_, None) => "this update disappeared in the last attempt".into(),
+        };
+        sink.warn(
+            warning!(divergence.span, "{} did not converge", divergence.subject.repr())
+                .with_hint(hint),
+        );
+    }
+
+    divergences
+}
+
+/// Deduplicate diagnostics.
+pub(crate) fn deduplicate(
+    mut diags: EcoVec<SourceDiagnostic>,
+) -> EcoVec<SourceDiagnostic> {
+    let mut unique = HashSet::new();
+    diags.retain(|diag| {
+        let hash = crate::utils::hash128(&(&diag.span, &diag.message));
+        unique.insert(hash)
+    });
+    diags
+}
+
+/// Sorts diagnostics by the position of their spans, grouped by file.
+///
+/// The order in which diagnostics are collected may vary between compilations
+/// since layout happens in parallel. Sorting them makes the output
+/// reproducible. Diagnostics with detached spans come last, and those with the
+/// same span keep their relative order.
+pub(crate) fn sort_by_span(
+    mut diags: EcoVec<SourceDiagnostic>,
+) -> EcoVec<SourceDiagnostic> {
+    diags.make_mut().sort_by_cached_key(|diag| {
+        let file = diag
+            .span
+            .id()
+            .map(|id| (id.package().map(ToString::to_string), id.vpath()));
+        (file.is_none(), file, diag.span.number())
+    });
+    diags
+}
@@ -9,7 +9,7 @@ use std::string::FromUtf8Error;
 use comemo::Tracked;
 use ecow::{eco_vec, EcoVec};
 
-use crate::syntax::package::{PackageSpec, PackageVersion};
+use crate::syntax::package::{BoundedPackageSpec, PackageSpec, PackageVersion};
 use crate::syntax::{Span, Spanned, SyntaxError};
 use crate::{World, WorldExt};
 
@@ -145,6 +145,9 @@ pub struct Warned<T> {
     pub output: T,
     /// Warnings generated while producing the output.
     pub warnings: EcoVec<SourceDiagnostic>,
+    /// Messages that the document logged through the `log` module while
+    /// producing the output.
+    pub logs: EcoVec<SourceDiagnostic>,
 }
 
 /// An error or warning in a source file.
@@ -173,6 +176,8 @@ pub enum Severity {
     Error,
     /// A non-fatal warning.
     Warning,
+    /// An informational message, as logged by a document.
+    Info,
 }
 
 impl SourceDiagnostic {
@@ -505,6 +510,8 @@ pub enum PackageError {
     NotFound(PackageSpec),
     /// The specified package found, but the version does not exist.
     VersionNotFound(PackageSpec, PackageVersion),
+    /// No available version of the package matches the version bound.
+    NoMatchingVersion(BoundedPackageSpec),
     /// Failed to retrieve the package through the network.
     NetworkFailed(Option<EcoString>),
     /// The package archive was malformed.
@@ -528,6 +535,9 @@ impl Display for PackageError {
                     spec.version, latest,
                 )
             }
+            Self::NoMatchingVersion(spec) => {
+                write!(f, "no matching package version found (searched for {spec})")
+            }
             Self::NetworkFailed(Some(err)) => {
                 write!(f, "failed to download package ({err})")
             }
@@ -2,6 +2,7 @@
 
 use std::collections::HashSet;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
 use comemo::{Track, Tracked, TrackedMut, Validate};
 use ecow::EcoVec;
@@ -21,6 +22,8 @@ pub struct Engine<'a> {
     pub introspector: Tracked<'a, Introspector>,
     /// May hold a span that is currently under inspection.
     pub traced: Tracked<'a, Traced>,
+    /// The time budget for layout.
+    pub budget: Tracked<'a, Budget>,
     /// A pure sink for warnings, delayed errors, and spans under inspection.
     pub sink: TrackedMut<'a, Sink>,
     /// The route the engine took during compilation. This is used to detect
@@ -55,7 +58,7 @@ impl Engine<'_> {
         U: Send,
         F: Fn(&mut Engine, T) -> U + Send + Sync,
     {
-        let Engine { world, introspector, traced, ref route, .. } = *self;
+        let Engine { world, introspector, traced, budget, ref route, .. } = *self;
 
         // We collect into a vector and then call `into_par_iter` instead of
         // using `par_bridge` because it does not retain the ordering.
@@ -70,6 +73,7 @@ impl Engine<'_> {
                     world,
                     introspector,
                     traced,
+                    budget,
                     sink: sink.track_mut(),
                     route: route.clone(),
                 };
@@ -80,7 +84,7 @@ impl Engine<'_> {
         // Apply the subsinks to the outer sink.
         for (_, sink) in &mut pairs {
             let sink = std::mem::take(sink);
-            self.sink.extend(sink.delayed, sink.warnings, sink.values);
+            self.sink.extend(sink.delayed, sink.warnings, sink.logs, sink.values);
         }
 
         pairs.into_iter().map(|(output, _)| output)
@@ -116,6 +120,50 @@ impl Traced {
     }
 }
 
+/// May hold a deadline after which layout should wrap up.
+///
+/// Once the deadline has passed, the root flows stop after the region they are
+/// currently laying out, so that the document only holds the pages finished so
+/// far. A budget without deadline never runs out.
+#[derive(Default)]
+pub struct Budget(Option<Instant>);
+
+impl Budget {
+    /// Creates a budget that runs out once the given `duration` has elapsed
+    /// from now.
+    ///
+    /// Call `Budget::default()` for an unlimited budget. On WebAssembly, there
+    /// is no clock to measure the elapsed time with, so the budget never runs
+    /// out there.
+    pub fn new(duration: Duration) -> Self {
+        #[cfg(target_arch = "wasm32")]
+        return {
+            let _ = duration;
+            Self(None)
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        Self(Instant::now().checked_add(duration))
+    }
+
+    /// Whether the deadline has passed.
+    pub(crate) fn is_spent(&self) -> bool {
+        self.0.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+#[comemo::track]
+impl Budget {
+    /// Whether the deadline has passed at the given checkpoint.
+    ///
+    /// Since the answer changes over time, each checkpoint must be unique.
+    /// Otherwise, memoization would see the same call with different results.
+    pub fn exceeded(&self, checkpoint: u128) -> bool {
+        let _ = checkpoint;
+        self.is_spent()
+    }
+}
+
 /// A push-only sink for delayed errors, warnings, and traced values.
 ///
 /// All tracked methods of this type are of the form `(&mut self, ..) -> ()`, so
@@ -129,10 +177,13 @@ pub struct Sink {
     /// proceed with empty content and only if the error remains by the end
     /// of the last iteration, we promote it.
     delayed: EcoVec<SourceDiagnostic>,
-    /// Warnings emitted during iteration.
-    warnings: EcoVec<SourceDiagnostic>,
-    /// Hashes of all warning's spans and messages for warning deduplication.
+    /// Warnings emitted during iteration along with the keys they are
+    /// deduplicated by.
+    warnings: EcoVec<(u128, SourceDiagnostic)>,
+    /// The keys of all warnings for warning deduplication.
     warnings_set: HashSet<u128>,
+    /// Messages logged by the document.
+    logs: EcoVec<SourceDiagnostic>,
     /// A sequence of traced values for a span.
     values: EcoVec<(Value, Option<Styles>)>,
 }
@@ -151,9 +202,44 @@ impl Sink {
         std::mem::take(&mut self.delayed)
     }
 
+    /// The number of messages logged so far.
+    pub fn logged(&self) -> usize {
+        self.logs.len()
+    }
+
+    /// Discard the messages logged after the first `len` ones, for instance
+    /// because the layout attempt that logged them is repeated.
+    pub fn truncate_logs(&mut self, len: usize) {
+        self.logs.truncate(len);
+    }
+
+    /// Remove repeated messages among those logged after the first `start`
+    /// ones.
+    ///
+    /// Layout may run the same code more than once, for instance to measure
+    /// content, so a message repeated during layout is only kept once.
+    pub fn deduplicate_logs(&mut self, start: usize) {
+        let mut seen = HashSet::new();
+        let mut i = 0;
+        self.logs.retain(|entry| {
+            i += 1;
+            i <= start
+                || seen.insert(crate::utils::hash128(&(
+                    &entry.span,
+                    entry.severity,
+                    &entry.message,
+                )))
+        });
+    }
+
+    /// Get the messages logged so far.
+    pub fn logs(&self) -> EcoVec<SourceDiagnostic> {
+        self.logs.clone()
+    }
+
     /// Get the stored warnings.
     pub fn warnings(self) -> EcoVec<SourceDiagnostic> {
-        self.warnings
+        self.warnings.into_iter().map(|(_, warning)| warning).collect()
     }
 
     /// Get the values for the traced span.
@@ -171,13 +257,23 @@ impl Sink {
 
     /// Add a warning.
     pub fn warn(&mut self, warning: SourceDiagnostic) {
-        // Check if warning is a duplicate.
-        let hash = crate::utils::hash128(&(&warning.span, &warning.message));
-        if self.warnings_set.insert(hash) {
-            self.warnings.push(warning);
+        let key = crate::utils::hash128(&(&warning.span, &warning.message));
+        self.warn_keyed(key, warning);
+    }
+
+    /// Add a warning that is deduplicated by the given key instead of its span
+    /// and message. Only the first warning with a key is kept.
+    pub fn warn_keyed(&mut self, key: u128, warning: SourceDiagnostic) {
+        if self.warnings_set.insert(key) {
+            self.warnings.push((key, warning));
         }
     }
 
+    /// Log a message from the document.
+    pub fn log(&mut self, entry: SourceDiagnostic) {
+        self.logs.push(entry);
+    }
+
     /// Trace a value and optionally styles for the traced span.
     pub fn value(&mut self, value: Value, styles: Option<Styles>) {
         if self.values.len() < Self::MAX_VALUES {
@@ -189,12 +285,16 @@ impl Sink {
     fn extend(
         &mut self,
         delayed: EcoVec<SourceDiagnostic>,
-        warnings: EcoVec<SourceDiagnostic>,
+        warnings: EcoVec<(u128, SourceDiagnostic)>,
+        logs: EcoVec<SourceDiagnostic>,
         values: EcoVec<(Value, Option<Styles>)>,
     ) {
         self.delayed.extend(delayed);
-        for warning in warnings {
-            self.warn(warning);
+        for (key, warning) in warnings {
+            self.warn_keyed(key, warning);
+        }
+        for entry in logs {
+            self.log(entry);
         }
         if let Some(remaining) = Self::MAX_VALUES.checked_sub(self.values.len()) {
             self.values.extend(values.into_iter().take(remaining));
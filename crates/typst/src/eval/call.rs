@@ -2,14 +2,15 @@ use comemo::{Tracked, TrackedMut};
 use ecow::{eco_format, EcoVec};
 
 use crate::diag::{bail, error, At, HintedStrResult, SourceResult, Trace, Tracepoint};
-use crate::engine::{Engine, Sink, Traced};
+use crate::engine::{Budget, Engine, Sink, Traced};
 use crate::eval::{Access, Eval, FlowEvent, Route, Vm};
 use crate::foundations::{
     call_method_mut, is_mutating_method, Arg, Args, Bytes, Capturer, Closure, Content,
-    Context, Func, IntoValue, NativeElement, Scope, Scopes, Value,
+    Context, Func, IntoValue, NativeElement, Scope, Scopes, Style, Value,
 };
 use crate::introspection::Introspector;
 use crate::math::LrElem;
+use crate::model::ApplyElem;
 use crate::syntax::ast::{self, AstNode};
 use crate::syntax::{Span, Spanned, SyntaxNode};
 use crate::text::TextElem;
@@ -125,7 +126,12 @@ impl Eval for ast::FuncCall<'_> {
                 bail!(error);
             }
         } else {
-            (callee.eval(vm)?, args.eval(vm)?.spanned(span))
+            let callee = callee.eval(vm)?;
+            let rules = matches!(
+                &callee,
+                Value::Func(func) if func.element() == Some(ApplyElem::elem())
+            );
+            (callee, eval_args(vm, args, rules)?.spanned(span))
         };
 
         let func_result = callee.clone().cast::<Func>();
@@ -182,51 +188,80 @@ impl Eval for ast::Args<'_> {
     type Output = Args;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        let mut items = EcoVec::with_capacity(self.items().count());
+        eval_args(vm, self, false)
+    }
+}
 
-        for arg in self.items() {
-            let span = arg.span();
-            match arg {
-                ast::Arg::Pos(expr) => {
-                    items.push(Arg {
+/// Evaluates the arguments of a call.
+///
+/// If `rules` is true, positional set and show rules evaluate to styles
+/// instead of applying to the rest of the block, e.g. in
+/// `{apply(<intro>, set text(red))}`. This is only enabled for calls to
+/// [`ApplyElem`].
+fn eval_args(vm: &mut Vm, args: ast::Args, rules: bool) -> SourceResult<Args> {
+    let mut items = EcoVec::with_capacity(args.items().count());
+
+    for arg in args.items() {
+        let span = arg.span();
+        match arg {
+            ast::Arg::Pos(expr) => {
+                let value = if rules { eval_rule_arg(vm, expr)? } else { expr.eval(vm)? };
+                items.push(Arg {
+                    span,
+                    name: None,
+                    value: Spanned::new(value, expr.span()),
+                });
+            }
+            ast::Arg::Named(named) => {
+                let expr = named.expr();
+                items.push(Arg {
+                    span,
+                    name: Some(named.name().get().clone().into()),
+                    value: Spanned::new(expr.eval(vm)?, expr.span()),
+                });
+            }
+            ast::Arg::Spread(spread) => match spread.expr().eval(vm)? {
+                Value::None => {}
+                Value::Array(array) => {
+                    items.extend(array.into_iter().map(|value| Arg {
                         span,
                         name: None,
-                        value: Spanned::new(expr.eval(vm)?, expr.span()),
-                    });
+                        value: Spanned::new(value, span),
+                    }));
                 }
-                ast::Arg::Named(named) => {
-                    let expr = named.expr();
-                    items.push(Arg {
+                Value::Dict(dict) => {
+                    items.extend(dict.into_iter().map(|(key, value)| Arg {
                         span,
-                        name: Some(named.name().get().clone().into()),
-                        value: Spanned::new(expr.eval(vm)?, expr.span()),
-                    });
+                        name: Some(key),
+                        value: Spanned::new(value, span),
+                    }));
                 }
-                ast::Arg::Spread(spread) => match spread.expr().eval(vm)? {
-                    Value::None => {}
-                    Value::Array(array) => {
-                        items.extend(array.into_iter().map(|value| Arg {
-                            span,
-                            name: None,
-                            value: Spanned::new(value, span),
-                        }));
-                    }
-                    Value::Dict(dict) => {
-                        items.extend(dict.into_iter().map(|(key, value)| Arg {
-                            span,
-                            name: Some(key),
-                            value: Spanned::new(value, span),
-                        }));
-                    }
-                    Value::Args(args) => items.extend(args.items),
-                    v => bail!(spread.span(), "cannot spread {}", v.ty()),
-                },
-            }
+                Value::Args(args) => items.extend(args.items),
+                v => bail!(spread.span(), "cannot spread {}", v.ty()),
+            },
         }
+    }
+
+    // We do *not* use the `args.span()` here because we want the callsite
+    // span to be one level higher (the whole function call).
+    Ok(Args { span: Span::detached(), items })
+}
 
-        // We do *not* use the `self.span()` here because we want the callsite
-        // span to be one level higher (the whole function call).
-        Ok(Args { span: Span::detached(), items })
+/// Evaluates a positional argument, which may also be a set or show rule.
+fn eval_rule_arg(vm: &mut Vm, expr: ast::Expr) -> SourceResult<Value> {
+    match expr {
+        ast::Expr::Set(set) => set.eval(vm).map(Value::Styles),
+        ast::Expr::Show(show) => {
+            let recipe = show.eval(vm)?;
+            if recipe.selector.is_none() {
+                bail!(
+                    show.span(),
+                    "show rules without a selector cannot be passed as arguments"
+                );
+            }
+            Ok(Value::Styles(Style::Recipe(recipe).into()))
+        }
+        _ => expr.eval(vm),
     }
 }
 
@@ -274,6 +309,7 @@ pub(crate) fn call_closure(
     world: Tracked<dyn World + '_>,
     introspector: Tracked<Introspector>,
     traced: Tracked<Traced>,
+    budget: Tracked<Budget>,
     sink: TrackedMut<Sink>,
     route: Tracked<Route>,
     context: Tracked<Context>,
@@ -294,6 +330,7 @@ pub(crate) fn call_closure(
         world,
         introspector,
         traced,
+        budget,
         sink,
         route: Route::extend(route),
     };
@@ -1,11 +1,13 @@
 use comemo::TrackedMut;
 use ecow::{eco_format, eco_vec, EcoString};
 
-use crate::diag::{bail, error, warning, At, FileError, SourceResult, Trace, Tracepoint};
+use crate::diag::{
+    bail, error, warning, At, FileError, SourceResult, StrResult, Trace, Tracepoint,
+};
 use crate::eval::{eval, Eval, Vm};
-use crate::foundations::{Content, Module, Value};
+use crate::foundations::{Content, Module, Scope, Value};
 use crate::syntax::ast::{self, AstNode};
-use crate::syntax::package::{PackageManifest, PackageSpec};
+use crate::syntax::package::{BoundedPackageSpec, PackageManifest, PackageSpec};
 use crate::syntax::{FileId, Span, VirtualPath};
 use crate::World;
 
@@ -161,7 +163,18 @@ pub fn import(
     // Handle package and file imports.
     let path = path.as_str();
     if path.starts_with('@') {
-        let spec = path.parse::<PackageSpec>().at(span)?;
+        let spec = match path.parse::<PackageSpec>() {
+            Ok(spec) => spec,
+            // Fall back to a version bound that the world resolves to an
+            // exact version.
+            Err(err) => match path.parse::<BoundedPackageSpec>() {
+                Ok(bounded) => {
+                    let version = vm.world().resolve_package(&bounded).at(span)?;
+                    bounded.at(version)
+                }
+                Err(_) => return Err(err).at(span),
+            },
+        };
         import_package(vm, spec, span)
     } else {
         import_file(vm, path, span)
@@ -183,7 +196,7 @@ fn import_package(vm: &mut Vm, spec: PackageSpec, span: Span) -> SourceResult<Mo
     let entrypoint_id = manifest_id.join(&manifest.package.entrypoint);
     let source = vm.world().source(entrypoint_id).at(span)?;
     let point = || Tracepoint::Import;
-    Ok(eval(
+    let module = eval(
         vm.world(),
         vm.engine.traced,
         TrackedMut::reborrow_mut(&mut vm.engine.sink),
@@ -191,7 +204,42 @@ fn import_package(vm: &mut Vm, spec: PackageSpec, span: Span) -> SourceResult<Mo
         &source,
     )
     .trace(vm.world(), point, span)?
-    .with_name(manifest.package.name))
+    .with_name(manifest.package.name);
+
+    // Restrict the module to the package's public API.
+    match &manifest.package.exports {
+        Some(exports) => {
+            let scope = exported_scope(module.scope(), exports).at(span)?;
+            Ok(module.with_scope(scope))
+        }
+        None => Ok(module),
+    }
+}
+
+/// Builds the scope of a package's module from the names listed in its
+/// manifest's `exports`.
+fn exported_scope(scope: &Scope, exports: &[EcoString]) -> StrResult<Scope> {
+    let mut exported = Scope::new();
+    for export in exports {
+        let mut path = export.split('.').peekable();
+        let mut current = scope;
+        while let Some(name) = path.next() {
+            let Some(value) = current.get(name) else {
+                bail!("package manifest exports unknown binding `{export}`");
+            };
+
+            if path.peek().is_none() {
+                exported.define(name, value.clone());
+            } else if let Some(inner) = value.scope() {
+                current = inner;
+            } else {
+                bail!(
+                    "package manifest exports `{export}`, but `{name}` is not a module"
+                );
+            }
+        }
+    }
+    Ok(exported)
 }
 
 /// Import a file from a path.
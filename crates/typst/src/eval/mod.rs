@@ -24,7 +24,7 @@ pub(crate) use self::flow::*;
 use comemo::{Track, Tracked, TrackedMut};
 
 use crate::diag::{bail, SourceResult};
-use crate::engine::{Engine, Route, Sink, Traced};
+use crate::engine::{Budget, Engine, Route, Sink, Traced};
 use crate::foundations::{Cast, Context, Module, NativeElement, Scope, Scopes, Value};
 use crate::introspection::Introspector;
 use crate::math::EquationElem;
@@ -49,10 +49,12 @@ pub fn eval(
 
     // Prepare the engine.
     let introspector = Introspector::default();
+    let budget = Budget::default();
     let engine = Engine {
         world,
         introspector: introspector.track(),
         traced,
+        budget: budget.track(),
         sink,
         route: Route::extend(route).with_id(id),
     };
@@ -118,10 +120,12 @@ pub fn eval_string(
     let mut sink = Sink::new();
     let introspector = Introspector::default();
     let traced = Traced::default();
+    let budget = Budget::default();
     let engine = Engine {
         world,
         introspector: introspector.track(),
         traced: traced.track(),
+        budget: budget.track(),
         sink: sink.track_mut(),
         route: Route::default(),
     };
@@ -1,6 +1,8 @@
 use crate::diag::{At, SourceResult};
 use crate::eval::{Eval, Vm};
-use crate::foundations::{Func, Recipe, ShowableSelector, Styles, Transformation};
+use crate::foundations::{
+    ContextualSet, Func, Recipe, ShowableSelector, Styles, Transformation,
+};
 use crate::syntax::ast::{self, AstNode};
 
 impl Eval for ast::SetRule<'_> {
@@ -23,8 +25,13 @@ impl Eval for ast::SetRule<'_> {
                 })
             })
             .at(target.span())?;
-        let args = self.args().eval(vm)?.spanned(self.span());
-        Ok(target.set(&mut vm.engine, args)?.spanned(self.span()))
+        let mut args = self.args().eval(vm)?.spanned(self.span());
+        let contextual = ContextualSet::extract(target, &mut args);
+        let mut styles = target.set(&mut vm.engine, args)?.spanned(self.span());
+        if let Some(contextual) = contextual {
+            styles.set(contextual);
+        }
+        Ok(styles)
     }
 }
 
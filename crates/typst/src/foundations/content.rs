@@ -14,7 +14,7 @@ use smallvec::smallvec;
 use crate::diag::{SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    elem, func, scope, ty, Context, Dict, Element, Fields, IntoValue, Label,
+    elem, func, scope, ty, Array, Context, Dict, Element, Fields, IntoValue, Label,
     NativeElement, Recipe, RecipeIndex, Repr, Selector, Str, Style, StyleChain, Styles,
     Value,
 };
@@ -185,6 +185,12 @@ impl Content {
         self.make_mut().lifecycle.insert(0);
     }
 
+    /// Whether this content and the other one are the same instance, i.e.
+    /// clones of each other that weren't modified since.
+    pub fn is_same(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
     /// How this element interacts with other elements in a stream.
     pub fn behaviour(&self) -> Behaviour {
         self.with::<dyn Behave>()
@@ -593,6 +599,86 @@ impl Content {
         dict
     }
 
+    /// The content nested directly within this content.
+    ///
+    /// These are the values of all fields that hold content or arrays of
+    /// content, in the order of the fields. Together with
+    /// [`func`]($content.func) and [`fields`]($content.fields), this lets you
+    /// walk arbitrary content, for instance, to transform what users pass to
+    /// your function.
+    ///
+    /// ```example
+    /// #let body = [Intro *bold* text]
+    /// #body.children().map(c => c.func())
+    /// ```
+    #[func]
+    pub fn children(&self) -> Array {
+        self.inner
+            .elem
+            .fields()
+            .into_iter()
+            .flat_map(|(_, value)| match value {
+                Value::Content(_) => vec![value],
+                Value::Array(array) => array
+                    .into_iter()
+                    .filter(|value| matches!(value, Value::Content(_)))
+                    .collect(),
+                _ => vec![],
+            })
+            .collect()
+    }
+
+    /// Searches this content and everything nested within it for the first
+    /// element matching the selector. Returns `{none}` if there is none.
+    ///
+    /// The search goes through the content as written, so elements produced
+    /// by show rules are not found. Selectors that depend on the position in
+    /// the document, like [`before`]($selector.before), never match. Neither
+    /// do fields that are only resolved during layout, like a heading's
+    /// `level`, so match on the fields as written, like its `depth`, instead.
+    ///
+    /// ```example
+    /// #let title(body) = body.find(heading)
+    ///
+    /// #let doc = [
+    ///   Preface.
+    ///   = Introduction
+    ///   = Conclusion
+    /// ]
+    ///
+    /// First heading: #title(doc).body
+    /// ```
+    #[func]
+    pub fn find(
+        &self,
+        /// The selector to search for.
+        selector: Selector,
+    ) -> Option<Content> {
+        self.query_first(selector)
+    }
+
+    /// Searches this content and everything nested within it for all elements
+    /// matching the selector, in the order in which they appear.
+    ///
+    /// The same restrictions as for [`find`]($content.find) apply.
+    ///
+    /// ```example
+    /// #let doc = [
+    ///   Some *bold* and
+    ///   *strong* words.
+    /// ]
+    ///
+    /// #doc.find-all(strong).len() strong elements
+    /// ```
+    #[func]
+    pub fn find_all(
+        &self,
+        /// The selector to search for.
+        selector: Selector,
+    ) -> Array {
+        self.query(selector).into_iter().map(Value::Content).collect()
+    }
+
     /// The location of the content. This is only available on content returned
     /// by [query] or provided by a [show rule]($reference/styling/#show-rules),
     /// for other content it will be `{none}`. The resulting location can be
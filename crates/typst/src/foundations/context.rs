@@ -1,11 +1,14 @@
-use comemo::Track;
+use comemo::{Track, Tracked};
+use ecow::EcoVec;
 
 use crate::diag::{bail, Hint, HintedStrResult, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    elem, Args, Construct, Content, Func, Packed, Show, StyleChain, Value,
+    elem, Arg, Args, CastInfo, Construct, Content, Element, Func, Packed, Show, Str,
+    StyleChain, Styles, Type, Value,
 };
 use crate::introspection::{Locatable, Location};
+use crate::syntax::{Span, Spanned};
 
 /// Data that is contextually made available to code.
 ///
@@ -83,3 +86,90 @@ impl Show for Packed<ContextElem> {
         Ok(self.func.call::<[Value; 0]>(engine, context.track(), [])?.display())
     }
 }
+
+/// The properties of a set rule that are given as `context` expressions.
+///
+/// A set rule like `{set text(fill: context ..)}` stores these properties in
+/// the resulting styles. When a style chain is extended with them, the
+/// expressions are evaluated with the styles in effect at that point and the
+/// results take effect like ordinary properties, so they are also inherited by
+/// nested content.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct ContextualSet {
+    /// The element whose properties are set.
+    target: Element,
+    /// The names of the properties along with the functions of the `context`
+    /// expressions computing their values.
+    values: EcoVec<(Str, Spanned<Func>)>,
+    /// The span of the set rule.
+    span: Span,
+}
+
+impl ContextualSet {
+    /// Removes the named arguments of a set rule for the target that are
+    /// `context` expressions, if the target's property doesn't accept content
+    /// anyway.
+    pub(crate) fn extract(target: Element, args: &mut Args) -> Option<Self> {
+        let mut values = EcoVec::new();
+        args.items.retain(|arg| {
+            let Some(name) = &arg.name else { return true };
+            let Value::Content(content) = &arg.value.v else { return true };
+            let Some(elem) = content.to_packed::<ContextElem>() else { return true };
+            if accepts_content(target, name) {
+                return true;
+            }
+            values.push((name.clone(), Spanned::new(elem.func.clone(), arg.value.span)));
+            false
+        });
+
+        if values.is_empty() {
+            return None;
+        }
+
+        Some(Self { target, values, span: args.span })
+    }
+
+    /// The element whose properties are set.
+    pub fn target(&self) -> Element {
+        self.target
+    }
+
+    /// The span of the set rule.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Evaluates the `context` expressions in the given context and returns
+    /// the resulting properties.
+    pub fn resolve(
+        &self,
+        engine: &mut Engine,
+        context: Tracked<Context>,
+    ) -> SourceResult<Styles> {
+        let mut args = Args::new::<Value>(self.span, []);
+        for (name, func) in &self.values {
+            let value = func.v.call::<[Value; 0]>(engine, context, [])?;
+            args.items.push(Arg {
+                span: func.span,
+                name: Some(name.clone()),
+                value: Spanned::new(value, func.span),
+            });
+        }
+        Ok(self.target.set(engine, args)?.spanned(self.span))
+    }
+}
+
+/// Whether the property of the element with the given name accepts content.
+fn accepts_content(elem: Element, name: &str) -> bool {
+    let Some(param) = elem.params().iter().find(|param| param.name == name) else {
+        return false;
+    };
+
+    let mut accepts = false;
+    param.input.walk(|info| match info {
+        CastInfo::Any => accepts = true,
+        CastInfo::Type(ty) => accepts |= *ty == Type::of::<Content>(),
+        _ => {}
+    });
+    accepts
+}
@@ -1,11 +1,12 @@
 use std::fmt::{self, Debug, Formatter};
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 
 use comemo::{Tracked, TrackedMut};
 use ecow::{eco_format, EcoString};
 use once_cell::sync::Lazy;
 
-use crate::diag::{bail, SourceResult, StrResult};
+use crate::diag::{bail, SourceDiagnostic, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
     cast, repr, scope, ty, Args, CastInfo, Content, Context, Element, IntoArgs, Scope,
@@ -150,6 +151,17 @@ enum Repr {
     Closure(Arc<LazyHash<Closure>>),
     /// A nested function with pre-applied arguments.
     With(Arc<(Func, Args)>),
+    /// A function that warns about its deprecation when called.
+    Deprecated(Arc<(Func, Deprecation)>),
+}
+
+/// Details about the deprecation of a function.
+#[derive(Clone, PartialEq, Hash)]
+struct Deprecation {
+    /// What to use instead.
+    message: EcoString,
+    /// The version since which the function is deprecated.
+    since: Option<EcoString>,
 }
 
 impl Func {
@@ -162,6 +174,7 @@ impl Func {
             Repr::Element(elem) => Some(elem.name()),
             Repr::Closure(closure) => closure.name(),
             Repr::With(with) => with.0.name(),
+            Repr::Deprecated(deprecated) => deprecated.0.name(),
         }
     }
 
@@ -174,6 +187,7 @@ impl Func {
             Repr::Element(elem) => Some(elem.title()),
             Repr::Closure(_) => None,
             Repr::With(with) => with.0.title(),
+            Repr::Deprecated(deprecated) => deprecated.0.title(),
         }
     }
 
@@ -184,6 +198,7 @@ impl Func {
             Repr::Element(elem) => Some(elem.docs()),
             Repr::Closure(_) => None,
             Repr::With(with) => with.0.docs(),
+            Repr::Deprecated(deprecated) => deprecated.0.docs(),
         }
     }
 
@@ -202,6 +217,7 @@ impl Func {
             Repr::Element(elem) => Some(elem.params()),
             Repr::Closure(_) => None,
             Repr::With(with) => with.0.params(),
+            Repr::Deprecated(deprecated) => deprecated.0.params(),
         }
     }
 
@@ -219,6 +235,7 @@ impl Func {
             Repr::Element(_) => Some(&CONTENT),
             Repr::Closure(_) => None,
             Repr::With(with) => with.0.returns(),
+            Repr::Deprecated(deprecated) => deprecated.0.returns(),
         }
     }
 
@@ -229,6 +246,7 @@ impl Func {
             Repr::Element(elem) => elem.keywords(),
             Repr::Closure(_) => &[],
             Repr::With(with) => with.0.keywords(),
+            Repr::Deprecated(deprecated) => deprecated.0.keywords(),
         }
     }
 
@@ -239,6 +257,7 @@ impl Func {
             Repr::Element(elem) => Some(elem.scope()),
             Repr::Closure(_) => None,
             Repr::With(with) => with.0.scope(),
+            Repr::Deprecated(deprecated) => deprecated.0.scope(),
         }
     }
 
@@ -298,6 +317,7 @@ impl Func {
                 engine.world,
                 engine.introspector,
                 engine.traced,
+                engine.budget,
                 TrackedMut::reborrow_mut(&mut engine.sink),
                 engine.route.track(),
                 context,
@@ -307,6 +327,20 @@ impl Func {
                 args.items = with.1.items.iter().cloned().chain(args.items).collect();
                 with.0.call(engine, context, args)
             }
+            Repr::Deprecated(deprecated) => {
+                let (func, deprecation) = &**deprecated;
+                let message = match &deprecation.since {
+                    Some(since) => eco_format!("function is deprecated since {since}"),
+                    None => "function is deprecated".into(),
+                };
+                // Warn only once per deprecated function, at its first use.
+                engine.sink.warn_keyed(
+                    crate::utils::hash128(deprecated),
+                    SourceDiagnostic::warning(args.span, message)
+                        .with_hint(deprecation.message.clone()),
+                );
+                func.call(engine, context, args)
+            }
         }
     }
 
@@ -345,6 +379,39 @@ impl Func {
         }
     }
 
+    /// Returns a new function that warns about its deprecation whenever it is
+    /// called.
+    ///
+    /// This lets package and template authors rename or replace a function
+    /// without breaking the documents that still use the old one. The warning
+    /// points to the call and is only reported once per call site, even if
+    /// the call is evaluated many times.
+    ///
+    /// ```example
+    /// #let emphasize(body) = text(red, body)
+    /// #let highlight = emphasize.deprecated(
+    ///   "use `emphasize` instead",
+    ///   since: "0.3.0",
+    /// )
+    ///
+    /// #highlight[Still works]
+    /// ```
+    #[func]
+    pub fn deprecated(
+        self,
+        /// A message telling users what to use instead.
+        message: EcoString,
+        /// The version since which the function is deprecated.
+        #[named]
+        since: Option<EcoString>,
+    ) -> Func {
+        let span = self.span;
+        Self {
+            repr: Repr::Deprecated(Arc::new((self, Deprecation { message, since }))),
+            span,
+        }
+    }
+
     /// Returns a selector that filters for elements belonging to this function
     /// whose fields have the values of the given arguments.
     ///
@@ -387,6 +454,72 @@ impl Func {
 
         Ok(element.where_(fields))
     }
+
+    /// Returns a selector that filters for elements belonging to this function
+    /// that are nested in an element matching `ancestor`. See
+    /// [`selector.within`]($selector.within) for details.
+    ///
+    /// ```example
+    /// #show strong.within(emph): set text(red)
+    /// *Strong* _and *emphasized*_
+    /// ```
+    #[func]
+    pub fn within(
+        self,
+        /// The element that matching elements must be nested in.
+        ancestor: Selector,
+    ) -> StrResult<Selector> {
+        let element = self
+            .element()
+            .ok_or("`within()` can only be called on element functions")?;
+        Ok(element.select().within(ancestor))
+    }
+
+    /// Returns a selector that filters for the first element belonging to
+    /// this function in each element matching `ancestor`. See
+    /// [`selector.first-in`]($selector.first-in) for details.
+    ///
+    /// ```example
+    /// #show par.first-in(block): set text(red)
+    /// #block[
+    ///   First paragraph.
+    ///
+    ///   Second paragraph.
+    /// ]
+    /// ```
+    #[func]
+    pub fn first_in(
+        self,
+        /// The element in which the first match is selected.
+        ancestor: Selector,
+    ) -> StrResult<Selector> {
+        let element = self
+            .element()
+            .ok_or("`first-in()` can only be called on element functions")?;
+        Ok(element.select().first_in(ancestor))
+    }
+
+    /// Returns a selector that filters for elements belonging to this function
+    /// that are nested at the given depth into each other. See
+    /// [`selector.at-depth`]($selector.at-depth) for details.
+    ///
+    /// ```example
+    /// #show list.item.at-depth(2): set text(gray)
+    /// - Fruits
+    ///   - Apples
+    /// - Vegetables
+    /// ```
+    #[func]
+    pub fn at_depth(
+        self,
+        /// The nesting depth, starting at 1.
+        depth: NonZeroUsize,
+    ) -> StrResult<Selector> {
+        let element = self
+            .element()
+            .ok_or("`at-depth()` can only be called on element functions")?;
+        Ok(element.select().at_depth(depth))
+    }
 }
 
 impl Debug for Func {
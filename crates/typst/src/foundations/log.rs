@@ -0,0 +1,104 @@
+//! Logging messages to the user of a document.
+
+use ecow::{eco_vec, EcoString};
+
+use crate::diag::{Severity, SourceDiagnostic};
+use crate::engine::Engine;
+use crate::foundations::{func, Module, NoneValue, Repr, Scope, Value};
+use crate::syntax::Span;
+
+/// A module with logging functions.
+pub fn module() -> Module {
+    let mut scope = Scope::new();
+    scope.define_func::<info>();
+    scope.define_func::<warn>();
+    Module::new("log", scope)
+}
+
+/// Logs an informational message.
+///
+/// The message is reported to the user along with the location of the call,
+/// but separately from the compiler's own warnings. Strings are displayed as
+/// they are and other values are converted with `repr`.
+///
+/// # Example
+/// ```typ
+/// #log.info("Using the", "compact", "layout")
+/// ```
+#[func]
+pub fn info(
+    /// The engine.
+    engine: &mut Engine,
+    /// The callsite span.
+    span: Span,
+    /// The values to log, separated by spaces.
+    #[variadic]
+    values: Vec<Value>,
+    /// A hint that tells the user what to do about the message.
+    #[named]
+    hint: Option<EcoString>,
+) -> NoneValue {
+    log(engine, Severity::Info, span, values, hint);
+    NoneValue
+}
+
+/// Logs a warning.
+///
+/// Works like [`log.info`]($log.info), but marks the message as a warning.
+/// This is useful to inform the user about problems in how they use a
+/// template, which don't prevent the document from compiling.
+///
+/// # Example
+/// ```typ
+/// #let title = none
+/// #if title == none {
+///   log.warn(
+///     "no title given",
+///     hint: "pass a title to the template",
+///   )
+/// }
+/// ```
+#[func]
+pub fn warn(
+    /// The engine.
+    engine: &mut Engine,
+    /// The callsite span.
+    span: Span,
+    /// The values to log, separated by spaces.
+    #[variadic]
+    values: Vec<Value>,
+    /// A hint that tells the user what to do about the message.
+    #[named]
+    hint: Option<EcoString>,
+) -> NoneValue {
+    log(engine, Severity::Warning, span, values, hint);
+    NoneValue
+}
+
+/// Records a logged message in the engine's sink.
+fn log(
+    engine: &mut Engine,
+    severity: Severity,
+    span: Span,
+    values: Vec<Value>,
+    hint: Option<EcoString>,
+) {
+    let mut message = EcoString::new();
+    for (i, value) in values.into_iter().enumerate() {
+        if i > 0 {
+            message.push(' ');
+        }
+        match value {
+            Value::Str(string) => message.push_str(&string),
+            value => message.push_str(&value.repr()),
+        }
+    }
+
+    engine.sink.log(SourceDiagnostic {
+        severity,
+        span,
+        message,
+        trace: eco_vec![],
+        hints: hint.into_iter().collect(),
+    });
+}
@@ -1,6 +1,7 @@
 //! Foundational types and functions.
 
 pub mod calc;
+pub mod log;
 pub mod repr;
 pub mod sys;
 
@@ -29,6 +30,7 @@ mod scope;
 mod selector;
 mod str;
 mod styles;
+mod test;
 mod ty;
 mod value;
 mod version;
@@ -58,6 +60,7 @@ pub use self::scope::*;
 pub use self::selector::*;
 pub use self::str::*;
 pub use self::styles::*;
+pub use self::test::*;
 pub use self::ty::*;
 pub use self::value::*;
 pub use self::version::*;
@@ -72,7 +75,7 @@ pub use {
 
 use ecow::EcoString;
 
-use crate::diag::{bail, SourceResult, StrResult};
+use crate::diag::{bail, HintedStrResult, HintedString, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::eval::EvalMode;
 use crate::syntax::Spanned;
@@ -85,7 +88,7 @@ use crate::syntax::Spanned;
 pub static FOUNDATIONS: Category;
 
 /// Hook up all `foundations` definitions.
-pub(super) fn define(global: &mut Scope, inputs: Dict) {
+pub(super) fn define(global: &mut Scope, inputs: Dict, capabilities: &sys::Capabilities) {
     global.category(FOUNDATIONS);
     global.define_type::<bool>();
     global.define_type::<i64>();
@@ -102,6 +105,7 @@ pub(super) fn define(global: &mut Scope, inputs: Dict) {
     global.define_type::<Module>();
     global.define_type::<Regex>();
     global.define_type::<Selector>();
+    global.define_type::<Styles>();
     global.define_type::<Datetime>();
     global.define_type::<Duration>();
     global.define_type::<Version>();
@@ -111,8 +115,10 @@ pub(super) fn define(global: &mut Scope, inputs: Dict) {
     global.define_func::<assert>();
     global.define_func::<eval>();
     global.define_func::<style>();
+    global.define_elem::<TestCaseElem>();
     global.define_module(calc::module());
-    global.define_module(sys::module(inputs));
+    global.define_module(log::module());
+    global.define_module(sys::module(inputs, capabilities));
 }
 
 /// Fails with an error.
@@ -181,8 +187,15 @@ impl assert {
     /// Fails with an error if the first value is not equal to the second. Does not
     /// produce any output in the document.
     ///
+    /// When [arrays]($array) or [dictionaries]($dictionary) differ, the error
+    /// points out the first differing element, for instance
+    /// `{.at(2).at("price")}`, so that the problem is easy to find even in large
+    /// data structures.
+    ///
     /// ```typ
     /// #assert.eq(10, 10)
+    /// #assert.eq((a: 1, b: (2, 3)), (a: 1, b: (2, 3)))
+    /// #assert.eq(0.1 + 0.2, 0.3, tolerance: 1e-9)
     /// ```
     #[func(title = "Assert Equal")]
     pub fn eq(
@@ -194,19 +207,34 @@ impl assert {
         /// of the compared values.
         #[named]
         message: Option<EcoString>,
-    ) -> StrResult<NoneValue> {
-        if left != right {
-            if let Some(message) = message {
-                bail!("equality assertion failed: {message}");
-            } else {
-                bail!(
-                    "equality assertion failed: value {} was not equal to {}",
-                    left.repr(),
-                    right.repr()
-                );
-            }
+        /// How much numbers may differ while still being considered equal.
+        ///
+        /// This also applies to numbers within arrays and dictionaries. Useful
+        /// to compare the results of floating point computations, which are
+        /// often subject to rounding errors.
+        #[named]
+        tolerance: Option<f64>,
+    ) -> HintedStrResult<NoneValue> {
+        let Some((path, detail)) = difference(&left, &right, tolerance) else {
+            return Ok(NoneValue);
+        };
+
+        if let Some(message) = message {
+            bail!("equality assertion failed: {message}");
         }
-        Ok(NoneValue)
+
+        let mut error = HintedString::new(eco_format!(
+            "equality assertion failed: value {} was not equal to {}",
+            left.repr(),
+            right.repr()
+        ));
+        if !path.is_empty() {
+            error.hint(eco_format!("the values differ at `{path}`: {detail}"));
+        } else if matches!(left, Value::Array(_) | Value::Dict(_)) {
+            error.hint(eco_format!("the values differ: {detail}"));
+        }
+
+        Err(error)
     }
 
     /// Ensures that two values are not equal.
@@ -294,3 +322,66 @@ pub fn eval(
     }
     crate::eval::eval_string(engine.world, &text, span, mode, scope)
 }
+
+/// Finds the first difference between two values.
+///
+/// Returns the path to the differing element (like `.at(1)`) and a
+/// description of the difference. Numbers that are at most `tolerance` apart
+/// are considered equal.
+fn difference(
+    left: &Value,
+    right: &Value,
+    tolerance: Option<f64>,
+) -> Option<(EcoString, EcoString)> {
+    match (left, right) {
+        (Value::Array(a), Value::Array(b)) => {
+            for (i, (x, y)) in a.iter().zip(b.iter()).enumerate() {
+                if let Some((path, detail)) = difference(x, y, tolerance) {
+                    return Some((eco_format!(".at({i}){path}"), detail));
+                }
+            }
+            (a.len() != b.len()).then(|| {
+                let detail =
+                    eco_format!("lengths {} and {} are not equal", a.len(), b.len());
+                (EcoString::new(), detail)
+            })
+        }
+        (Value::Dict(a), Value::Dict(b)) => {
+            for (key, x) in a.iter() {
+                let Ok(y) = b.get(key) else {
+                    let detail =
+                        eco_format!("key {} is missing in the second value", key.repr());
+                    return Some((EcoString::new(), detail));
+                };
+                if let Some((path, detail)) = difference(x, y, tolerance) {
+                    return Some((eco_format!(".at({}){path}", key.repr()), detail));
+                }
+            }
+            b.iter().map(|(key, _)| key).find(|key| !a.contains(key)).map(|key| {
+                let detail =
+                    eco_format!("key {} is missing in the first value", key.repr());
+                (EcoString::new(), detail)
+            })
+        }
+        _ => {
+            let equal = match (tolerance, number(left), number(right)) {
+                (Some(tolerance), Some(a), Some(b)) => (a - b).abs() <= tolerance,
+                _ => left == right,
+            };
+            (!equal).then(|| {
+                let detail =
+                    eco_format!("{} was not equal to {}", left.repr(), right.repr());
+                (EcoString::new(), detail)
+            })
+        }
+    }
+}
+
+/// The value as a float if it is a number.
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Int(v) => Some(*v as f64),
+        Value::Float(v) => Some(*v),
+        _ => None,
+    }
+}
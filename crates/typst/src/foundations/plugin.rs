@@ -158,6 +158,9 @@ impl Plugin {
         path: Spanned<EcoString>,
     ) -> SourceResult<Plugin> {
         let Spanned { v: path, span } = path;
+        if !engine.world.library().capabilities.plugins {
+            bail!(span, "plugins are disabled in this environment");
+        }
         let id = span.resolve_path(&path).at(span)?;
         let data = engine.world.file(id).at(span)?;
         Plugin::new(data).at(span)
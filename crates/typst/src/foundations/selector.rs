@@ -1,4 +1,5 @@
 use std::any::{Any, TypeId};
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 
 use comemo::Tracked;
@@ -7,8 +8,8 @@ use smallvec::SmallVec;
 
 use crate::diag::{bail, HintedStrResult, StrResult};
 use crate::foundations::{
-    cast, func, repr, scope, ty, CastInfo, Content, Context, Dict, Element, FromValue,
-    Func, Label, Reflect, Regex, Repr, Str, StyleChain, Type, Value,
+    cast, elem, func, repr, scope, ty, CastInfo, Content, Context, Dict, Element,
+    FromValue, Func, Label, Reflect, Regex, Repr, Str, StyleChain, Type, Value,
 };
 use crate::introspection::{Introspector, Locatable, Location};
 use crate::symbols::Symbol;
@@ -60,6 +61,11 @@ pub use crate::__select_where as select_where;
 /// Multiple selectors can be combined using the methods shown below. However,
 /// not all kinds of selectors are supported in all places, at the moment.
 ///
+/// With the [`within`]($selector.within), [`first-in`]($selector.first-in),
+/// and [`at-depth`]($selector.at-depth) methods, show rules can also depend on
+/// how an element is nested into other elements. These structural selectors
+/// are only supported in show rules.
+///
 /// # Example
 /// ```example
 /// #context query(
@@ -95,6 +101,14 @@ pub enum Selector {
     Before { selector: Arc<Self>, end: Arc<Self>, inclusive: bool },
     /// Matches all matches of `selector` after `start`.
     After { selector: Arc<Self>, start: Arc<Self>, inclusive: bool },
+    /// Matches all matches of `selector` that are nested in a match of
+    /// `ancestor`.
+    Within { selector: Arc<Self>, ancestor: Arc<Self> },
+    /// Matches the first match of `selector` in each match of `ancestor`.
+    FirstIn { selector: Arc<Self>, ancestor: Arc<Self> },
+    /// Matches all matches of `selector` that are nested in exactly
+    /// `depth - 1` other matches of `selector`.
+    AtDepth { selector: Arc<Self>, depth: NonZeroUsize },
 }
 
 impl Selector {
@@ -143,10 +157,85 @@ impl Selector {
                 selectors.iter().all(move |sel| sel.matches(target, styles))
             }
             Self::Location(location) => target.location() == Some(*location),
+            // Whether this is the first match within the ancestor is checked
+            // during realization.
+            Self::Within { selector, ancestor }
+            | Self::FirstIn { selector, ancestor } => {
+                selector.matches(target, styles)
+                    && styles.is_some_and(|styles| {
+                        ancestor.nearest_ancestor(target, styles).is_some()
+                    })
+            }
+            Self::AtDepth { selector, depth } => {
+                selector.matches(target, styles)
+                    && styles.is_some_and(|styles| {
+                        let nested = ancestors(target, styles)
+                            .iter()
+                            .filter(|elem| selector.matches(elem, Some(styles)))
+                            .count();
+                        nested + 1 == depth.get()
+                    })
+            }
             // Not supported here.
             Self::Before { .. } | Self::After { .. } => false,
         }
     }
+
+    /// Finds the innermost ancestor of the target that matches this selector.
+    ///
+    /// Ancestors are only known during realization, and only if they are
+    /// [tracked](Self::tracks) by a structural selector.
+    pub fn nearest_ancestor(
+        &self,
+        target: &Content,
+        styles: StyleChain,
+    ) -> Option<Content> {
+        ancestors(target, styles)
+            .into_iter()
+            .find(|elem| self.matches(elem, Some(styles)))
+    }
+
+    /// Whether realization needs to record the target as an ancestor of its
+    /// output for this selector to match correctly.
+    pub fn tracks(&self, target: &Content, styles: StyleChain) -> bool {
+        match self {
+            Self::Within { selector, ancestor }
+            | Self::FirstIn { selector, ancestor } => {
+                ancestor.matches(target, Some(styles))
+                    || ancestor.tracks(target, styles)
+                    || selector.tracks(target, styles)
+            }
+            Self::AtDepth { selector, .. } => {
+                selector.matches(target, Some(styles)) || selector.tracks(target, styles)
+            }
+            Self::Or(selectors) | Self::And(selectors) => {
+                selectors.iter().any(|sel| sel.tracks(target, styles))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The tracked ancestors of the target, from the innermost to the outermost
+/// one.
+fn ancestors(target: &Content, styles: StyleChain) -> Vec<Content> {
+    let mut ancestors = AncestryElem::ancestors_in(styles);
+    ancestors.retain(|elem| elem != target);
+    ancestors.reverse();
+    ancestors
+}
+
+/// Records the elements that content is nested in during realization.
+///
+/// Only elements that a structural selector in a show rule depends on are
+/// recorded.
+#[elem]
+pub struct AncestryElem {
+    /// The recorded ancestors, from the outermost to the innermost one.
+    #[internal]
+    #[fold]
+    #[ghost]
+    pub ancestors: Vec<Content>,
 }
 
 #[scope]
@@ -226,6 +315,79 @@ impl Selector {
             inclusive,
         }
     }
+
+    /// Returns a modified selector that will only match elements that are
+    /// nested in an element matching `ancestor`.
+    ///
+    /// ```example
+    /// #show heading.within(figure): set text(blue)
+    ///
+    /// = Outside
+    /// #figure(
+    ///   [= Inside],
+    ///   caption: [A heading],
+    /// )
+    /// ```
+    #[func]
+    pub fn within(
+        self,
+        /// The element that matching elements must be nested in.
+        ancestor: Selector,
+    ) -> Selector {
+        Self::Within {
+            selector: Arc::new(self),
+            ancestor: Arc::new(ancestor),
+        }
+    }
+
+    /// Returns a modified selector that will only match the first matching
+    /// element in each element matching `ancestor`.
+    ///
+    /// Only the innermost element matching `ancestor` is considered. For
+    /// instance, the paragraphs in a block that is nested in another block
+    /// only count towards the inner block.
+    ///
+    /// ```example
+    /// #show par.first-in(block): set text(red)
+    ///
+    /// #block[
+    ///   This is the first paragraph.
+    ///
+    ///   This is the second one.
+    /// ]
+    /// ```
+    #[func]
+    pub fn first_in(
+        self,
+        /// The element in which the first match is selected.
+        ancestor: Selector,
+    ) -> Selector {
+        Self::FirstIn {
+            selector: Arc::new(self),
+            ancestor: Arc::new(ancestor),
+        }
+    }
+
+    /// Returns a modified selector that will only match elements nested at
+    /// the given depth into elements matching the same selector. Elements
+    /// that are not nested into another match are at depth 1.
+    ///
+    /// ```example
+    /// #show list.item.at-depth(2): set text(gray)
+    ///
+    /// - Fruits
+    ///   - Apples
+    ///   - Bananas
+    /// - Vegetables
+    /// ```
+    #[func]
+    pub fn at_depth(
+        self,
+        /// The nesting depth, starting at 1.
+        depth: NonZeroUsize,
+    ) -> Selector {
+        Self::AtDepth { selector: Arc::new(self), depth }
+    }
 }
 
 impl From<Location> for Selector {
@@ -271,6 +433,18 @@ impl Repr for Selector {
                     inclusive_arg
                 )
             }
+            Self::Within { selector, ancestor }
+            | Self::FirstIn { selector, ancestor } => {
+                let method = if matches!(self, Self::Within { .. }) {
+                    "within"
+                } else {
+                    "first-in"
+                };
+                eco_format!("{}.{}({})", selector.repr(), method, ancestor.repr())
+            }
+            Self::AtDepth { selector, depth } => {
+                eco_format!("{}.at-depth({})", selector.repr(), depth)
+            }
         }
     }
 }
@@ -362,6 +536,11 @@ impl FromValue for LocatableSelector {
                         validate(selector)?;
                     }
                 }
+                Selector::Within { .. }
+                | Selector::FirstIn { .. }
+                | Selector::AtDepth { .. } => {
+                    bail!("structural selectors are not locatable")
+                }
             }
             Ok(())
         }
@@ -432,6 +611,12 @@ impl FromValue for ShowableSelector {
                         validate(selector, true)?;
                     }
                 }
+                Selector::Within { selector, ancestor }
+                | Selector::FirstIn { selector, ancestor } => {
+                    validate(selector, true)?;
+                    validate(ancestor, true)?;
+                }
+                Selector::AtDepth { selector, .. } => validate(selector, true)?,
                 Selector::Regex(_)
                 | Selector::Location(_)
                 | Selector::Can(_)
@@ -390,7 +390,7 @@ impl Str {
             StrPattern::Str(pat) => {
                 self.0.match_indices(pat.as_str()).next().map(match_to_dict)
             }
-            StrPattern::Regex(re) => re.captures(self).map(captures_to_dict),
+            StrPattern::Regex(re) => re.captures(self).as_ref().map(captures_to_dict),
         }
     }
 
@@ -412,7 +412,7 @@ impl Str {
                 .collect(),
             StrPattern::Regex(re) => re
                 .captures_iter(self)
-                .map(captures_to_dict)
+                .map(|cap| captures_to_dict(&cap))
                 .map(Value::Dict)
                 .collect(),
         }
@@ -477,7 +477,7 @@ impl Str {
                 for caps in re.captures_iter(self).take(count) {
                     // Extract the entire match over all capture groups.
                     let m = caps.get(0).unwrap();
-                    handle_match(m.start()..m.end(), captures_to_dict(caps))?;
+                    handle_match(m.start()..m.end(), captures_to_dict(&caps))?;
                 }
             }
         }
@@ -797,7 +797,7 @@ fn match_to_dict((start, text): (usize, &str)) -> Dict {
 }
 
 /// Convert regex captures to a dictionary.
-fn captures_to_dict(cap: regex::Captures) -> Dict {
+fn captures_to_dict(cap: &regex::Captures) -> Dict {
     let m = cap.get(0).expect("missing first match");
     dict! {
         "start" => m.start(),
@@ -861,6 +861,27 @@ impl Regex {
     pub fn new(re: &str) -> StrResult<Self> {
         regex::Regex::new(re).map(Self).map_err(|err| eco_format!("{err}"))
     }
+
+    /// Describe a match of this regex in the `haystack` as exposed to regex
+    /// show rules.
+    pub fn details(&self, cap: &regex::Captures, haystack: &str) -> Dict {
+        let m = cap.get(0).expect("missing first match");
+        let named = self
+            .capture_names()
+            .flatten()
+            .map(|name| {
+                let value =
+                    cap.name(name).map_or(Value::None, |m| m.as_str().into_value());
+                (name.into(), value)
+            })
+            .collect::<Dict>();
+
+        let mut dict = captures_to_dict(cap);
+        dict.insert("named".into(), named.into_value());
+        dict.insert("before".into(), haystack[..m.start()].into_value());
+        dict.insert("after".into(), haystack[m.end()..].into_value());
+        dict
+    }
 }
 
 #[scope]
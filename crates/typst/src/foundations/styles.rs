@@ -4,19 +4,21 @@ use std::hash::{Hash, Hasher};
 use std::{mem, ptr};
 
 use comemo::{Track, Tracked};
-use ecow::{eco_vec, EcoString, EcoVec};
+use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 use smallvec::SmallVec;
 
-use crate::diag::{SourceResult, Trace, Tracepoint};
+use crate::diag::{HintedStrResult, SourceResult, Trace, Tracepoint};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, func, ty, Content, Context, Element, Func, NativeElement, Packed, Repr,
-    Selector, Show,
+    cast, elem, func, scope, ty, Content, Context, ContextualSet, Element, Func,
+    IntoValue, NativeElement, Packed, Repr, Selector, Show, Str, Value,
 };
-use crate::introspection::Locatable;
+use crate::introspection::{Locatable, Location};
+use crate::layout::Length;
 use crate::syntax::Span;
 use crate::text::{FontFamily, FontList, TextElem};
 use crate::utils::LazyHash;
+use crate::visualize::Stroke;
 
 /// Provides access to active styles.
 ///
@@ -66,10 +68,80 @@ impl Show for Packed<StyleElem> {
 }
 
 /// A list of style properties.
-#[ty(cast)]
+///
+/// The `styles` type also provides access to the effective value of any style
+/// property at the current position through its [`get`]($styles.get)
+/// function.
+#[ty(scope, cast)]
 #[derive(Default, PartialEq, Clone, Hash)]
 pub struct Styles(EcoVec<LazyHash<Style>>);
 
+#[scope]
+impl Styles {
+    /// Retrieves the effective value of a style property at the current
+    /// position.
+    ///
+    /// Like accessing the property on the element function directly (e.g.
+    /// `{par.leading}`), this takes set rules and default values into account.
+    /// In addition, all lengths in the value are resolved to absolute lengths.
+    /// This way, you can for instance draw a rule whose thickness matches the
+    /// current text size without re-deriving it yourself.
+    ///
+    /// ```example
+    /// #set text(14pt)
+    /// #set par(leading: 1em)
+    ///
+    /// #context par.leading \
+    /// #context styles.get(par, "leading")
+    /// ```
+    #[func(contextual)]
+    pub fn get(
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The element function whose property to retrieve.
+        element: Element,
+        /// The name of the property.
+        property: Str,
+    ) -> HintedStrResult<Value> {
+        let styles = context.styles()?;
+        let value = element
+            .field_id(&property)
+            .and_then(|id| element.field_from_styles(id, styles).ok())
+            .ok_or_else(|| {
+                eco_format!(
+                    "{} does not have a settable property {}",
+                    element.name(),
+                    property.repr()
+                )
+            })?;
+        Ok(resolve_lengths(value, styles))
+    }
+}
+
+/// Resolves all lengths in a value to absolute lengths.
+fn resolve_lengths(value: Value, styles: StyleChain) -> Value {
+    match value {
+        Value::Length(length) => Value::Length(length.resolve(styles).into()),
+        Value::Relative(rel) => Value::Relative(rel.resolve(styles).map(Length::from)),
+        Value::Array(array) => Value::Array(
+            array
+                .into_iter()
+                .map(|value| resolve_lengths(value, styles))
+                .collect(),
+        ),
+        Value::Dict(dict) => Value::Dict(
+            dict.into_iter()
+                .map(|(key, value)| (key, resolve_lengths(value, styles)))
+                .collect(),
+        ),
+        Value::Dyn(dynamic) => match dynamic.downcast::<Stroke>() {
+            Some(stroke) => stroke.clone().resolve(styles).map(Length::from).into_value(),
+            None => Value::Dyn(dynamic),
+        },
+        value => value,
+    }
+}
+
 impl Styles {
     /// Create a new, empty style list.
     pub const fn new() -> Self {
@@ -81,6 +153,11 @@ impl Styles {
         self.0.is_empty()
     }
 
+    /// The contained styles as a slice.
+    pub fn as_slice(&self) -> &[LazyHash<Style>] {
+        self.0.as_slice()
+    }
+
     /// Iterate over the contained styles.
     pub fn iter(&self) -> impl Iterator<Item = &Style> {
         self.0.iter().map(|style| &**style)
@@ -126,6 +203,42 @@ impl Styles {
         self
     }
 
+    /// Whether this contains properties from contextual set rules that still
+    /// need to be resolved.
+    pub fn is_contextual(&self) -> bool {
+        self.0.iter().any(|entry| matches!(**entry, Style::Contextual(_)))
+    }
+
+    /// Resolve the properties from contextual set rules, evaluating them with
+    /// the outer styles and the preceding styles of this list.
+    ///
+    /// The location is made available to the evaluated expressions if the
+    /// styles are applied to a located element.
+    pub fn resolve_contextual(
+        &self,
+        engine: &mut Engine,
+        location: Option<Location>,
+        outer: StyleChain,
+    ) -> SourceResult<Self> {
+        if !self.is_contextual() {
+            return Ok(self.clone());
+        }
+
+        let mut resolved = Self::new();
+        for entry in &self.0 {
+            let Style::Contextual(set) = &**entry else {
+                resolved.0.push(entry.clone());
+                continue;
+            };
+            let styles = {
+                let context = Context::new(location, Some(outer.chain(&resolved)));
+                set.resolve(engine, context.track())?
+            };
+            resolved.0.extend(styles.0);
+        }
+        Ok(resolved)
+    }
+
     /// Returns `Some(_)` with an optional span if this list contains
     /// styles for the given element.
     pub fn interruption<T: NativeElement>(&self) -> Option<Option<Span>> {
@@ -133,6 +246,7 @@ impl Styles {
         self.0.iter().find_map(|entry| match &**entry {
             Style::Property(property) => property.is_of(elem).then_some(property.span),
             Style::Recipe(recipe) => recipe.is_of(elem).then_some(Some(recipe.span)),
+            Style::Contextual(set) => (set.target() == elem).then_some(Some(set.span())),
             Style::Revocation(_) => None,
         })
     }
@@ -182,6 +296,8 @@ pub enum Style {
     Recipe(Recipe),
     /// Disables a specific show rule recipe.
     Revocation(RecipeIndex),
+    /// Properties from a set rule that are computed contextually.
+    Contextual(ContextualSet),
 }
 
 impl Style {
@@ -208,6 +324,7 @@ impl Debug for Style {
             Self::Property(property) => property.fmt(f),
             Self::Recipe(recipe) => recipe.fmt(f),
             Self::Revocation(guard) => guard.fmt(f),
+            Self::Contextual(set) => set.fmt(f),
         }
     }
 }
@@ -224,6 +341,12 @@ impl From<Recipe> for Style {
     }
 }
 
+impl From<ContextualSet> for Style {
+    fn from(set: ContextualSet) -> Self {
+        Self::Contextual(set)
+    }
+}
+
 /// A style property originating from a set rule or constructor.
 #[derive(Clone, Hash)]
 pub struct Property {
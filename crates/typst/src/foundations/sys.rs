@@ -1,9 +1,11 @@
 //! System-related things.
 
-use crate::foundations::{Dict, Module, Scope, Version};
+use ecow::EcoString;
+
+use crate::foundations::{dict, Array, Dict, IntoValue, Module, Scope, Version};
 
 /// A module with system-related things.
-pub fn module(inputs: Dict) -> Module {
+pub fn module(inputs: Dict, capabilities: &Capabilities) -> Module {
     let mut scope = Scope::deduplicating();
     scope.define(
         "version",
@@ -14,5 +16,46 @@ pub fn module(inputs: Dict) -> Module {
         ]),
     );
     scope.define("inputs", inputs);
+    scope.define("capabilities", capabilities.to_dict());
     Module::new("sys", scope)
 }
+
+/// The capabilities the host grants to a document.
+///
+/// These are visible through `sys.capabilities`, so that templates can adapt
+/// to restricted environments instead of failing.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Capabilities {
+    /// The directories from which files may be read, as paths relative to the
+    /// project root (e.g. `"/"` for the whole project).
+    pub read_roots: Vec<EcoString>,
+    /// Whether WebAssembly plugins may be loaded.
+    pub plugins: bool,
+    /// Whether the host may access the network during compilation, for
+    /// example to download packages.
+    pub network: bool,
+}
+
+impl Capabilities {
+    /// The dictionary exposed as `sys.capabilities`.
+    fn to_dict(&self) -> Dict {
+        let read_roots: Array =
+            self.read_roots.iter().cloned().map(IntoValue::into_value).collect();
+        dict! {
+            "read-roots" => read_roots,
+            "plugins" => self.plugins,
+            "network" => self.network,
+        }
+    }
+}
+
+impl Default for Capabilities {
+    /// Grants everything.
+    fn default() -> Self {
+        Self {
+            read_roots: vec!["/".into()],
+            plugins: true,
+            network: true,
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use ecow::EcoString;
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, Func, Packed, Show, StyleChain};
+
+/// Defines a test case, typically for a package.
+///
+/// Tests are invisible in the document. Instead, they are run by
+/// `typst test`, which evaluates a file, collects the tests at its top level,
+/// and reports those that fail along with the location of the failure. The
+/// test's function is called without arguments and fails if it fails, for
+/// instance, because of a failed [assertion]($assert). No layout takes place
+/// unless the test asks for it.
+///
+/// # Example
+/// ```typ
+/// #import "lib.typ": slugify
+///
+/// #test-case("replaces spaces", () => {
+///   assert.eq(slugify("Hello World"), "hello-world")
+/// })
+///
+/// #test-case("renders a badge", layout: true, () => {
+///   badge[New]
+/// })
+/// ```
+#[elem(Show)]
+pub struct TestCaseElem {
+    /// The name of the test.
+    #[required]
+    pub name: EcoString,
+
+    /// The function that performs the test.
+    #[required]
+    pub body: Func,
+
+    /// Whether to lay out the content returned by the test's function.
+    ///
+    /// The resulting frame can be compared against a reference snapshot with
+    /// `typst test --snapshots`. Layout errors fail the test, too.
+    #[default(false)]
+    pub layout: bool,
+}
+
+impl Show for Packed<TestCaseElem> {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
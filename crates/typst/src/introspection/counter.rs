@@ -6,17 +6,18 @@ use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 use smallvec::{smallvec, SmallVec};
 
 use crate::diag::{bail, At, HintedStrResult, SourceResult};
-use crate::engine::{Engine, Route, Sink, Traced};
+use crate::engine::{Budget, Engine, Route, Sink, Traced};
 use crate::foundations::{
     cast, elem, func, scope, select_where, ty, Args, Array, Construct, Content, Context,
     Element, Func, IntoValue, Label, LocatableSelector, NativeElement, Packed, Repr,
     Selector, Show, Smart, Str, StyleChain, Value,
 };
-use crate::introspection::{Introspector, Locatable, Location};
+use crate::introspection::{Introspector, Locatable, Location, Tag};
 use crate::layout::{Frame, FrameItem, PageElem};
 use crate::math::EquationElem;
 use crate::model::{FigureElem, HeadingElem, Numbering, NumberingPattern};
 use crate::syntax::Span;
+use crate::text::{localized_str, TextElem};
 use crate::utils::NonZeroExt;
 use crate::World;
 
@@ -254,6 +255,17 @@ impl Counter {
         Ok(state)
     }
 
+    /// Gets the value of the counter at the end of the document.
+    fn final_state(&self, engine: &mut Engine) -> SourceResult<CounterState> {
+        let sequence = self.sequence(engine)?;
+        let (mut state, page) = sequence.last().unwrap().clone();
+        if self.is_page() {
+            let delta = engine.introspector.pages().get().saturating_sub(page.get());
+            state.step(NonZeroUsize::ONE, delta);
+        }
+        Ok(state)
+    }
+
     /// Displays the value of the counter at the given location.
     pub fn display_at_loc(
         &self,
@@ -281,6 +293,7 @@ impl Counter {
             engine.world,
             engine.introspector,
             engine.traced,
+            engine.budget,
             TrackedMut::reborrow_mut(&mut engine.sink),
             engine.route.track(),
         )
@@ -293,6 +306,7 @@ impl Counter {
         world: Tracked<dyn World + '_>,
         introspector: Tracked<Introspector>,
         traced: Tracked<Traced>,
+        budget: Tracked<Budget>,
         sink: TrackedMut<Sink>,
         route: Tracked<Route>,
     ) -> SourceResult<EcoVec<(CounterState, NonZeroUsize)>> {
@@ -300,6 +314,7 @@ impl Counter {
             world,
             introspector,
             traced,
+            budget,
             sink,
             route: Route::extend(route).unnested(),
         };
@@ -320,7 +335,7 @@ impl Counter {
             }
 
             if let Some(update) = match elem.with::<dyn Count>() {
-                Some(countable) => countable.update(),
+                Some(countable) => countable.update_for(&self.0),
                 None => Some(CounterUpdate::Step(NonZeroUsize::ONE)),
             } {
                 state.update(&mut engine, update)?;
@@ -332,6 +347,39 @@ impl Counter {
         Ok(stops)
     }
 
+    /// The counters that are updated in a document, either explicitly or by
+    /// counted elements.
+    pub(super) fn all(introspector: &Introspector) -> Vec<Self> {
+        let mut counters = vec![];
+        for elem in introspector.all() {
+            let counter = match elem.to_packed::<CounterUpdateElem>() {
+                Some(update) => Self::new(update.key().clone()),
+                None if elem.with::<dyn Count>().is_some() => Self::of(elem.func()),
+                None => continue,
+            };
+            if !counters.contains(&counter) {
+                counters.push(counter);
+            }
+        }
+        counters
+    }
+
+    /// The elements that update this counter, each with the counter's value
+    /// right after it.
+    pub(super) fn updates(
+        &self,
+        engine: &mut Engine,
+    ) -> SourceResult<Vec<(Content, Value)>> {
+        let sequence = self.sequence(engine)?;
+        Ok(engine
+            .introspector
+            .query(&self.selector())
+            .into_iter()
+            .zip(sequence.into_iter().skip(1))
+            .map(|(elem, (state, _))| (elem, state.into_value()))
+            .collect())
+    }
+
     /// The selector relevant for this counter's updates.
     fn selector(&self) -> Selector {
         let mut selector = select_where!(CounterUpdateElem, Key => self.0.clone());
@@ -358,7 +406,25 @@ impl Counter {
         both: bool,
         styles: Option<StyleChain>,
     ) -> SourceResult<Value> {
-        let numbering = numbering
+        let numbering = self.resolve_numbering(numbering, styles);
+        let state = if both {
+            self.both(engine, location)?
+        } else {
+            self.at_loc(engine, location)?
+        };
+
+        let context = Context::new(Some(location), styles);
+        state.display(engine, context.track(), &numbering)
+    }
+
+    /// Determines the numbering to display this counter with, falling back to
+    /// the numbering of the counted element.
+    fn resolve_numbering(
+        &self,
+        numbering: Smart<Numbering>,
+        styles: Option<StyleChain>,
+    ) -> Numbering {
+        numbering
             .custom()
             .or_else(|| {
                 let styles = styles?;
@@ -376,16 +442,7 @@ impl Counter {
                     None
                 }
             })
-            .unwrap_or_else(|| NumberingPattern::from_str("1.1").unwrap().into());
-
-        let state = if both {
-            self.both(engine, location)?
-        } else {
-            self.at_loc(engine, location)?
-        };
-
-        let context = Context::new(Some(location), styles);
-        state.display(engine, context.track(), &numbering)
+            .unwrap_or_else(|| NumberingPattern::from_str("1.1").unwrap().into())
     }
 }
 
@@ -517,13 +574,119 @@ impl Counter {
             context.location().at(span)?;
         }
 
-        let sequence = self.sequence(engine)?;
-        let (mut state, page) = sequence.last().unwrap().clone();
-        if self.is_page() {
-            let delta = engine.introspector.pages().get().saturating_sub(page.get());
-            state.step(NonZeroUsize::ONE, delta);
-        }
-        Ok(state)
+        self.final_state(engine)
+    }
+
+    /// Displays the value of the counter at the end of the document with a
+    /// numbering and returns the formatted output.
+    ///
+    /// This is a shorthand for displaying the result of
+    /// [`final`]($counter.final) with a numbering.
+    ///
+    /// ```example
+    /// #set heading(numbering: "I.")
+    /// #context [
+    ///   There are #counter(heading).final-display()
+    ///   sections.
+    /// ]
+    ///
+    /// = Introduction
+    /// = Background
+    /// ```
+    #[func(contextual)]
+    pub fn final_display(
+        &self,
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
+        /// A [numbering pattern or a function]($numbering), which specifies how
+        /// to display the counter. Like for [`display`]($counter.display), this
+        /// defaults to the numbering style of the counted element.
+        #[default]
+        numbering: Smart<Numbering>,
+    ) -> SourceResult<Value> {
+        let location = context.location().at(span)?;
+        let numbering = self.resolve_numbering(numbering, context.styles().ok());
+        let context = Context::new(Some(location), context.styles().ok());
+        self.final_state(engine)?.display(engine, context.track(), &numbering)
+    }
+
+    /// Displays the current and the final value of the counter, like
+    /// "3 of 12".
+    ///
+    /// Both values are displayed with the same numbering. If both results are
+    /// strings, so is the output. Otherwise, it is content.
+    ///
+    /// ```example
+    /// #set page(
+    ///   height: 80pt,
+    ///   footer: context align(center)[
+    ///     Page #counter(page).display-of()
+    ///   ],
+    /// )
+    ///
+    /// #figure(rect(), caption: context [
+    ///   Figure #counter(figure).display-of()
+    /// ])
+    /// ```
+    #[func(contextual)]
+    pub fn display_of(
+        &self,
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
+        /// A [numbering pattern or a function]($numbering), which specifies how
+        /// to display both values. Like for [`display`]($counter.display),
+        /// this defaults to the numbering style of the counted element.
+        #[default]
+        numbering: Smart<Numbering>,
+        /// What to put between the two values.
+        ///
+        /// If this is `{auto}`, the word "of" in the
+        /// [text language]($text.lang) is used.
+        #[named]
+        #[default]
+        separator: Smart<Str>,
+    ) -> SourceResult<Value> {
+        let location = context.location().at(span)?;
+        let styles = context.styles().ok();
+        let numbering = self.resolve_numbering(numbering, styles);
+        let separator = separator.unwrap_or_else(|| {
+            let word = match styles {
+                Some(styles) => localized_str(
+                    TextElem::lang_in(styles),
+                    TextElem::region_in(styles),
+                    "of",
+                ),
+                None => "of",
+            };
+            eco_format!(" {word} ").into()
+        });
+
+        let context = Context::new(Some(location), styles);
+        let current = self.at_loc(engine, location)?.display(
+            engine,
+            context.track(),
+            &numbering,
+        )?;
+        let total =
+            self.final_state(engine)?
+                .display(engine, context.track(), &numbering)?;
+
+        Ok(match (current, total) {
+            (Value::Str(current), Value::Str(total)) => {
+                Value::Str(current + separator + total)
+            }
+            (current, total) => Value::Content(
+                current.display() + TextElem::packed(separator) + total.display(),
+            ),
+        })
     }
 
     /// Increases the value of the counter by one.
@@ -635,6 +798,14 @@ cast! {
 pub trait Count {
     /// Get the counter update for this element.
     fn update(&self) -> Option<CounterUpdate>;
+
+    /// Get the counter update for this element when it is counted by the
+    /// counter with the given key. Elements that are selected by several
+    /// counters, but only step some of them, can override this.
+    fn update_for(&self, key: &CounterKey) -> Option<CounterUpdate> {
+        let _ = key;
+        self.update()
+    }
 }
 
 /// Counts through elements with different levels.
@@ -786,16 +957,26 @@ impl Show for Packed<CounterDisplayElem> {
 
 /// An specialized handler of the page counter that tracks both the physical
 /// and the logical page counter.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct ManualPageCounter {
     physical: NonZeroUsize,
     logical: usize,
+    /// The current numbering scope.
+    scope: Option<Str>,
+    /// The logical page counters of the other numbering scopes that were
+    /// entered so far.
+    scopes: EcoVec<(Option<Str>, usize)>,
 }
 
 impl ManualPageCounter {
     /// Create a new fast page counter, starting at 1.
     pub fn new() -> Self {
-        Self { physical: NonZeroUsize::ONE, logical: 1 }
+        Self {
+            physical: NonZeroUsize::ONE,
+            logical: 1,
+            scope: None,
+            scopes: EcoVec::new(),
+        }
     }
 
     /// Get the current physical page counter state.
@@ -830,6 +1011,39 @@ impl ManualPageCounter {
         Ok(())
     }
 
+    /// Switch to a page numbering scope before the next page.
+    ///
+    /// If the scope changes, the logical page counter of the current scope is
+    /// set aside and the one of the new scope is restored, starting at 1 for
+    /// a new scope. Returns a tag with the matching update of the page counter,
+    /// located at the given location, which must be placed at the start of
+    /// the next page such that `counter(page)` follows the scope, too.
+    pub fn enter(
+        &mut self,
+        scope: Option<Str>,
+        location: impl FnOnce() -> Location,
+    ) -> Option<Tag> {
+        if scope == self.scope {
+            return None;
+        }
+
+        let restored = match self.scopes.iter().position(|(s, _)| *s == scope) {
+            Some(i) => self.scopes.remove(i).1,
+            None => 1,
+        };
+
+        let previous = std::mem::replace(&mut self.scope, scope);
+        self.scopes.push((previous, self.logical));
+        self.logical = restored;
+
+        let state = CounterState(smallvec![restored]);
+        let location = location();
+        let mut elem =
+            CounterUpdateElem::new(CounterKey::Page, CounterUpdate::Set(state)).pack();
+        elem.set_location(location);
+        Some(Tag::new(elem, location.hash()))
+    }
+
     /// Step past a page _boundary._
     pub fn step(&mut self) {
         self.physical = self.physical.saturating_add(1);
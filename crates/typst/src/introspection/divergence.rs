@@ -0,0 +1,136 @@
+use comemo::{Track, Tracked};
+use ecow::{eco_format, EcoString};
+
+use crate::engine::{Budget, Engine, Route, Sink, Traced};
+use crate::foundations::{Content, Repr, Str, Value};
+use crate::introspection::{Counter, Introspector, Location, State};
+use crate::syntax::Span;
+use crate::World;
+
+/// A counter or state whose value changed in the last layout attempt of a
+/// document that did not converge.
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    /// The counter or state that changed.
+    pub subject: Divergent,
+    /// The location of the first update after which the value changed.
+    pub location: Location,
+    /// The span of that update.
+    pub span: Span,
+    /// The value after the update in the second to last attempt or `None` if
+    /// the update didn't exist back then.
+    pub previous: Option<Value>,
+    /// The value after the update in the last attempt or `None` if the update
+    /// doesn't exist anymore.
+    pub current: Option<Value>,
+}
+
+/// What failed to converge.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum Divergent {
+    /// A counter.
+    Counter(Counter),
+    /// A state, identified by its key.
+    State(Str),
+}
+
+impl Repr for Divergent {
+    fn repr(&self) -> EcoString {
+        match self {
+            Self::Counter(counter) => counter.repr(),
+            Self::State(key) => eco_format!("state({})", key.repr()),
+        }
+    }
+}
+
+/// Determines the counters and states whose values differ between the
+/// introspectors of two layout attempts.
+///
+/// For counters, the values are compared after each update. For states, the
+/// updates themselves are compared since the state's initial value isn't
+/// known here.
+pub fn divergences(
+    world: Tracked<dyn World + '_>,
+    previous: &Introspector,
+    current: &Introspector,
+) -> Vec<Divergence> {
+    let traced = Traced::default();
+    let budget = Budget::default();
+    let mut sink = Sink::new();
+    let mut counter_updates = |introspector: &Introspector, counter: &Counter| {
+        let mut engine = Engine {
+            world,
+            introspector: introspector.track(),
+            traced: traced.track(),
+            budget: budget.track(),
+            sink: sink.track_mut(),
+            route: Route::default(),
+        };
+        counter.updates(&mut engine).unwrap_or_default()
+    };
+
+    let mut divergences = vec![];
+    let mut counters = Counter::all(current);
+    for counter in Counter::all(previous) {
+        if !counters.contains(&counter) {
+            counters.push(counter);
+        }
+    }
+
+    for counter in counters {
+        let before = counter_updates(previous, &counter);
+        let after = counter_updates(current, &counter);
+        divergences.extend(first_difference(Divergent::Counter(counter), before, after));
+    }
+
+    let mut states = State::updates(current);
+    for (key, before) in State::updates(previous) {
+        match states.iter().position(|(other, _)| *other == key) {
+            Some(i) => {
+                let (key, after) = states.remove(i);
+                divergences.extend(first_difference(
+                    Divergent::State(key),
+                    before,
+                    after,
+                ));
+            }
+            None => divergences.extend(first_difference(
+                Divergent::State(key),
+                before,
+                vec![],
+            )),
+        }
+    }
+
+    for (key, after) in states {
+        divergences.extend(first_difference(Divergent::State(key), vec![], after));
+    }
+
+    divergences
+}
+
+/// Finds the first update whose value differs between two attempts.
+///
+/// The updates are matched by their order since an update whose value changed
+/// also has a new location.
+fn first_difference(
+    subject: Divergent,
+    before: Vec<(Content, Value)>,
+    after: Vec<(Content, Value)>,
+) -> Option<Divergence> {
+    fn value(updates: &[(Content, Value)], i: usize) -> Option<&Value> {
+        updates.get(i).map(|(_, value)| value)
+    }
+
+    let i = (0..before.len().max(after.len()))
+        .find(|&i| value(&before, i) != value(&after, i))?;
+    let (elem, _) = after.get(i).or(before.get(i))?;
+
+    Some(Divergence {
+        subject,
+        location: elem.location()?,
+        span: elem.span(),
+        previous: value(&before, i).cloned(),
+        current: value(&after, i).cloned(),
+    })
+}
@@ -2,6 +2,7 @@ use std::collections::{BTreeSet, HashMap};
 use std::fmt::{self, Debug, Formatter};
 use std::hash::Hash;
 use std::num::NonZeroUsize;
+use std::ops::Range;
 use std::sync::RwLock;
 
 use ecow::{eco_format, EcoVec};
@@ -13,15 +14,16 @@ use crate::foundations::{Content, Label, Repr, Selector};
 use crate::introspection::Location;
 use crate::layout::{Frame, FrameItem, Page, Point, Position, Transform};
 use crate::model::Numbering;
-use crate::utils::NonZeroExt;
+use crate::utils::{hash128, NonZeroExt};
 
 /// Can be queried for elements and their positions.
 #[derive(Default, Clone)]
 pub struct Introspector {
     /// The number of pages in the document.
     pages: usize,
-    /// All introspectable elements.
-    elems: IndexMap<Location, (Content, Position)>,
+    /// All introspectable elements, along with their positions and the keys
+    /// of their tags.
+    elems: IndexMap<Location, (Content, Position, u128)>,
     /// Maps labels to their indices in the element list. We use a smallvec such
     /// that if the label is unique, we don't need to allocate.
     labels: HashMap<Label, SmallVec<[usize; 1]>>,
@@ -30,6 +32,9 @@ pub struct Introspector {
     keys: HashMap<u128, SmallVec<[Location; 1]>>,
     /// The page numberings, indexed by page number minus 1.
     page_numberings: Vec<Option<Numbering>>,
+    /// What the introspector extracted from each page, indexed by page number
+    /// minus 1. Used to only update the introspector for changed pages.
+    records: Vec<PageRecord>,
     /// Caches queries done on the introspector. This is important because
     /// even if all top-level queries are distinct, they often have shared
     /// subqueries. Example: Individual counter queries with `before` that
@@ -39,58 +44,132 @@ pub struct Introspector {
 
 impl Introspector {
     /// Applies new frames in-place, reusing the existing allocations.
+    ///
+    /// Only the pages that changed since the last rebuild are traversed
+    /// again. Since the elements are ordered, the elements of all pages after
+    /// the first changed one need to be re-inserted, but those of unchanged
+    /// pages are copied over instead of being extracted from the frames.
+    ///
+    /// A page counts as unchanged if its frame is the same instance as in the
+    /// last rebuild, which is the case when layout reused the page run from
+    /// its cache. This way, detecting changes doesn't require traversing any
+    /// frames.
     #[typst_macros::time(name = "introspect")]
     pub fn rebuild(&mut self, pages: &[Page]) {
         self.pages = pages.len();
-        self.elems.clear();
-        self.labels.clear();
-        self.keys.clear();
         self.page_numberings.clear();
+        self.page_numberings
+            .extend(pages.iter().map(|page| page.numbering.clone()));
+
+        // Find the first page that changed. If there is none, the elements
+        // and thus also the cached queries remain valid.
+        let start = pages
+            .iter()
+            .zip(&self.records)
+            .position(|(page, record)| !page.frame.is_same(&record.frame))
+            .unwrap_or(pages.len().min(self.records.len()));
+        if start == pages.len() && start == self.records.len() {
+            return;
+        }
+
+        // Keep everything extracted from the pages before the first changed
+        // one and set the rest aside for reuse.
+        let split = self.records.get(start).map_or(self.elems.len(), |r| r.range.start);
+        let previous = self.elems.split_off(split);
+        let records = self.records.split_off(start);
+        self.labels.retain(|_, indices| {
+            indices.retain(|index| *index < split);
+            !indices.is_empty()
+        });
+        self.keys.retain(|_, locations| {
+            locations.retain(|location| self.elems.contains_key(location));
+            !locations.is_empty()
+        });
         self.queries.clear();
 
-        for (i, page) in pages.iter().enumerate() {
+        for (i, page) in pages.iter().enumerate().skip(start) {
             let page_nr = NonZeroUsize::new(1 + i).unwrap();
-            self.extract(&page.frame, page_nr, Transform::identity());
-            self.page_numberings.push(page.numbering.clone());
+            let begin = self.elems.len();
+            let mut complete = true;
+            match records.get(i - start) {
+                Some(record) if record.complete && page.frame.is_same(&record.frame) => {
+                    let range = record.range.start - split..record.range.end - split;
+                    for (&loc, (elem, pos, key)) in &previous.as_slice()[range] {
+                        complete &= self.insert(loc, elem, *pos, *key);
+                    }
+                }
+                _ => self.extract(
+                    &page.frame,
+                    page_nr,
+                    Transform::identity(),
+                    &mut complete,
+                ),
+            }
+
+            self.records.push(PageRecord {
+                frame: page.frame.clone(),
+                range: begin..self.elems.len(),
+                complete,
+            });
         }
     }
 
     /// Extract metadata from a frame.
-    fn extract(&mut self, frame: &Frame, page: NonZeroUsize, ts: Transform) {
+    fn extract(
+        &mut self,
+        frame: &Frame,
+        page: NonZeroUsize,
+        ts: Transform,
+        complete: &mut bool,
+    ) {
         for (pos, item) in frame.items() {
             match item {
                 FrameItem::Group(group) => {
                     let ts = ts
                         .pre_concat(Transform::translate(pos.x, pos.y))
                         .pre_concat(group.transform);
-                    self.extract(&group.frame, page, ts);
+                    self.extract(&group.frame, page, ts, complete);
                 }
-                FrameItem::Tag(tag)
-                    if !self.elems.contains_key(&tag.elem.location().unwrap()) =>
-                {
-                    let pos = pos.transform(ts);
+                FrameItem::Tag(tag) => {
                     let loc = tag.elem.location().unwrap();
-                    let ret = self
-                        .elems
-                        .insert(loc, (tag.elem.clone(), Position { page, point: pos }));
-                    assert!(ret.is_none(), "duplicate locations");
-
-                    // Build the key map.
-                    self.keys.entry(tag.key).or_default().push(loc);
-
-                    // Build the label cache.
-                    if let Some(label) = tag.elem.label() {
-                        self.labels.entry(label).or_default().push(self.elems.len() - 1);
-                    }
+                    let pos = Position { page, point: pos.transform(ts) };
+                    *complete &= self.insert(loc, &tag.elem, pos, tag.key);
                 }
                 _ => {}
             }
         }
     }
 
+    /// Insert an element unless one with the same location already exists.
+    ///
+    /// Returns whether the element was inserted.
+    fn insert(
+        &mut self,
+        loc: Location,
+        elem: &Content,
+        pos: Position,
+        key: u128,
+    ) -> bool {
+        if self.elems.contains_key(&loc) {
+            return false;
+        }
+
+        self.elems.insert(loc, (elem.clone(), pos, key));
+
+        // Build the key map.
+        self.keys.entry(key).or_default().push(loc);
+
+        // Build the label cache.
+        if let Some(label) = elem.label() {
+            self.labels.entry(label).or_default().push(self.elems.len() - 1);
+        }
+
+        true
+    }
+
     /// Iterate over all locatable elements.
     pub fn all(&self) -> impl Iterator<Item = &Content> + '_ {
-        self.elems.values().map(|(c, _)| c)
+        self.elems.values().map(|(c, _, _)| c)
     }
 
     /// Perform a binary search for `elem` among the `list`.
@@ -100,7 +179,7 @@ impl Introspector {
 
     /// Get an element by its location.
     fn get(&self, location: &Location) -> Option<&Content> {
-        self.elems.get(location).map(|(elem, _)| elem)
+        self.elems.get(location).map(|(elem, _, _)| elem)
     }
 
     /// Get the index of this element among all.
@@ -118,7 +197,7 @@ impl Introspector {
 impl Introspector {
     /// Query for all matching elements.
     pub fn query(&self, selector: &Selector) -> EcoVec<Content> {
-        let hash = crate::utils::hash128(selector);
+        let hash = hash128(selector);
         if let Some(output) = self.queries.get(hash) {
             return output;
         }
@@ -199,7 +278,10 @@ impl Introspector {
                 .map(|index| self.elems[index].0.clone())
                 .collect(),
             // Not supported here.
-            Selector::Regex(_) => EcoVec::new(),
+            Selector::Regex(_)
+            | Selector::Within { .. }
+            | Selector::FirstIn { .. }
+            | Selector::AtDepth { .. } => EcoVec::new(),
         };
 
         self.queries.insert(hash, output.clone());
@@ -253,6 +335,21 @@ impl Introspector {
         Ok(&self.elems[indices[0]].0)
     }
 
+    /// Query for all matching elements on the given physical page.
+    pub fn query_page(&self, selector: &Selector, page: NonZeroUsize) -> EcoVec<Content> {
+        let Some(record) = self.records.get(page.get() - 1) else {
+            return EcoVec::new();
+        };
+
+        // The query results are in document order, so the ones on the page
+        // are a contiguous slice.
+        let list = self.query(selector);
+        let start =
+            list.partition_point(|elem| self.elem_index(elem) < record.range.start);
+        let end = list.partition_point(|elem| self.elem_index(elem) < record.range.end);
+        list[start..end].iter().cloned().collect()
+    }
+
     /// This is an optimized version of
     /// `query(selector.before(end, true).len()` used by counters and state.
     pub fn query_count_before(&self, selector: &Selector, end: Location) -> usize {
@@ -290,7 +387,7 @@ impl Introspector {
     pub fn position(&self, location: Location) -> Position {
         self.elems
             .get(&location)
-            .map(|&(_, pos)| pos)
+            .map(|&(_, pos, _)| pos)
             .unwrap_or(Position { page: NonZeroUsize::ONE, point: Point::zero() })
     }
 
@@ -316,6 +413,19 @@ impl Debug for Introspector {
     }
 }
 
+/// What the introspector extracted from a page.
+#[derive(Clone)]
+struct PageRecord {
+    /// The page's frame, to detect whether it changed.
+    frame: Frame,
+    /// The range of the page's elements in the element list.
+    range: Range<usize>,
+    /// Whether all elements of the page made it into the list, i.e. none was
+    /// skipped because an earlier page already contained it. Only then can
+    /// the page's elements be copied over when it didn't change.
+    complete: bool,
+}
+
 /// Caches queries.
 #[derive(Default)]
 struct QueryCache(RwLock<HashMap<u128, EcoVec<Content>>>);
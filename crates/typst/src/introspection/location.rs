@@ -1,10 +1,10 @@
 use std::fmt::{self, Debug, Formatter};
 use std::num::NonZeroUsize;
 
-use ecow::EcoString;
+use ecow::{eco_format, EcoString};
 
 use crate::engine::Engine;
-use crate::foundations::{func, scope, ty, Repr};
+use crate::foundations::{func, scope, ty, Repr, Str};
 use crate::layout::Position;
 use crate::model::Numbering;
 
@@ -15,6 +15,11 @@ use crate::model::Numbering;
 /// location with the [`here`] function and the location of a queried or shown
 /// element with the [`location()`]($content.location) method on content.
 ///
+/// Locations are derived from the content of the document and its structure,
+/// so the same element has the same location when the same document is
+/// compiled again. Its [`id`]($location.id) can thus be used to refer to parts
+/// of a document from the outside.
+///
 /// # Locatable elements { #locatable }
 /// Currently, only a subset of element functions is locatable. Aside from
 /// headings and figures, this includes equations, references and all elements
@@ -91,6 +96,30 @@ impl Location {
     pub fn page_numbering(self, engine: &mut Engine) -> Option<Numbering> {
         engine.introspector.page_numbering(self).cloned()
     }
+
+    /// Returns a stable identifier for this location.
+    ///
+    /// The identifier is a string of 32 lowercase hexadecimal digits. It is
+    /// derived from the element and its place in the document's structure
+    /// and not from the element's position on the pages. Thus, it stays the
+    /// same when the same document is compiled again, which makes it usable
+    /// as an anchor name. Edits to the document may change it, in particular
+    /// edits to the element itself, its ancestors, or equal elements that
+    /// precede it.
+    ///
+    /// ```example
+    /// #show heading: it => block[
+    ///   #it.body
+    ///   #text(6pt, raw(it.location().id().slice(0, 8)))
+    /// ]
+    ///
+    /// = Introduction
+    /// = Methods
+    /// ```
+    #[func]
+    pub fn id(self) -> Str {
+        eco_format!("{:032x}", self.0).into()
+    }
 }
 
 impl Debug for Location {
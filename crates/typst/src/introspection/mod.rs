@@ -1,6 +1,7 @@
 //! Interaction between document parts.
 
 mod counter;
+mod divergence;
 #[path = "here.rs"]
 mod here_;
 mod introspector;
@@ -14,6 +15,7 @@ mod query_;
 mod state;
 
 pub use self::counter::*;
+pub use self::divergence::*;
 pub use self::here_::*;
 pub use self::introspector::*;
 pub use self::locate_::*;
@@ -2,11 +2,11 @@ use comemo::{Track, Tracked, TrackedMut};
 use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 
 use crate::diag::{bail, At, SourceResult};
-use crate::engine::{Engine, Route, Sink, Traced};
+use crate::engine::{Budget, Engine, Route, Sink, Traced};
 use crate::foundations::{
     cast, elem, func, scope, select_where, ty, Args, Construct, Content, Context, Func,
-    LocatableSelector, NativeElement, Packed, Repr, Selector, Show, Str, StyleChain,
-    Value,
+    IntoValue, LocatableSelector, NativeElement, Packed, Repr, Selector, Show, Str,
+    StyleChain, Value,
 };
 use crate::introspection::{Introspector, Locatable, Location};
 use crate::syntax::Span;
@@ -214,6 +214,7 @@ impl State {
             engine.world,
             engine.introspector,
             engine.traced,
+            engine.budget,
             TrackedMut::reborrow_mut(&mut engine.sink),
             engine.route.track(),
         )
@@ -226,6 +227,7 @@ impl State {
         world: Tracked<dyn World + '_>,
         introspector: Tracked<Introspector>,
         traced: Tracked<Traced>,
+        budget: Tracked<Budget>,
         sink: TrackedMut<Sink>,
         route: Tracked<Route>,
     ) -> SourceResult<EcoVec<Value>> {
@@ -233,6 +235,7 @@ impl State {
             world,
             introspector,
             traced,
+            budget,
             sink,
             route: Route::extend(route).unnested(),
         };
@@ -257,6 +260,27 @@ impl State {
     fn selector(&self) -> Selector {
         select_where!(StateUpdateElem, Key => self.key.clone())
     }
+
+    /// The updates of all states in a document, grouped by the states' keys.
+    /// Each update comes with the value it sets or the function it applies.
+    pub(super) fn updates(
+        introspector: &Introspector,
+    ) -> Vec<(Str, Vec<(Content, Value)>)> {
+        let mut states: Vec<(Str, Vec<(Content, Value)>)> = vec![];
+        for elem in introspector.query(&StateUpdateElem::elem().select()) {
+            let update = elem.to_packed::<StateUpdateElem>().unwrap();
+            let value = match update.update() {
+                StateUpdate::Set(value) => value.clone(),
+                StateUpdate::Func(func) => func.clone().into_value(),
+            };
+            let key = update.key();
+            match states.iter_mut().find(|(other, _)| other == key) {
+                Some((_, updates)) => updates.push((elem, value)),
+                None => states.push((key.clone(), vec![(elem, value)])),
+            }
+        }
+        states
+    }
 }
 
 #[scope]
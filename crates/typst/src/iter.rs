@@ -0,0 +1,169 @@
+//! Incremental layout of a document.
+
+use comemo::{Track, Tracked};
+use ecow::EcoVec;
+
+use crate::compile::{deduplicate, layout_converged, sort_by_span};
+use crate::diag::{SourceDiagnostic, SourceResult};
+use crate::engine::{Budget, Route, Sink, Traced};
+use crate::foundations::{Content, StyleChain};
+use crate::introspection::Introspector;
+use crate::layout::Page;
+use crate::World;
+
+/// Lay out the main source file chunk by chunk, yielding pages as soon as they
+/// are finished.
+///
+/// Unlike [`compile`], this doesn't wait for the whole document, such that a
+/// preview can show the first pages of a long document while the rest is still
+/// being laid out. The first chunk stops after the first page, and each
+/// following chunk lays out twice as many pages as the one before, such that
+/// the time spent on relayouting earlier pages stays proportional to the size
+/// of the document. Each chunk is laid out until introspections stabilize, and
+/// then its pages are yielded along with their index.
+///
+/// Introspections can look ahead, for example when an outline on the first
+/// page lists the headings of later chapters. If laying out a chunk changes
+/// pages that were already yielded, these pages are yielded again with their
+/// new content. Once the iterator is exhausted, the latest version of each page
+/// reflects the complete document, and [`PageIter::pages`] holds all of them.
+pub fn layout_document_iter(world: &dyn World) -> PageIter<'_> {
+    PageIter {
+        world: world.track(),
+        content: None,
+        sink: Sink::new(),
+        logged: None,
+        introspector: Introspector::default(),
+        pages: vec![],
+        yielded: vec![],
+        limit: 0,
+        done: false,
+    }
+}
+
+/// An iterator over the pages of a document that is laid out lazily.
+///
+/// Created by [`layout_document_iter`].
+pub struct PageIter<'a> {
+    /// The world to compile in.
+    world: Tracked<'a, dyn World + 'a>,
+    /// The evaluated content of the main source file.
+    content: Option<Content>,
+    /// Collects warnings and delayed errors.
+    sink: Sink,
+    /// The number of messages logged during evaluation.
+    logged: Option<usize>,
+    /// The introspector of the pages laid out so far.
+    introspector: Introspector,
+    /// The latest version of the pages laid out so far.
+    pages: Vec<Page>,
+    /// Hashes of the yielded versions of the pages.
+    yielded: Vec<u128>,
+    /// The number of pages the last chunk was limited to.
+    limit: usize,
+    /// Whether all pages were laid out or an error occurred.
+    done: bool,
+}
+
+impl PageIter<'_> {
+    /// The latest version of the pages laid out so far.
+    pub fn pages(&self) -> &[Page] {
+        &self.pages
+    }
+
+    /// The messages that the document logged so far.
+    pub fn logs(&self) -> EcoVec<SourceDiagnostic> {
+        sort_by_span(self.sink.logs())
+    }
+
+    /// The warnings that occurred during layout.
+    pub fn warnings(self) -> EcoVec<SourceDiagnostic> {
+        sort_by_span(self.sink.warnings())
+    }
+
+    /// Lay out the next chunk together with the previous ones. Returns whether
+    /// there are more pages.
+    fn layout_chunk(&mut self) -> SourceResult<bool> {
+        let world = self.world;
+        let library = world.library();
+        let styles = StyleChain::new(&library.styles);
+        let traced = Traced::default();
+
+        let content = match &self.content {
+            Some(content) => content,
+            None => self.content.insert(
+                crate::eval::eval(
+                    world,
+                    traced.track(),
+                    self.sink.track_mut(),
+                    Route::default().track(),
+                    &world.main(),
+                )?
+                .content(),
+            ),
+        };
+
+        // Each chunk lays out the previous ones again, so the messages logged
+        // while laying out the previous chunk are dropped.
+        let logged = *self.logged.get_or_insert(self.sink.logged());
+        self.sink.truncate_logs(logged);
+
+        self.limit = (2 * self.limit).max(1);
+        let document = layout_converged(
+            world,
+            traced.track(),
+            &Budget::default(),
+            content,
+            styles,
+            Some(self.limit),
+            std::mem::take(&mut self.introspector),
+            &mut self.sink,
+        )?;
+
+        // The pages after the limit are only there to fill up a page run
+        // and may be laid out differently once more pages are allowed.
+        let more = document.incomplete.is_some();
+        self.pages = document.pages;
+        if more {
+            self.pages.truncate(self.limit);
+        }
+
+        self.introspector = document.introspector;
+        self.yielded.truncate(self.pages.len());
+        Ok(more)
+    }
+}
+
+impl Iterator for PageIter<'_> {
+    type Item = SourceResult<(usize, Page)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // Yield the first page that is new or changed since it was
+            // last yielded.
+            for (i, page) in self.pages.iter().enumerate() {
+                let hash = crate::utils::hash128(&(&page.frame, page.number));
+                if self.yielded.get(i) != Some(&hash) {
+                    match self.yielded.get_mut(i) {
+                        Some(yielded) => *yielded = hash,
+                        None => self.yielded.push(hash),
+                    }
+                    return Some(Ok((i, page.clone())));
+                }
+            }
+
+            if self.done {
+                return None;
+            }
+
+            match self.layout_chunk() {
+                Ok(more) => self.done = !more,
+                Err(errors) => {
+                    self.done = true;
+                    self.pages.clear();
+                    return Some(Err(sort_by_span(deduplicate(errors))));
+                }
+            }
+        }
+    }
+}
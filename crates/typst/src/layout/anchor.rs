@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use comemo::Tracked;
+
+use crate::foundations::{elem, NativeElement, Packed, Str};
+use crate::introspection::{Introspector, Locatable, Location};
+use crate::layout::{Abs, Length};
+use crate::realize::{Behave, Behaviour};
+
+/// Aligns content across sibling columns or grid cells.
+///
+/// Anchors with the same key that end up on the same page are moved down to
+/// the same vertical position: Wherever an anchor is higher up than one of its
+/// siblings, space is inserted in front of the content following it. This way,
+/// a text and its translation can be aligned paragraph by paragraph, even if
+/// the paragraphs differ in length.
+///
+/// Anchors are resolved during layout through [introspection]($context), so a
+/// document with anchors needs an extra layout iteration. They are block-level
+/// and break a paragraph they appear in.
+///
+/// # Example
+/// ```example
+/// #set page(columns: 2, height: 120pt)
+/// Der Anfang ist die Hälfte
+/// des Ganzen.
+/// #anchor("two")
+/// Aller Anfang ist schwer.
+/// #colbreak()
+/// The beginning is half of
+/// the whole, at least that
+/// is what they say.
+/// #anchor("two")
+/// Every beginning is hard.
+/// ```
+#[elem(Locatable, Behave)]
+pub struct AnchorElem {
+    /// The key identifying the anchors that are aligned with each other.
+    #[required]
+    pub key: Str,
+
+    /// The space inserted after previous anchors in the same region.
+    #[internal]
+    #[synthesized]
+    pub shift: Length,
+
+    /// The previous anchor in the same region.
+    #[internal]
+    #[synthesized]
+    pub prev: Option<Location>,
+}
+
+impl Behave for Packed<AnchorElem> {
+    fn behaviour(&self) -> Behaviour {
+        Behaviour::Invisible
+    }
+}
+
+impl Packed<AnchorElem> {
+    /// Prepares this anchor for being placed into a region in which the given
+    /// space was inserted after the given previous anchor.
+    pub(crate) fn placed(&self, shift: Abs, prev: Option<Location>) -> Self {
+        let mut anchor = self.clone();
+        anchor.push_shift(shift.into());
+        anchor.push_prev(prev);
+        anchor
+    }
+}
+
+/// Determines how much space to insert after the anchor at the given location,
+/// based on the positions of all anchors in the previous layout iteration.
+///
+/// The computation only uses the anchors' positions without any inserted
+/// space, such that it yields the same result in all iterations once the
+/// content between the anchors is stable.
+#[comemo::memoize]
+pub(crate) fn anchor_spacing(
+    introspector: Tracked<Introspector>,
+    location: Location,
+) -> Abs {
+    let page = introspector.page(location);
+    let anchors: HashMap<Location, Packed<AnchorElem>> = introspector
+        .query(&AnchorElem::elem().select())
+        .into_iter()
+        .filter_map(|elem| elem.into_packed::<AnchorElem>().ok())
+        .filter_map(|anchor| Some((anchor.location()?, anchor)))
+        .filter(|(loc, _)| introspector.page(*loc) == page)
+        .collect();
+
+    let mut resolver = AnchorResolver { introspector, anchors, aligned: HashMap::new() };
+    let Some(anchor) = resolver.anchors.get(&location).cloned() else {
+        return Abs::zero();
+    };
+
+    let natural = resolver.natural(&anchor);
+    (resolver.aligned(&anchor) - natural).max(Abs::zero())
+}
+
+/// Resolves the aligned positions of the anchors on one page.
+struct AnchorResolver<'a> {
+    introspector: Tracked<'a, Introspector>,
+    /// The anchors on the page.
+    anchors: HashMap<Location, Packed<AnchorElem>>,
+    /// The aligned positions of the anchors, by key.
+    aligned: HashMap<Str, Abs>,
+}
+
+impl AnchorResolver<'_> {
+    /// The position of an anchor without any inserted space.
+    fn raw(&self, anchor: &Packed<AnchorElem>) -> Abs {
+        let y = self.introspector.position(anchor.location().unwrap()).point.y;
+        y - anchor.shift().map_or(Abs::zero(), |shift| shift.abs)
+    }
+
+    /// The position of an anchor once the anchors before it are aligned, but
+    /// it isn't yet.
+    fn natural(&mut self, anchor: &Packed<AnchorElem>) -> Abs {
+        let prev = anchor
+            .prev()
+            .copied()
+            .flatten()
+            .and_then(|prev| self.anchors.get(&prev).cloned());
+        match prev {
+            Some(prev) => self.aligned(&prev) + self.raw(anchor) - self.raw(&prev),
+            None => self.raw(anchor),
+        }
+    }
+
+    /// The position at which all anchors with the same key as this one end
+    /// up.
+    fn aligned(&mut self, anchor: &Packed<AnchorElem>) -> Abs {
+        if let Some(&y) = self.aligned.get(anchor.key()) {
+            return y;
+        }
+
+        // Provisionally use the raw position to guard against anchors whose
+        // key repeats in a region.
+        self.aligned.insert(anchor.key().clone(), self.raw(anchor));
+
+        let siblings: Vec<_> = self
+            .anchors
+            .values()
+            .filter(|sibling| sibling.key() == anchor.key())
+            .cloned()
+            .collect();
+        let y = siblings
+            .iter()
+            .map(|sibling| self.natural(sibling))
+            .fold(Abs::zero(), Abs::max);
+
+        self.aligned.insert(anchor.key().clone(), y);
+        y
+    }
+}
@@ -1,23 +1,26 @@
 use std::num::NonZeroUsize;
 
-use crate::diag::SourceResult;
+use crate::diag::{bail, SourceResult};
 use crate::engine::Engine;
-use crate::foundations::{elem, Content, NativeElement, Packed, Show, StyleChain};
+use crate::foundations::{elem, Cast, Content, NativeElement, Packed, Show, StyleChain};
 use crate::introspection::Locator;
 use crate::layout::{
-    Abs, Axes, BlockElem, Dir, Fragment, Frame, Length, Point, Ratio, Regions, Rel, Size,
+    find_footnotes, Abs, Axes, BlockElem, Dir, Em, Fragment, Frame, Length, Point, Ratio,
+    Regions, Rel, Size,
 };
+use crate::model::{FootnoteEntry, FootnoteScope};
 use crate::realize::{Behave, Behaviour};
+use crate::syntax::Spanned;
 use crate::text::TextElem;
 use crate::utils::Numeric;
 
 /// Separates a region into multiple equally sized columns.
 ///
 /// The `column` function allows to separate the interior of any container into
-/// multiple columns. It will not equalize the height of the columns, instead,
-/// the columns will take up the height of their container or the remaining
-/// height on the page. The columns function can break across pages if
-/// necessary.
+/// multiple columns. By default, it will not equalize the height of the
+/// columns, instead, the columns will take up the height of their container or
+/// the remaining height on the page. The columns function can break across
+/// pages if necessary.
 ///
 /// If you need to insert columns across your whole document, you can use the
 /// [`{page}` function's `columns` parameter]($page.columns) instead.
@@ -54,9 +57,67 @@ pub struct ColumnsElem {
     #[default(Ratio::new(0.04).into())]
     pub gutter: Rel<Length>,
 
+    /// Whether to balance the columns of the last region.
+    ///
+    /// When enabled, the content in the last region (typically the last page)
+    /// is distributed such that all columns end at roughly the same height,
+    /// instead of filling one column after the other. This also applies to
+    /// the [columns of a page]($page.columns).
+    ///
+    /// ```example
+    /// #columns(3, balance: true)[
+    ///   Balanced columns look tidier
+    ///   at the end of a chapter,
+    ///   where there may not be
+    ///   enough content to fill all
+    ///   columns to the bottom.
+    /// ]
+    /// ```
+    #[default(false)]
+    pub balance: bool,
+
+    /// How much the heights of balanced columns may differ.
+    ///
+    /// Smaller values result in more evenly balanced columns, but take more
+    /// time to compute. Must be positive.
+    #[parse({
+        let tolerance: Option<Spanned<Length>> = args.named("tolerance")?;
+        if let Some(Spanned { v, span }) = &tolerance {
+            if v.abs < Abs::zero() || v.em < Em::zero() || v.is_zero() {
+                bail!(*span, "tolerance must be positive");
+            }
+        }
+        tolerance.map(|tolerance| tolerance.v)
+    })]
+    #[resolve]
+    #[default(Abs::pt(1.0).into())]
+    pub tolerance: Length,
+
     /// The content that should be layouted into the columns.
     #[required]
     pub body: Content,
+
+    /// How the columns the content is currently laid out into are arranged.
+    #[internal]
+    #[ghost]
+    pub arrangement: Option<ColumnArrangement>,
+}
+
+/// How the columns of a [`ColumnsElem`] are arranged in their parent.
+///
+/// This is made available to the flow of the columns' body, such that floats
+/// with [parent scope]($place.scope) can span all columns.
+#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+pub struct ColumnArrangement {
+    /// The amount of columns per region.
+    pub count: usize,
+    /// The width of the parent, which is spanned by all columns.
+    pub width: Abs,
+    /// The horizontal offset of the first column in the parent.
+    pub first: Abs,
+    /// The horizontal distance from the start of one column to the start of
+    /// the next one, which is negative for right-to-left columns.
+    pub step: Abs,
 }
 
 impl Show for Packed<ColumnsElem> {
@@ -89,30 +150,139 @@ fn layout_columns(
     let columns = elem.count(styles).get();
     let gutter = elem.gutter(styles).relative_to(regions.base().x);
     let width = (regions.size.x - gutter * (columns - 1) as f64) / columns as f64;
+    let dir = TextElem::dir_in(styles);
 
-    let backlog: Vec<_> = std::iter::once(&regions.size.y)
+    // Let the body's flow know how the columns are arranged, such that
+    // parent-scoped floats can span all of them.
+    let arrangement = ColumnArrangement {
+        count: columns,
+        width: regions.size.x,
+        first: if dir == Dir::LTR { Abs::zero() } else { regions.size.x - width },
+        step: if dir == Dir::LTR { width + gutter } else { -(width + gutter) },
+    };
+    let local = ColumnsElem::set_arrangement(Some(arrangement)).wrap();
+    let inner = styles.chain(&local);
+
+    // The heights of all columns, in order.
+    let heights: Vec<_> = std::iter::once(&regions.size.y)
         .chain(regions.backlog)
         .flat_map(|&height| std::iter::repeat(height).take(columns))
-        .skip(1)
         .collect();
 
-    // Create the pod regions.
-    let pod = Regions {
-        size: Size::new(width, regions.size.y),
-        full: regions.full,
-        backlog: &backlog,
-        last: regions.last,
-        expand: Axes::new(true, regions.expand.y),
-        root: regions.root,
+    // Layout the children into columns with the given heights.
+    let layout = |engine: &mut Engine, locator: Locator, heights: &[Abs]| {
+        let pod = Regions {
+            size: Size::new(width, heights[0]),
+            full: regions.full,
+            backlog: &heights[1..],
+            last: regions.last,
+            expand: Axes::new(true, regions.expand.y),
+            root: regions.root,
+        };
+        body.layout(engine, locator, inner, pod)
     };
 
-    // Layout the children.
-    let mut frames = body.layout(engine, locator, styles, pod)?.into_iter();
-    let mut finished = vec![];
+    // Layout the children, balancing the columns in the last region if
+    // requested.
+    let balanced = |engine: &mut Engine, locator: Locator, mut heights: Vec<Abs>| {
+        if !elem.balance(styles) {
+            return layout(engine, locator, &heights);
+        }
 
-    let dir = TextElem::dir_in(styles);
+        let tolerance = elem.tolerance(styles);
+        let natural = layout(engine, locator.relayout(), &heights)?;
+
+        // The index of the first column in the last region with content.
+        let last = natural.iter().rposition(|frame| !frame.is_empty()).unwrap_or(0);
+        let first = last / columns * columns;
+        if let Some(&last) = regions.last.as_ref().or(heights.last()) {
+            heights.resize(heights.len().max(first + columns), last);
+        }
+
+        // Search for the smallest column height in the last region at which
+        // the content still fits into it. In an unbounded region, all content
+        // ended up in the first column, so its height is an upper bound.
+        let mut lo = Abs::zero();
+        let mut hi = heights[first];
+        if !hi.is_finite() {
+            hi = natural.as_slice()[first].height();
+        }
+
+        // The trial layouts get an extra column after the last region, so
+        // that content which doesn't fit spills over into it instead of
+        // overflowing the last column. The content fits if it stays empty.
+        let mut trial = heights[..first + columns].to_vec();
+        trial.push(hi);
+        while hi - lo > tolerance {
+            let mid = (lo + hi) / 2.0;
+            trial[first..first + columns].fill(mid);
+            let fragment = layout(engine, locator.relayout(), &trial)?;
+            if fragment.iter().skip(first + columns).all(Frame::is_empty) {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+        }
+
+        heights[first..first + columns].fill(hi);
+        layout(engine, locator, &heights)
+    };
+
+    // With page-scoped footnotes, the flow leaves the footnotes to us. We then
+    // reserve room for them below the columns of each region and lay the
+    // columns out again until the footnotes of each region fit.
+    let page_footnotes =
+        regions.root && FootnoteEntry::scope_in(styles) == FootnoteScope::Page;
+    let mut reserved: Vec<Abs> = vec![];
+    let mut attempts = 0;
+    let (fragment, areas) = loop {
+        let mut heights = heights.clone();
+        for (region, &height) in reserved.iter().enumerate() {
+            let start = region * columns;
+            if let Some(&last) = regions.last.as_ref().or(heights.last()) {
+                heights.resize(heights.len().max(start + columns), last);
+            }
+            for column in &mut heights[start..start + columns] {
+                *column -= height;
+            }
+        }
+
+        let fragment = balanced(engine, locator.relayout(), heights)?;
+        if !page_footnotes {
+            break (fragment, vec![]);
+        }
+
+        let areas = fragment
+            .as_slice()
+            .chunks(columns)
+            .map(|frames| layout_footnote_area(engine, frames, regions.size.x, styles))
+            .collect::<SourceResult<Vec<_>>>()?;
+
+        // Only ever grow the reserved room, so that this terminates.
+        let mut grown = false;
+        for (region, area) in areas.iter().enumerate() {
+            let height = area.as_ref().map(Frame::height).unwrap_or_default();
+            if region >= reserved.len() {
+                reserved.push(Abs::zero());
+            }
+            if height > reserved[region] {
+                reserved[region] = height;
+                grown = true;
+            }
+        }
+
+        attempts += 1;
+        if !grown || attempts >= MAX_FOOTNOTE_ATTEMPTS {
+            break (fragment, areas);
+        }
+    };
+
+    let mut frames = fragment.into_iter();
+    let mut finished = vec![];
     let total_regions = (frames.len() as f32 / columns as f32).ceil() as usize;
 
+    let mut areas = areas.into_iter();
+
     // Stitch together the columns for each region.
     for region in regions.iter().take(total_regions) {
         // The height should be the parent height if we should expand.
@@ -137,12 +307,101 @@ fn layout_columns(
             cursor += width + gutter;
         }
 
+        // Place the page-scoped footnotes at the bottom.
+        if let Some(area) = areas.next().flatten() {
+            let y = if regions.expand.y {
+                output.height() - area.height()
+            } else {
+                output.height()
+            };
+            output.size_mut().y = output.height().max(y + area.height());
+            output.push_frame(Point::with_y(y), area);
+        }
+
         finished.push(output);
     }
 
     Ok(Fragment::frames(finished))
 }
 
+/// How often the columns are laid out again to make room for page-scoped
+/// footnotes.
+const MAX_FOOTNOTE_ATTEMPTS: usize = 5;
+
+/// Lays out the page-scoped footnotes referenced in the columns of one region
+/// into an area that spans all columns, starting with the separator.
+fn layout_footnote_area(
+    engine: &mut Engine,
+    frames: &[Frame],
+    width: Abs,
+    styles: StyleChain,
+) -> SourceResult<Option<Frame>> {
+    let mut notes = vec![];
+    for frame in frames {
+        find_footnotes(&mut notes, frame);
+    }
+    if notes.iter().all(|note| note.is_ref()) {
+        return Ok(None);
+    }
+
+    let pod = Regions::one(Size::new(width, Abs::inf()), Axes::new(true, false));
+    let clearance = FootnoteEntry::clearance_in(styles);
+    let gap = FootnoteEntry::gap_in(styles);
+
+    // FIXME: Shouldn't use `root()` here.
+    let separator = FootnoteEntry::separator_in(styles)
+        .layout(engine, Locator::root(), styles, pod)?
+        .into_frame();
+
+    let mut entries = vec![];
+    let mut k = 0;
+    while k < notes.len() {
+        let note = notes[k].clone();
+        k += 1;
+        if note.is_ref() {
+            continue;
+        }
+
+        let stream = note.stream(StyleChain::default()).clone();
+        let locator = Locator::synthesize(note.location().unwrap());
+        let frame = FootnoteEntry::new(note)
+            .pack()
+            .layout(engine, locator, styles, pod)?
+            .into_frame();
+
+        // Place nested notes right after the one they occur in.
+        let prev = notes.len();
+        find_footnotes(&mut notes, &frame);
+        let nested = notes.len() - prev;
+        notes[k..].rotate_right(nested);
+
+        entries.push((stream, frame));
+    }
+
+    // Stack the entries of each note stream below a separator of their own,
+    // starting with the default stream.
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let mut output = Frame::soft(Size::with_x(width));
+    let mut prev = None;
+    for (stream, frame) in entries {
+        let mut y = output.height();
+        if prev.as_ref() != Some(&stream) {
+            if prev.is_some() {
+                y += gap;
+            }
+            output.push_frame(Point::with_y(y + clearance), separator.clone());
+            y += clearance + separator.height();
+            prev = Some(stream);
+        }
+
+        y += gap;
+        output.size_mut().y = y + frame.height();
+        output.push_frame(Point::with_y(y), frame);
+    }
+
+    Ok(Some(output))
+}
+
 /// Forces a column break.
 ///
 /// The function will behave like a [page break]($pagebreak) when used in a
@@ -170,13 +429,46 @@ fn layout_columns(
 pub struct ColbreakElem {
     /// If `{true}`, the column break is skipped if the current column is
     /// already empty.
+    ///
+    /// When breaking to the next page, a weak break is only skipped if the
+    /// current column is also the first one on its page.
+    ///
+    /// ```example
+    /// #set page(columns: 2, height: 60pt)
+    /// #colbreak(weak: true)
+    /// Still in the first column.
+    /// ```
     #[default(false)]
     pub weak: bool,
+
+    /// Where to continue after the break.
+    ///
+    /// ```example
+    /// #set page(columns: 3, height: 60pt)
+    /// First column.
+    /// #colbreak(break-to: "next-page")
+    /// First column of the next page.
+    /// ```
+    #[default(ColbreakTarget::NextColumn)]
+    pub break_to: ColbreakTarget,
+}
+
+/// Where to continue after a column break.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum ColbreakTarget {
+    /// In the next column, or on the next page after the last column.
+    NextColumn,
+    /// In the first column of the next page, skipping the remaining columns
+    /// of the current page.
+    NextPage,
 }
 
 impl Behave for Packed<ColbreakElem> {
     fn behaviour(&self) -> Behaviour {
-        if self.weak(StyleChain::default()) {
+        // A weak break to the next page isn't redundant after another break,
+        // so the flow decides whether it collapses.
+        let styles = StyleChain::default();
+        if self.weak(styles) && self.break_to(styles) == ColbreakTarget::NextColumn {
             Behaviour::Weak(1)
         } else {
             Behaviour::Destructive
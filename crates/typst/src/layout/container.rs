@@ -1,18 +1,22 @@
+use std::num::NonZeroUsize;
+
 use once_cell::unsync::Lazy;
 use smallvec::SmallVec;
 
 use crate::diag::{bail, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, Args, AutoValue, Construct, Content, NativeElement, Packed, Resolve,
-    Smart, StyleChain, Value,
+    cast, elem, Args, AutoValue, Cast, Construct, Content, NativeElement, Packed,
+    Resolve, Smart, StyleChain, Value,
 };
 use crate::introspection::Locator;
 use crate::layout::{
-    Abs, Axes, Corners, Em, Fr, Fragment, Frame, FrameKind, Length, Region, Regions, Rel,
-    Sides, Size, Spacing,
+    Abs, Axes, Corners, Em, Fr, Fragment, Frame, FrameKind, Length, Point, Ratio, Region,
+    Regions, Rel, Sides, Size, Spacing, Transform,
 };
-use crate::utils::Numeric;
+use crate::model::{first_baseline, last_baseline};
+use crate::text::TextElem;
+use crate::utils::{NonZeroExt, Numeric};
 use crate::visualize::{clip_rect, Paint, Stroke};
 
 /// An inline-level container that sizes content.
@@ -50,13 +54,28 @@ pub struct BoxElem {
     /// The height of the box.
     pub height: Smart<Rel<Length>>,
 
-    /// An amount to shift the box's baseline by.
+    /// An amount to shift the box's baseline by or which of its lines to
+    /// align with the surrounding text.
+    ///
+    /// - If given a length, shifts the box's baseline up from its bottom edge
+    ///   by that amount. A relative length is resolved relative to the box's
+    ///   height.
+    /// - If given `{"first"}` or `{"last"}`, aligns the baseline of the box's
+    ///   first or last line of text with the baseline of the surrounding
+    ///   line. If the box contains no text, its bottom edge is aligned with
+    ///   the baseline instead.
     ///
     /// ```example
     /// Image: #box(baseline: 40%, image("tiger.jpg", width: 2cm)).
+    ///
+    /// Note: #box(
+    ///   baseline: "first",
+    ///   width: 3.5cm,
+    ///   stroke: 0.5pt,
+    ///   inset: 3pt,
+    /// )[The first line of this box sits on the baseline.]
     /// ```
-    #[resolve]
-    pub baseline: Rel<Length>,
+    pub baseline: BoxBaseline,
 
     /// The box's background color. See the
     /// [rectangle's documentation]($rect.fill) for more details.
@@ -109,6 +128,21 @@ pub struct BoxElem {
     #[default(false)]
     pub clip: bool,
 
+    /// What to do with content that does not fit into the box.
+    ///
+    /// This only has an effect when the content is larger than the box's
+    /// size. Setting `clip` to `{true}` is equivalent to an overflow of
+    /// `{"clip"}`.
+    ///
+    /// ```example
+    /// #set box(width: 40pt, stroke: 0.5pt, inset: 2pt)
+    /// #box(overflow: "visible")[Overflowing] \
+    /// #box(overflow: "clip")[Overflowing] \
+    /// #box(overflow: "ellipsis")[Overflowing] \
+    /// #box(overflow: "scale")[Overflowing]
+    /// ```
+    pub overflow: Overflow,
+
     /// The contents of the box.
     #[positional]
     #[borrowed]
@@ -134,6 +168,7 @@ impl Packed<BoxElem> {
         let pod = Self::pod(&width, &height, &inset, styles, region);
 
         // Layout the body.
+        let overflow = self.overflow(styles);
         let mut frame = match self.body(styles) {
             // If we have no body, just create an empty frame. If necessary,
             // its size will be adjusted below.
@@ -141,10 +176,17 @@ impl Packed<BoxElem> {
 
             // If we have a child, layout it into the body. Boxes are boundaries
             // for gradient relativeness, so we set the `FrameKind` to `Hard`.
-            Some(body) => body
-                .layout(engine, locator, styles, pod.into_regions())?
-                .into_frame()
-                .with_kind(FrameKind::Hard),
+            Some(body) => match overflow {
+                Overflow::Visible | Overflow::Clip => {
+                    body.layout(engine, locator, styles, pod.into_regions())?
+                }
+                Overflow::Ellipsis => {
+                    layout_ellipsized(engine, locator, styles, body, pod)?
+                }
+                Overflow::Scale => layout_scaled(engine, locator, styles, body, pod)?,
+            }
+            .into_frame()
+            .with_kind(FrameKind::Hard),
         };
 
         // Enforce a correct frame size on the expanded axes. Do this before
@@ -156,12 +198,26 @@ impl Packed<BoxElem> {
             crate::layout::grow(&mut frame, &inset);
         }
 
-        // Apply baseline shift. Do this after setting the size and applying the
-        // inset, so that a relative shift is resolved relative to the final
-        // height.
-        let shift = self.baseline(styles).relative_to(frame.height());
-        if !shift.is_zero() {
-            frame.set_baseline(frame.baseline() - shift);
+        // Apply baseline shift or alignment. Do this after setting the size and
+        // applying the inset, so that a relative shift is resolved relative to
+        // the final height and the lines are at their final positions.
+        match self.baseline(styles) {
+            BoxBaseline::Shift(shift) => {
+                let shift = shift.resolve(styles).relative_to(frame.height());
+                if !shift.is_zero() {
+                    frame.set_baseline(frame.baseline() - shift);
+                }
+            }
+            BoxBaseline::First => {
+                if let Some(baseline) = first_baseline(&frame) {
+                    frame.set_baseline(baseline);
+                }
+            }
+            BoxBaseline::Last => {
+                if let Some(baseline) = last_baseline(&frame) {
+                    frame.set_baseline(baseline);
+                }
+            }
         }
 
         // Prepare fill and stroke.
@@ -176,7 +232,7 @@ impl Packed<BoxElem> {
         let radius = Lazy::new(|| self.radius(styles).unwrap_or_default());
 
         // Clip the contents, if requested.
-        if self.clip(styles) {
+        if self.clip(styles) || overflow != Overflow::Visible {
             let size = frame.size() + outset.relative_to(frame.size()).sum_by_axis();
             frame.clip(clip_rect(size, &radius, &stroke));
         }
@@ -228,6 +284,120 @@ impl Packed<BoxElem> {
     }
 }
 
+/// Layout a box body on a single line, cutting it off with an ellipsis if it
+/// is wider than the pod.
+fn layout_ellipsized(
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    body: &Content,
+    pod: Region,
+) -> SourceResult<Fragment> {
+    let mut locator = locator.split();
+
+    // Measure the body without a width limit, so that it stays on one line.
+    let unbounded = Region::new(Size::new(Abs::inf(), pod.size.y), Axes::splat(false));
+    let mut frame = body
+        .layout(engine, locator.next(&()), styles, unbounded.into_regions())?
+        .into_frame();
+    if !pod.size.x.is_finite() || pod.size.x.fits(frame.width()) {
+        frame.set_size(pod.expand.select(pod.size, frame.size()));
+        return Ok(Fragment::frame(frame));
+    }
+
+    // Cut off the content and append the ellipsis after what remains.
+    let ellipsis = TextElem::packed('…')
+        .layout(engine, locator.next(&()), styles, unbounded.into_regions())?
+        .into_frame();
+    let limit = (pod.size.x - ellipsis.width()).max(Abs::zero());
+    let end = frame.truncate(limit);
+    let y = frame.baseline() - ellipsis.baseline();
+    frame.push_frame(Point::new(end, y), ellipsis);
+    frame.size_mut().x = pod.size.x;
+    Ok(Fragment::frame(frame))
+}
+
+/// Layout a box body at its natural size and uniformly scale it down if it is
+/// larger than the pod.
+fn layout_scaled(
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    body: &Content,
+    pod: Region,
+) -> SourceResult<Fragment> {
+    // Measure the body without a width limit, so that it isn't wrapped.
+    let unbounded = Region::new(Size::new(Abs::inf(), pod.size.y), Axes::splat(false));
+    let mut frame = body
+        .layout(engine, locator, styles, unbounded.into_regions())?
+        .into_frame();
+
+    // Determine the largest factor that makes the frame fit on both axes.
+    let ratios = pod.size.zip_map(frame.size(), |available, natural| {
+        if available.is_finite() && natural > available {
+            available / natural
+        } else {
+            1.0
+        }
+    });
+    let factor = ratios.x.min(ratios.y);
+
+    if factor < 1.0 {
+        let baseline = frame.baseline();
+        frame.transform(Transform::scale(Ratio::new(factor), Ratio::new(factor)));
+        frame.set_size(frame.size() * factor);
+        frame.set_baseline(baseline * factor);
+    }
+
+    Ok(Fragment::frame(frame))
+}
+
+/// Defines the baseline of a box.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum BoxBaseline {
+    /// The bottom edge of the box, shifted up by an amount.
+    Shift(Rel<Length>),
+    /// The baseline of the box's first line of text.
+    First,
+    /// The baseline of the box's last line of text.
+    Last,
+}
+
+impl Default for BoxBaseline {
+    fn default() -> Self {
+        Self::Shift(Rel::zero())
+    }
+}
+
+cast! {
+    BoxBaseline,
+    self => match self {
+        Self::Shift(shift) => shift.into_value(),
+        Self::First => "first".into_value(),
+        Self::Last => "last".into_value(),
+    },
+    /// The baseline of the box's first line of text.
+    "first" => Self::First,
+    /// The baseline of the box's last line of text.
+    "last" => Self::Last,
+    v: Rel<Length> => Self::Shift(v),
+}
+
+/// What to do with content that does not fit into its container.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Overflow {
+    /// The content is shown in full, even beyond the container's bounds.
+    #[default]
+    Visible,
+    /// The content is cut off at the container's bounds.
+    Clip,
+    /// The content is laid out on a single line and cut off with an ellipsis
+    /// if it is too wide.
+    Ellipsis,
+    /// The content is uniformly scaled down until it fits.
+    Scale,
+}
+
 /// An inline-level container that can produce arbitrary items that can break
 /// across lines.
 #[elem(Construct)]
@@ -357,6 +527,48 @@ pub struct BlockElem {
     #[default(true)]
     pub breakable: bool,
 
+    /// Content to show at the end of each region the block breaks out of,
+    /// that is, in every region but the last one.
+    ///
+    /// Space for it is reserved in every region, so that it never overlaps
+    /// with the block's contents. It has no effect if the block is not
+    /// breakable.
+    ///
+    /// ```example
+    /// #set page(height: 100pt)
+    /// #block(
+    ///   before-break: align(right)[_Continued ..._],
+    ///   after-break: [_... continuation_],
+    ///   lorem(25),
+    /// )
+    /// ```
+    #[borrowed]
+    pub before_break: Option<Content>,
+
+    /// Content to show at the start of each region the block continues in,
+    /// that is, in every region but the first one.
+    ///
+    /// See [`before-break`]($block.before-break) for more details.
+    #[borrowed]
+    pub after_break: Option<Content>,
+
+    /// Content to show at the top of every region the block spans, like a
+    /// repeated table header.
+    ///
+    /// This is useful for long blocks like code listings or proofs, whose
+    /// title should stay visible on every page. Like the content around
+    /// breaks, it has no effect if the block is not breakable.
+    ///
+    /// ```example
+    /// #set page(height: 100pt)
+    /// #block(
+    ///   sticky-header: strong[Proof.],
+    ///   lorem(25),
+    /// )
+    /// ```
+    #[borrowed]
+    pub sticky_header: Option<Content>,
+
     /// The block's background color. See the
     /// [rectangle's documentation]($rect.fill) for more details.
     pub fill: Option<Paint>,
@@ -427,13 +639,55 @@ pub struct BlockElem {
     #[default(false)]
     pub clip: bool,
 
-    /// Whether this block must stick to the following one.
+    /// What to do with content that does not fit into the block.
     ///
-    /// Use this to prevent page breaks between e.g. a heading and its body.
-    #[internal]
+    /// This works like the [`overflow`]($box.overflow) of a box. With
+    /// `{"ellipsis"}` and `{"scale"}`, the block is not broken across pages
+    /// or columns.
+    ///
+    /// ```example
+    /// #set block(width: 60pt, stroke: 0.5pt, inset: 2pt)
+    /// #block(overflow: "ellipsis")[Overflowing text]
+    /// #block(overflow: "scale")[Overflowing text]
+    /// ```
+    pub overflow: Overflow,
+
+    /// Whether this block must stay in the same region as the content
+    /// following it.
+    ///
+    /// If the next block or the first line of the next paragraph doesn't fit
+    /// into the current region anymore, this block is moved to the next
+    /// region along with it. Headings set this property, so that they are not
+    /// separated from their body. To keep a larger group of blocks together,
+    /// use [`keep`] instead.
+    ///
+    /// ```example
+    /// #set page(height: 80pt)
+    /// #lorem(14)
+    ///
+    /// #block(keep-with-next: true)[
+    ///   *Summary:*
+    /// ]
+    /// #lorem(6)
+    /// ```
     #[default(false)]
-    #[parse(None)]
-    pub sticky: bool,
+    pub keep_with_next: bool,
+
+    /// How many lines of a paragraph following this block must fit into the
+    /// current region along with it. Only has an effect if the block
+    /// [keeps with the next one]($block.keep-with-next).
+    ///
+    /// ```example
+    /// #set page(height: 100pt)
+    /// #lorem(14)
+    ///
+    /// #block(keep-with-next: true, keep-lines-after: 3)[
+    ///   *Summary:*
+    /// ]
+    /// #lorem(12)
+    /// ```
+    #[default(NonZeroUsize::ONE)]
+    pub keep_lines_after: NonZeroUsize,
 
     /// Whether this block can host footnotes.
     #[internal]
@@ -500,7 +754,9 @@ impl Packed<BlockElem> {
         let width = self.width(styles);
         let height = self.height(styles);
         let inset = self.inset(styles).unwrap_or_default();
-        let breakable = self.breakable(styles);
+        let overflow = self.overflow(styles);
+        let breakable = self.breakable(styles)
+            && !matches!(overflow, Overflow::Ellipsis | Overflow::Scale);
 
         // Allocate a small vector for backlogs.
         let mut buf = SmallVec::<[Abs; 2]>::new();
@@ -509,6 +765,54 @@ impl Packed<BlockElem> {
         let pod =
             Self::pod(&width, &height, &inset, breakable, styles, regions, &mut buf);
 
+        // Fetch the content to show around region breaks.
+        let (before_break, after_break, header) = match breakable {
+            true => (
+                self.before_break(styles),
+                self.after_break(styles),
+                self.sticky_header(styles),
+            ),
+            false => (&None, &None, &None),
+        };
+
+        // If there is such content, measure it and reserve space for it in the
+        // pod regions: The content before a break and the header need space in
+        // every region and the content after a break in every region but the
+        // first.
+        let mut pod = pod;
+        let mut backlog = vec![];
+        let mut markers = None;
+        let locator = if before_break.is_some()
+            || after_break.is_some()
+            || header.is_some()
+        {
+            let mut split = locator.split();
+            let mut measure = |content: &Option<Content>| -> SourceResult<Size> {
+                let Some(content) = content else { return Ok(Size::zero()) };
+                let locator = split.next(&content.span());
+                let region =
+                    Region::new(Size::new(pod.size.x, Abs::inf()), Axes::splat(false));
+                Ok(layout_break_marker(engine, locator, styles, content, region)?.size())
+            };
+
+            let before = measure(before_break)?;
+            let after = measure(after_break)?;
+            let head = measure(header)?;
+            let each = before.y + head.y;
+            let (first, full) = (pod.size.y - each, pod.full - each);
+            pod = pod.map(&mut backlog, |size| {
+                Size::new(size.x, (size.y - each - after.y).max(Abs::zero()))
+            });
+            pod.size.y = first.max(Abs::zero());
+            pod.full = full.max(Abs::zero());
+
+            let locator = split.next(&());
+            markers = Some((split, before.x.max(after.x).max(head.x)));
+            locator
+        } else {
+            locator
+        };
+
         // Layout the body.
         let body = self.body(styles);
         let mut fragment = match body {
@@ -528,6 +832,17 @@ impl Packed<BlockElem> {
                 Fragment::frames(frames)
             }
 
+            // Content that is ellipsized or scaled is laid out into a single
+            // region like for a box.
+            Some(BlockChild::Content(body)) if overflow == Overflow::Ellipsis => {
+                let pod = Region::new(pod.size, pod.expand);
+                layout_ellipsized(engine, locator, styles, body, pod)?
+            }
+            Some(BlockChild::Content(body)) if overflow == Overflow::Scale => {
+                let pod = Region::new(pod.size, pod.expand);
+                layout_scaled(engine, locator, styles, body, pod)?
+            }
+
             // If we have content as our body, just layout it.
             Some(BlockChild::Content(body)) => {
                 let mut fragment =
@@ -590,19 +905,18 @@ impl Packed<BlockElem> {
         let radius = Lazy::new(|| self.radius(styles).unwrap_or_default());
 
         // Fetch/compute these outside of the loop.
-        let clip = self.clip(styles);
+        let clip = self.clip(styles) || overflow != Overflow::Visible;
         let has_fill_or_stroke = fill.is_some() || stroke.iter().any(Option::is_some);
         let has_inset = !inset.is_zero();
         let is_explicit = matches!(body, None | Some(BlockChild::Content(_)));
 
-        // Skip filling/stroking the first frame if it is empty and a non-empty
-        // one follows.
+        // Skip filling/stroking the first frame and adding content around
+        // breaks to it if it is empty and a non-empty one follows.
         let mut skip_first = false;
         if let [first, rest @ ..] = fragment.as_slice() {
-            skip_first = has_fill_or_stroke
-                && first.is_empty()
-                && rest.iter().any(|frame| !frame.is_empty());
+            skip_first = first.is_empty() && rest.iter().any(|frame| !frame.is_empty());
         }
+        let count = fragment.len();
 
         // Post-process to apply insets, clipping, fills, and strokes.
         for (i, (frame, region)) in fragment.iter_mut().zip(pod.iter()).enumerate() {
@@ -615,6 +929,48 @@ impl Packed<BlockElem> {
             // applying the inset, since the pod shrunk.
             frame.set_size(pod.expand.select(region, frame.size()));
 
+            // Add the content around region breaks. An empty first frame
+            // doesn't count as a region of the block.
+            if let Some((split, width)) = &mut markers {
+                // Make sure that the content around breaks fits horizontally.
+                // This is done for all frames to keep the width consistent.
+                frame.size_mut().x.set_max(*width);
+                let region = Region::new(
+                    Size::new(frame.width(), Abs::inf()),
+                    Axes::new(true, false),
+                );
+
+                let start = usize::from(skip_first);
+                if let Some(content) =
+                    before_break.as_ref().filter(|_| i >= start && i + 1 < count)
+                {
+                    let locator = split.next(&content.span());
+                    let marker =
+                        layout_break_marker(engine, locator, styles, content, region)?;
+                    let pos = Point::with_y(frame.height());
+                    frame.size_mut().y += marker.height();
+                    frame.push_frame(pos, marker);
+                }
+
+                if let Some(content) = after_break.as_ref().filter(|_| i > start) {
+                    let locator = split.next(&content.span());
+                    let marker =
+                        layout_break_marker(engine, locator, styles, content, region)?;
+                    frame.translate(Point::with_y(marker.height()));
+                    frame.size_mut().y += marker.height();
+                    frame.prepend_frame(Point::zero(), marker);
+                }
+
+                if let Some(content) = header.as_ref().filter(|_| i >= start) {
+                    let locator = split.next(&content.span());
+                    let marker =
+                        layout_break_marker(engine, locator, styles, content, region)?;
+                    frame.translate(Point::with_y(marker.height()));
+                    frame.size_mut().y += marker.height();
+                    frame.prepend_frame(Point::zero(), marker);
+                }
+            }
+
             // Apply the inset.
             if has_inset {
                 crate::layout::grow(frame, &inset);
@@ -752,6 +1108,20 @@ impl Packed<BlockElem> {
     }
 }
 
+/// Layout content that is shown around the region breaks of a block or at the
+/// top of its regions.
+fn layout_break_marker(
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    content: &Content,
+    region: Region,
+) -> SourceResult<Frame> {
+    Ok(content
+        .layout(engine, locator, styles, region.into_regions())?
+        .into_frame())
+}
+
 /// The contents of a block.
 #[derive(Debug, Clone, PartialEq, Hash)]
 pub enum BlockChild {
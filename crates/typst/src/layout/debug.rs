@@ -0,0 +1,117 @@
+//! Debug overlays that visualize the layouter's decisions.
+//!
+//! When enabled through [`LibraryBuilder::with_layout_debug`], the page and
+//! flow layouters draw the areas they work with on top of the finished pages:
+//! a coordinate grid, the margin boxes, the boundaries of the flow's regions,
+//! the baseline grid, and the areas of floats, footnotes, and chains of
+//! sticky blocks.
+//!
+//! [`LibraryBuilder::with_layout_debug`]: crate::LibraryBuilder::with_layout_debug
+
+use crate::engine::Engine;
+use crate::layout::{Abs, Frame, FrameItem, Point, Size};
+use crate::syntax::Span;
+use crate::utils::Numeric;
+use crate::visualize::{Color, DashPattern, FixedStroke, Geometry};
+use crate::World;
+
+/// The kinds of areas that can be outlined by the debug overlay.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub(crate) enum DebugArea {
+    /// A line of the coordinate grid.
+    Coordinates,
+    /// The page's content area inside of its margins.
+    Margin,
+    /// The area of a header or footer.
+    Marginal,
+    /// The boundary of a region of a flow.
+    Region,
+    /// A line of the baseline grid.
+    Baseline,
+    /// The area of a floating element.
+    Float,
+    /// The area of the footnotes at the bottom of a region.
+    Footnotes,
+    /// A chain of sticky blocks and the block they stick to.
+    Sticky,
+}
+
+impl DebugArea {
+    /// The stroke to outline the area with.
+    fn stroke(self) -> FixedStroke {
+        let (color, dashed) = match self {
+            Self::Coordinates => (Color::SILVER, false),
+            Self::Margin => (Color::BLUE, false),
+            Self::Marginal => (Color::TEAL, true),
+            Self::Region => (Color::GREEN, false),
+            Self::Baseline => (Color::AQUA, false),
+            Self::Float => (Color::ORANGE, true),
+            Self::Footnotes => (Color::PURPLE, true),
+            Self::Sticky => (Color::RED, true),
+        };
+
+        let mut stroke = FixedStroke::from_pair(color.with_alpha(0.8), Abs::pt(0.5));
+        if dashed {
+            stroke.dash = Some(DashPattern {
+                array: vec![Abs::pt(2.0), Abs::pt(2.0)],
+                phase: Abs::zero(),
+            });
+        }
+        stroke
+    }
+}
+
+/// Whether the debug overlay is enabled for this compilation.
+pub(crate) fn debugging(engine: &Engine) -> bool {
+    engine.world.library().layout_debug
+}
+
+/// Outline a rectangular area in the frame.
+pub(crate) fn outline(frame: &mut Frame, pos: Point, size: Size, area: DebugArea) {
+    let shape = Geometry::Rect(size).stroked(area.stroke());
+    frame.push(pos, FrameItem::Shape(shape, Span::detached()));
+}
+
+/// Draw horizontal lines across an area of the frame at the given distance,
+/// starting at the area's top edge.
+pub(crate) fn rule(
+    frame: &mut Frame,
+    pos: Point,
+    size: Size,
+    distance: Abs,
+    area: DebugArea,
+) {
+    if distance <= Abs::zero() || !size.is_finite() {
+        return;
+    }
+
+    let stroke = area.stroke();
+    let mut y = Abs::zero();
+    while y <= size.y {
+        let shape = Geometry::Line(Point::with_x(size.x)).stroked(stroke.clone());
+        frame.push(pos + Point::with_y(y), FrameItem::Shape(shape, Span::detached()));
+        y += distance;
+    }
+}
+
+/// Draw a coordinate grid across the whole frame, with lines every
+/// centimeter starting at the given origin.
+pub(crate) fn coordinates(frame: &mut Frame, origin: Point) {
+    let size = frame.size();
+    let step = Abs::cm(1.0);
+    let stroke = DebugArea::Coordinates.stroke();
+
+    let mut x = origin.x - step * (origin.x / step).floor();
+    while x <= size.x {
+        let shape = Geometry::Line(Point::with_y(size.y)).stroked(stroke.clone());
+        frame.push(Point::with_x(x), FrameItem::Shape(shape, Span::detached()));
+        x += step;
+    }
+
+    let mut y = origin.y - step * (origin.y / step).floor();
+    while y <= size.y {
+        let shape = Geometry::Line(Point::with_x(size.x)).stroked(stroke.clone());
+        frame.push(Point::with_y(y), FrameItem::Shape(shape, Span::detached()));
+        y += step;
+    }
+}
@@ -0,0 +1,124 @@
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, NativeElement, Packed, Show, StyleChain};
+use crate::introspection::Locator;
+use crate::layout::{
+    Abs, Axes, BlockElem, Frame, FrameItem, Length, Region, Regions, Size,
+};
+use crate::text::{TextElem, TextSize};
+
+/// Sizes text so that content fits into the available space.
+///
+/// Searches for the largest text size between `min-size` and `max-size` at
+/// which the content still fits into its container. This is useful for
+/// content of varying length that needs to fill a fixed area, like the names
+/// on badges or the titles of slides.
+///
+/// # Example
+/// ```example
+/// #set box(width: 80pt, height: 30pt, stroke: 0.5pt)
+/// #box(fit[Ada Lovelace]) \
+/// #box(fit[Katherine Johnson])
+/// ```
+#[elem(Show)]
+pub struct FitElem {
+    /// The largest text size to use.
+    ///
+    /// If the content fits at this size, it is not enlarged any further.
+    #[resolve]
+    #[default(Abs::pt(24.0).into())]
+    pub max_size: Length,
+
+    /// The smallest text size to use.
+    ///
+    /// If the content doesn't even fit at this size, it is laid out at this
+    /// size anyway and overflows its container.
+    #[resolve]
+    #[default(Abs::pt(10.0).into())]
+    pub min_size: Length,
+
+    /// The content to fit.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for Packed<FitElem> {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(BlockElem::single_layouter(self.clone(), layout_fit)
+            .pack()
+            .spanned(self.span()))
+    }
+}
+
+/// Layout the fitted content.
+#[typst_macros::time(span = elem.span())]
+fn layout_fit(
+    elem: &Packed<FitElem>,
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    region: Region,
+) -> SourceResult<Frame> {
+    let max = elem.max_size(styles);
+    let min = elem.min_size(styles).min(max);
+    let body = elem.body();
+
+    // The text size difference at which the search stops.
+    let precision = Abs::pt(0.1);
+
+    // Checks whether the body fits into the region at the given text size.
+    // The body is measured with unbounded height because layout clamps the
+    // frame size to the region.
+    let pod = Regions::one(Size::new(region.size.x, Abs::inf()), Axes::splat(false));
+    let fits = |engine: &mut Engine, size: Abs| -> SourceResult<bool> {
+        let local = TextElem::set_size(TextSize(size.into())).wrap();
+        let frame = body
+            .layout(engine, locator.relayout(), styles.chain(&local), pod)?
+            .into_frame();
+        let width = frame.width().max(extent(&frame));
+        Ok(region.size.x.fits(width) && region.size.y.fits(frame.height()))
+    };
+
+    // Binary search for the largest size that fits.
+    let size = if fits(engine, max)? {
+        max
+    } else {
+        let (mut lo, mut hi) = (min, max);
+        while hi - lo > precision {
+            let mid = (lo + hi) / 2.0;
+            if fits(engine, mid)? {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    };
+
+    let local = TextElem::set_size(TextSize(size.into())).wrap();
+    Ok(body
+        .layout(engine, locator, styles.chain(&local), region.into_regions())?
+        .into_frame())
+}
+
+/// The horizontal extent of a frame's contents, which may exceed the frame's
+/// width if something, like a long word, couldn't be broken.
+fn extent(frame: &Frame) -> Abs {
+    frame
+        .items()
+        .map(|(pos, item)| {
+            pos.x
+                + match item {
+                    FrameItem::Group(group) if group.transform.is_identity() => {
+                        extent(&group.frame)
+                    }
+                    FrameItem::Group(group) => group.frame.width(),
+                    FrameItem::Text(text) => text.width(),
+                    FrameItem::Shape(shape, _) => shape.geometry.bbox_size().x,
+                    FrameItem::Image(_, size, _) => size.x,
+                    FrameItem::Link(_, _) | FrameItem::Tag(_) => Abs::zero(),
+                }
+        })
+        .max()
+        .unwrap_or_default()
+}
@@ -5,19 +5,32 @@
 //! inline-level layoutable elements.
 
 use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
 
-use crate::diag::{bail, SourceResult};
+use comemo::Track;
+
+use crate::diag::{bail, warning, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    elem, Args, Construct, Content, NativeElement, Packed, Resolve, Smart, StyleChain,
+    elem, Args, Construct, Content, Context, NativeElement, Packed, Repr, Resolve,
+    Selector, ShowSet, Smart, Str, StyleChain,
 };
-use crate::introspection::{Locator, SplitLocator, Tag, TagElem};
+use crate::introspection::{Location, Locator, SplitLocator, Tag, TagElem};
+use crate::layout::debug::{self, debugging, DebugArea};
 use crate::layout::{
-    Abs, AlignElem, Axes, BlockElem, ColbreakElem, FixedAlignment, FlushElem, Fr,
-    Fragment, Frame, FrameItem, PlaceElem, Point, Regions, Rel, Size, Spacing, VElem,
+    anchor_spacing, Abs, AlignElem, AnchorElem, Axes, Binding, BlockElem, ColbreakElem,
+    ColbreakTarget, ColumnArrangement, ColumnsElem, Dir, Exclusion, FixedAlignment,
+    FlushElem, Fr, Fragment, Frame, FrameItem, Length, PageElem, PageSide, PlaceElem,
+    PlaceMarker, PlacementOrder, PlacementScope, Point, Regions, Rel, Size, Spacing,
+    VElem,
+};
+use crate::model::{
+    first_baseline, FootnoteElem, FootnoteEntry, FootnoteScope, HeadingElem,
+    LineNumberingScope, ParElem, ParLineMarker, ParLineNumbering,
 };
-use crate::model::{FootnoteElem, FootnoteEntry, ParElem};
 use crate::realize::StyleVec;
+use crate::syntax::Span;
+use crate::text::TextElem;
 use crate::utils::Numeric;
 
 /// Arranges spacing, paragraphs and block-level elements into a flow.
@@ -67,8 +80,18 @@ impl Packed<FlowElem> {
             alone = child.is::<BlockElem>();
         }
 
-        let mut layouter = FlowLayouter::new(locator, styles, regions, alone);
+        // Only the flow that is directly laid out into columns knows about
+        // them. Nested flows are confined to a single column.
+        let columns = ColumnsElem::arrangement_in(styles);
+        let local = ColumnsElem::set_arrangement(None).wrap();
+        let styles = if columns.is_some() { styles.chain(&local) } else { styles };
+
+        let mut layouter = FlowLayouter::new(locator, styles, regions, alone, columns);
         for (child, styles) in self.children().chain(&styles) {
+            if layouter.exhausted {
+                break;
+            }
+
             if let Some(elem) = child.to_packed::<TagElem>() {
                 layouter.layout_tag(elem);
             } else if child.is::<FlushElem>() {
@@ -81,11 +104,10 @@ impl Packed<FlowElem> {
                 layouter.layout_block(engine, elem, styles)?;
             } else if let Some(placed) = child.to_packed::<PlaceElem>() {
                 layouter.layout_placed(engine, placed, styles)?;
-            } else if child.is::<ColbreakElem>() {
-                if !layouter.regions.backlog.is_empty() || layouter.regions.last.is_some()
-                {
-                    layouter.finish_region(engine, true)?;
-                }
+            } else if let Some(colbreak) = child.to_packed::<ColbreakElem>() {
+                layouter.layout_colbreak(engine, colbreak, styles)?;
+            } else if let Some(anchor) = child.to_packed::<AnchorElem>() {
+                layouter.layout_anchor(engine, anchor);
             } else {
                 bail!(child.span(), "unexpected flow child");
             }
@@ -121,18 +143,47 @@ struct FlowLayouter<'a> {
     ///
     /// Used for indenting paragraphs after the first in a block.
     last_was_par: bool,
+    /// How many lines of a paragraph must fit along with the last sticky
+    /// block.
+    sticky_lines: usize,
     /// Spacing and layouted blocks for the current region.
     items: Vec<FlowItem>,
     /// A queue of tags that will be attached to the next frame.
     pending_tags: Vec<Tag>,
     /// A queue of floating elements.
     pending_floats: Vec<FlowItem>,
-    /// Whether we have any footnotes in the current region.
-    has_footnotes: bool,
+    /// How the columns are arranged if the flow is laid out into multiple
+    /// columns.
+    columns: Option<ColumnArrangement>,
+    /// The number of regions that were already finished.
+    region: usize,
+    /// A queue of floating elements spanning all columns, which wait for the
+    /// first column of the next region.
+    pending_parent_floats: Vec<FlowItem>,
+    /// The heights and alignments of the floats spanning all columns of the
+    /// current region. The space for them is reserved in each column.
+    parent_floats: Vec<(Abs, Smart<Option<FixedAlignment>>)>,
+    /// The areas of placed elements in the current region that paragraphs
+    /// wrap around.
+    exclusions: Vec<Exclusion>,
+    /// The last anchor in the current region and the total space inserted
+    /// after anchors in it.
+    anchors: (Option<Location>, Abs),
+    /// The distance between the lines of the baseline grid to which the
+    /// baselines of lines are snapped. Only set for the root flow.
+    baseline_grid: Option<Abs>,
+    /// The note streams with footnotes in the current region, whose
+    /// separators are thus already laid out.
+    footnote_streams: Vec<Option<Str>>,
     /// Footnote configuration.
     footnote_config: FootnoteConfig,
     /// Finished frames for previous regions.
     finished: Vec<Frame>,
+    /// How many pages a root flow may fill before it is exhausted.
+    limit: Option<usize>,
+    /// Whether the time budget ran out or the page limit was reached. A root
+    /// flow then stops after the region it was laying out at that point.
+    exhausted: bool,
 }
 
 /// Cached footnote configuration.
@@ -140,6 +191,9 @@ struct FootnoteConfig {
     separator: Content,
     clearance: Abs,
     gap: Abs,
+    continued: Option<Content>,
+    continuation: Option<Content>,
+    scope: FootnoteScope,
 }
 
 /// A prepared item in a flow layout.
@@ -170,9 +224,10 @@ enum FlowItem {
         delta: Axes<Rel<Abs>>,
         float: bool,
         clearance: Abs,
+        order: Smart<PlacementOrder>,
     },
-    /// A footnote frame (can also be the separator).
-    Footnote(Frame),
+    /// A footnote frame (can also be the separator) and its note stream.
+    Footnote(Frame, Option<Str>),
 }
 
 impl FlowItem {
@@ -200,6 +255,7 @@ impl<'a> FlowLayouter<'a> {
         styles: StyleChain<'a>,
         mut regions: Regions<'a>,
         alone: bool,
+        columns: Option<ColumnArrangement>,
     ) -> Self {
         let expand = regions.expand;
         let root = std::mem::replace(&mut regions.root, false);
@@ -218,16 +274,30 @@ impl<'a> FlowLayouter<'a> {
             expand,
             initial: regions.size,
             last_was_par: false,
+            sticky_lines: 1,
             items: vec![],
             pending_tags: vec![],
             pending_floats: vec![],
-            has_footnotes: false,
+            columns,
+            region: 0,
+            pending_parent_floats: vec![],
+            parent_floats: vec![],
+            exclusions: vec![],
+            anchors: (None, Abs::zero()),
+            baseline_grid: PageElem::baseline_grid_in(styles)
+                .filter(|grid| root && *grid > Abs::zero()),
+            footnote_streams: vec![],
             footnote_config: FootnoteConfig {
                 separator: FootnoteEntry::separator_in(styles),
                 clearance: FootnoteEntry::clearance_in(styles),
                 gap: FootnoteEntry::gap_in(styles),
+                continued: FootnoteEntry::continued_in(styles),
+                continuation: FootnoteEntry::continuation_in(styles),
+                scope: FootnoteEntry::scope_in(styles),
             },
             finished: vec![],
+            limit: PageElem::limit_in(styles).filter(|_| root),
+            exhausted: false,
         }
     }
 
@@ -265,6 +335,23 @@ impl<'a> FlowLayouter<'a> {
         let align = AlignElem::alignment_in(styles).resolve(styles);
         let leading = ParElem::leading_in(styles);
         let consecutive = self.last_was_par;
+
+        // Move the exclusions into the paragraph's coordinate system.
+        let top = self.cursor();
+        let exclusions: Vec<_> = self
+            .exclusions
+            .iter()
+            .filter(|exclusion| exclusion.bottom() > top)
+            .map(|exclusion| Exclusion {
+                vertices: exclusion
+                    .vertices
+                    .iter()
+                    .map(|&point| point - Point::with_y(top))
+                    .collect(),
+                gap: exclusion.gap,
+            })
+            .collect();
+
         let lines = par
             .layout(
                 engine,
@@ -273,6 +360,7 @@ impl<'a> FlowLayouter<'a> {
                 consecutive,
                 self.regions.base(),
                 self.regions.expand.x,
+                &exclusions,
             )?
             .into_frames();
 
@@ -280,34 +368,42 @@ impl<'a> FlowLayouter<'a> {
         // previous sticky frame to the next region (if available)
         if let Some(first) = lines.first() {
             while !self.regions.size.y.fits(first.height()) && !self.regions.in_last() {
-                let mut sticky = self.items.len();
-                for (i, item) in self.items.iter().enumerate().rev() {
-                    match *item {
-                        FlowItem::Absolute(_, _) => {}
-                        FlowItem::Frame { sticky: true, .. } => sticky = i,
-                        _ => break,
-                    }
-                }
-
-                let carry: Vec<_> = self.items.drain(sticky..).collect();
-                self.finish_region(engine, false)?;
-                let in_last = self.regions.in_last();
-
-                for item in carry {
-                    self.layout_item(engine, item)?;
-                }
-
-                if in_last {
+                if self.carry_sticky(engine)? {
                     break;
                 }
             }
         }
 
+        // A sticky frame may also require more than the first line to fit
+        // along with it.
+        if self.sticky_lines > 1 && self.has_sticky() && !self.regions.in_last() {
+            let kept = &lines[..lines.len().min(self.sticky_lines)];
+            let height = kept.iter().map(Frame::height).sum::<Abs>()
+                + leading * kept.len().saturating_sub(1) as f64;
+            if !self.regions.size.y.fits(height) {
+                self.carry_sticky(engine)?;
+            }
+        }
+
+        let numbered = ParLineNumbering::numbering_in(styles).is_some();
+        let key = crate::utils::hash128(&par.span());
         for (i, mut frame) in lines.into_iter().enumerate() {
+            if self.exhausted {
+                break;
+            }
+
             if i > 0 {
                 self.layout_item(engine, FlowItem::Absolute(leading, true))?;
             }
 
+            if let Some(grid) = self.baseline_grid {
+                self.snap_to_grid(engine, &frame, grid)?;
+            }
+
+            if numbered {
+                frame = self.locate_line_markers(engine, frame, key);
+            }
+
             self.drain_tag(&mut frame);
             self.layout_item(
                 engine,
@@ -319,6 +415,103 @@ impl<'a> FlowLayouter<'a> {
         Ok(())
     }
 
+    /// Adds spacing in front of a line such that its baseline lands on the
+    /// baseline grid.
+    fn snap_to_grid(
+        &mut self,
+        engine: &mut Engine,
+        line: &Frame,
+        grid: Abs,
+    ) -> SourceResult<()> {
+        let padding = |this: &Self| {
+            let y = this.cursor() + line.baseline();
+            let snapped = grid * (y / grid).ceil();
+            if (snapped - grid).approx_eq(y) {
+                Abs::zero()
+            } else {
+                snapped - y
+            }
+        };
+
+        // If the line doesn't fit into the region with the padding, it will
+        // move on to the next region, where the padding is different.
+        let mut amount = padding(self);
+        if !self.regions.size.y.fits(amount + line.height()) && !self.regions.in_last() {
+            self.finish_region(engine, false)?;
+            amount = padding(self);
+        }
+
+        if amount > Abs::zero() {
+            self.layout_item(engine, FlowItem::Absolute(amount, false))?;
+        }
+
+        Ok(())
+    }
+
+    /// Assigns locations to the line markers in the frame of one or more lines
+    /// of a paragraph. Only the lines of the root flow are numbered, so that
+    /// other flows remove the markers instead.
+    fn locate_line_markers(
+        &mut self,
+        engine: &mut Engine,
+        lines: Frame,
+        key: u128,
+    ) -> Frame {
+        let mut output = Frame::new(lines.size(), lines.kind());
+        output.set_baseline(lines.baseline());
+
+        for (pos, item) in lines.items() {
+            match item {
+                FrameItem::Tag(tag) if tag.elem.is::<ParLineMarker>() => {
+                    if self.root {
+                        let mut marker = tag.elem.clone();
+                        let loc = self.locator.next_location(engine.introspector, key);
+                        marker.set_location(loc);
+                        output.push(*pos, FrameItem::Tag(Tag::new(marker, key)));
+                    }
+                }
+                _ => output.push(*pos, item.clone()),
+            }
+        }
+
+        output
+    }
+
+    /// Whether the current region ends with sticky frames, which must move
+    /// along with the next frame.
+    fn has_sticky(&self) -> bool {
+        self.items
+            .iter()
+            .rev()
+            .find(|item| !matches!(item, FlowItem::Absolute(_, _)))
+            .is_some_and(|item| matches!(item, FlowItem::Frame { sticky: true, .. }))
+    }
+
+    /// Finishes the current region, but carries the sticky frames at its end
+    /// over to the next region.
+    ///
+    /// Returns whether the flow is in the last region afterwards.
+    fn carry_sticky(&mut self, engine: &mut Engine) -> SourceResult<bool> {
+        let mut sticky = self.items.len();
+        for (i, item) in self.items.iter().enumerate().rev() {
+            match *item {
+                FlowItem::Absolute(_, _) => {}
+                FlowItem::Frame { sticky: true, .. } => sticky = i,
+                _ => break,
+            }
+        }
+
+        let carry: Vec<_> = self.items.drain(sticky..).collect();
+        self.finish_region(engine, false)?;
+        let in_last = self.regions.in_last();
+
+        for item in carry {
+            self.layout_item(engine, item)?;
+        }
+
+        Ok(in_last)
+    }
+
     /// Layout into multiple regions.
     fn layout_block(
         &mut self,
@@ -339,13 +532,26 @@ impl<'a> FlowLayouter<'a> {
         }
 
         // Layout the block itself.
-        let sticky = block.sticky(styles);
-        let fragment = block.layout(
-            engine,
-            self.locator.next(&block.span()),
-            styles,
-            self.regions,
-        )?;
+        let sticky = block.keep_with_next(styles);
+        if sticky {
+            self.sticky_lines = block.keep_lines_after(styles).get();
+        }
+        let locator = self.locator.next(&block.span());
+        let mut fragment =
+            block.layout(engine, locator.relayout(), styles, self.regions)?;
+
+        // If the block can't start in this region, then defer any previous
+        // sticky frame to the next region along with it.
+        if let Some(first) = fragment.as_slice().first() {
+            if (!self.regions.size.y.fits(first.height())
+                || (fragment.len() > 1 && first.is_empty()))
+                && self.has_sticky()
+                && !self.regions.in_last()
+            {
+                self.carry_sticky(engine)?;
+                fragment = block.layout(engine, locator, styles, self.regions)?;
+            }
+        }
 
         // How to align the block.
         let align = AlignElem::alignment_in(styles).resolve(styles);
@@ -361,6 +567,10 @@ impl<'a> FlowLayouter<'a> {
                 self.finish_region(engine, false)?;
             }
 
+            if PageElem::diagnose_in(styles) {
+                self.diagnose_block(engine, block.span(), &frame, i == 0);
+            }
+
             self.drain_tag(&mut frame);
             frame.post_process(styles);
             self.layout_item(
@@ -378,6 +588,130 @@ impl<'a> FlowLayouter<'a> {
         Ok(())
     }
 
+    /// Warns if a frame of a block doesn't fit into the current region.
+    ///
+    /// The frame then either moves on to the next region, possibly leaving
+    /// much of the current one empty, or overflows because no region is large
+    /// enough.
+    fn diagnose_block(
+        &self,
+        engine: &mut Engine,
+        span: Span,
+        frame: &Frame,
+        first: bool,
+    ) {
+        let height = frame.height();
+        let available = self.regions.size.y;
+        if span.is_detached() || !available.is_finite() || available.fits(height) {
+            return;
+        }
+
+        // The frame moves on until it fits or there are no more regions.
+        let mut target = available;
+        if !self.regions.in_last() {
+            if first && available > self.initial.y / 2.0 {
+                engine.sink.warn(warning!(
+                    span, "block does not fit into the remaining space of its region";
+                    hint: "this leaves {} of the region empty", available.repr();
+                    hint: "consider making the block breakable or moving it elsewhere",
+                ));
+            }
+
+            let mut heights =
+                self.regions.backlog.iter().chain(&self.regions.last).copied();
+            target = heights
+                .clone()
+                .find(|h| h.fits(height))
+                .or_else(|| heights.next_back())
+                .unwrap_or(available);
+        }
+
+        if !target.fits(height) {
+            engine.sink.warn(warning!(
+                span, "block overflows its region by {}", (height - target).repr();
+                hint: "the block is {} tall but only {} are available",
+                    height.repr(), target.repr();
+                hint: "consider making the block breakable or reducing its size",
+            ));
+        }
+    }
+
+    /// Layout a column break.
+    fn layout_colbreak(
+        &mut self,
+        engine: &mut Engine,
+        colbreak: &Packed<ColbreakElem>,
+        styles: StyleChain,
+    ) -> SourceResult<()> {
+        // The number of columns per page and the index of the current column
+        // on its page.
+        let count = self.columns.map_or(1, |columns| columns.count);
+        let column = self.region % count;
+        let target = colbreak.break_to(styles);
+
+        // A weak break collapses if we're at the top of the column it would
+        // break to anyway.
+        if colbreak.weak(styles)
+            && (target == ColbreakTarget::NextColumn || column == 0)
+            && self.items.iter().all(|item| {
+                matches!(item, FlowItem::Absolute(..) | FlowItem::Fractional(..))
+                    || item.is_out_of_flow()
+            })
+        {
+            return Ok(());
+        }
+
+        loop {
+            if self.regions.backlog.is_empty() && self.regions.last.is_none() {
+                break;
+            }
+
+            self.finish_region(engine, true)?;
+            if target == ColbreakTarget::NextColumn || self.region % count == 0 {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Layout an anchor, inserting space to align it with its siblings.
+    fn layout_anchor(&mut self, engine: &mut Engine, anchor: &Packed<AnchorElem>) {
+        let loc = anchor.location().unwrap();
+        let spacing = anchor_spacing(engine.introspector, loc);
+
+        // Record where the anchor would be without the space inserted in the
+        // region so far, which is what the spacing is computed from.
+        let (prev, shift) = self.anchors;
+        let placed = anchor.placed(shift, prev).pack();
+        for tag in &mut self.pending_tags {
+            if tag.elem.location() == Some(loc) {
+                tag.elem = placed.clone();
+            }
+        }
+
+        // Place the tags at the anchor's position before the space.
+        let mut frame = Frame::soft(Size::zero());
+        frame.push_multiple(
+            self.pending_tags
+                .drain(..)
+                .map(|tag| (Point::zero(), FrameItem::Tag(tag))),
+        );
+        self.items.push(FlowItem::Frame {
+            frame,
+            align: Axes::splat(FixedAlignment::Start),
+            sticky: false,
+            movable: false,
+        });
+
+        if spacing > Abs::zero() {
+            self.regions.size.y -= spacing;
+            self.items.push(FlowItem::Absolute(spacing, false));
+        }
+
+        self.anchors = (Some(loc), shift + spacing);
+    }
+
     /// Layout a placed element.
     fn layout_placed(
         &mut self,
@@ -387,23 +721,261 @@ impl<'a> FlowLayouter<'a> {
     ) -> SourceResult<()> {
         let float = placed.float(styles);
         let clearance = placed.clearance(styles);
+        let order = placed.order(styles);
         let alignment = placed.alignment(styles);
         let delta = Axes::new(placed.dx(styles), placed.dy(styles)).resolve(styles);
         let x_align = alignment.map_or(FixedAlignment::Center, |align| {
             align.x().unwrap_or_default().resolve(styles)
         });
         let y_align = alignment.map(|align| align.y().map(|y| y.resolve(styles)));
+
+        // Content that is only placed on one side of a spread leaves a marker
+        // from which the page it ends up on is known in the next iteration.
+        let mut marker = None;
+        if let Some(side) = placed.on(styles) {
+            let key = crate::utils::hash128(&placed.span());
+            let loc = self.locator.next_location(engine.introspector, key);
+            let mut elem = PlaceMarker::new().pack();
+            elem.set_location(loc);
+            let tag = FrameItem::Tag(Tag::new(elem, key));
+
+            let number = engine.introspector.page(loc);
+            if PageSide::of(Binding::resolve_in(styles), number) != side {
+                let mut frame = Frame::soft(Size::zero());
+                frame.push(Point::zero(), tag);
+                let item = FlowItem::Placed {
+                    frame,
+                    x_align: FixedAlignment::Start,
+                    y_align: Smart::Auto,
+                    delta: Axes::splat(Rel::zero()),
+                    float: false,
+                    clearance: Abs::zero(),
+                    order: Smart::Auto,
+                };
+                return self.layout_item(engine, item);
+            }
+
+            marker = Some(tag);
+        }
+
+        // Floats with parent scope span all columns.
+        let columns = self
+            .columns
+            .filter(|_| float && placed.scope(styles) == PlacementScope::Parent);
+        let base = match columns {
+            Some(columns) => Size::new(columns.width, self.regions.base().y),
+            None => self.regions.base(),
+        };
+
         let mut frame = placed
-            .layout(
-                engine,
-                self.locator.next(&placed.span()),
-                styles,
-                self.regions.base(),
-            )?
+            .layout(engine, self.locator.next(&placed.span()), styles, base)?
             .into_frame();
         frame.post_process(styles);
-        let item = FlowItem::Placed { frame, x_align, y_align, delta, float, clearance };
-        self.layout_item(engine, item)
+        if let Some(tag) = marker {
+            frame.prepend(Point::zero(), tag);
+        }
+
+        let Some(columns) = columns else {
+            let mut item = FlowItem::Placed {
+                frame,
+                x_align,
+                y_align,
+                delta,
+                float,
+                clearance,
+                order,
+            };
+            if let Some(wrap) = placed.wrap(styles) {
+                self.wrap_around(&mut item, wrap, styles);
+            }
+            return self.layout_item(engine, item);
+        };
+
+        // Position the float relative to the parent. It is moved into the
+        // column it ends up in once that is known.
+        let x = x_align.position(columns.width - frame.width())
+            + delta.x.relative_to(columns.width);
+        let delta = Axes::new(Rel::from(x), delta.y);
+        let x_align = FixedAlignment::Start;
+        let item = FlowItem::Placed {
+            frame,
+            x_align,
+            y_align,
+            delta,
+            float,
+            clearance,
+            order,
+        };
+        self.layout_parent_float(engine, item)
+    }
+
+    /// Registers the area of a placed element for paragraphs to wrap around.
+    ///
+    /// Floats that text wraps around don't take up space in the flow. Instead,
+    /// they are placed at the current position in the flow or at the bottom of
+    /// the region.
+    fn wrap_around(
+        &mut self,
+        item: &mut FlowItem,
+        wrap: Smart<Vec<Axes<Rel<Length>>>>,
+        styles: StyleChain,
+    ) {
+        let FlowItem::Placed {
+            ref mut frame,
+            x_align,
+            ref mut y_align,
+            delta,
+            ref mut float,
+            clearance,
+            ..
+        } = *item
+        else {
+            return;
+        };
+
+        if !self.initial.x.is_finite() {
+            return;
+        }
+
+        if *float {
+            self.prepare_float(frame, y_align, Abs::zero());
+            if *y_align == Smart::Custom(Some(FixedAlignment::Start)) {
+                *y_align = Smart::Auto;
+            }
+            *float = false;
+        }
+
+        // Determine where the element will end up in the region.
+        let size = frame.size();
+        let y = match *y_align {
+            Smart::Custom(Some(align)) if self.initial.y.is_finite() => {
+                align.position(self.initial.y - size.y)
+            }
+            Smart::Custom(Some(_)) => return,
+            _ => self.cursor(),
+        };
+        let origin = Point::new(x_align.position(self.initial.x - size.x), y)
+            + delta.zip_map(self.initial, Rel::relative_to).to_point();
+
+        let vertices = match wrap {
+            Smart::Auto => vec![
+                Point::zero(),
+                Point::with_x(size.x),
+                size.to_point(),
+                Point::with_y(size.y),
+            ],
+            Smart::Custom(points) => points
+                .into_iter()
+                .map(|point| point.resolve(styles).zip_map(size, Rel::relative_to))
+                .map(Axes::to_point)
+                .collect(),
+        };
+
+        self.exclusions.push(Exclusion {
+            vertices: vertices.into_iter().map(|point| point + origin).collect(),
+            gap: clearance,
+        });
+    }
+
+    /// The vertical position in the current region at which the next in-flow
+    /// item is placed.
+    fn cursor(&self) -> Abs {
+        self.items
+            .iter()
+            .map(|item| match item {
+                FlowItem::Absolute(v, _) => *v,
+                FlowItem::Frame { frame, .. } => frame.height(),
+                FlowItem::Placed {
+                    frame,
+                    float: true,
+                    y_align: Smart::Custom(Some(FixedAlignment::Start)),
+                    ..
+                } => frame.height(),
+                _ => Abs::zero(),
+            })
+            .sum()
+    }
+
+    /// Layout a float which spans all columns of a region.
+    ///
+    /// The float is placed into the first column of the region and space is
+    /// reserved for it in the other columns. If the first column is already
+    /// complete, the float waits for the next region. If there is no next
+    /// region, it is placed into the current column instead.
+    fn layout_parent_float(
+        &mut self,
+        engine: &mut Engine,
+        mut item: FlowItem,
+    ) -> SourceResult<()> {
+        let FlowItem::Placed {
+            ref mut frame,
+            ref mut y_align,
+            ref mut delta,
+            clearance,
+            ..
+        } = item
+        else {
+            unreachable!("parent float must be placed");
+        };
+
+        let Some(columns) = self.columns else {
+            unreachable!("parent float must be in columns");
+        };
+
+        let column = self.region % columns.count;
+        let has_next = self.regions.last.is_some()
+            || self.regions.backlog.len() >= columns.count - column;
+        if has_next
+            && (column > 0
+                || !self.pending_parent_floats.is_empty()
+                || !self.regions.size.y.fits(frame.height() + clearance))
+        {
+            self.pending_parent_floats.push(item);
+            return Ok(());
+        }
+
+        // Move the float from the parent into the column.
+        delta.x.abs -= columns.first + columns.step * column as f64;
+
+        self.prepare_float(frame, y_align, clearance);
+        self.regions.size.y -= frame.height();
+        self.parent_floats.push((frame.height(), *y_align));
+
+        // Find footnotes in the frame.
+        if self.root {
+            let mut notes = vec![];
+            find_footnotes(&mut notes, frame);
+            self.items.push(item);
+            self.try_handle_footnotes(engine, notes)?;
+        } else {
+            self.items.push(item);
+        }
+
+        Ok(())
+    }
+
+    /// Selects the placement of a float and adds clearance to its frame.
+    fn prepare_float(
+        &self,
+        frame: &mut Frame,
+        y_align: &mut Smart<Option<FixedAlignment>>,
+        clearance: Abs,
+    ) {
+        // Select the closer placement, top or bottom.
+        if y_align.is_auto() {
+            let ratio = (self.regions.size.y - (frame.height() + clearance) / 2.0)
+                / self.regions.full;
+            let better_align =
+                if ratio <= 0.5 { FixedAlignment::End } else { FixedAlignment::Start };
+            *y_align = Smart::Custom(Some(better_align));
+        }
+
+        // Add some clearance so that the float doesn't touch the main
+        // content.
+        frame.size_mut().y += clearance;
+        if *y_align == Smart::Custom(Some(FixedAlignment::End)) {
+            frame.translate(Point::with_y(clearance));
+        }
     }
 
     /// Attach currently pending metadata to the frame.
@@ -479,26 +1051,7 @@ impl<'a> FlowLayouter<'a> {
                     return Ok(());
                 }
 
-                // Select the closer placement, top or bottom.
-                if y_align.is_auto() {
-                    let ratio = (self.regions.size.y
-                        - (frame.height() + clearance) / 2.0)
-                        / self.regions.full;
-                    let better_align = if ratio <= 0.5 {
-                        FixedAlignment::End
-                    } else {
-                        FixedAlignment::Start
-                    };
-                    *y_align = Smart::Custom(Some(better_align));
-                }
-
-                // Add some clearance so that the float doesn't touch the main
-                // content.
-                frame.size_mut().y += clearance;
-                if *y_align == Smart::Custom(Some(FixedAlignment::End)) {
-                    frame.translate(Point::with_y(clearance));
-                }
-
+                self.prepare_float(frame, y_align, clearance);
                 self.regions.size.y -= frame.height();
 
                 // Find footnotes in the frame.
@@ -508,7 +1061,7 @@ impl<'a> FlowLayouter<'a> {
                     self.try_handle_footnotes(engine, notes)?;
                 }
             }
-            FlowItem::Footnote(_) => {}
+            FlowItem::Footnote(..) => {}
         }
 
         self.items.push(item);
@@ -526,9 +1079,7 @@ impl<'a> FlowLayouter<'a> {
             && self.items.iter().all(FlowItem::is_out_of_flow)
         {
             self.finished.push(Frame::soft(self.initial));
-            self.regions.next();
-            self.initial = self.regions.size;
-            return Ok(());
+            return self.next_region(engine);
         }
 
         // Trim weak spacing.
@@ -565,7 +1116,7 @@ impl<'a> FlowLayouter<'a> {
                     }
                     _ => {}
                 },
-                FlowItem::Footnote(frame) => {
+                FlowItem::Footnote(frame, _) => {
                     footnote_height += frame.height();
                     if !first_footnote {
                         footnote_height += self.footnote_config.gap;
@@ -581,7 +1132,9 @@ impl<'a> FlowLayouter<'a> {
         // the region expands. Also account for fractional spacing and
         // footnotes.
         let mut size = self.expand.select(self.initial, used).min(self.initial);
-        if (fr.get() > 0.0 || self.has_footnotes) && self.initial.y.is_finite() {
+        if (fr.get() > 0.0 || !self.footnote_streams.is_empty())
+            && self.initial.y.is_finite()
+        {
             size.y = self.initial.y;
         }
 
@@ -590,7 +1143,32 @@ impl<'a> FlowLayouter<'a> {
         let mut float_top_offset = Abs::zero();
         let mut offset = float_top_height;
         let mut float_bottom_offset = Abs::zero();
-        let mut footnote_offset = Abs::zero();
+        let mut lines = vec![];
+        let mut first = vec![];
+        let mut last = vec![];
+        let mut areas = vec![];
+        let mut chain = None;
+
+        // Stack the footnotes of each note stream, starting with the default
+        // one. The frames are still placed in the order of the items, such
+        // that introspection sees nested footnotes right after their parents.
+        let mut footnotes: Vec<_> = self
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                FlowItem::Footnote(frame, stream) => Some((stream, frame.height())),
+                _ => None,
+            })
+            .enumerate()
+            .collect();
+        footnotes.sort_by_key(|(_, (stream, _))| *stream);
+        let mut footnote_ys = vec![Abs::zero(); footnotes.len()];
+        let mut y = size.y - footnote_height;
+        for (i, (_, height)) in footnotes {
+            footnote_ys[i] = y;
+            y += height + self.footnote_config.gap;
+        }
+        let mut footnote_ys = footnote_ys.into_iter();
 
         // Place all frames.
         for item in self.items.drain(..) {
@@ -603,15 +1181,28 @@ impl<'a> FlowLayouter<'a> {
                     let length = v.share(fr, remaining);
                     offset += length;
                 }
-                FlowItem::Frame { frame, align, .. } => {
+                FlowItem::Frame { frame, align, sticky, .. } => {
                     ruler = ruler.max(align.y);
                     let x = align.x.position(size.x - frame.width());
                     let y = offset + ruler.position(size.y - used.y);
                     let pos = Point::new(x, y);
                     offset += frame.height();
+                    if sticky {
+                        chain.get_or_insert(y);
+                    } else if let Some(start) = chain.take() {
+                        let end = y + frame.height();
+                        let area = Size::new(size.x, end - start);
+                        areas.push((Point::with_y(start), area, DebugArea::Sticky));
+                    }
+                    lines.extend(
+                        find_line_markers(&frame)
+                            .map(|(line, marker)| (pos.y + line, marker)),
+                    );
                     output.push_frame(pos, frame);
                 }
-                FlowItem::Placed { frame, x_align, y_align, delta, float, .. } => {
+                FlowItem::Placed {
+                    frame, x_align, y_align, delta, float, order, ..
+                } => {
                     let x = x_align.position(size.x - frame.width());
                     let y = if float {
                         match y_align {
@@ -639,17 +1230,56 @@ impl<'a> FlowLayouter<'a> {
 
                     let pos = Point::new(x, y)
                         + delta.zip_map(size, Rel::relative_to).to_point();
+                    if float {
+                        areas.push((pos, frame.size(), DebugArea::Float));
+                    }
 
-                    output.push_frame(pos, frame);
+                    match order {
+                        Smart::Auto => output.push_frame(pos, frame),
+                        Smart::Custom(PlacementOrder::Start) => first.push((pos, frame)),
+                        Smart::Custom(PlacementOrder::End) => last.push((pos, frame)),
+                    }
                 }
-                FlowItem::Footnote(frame) => {
-                    let y = size.y - footnote_height + footnote_offset;
-                    footnote_offset += frame.height() + self.footnote_config.gap;
+                FlowItem::Footnote(frame, _) => {
+                    let y = footnote_ys.next().unwrap_or_default();
                     output.push_frame(Point::with_y(y), frame);
                 }
             }
         }
 
+        // Placed frames with a custom reading order go before or after all
+        // others.
+        for (pos, frame) in first.into_iter().rev() {
+            output.prepend_frame(pos, frame);
+        }
+        for (pos, frame) in last {
+            output.push_frame(pos, frame);
+        }
+
+        self.layout_line_numbers(engine, &mut output, lines)?;
+
+        if debugging(engine) {
+            if let Some(grid) = self.baseline_grid {
+                debug::rule(&mut output, Point::zero(), size, grid, DebugArea::Baseline);
+            }
+
+            if let Some(start) = chain {
+                let area = Size::new(size.x, offset - start);
+                areas.push((Point::with_y(start), area, DebugArea::Sticky));
+            }
+
+            if footnote_height > Abs::zero() {
+                let pos = Point::with_y(size.y - footnote_height);
+                let area = Size::new(size.x, footnote_height);
+                areas.push((pos, area, DebugArea::Footnotes));
+            }
+
+            areas.push((Point::zero(), size, DebugArea::Region));
+            for (pos, area, kind) in areas {
+                debug::outline(&mut output, pos, area, kind);
+            }
+        }
+
         if force && !self.pending_tags.is_empty() {
             let pos = Point::with_y(offset);
             output.push_multiple(
@@ -659,9 +1289,14 @@ impl<'a> FlowLayouter<'a> {
 
         // Advance to the next region.
         self.finished.push(output);
-        self.regions.next();
-        self.initial = self.regions.size;
-        self.has_footnotes = false;
+        self.footnote_streams.clear();
+        if self.root {
+            let checkpoint = self.locator.next_location(engine.introspector, 0).hash();
+            let count = self.columns.map_or(1, |columns| columns.count);
+            self.exhausted = engine.budget.exceeded(checkpoint)
+                || self.limit.is_some_and(|limit| self.finished.len() >= limit * count);
+        }
+        self.next_region(engine)?;
 
         // Try to place floats into the next region.
         for item in std::mem::take(&mut self.pending_floats) {
@@ -671,12 +1306,93 @@ impl<'a> FlowLayouter<'a> {
         Ok(())
     }
 
+    /// Lays out the numbers of the numbered lines in a region, given the
+    /// positions of the lines' baselines and their markers.
+    fn layout_line_numbers(
+        &self,
+        engine: &mut Engine,
+        output: &mut Frame,
+        lines: Vec<(Abs, Packed<ParLineMarker>)>,
+    ) -> SourceResult<()> {
+        let binding = PageElem::binding_in(self.styles).unwrap_or_else(|| {
+            match TextElem::dir_in(self.styles) {
+                Dir::LTR => Binding::Left,
+                _ => Binding::Right,
+            }
+        });
+        let two_sided = PageElem::margin_in(self.styles).two_sided.unwrap_or(false);
+
+        for (baseline, marker) in lines {
+            let loc = marker.location().unwrap();
+            let number = line_number(engine, loc, *marker.scope());
+            let context = Context::new(Some(loc), Some(self.styles));
+            let content = marker
+                .numbering()
+                .apply(engine, context.track(), &[number])?
+                .display();
+
+            let pod = Regions::one(Size::splat(Abs::inf()), Axes::splat(false));
+            let frame = content
+                .layout(engine, Locator::synthesize(loc), self.styles, pod)?
+                .into_frame();
+
+            // Place the number next to the line, aligning the baselines.
+            let bound = binding.on_page(engine.introspector.page(loc), two_sided);
+            let clearance = marker.clearance().resolve(self.styles);
+            let x = if marker.side().is_left(bound) {
+                -clearance - frame.width()
+            } else {
+                output.width() + clearance
+            };
+            let y = baseline - first_baseline(&frame).unwrap_or(frame.height());
+            output.push_frame(Point::new(x, y), frame);
+        }
+
+        Ok(())
+    }
+
+    /// Advances to the next region.
+    ///
+    /// In a multi-column layout, this reserves the space for floats spanning
+    /// all columns in the following columns of a region and places waiting
+    /// ones at the start of the next region.
+    fn next_region(&mut self, engine: &mut Engine) -> SourceResult<()> {
+        self.regions.next();
+        self.initial = self.regions.size;
+        self.region += 1;
+        self.exclusions.clear();
+        self.anchors = (None, Abs::zero());
+
+        let Some(columns) = self.columns else { return Ok(()) };
+        if self.region % columns.count == 0 {
+            self.parent_floats.clear();
+            for item in std::mem::take(&mut self.pending_parent_floats) {
+                self.layout_parent_float(engine, item)?;
+            }
+        } else {
+            for &(height, y_align) in &self.parent_floats {
+                self.regions.size.y -= height;
+                self.items.push(FlowItem::Placed {
+                    frame: Frame::soft(Size::new(Abs::zero(), height)),
+                    x_align: FixedAlignment::Start,
+                    y_align,
+                    delta: Axes::splat(Rel::zero()),
+                    float: true,
+                    clearance: Abs::zero(),
+                    order: Smart::Auto,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     /// Lays out all floating elements before continuing with other content.
     fn flush(&mut self, engine: &mut Engine) -> SourceResult<()> {
         for item in std::mem::take(&mut self.pending_floats) {
             self.layout_item(engine, item)?;
         }
-        while !self.pending_floats.is_empty() {
+        while !self.pending_floats.is_empty() || !self.pending_parent_floats.is_empty() {
             self.finish_region(engine, false)?;
         }
 
@@ -685,6 +1401,11 @@ impl<'a> FlowLayouter<'a> {
 
     /// Finish layouting and return the resulting fragment.
     fn finish(mut self, engine: &mut Engine) -> SourceResult<Fragment> {
+        // Drop whatever was laid out after the budget ran out.
+        if self.exhausted {
+            return Ok(Fragment::frames(self.finished));
+        }
+
         if self.expand.y {
             while !self.regions.backlog.is_empty() {
                 self.finish_region(engine, true)?;
@@ -692,7 +1413,7 @@ impl<'a> FlowLayouter<'a> {
         }
 
         self.finish_region(engine, true)?;
-        while !self.items.is_empty() {
+        while !self.items.is_empty() || !self.pending_parent_floats.is_empty() {
             self.finish_region(engine, true)?;
         }
 
@@ -736,10 +1457,16 @@ impl FlowLayouter<'_> {
         movable: bool,
         force: bool,
     ) -> SourceResult<bool> {
+        // Page-scoped footnotes are laid out below all columns once they are
+        // complete.
+        if self.columns.is_some() && self.footnote_config.scope == FootnoteScope::Page {
+            return Ok(true);
+        }
+
         let prev_notes_len = notes.len();
         let prev_items_len = self.items.len();
         let prev_size = self.regions.size;
-        let prev_has_footnotes = self.has_footnotes;
+        let prev_streams_len = self.footnote_streams.len();
 
         // Process footnotes one at a time.
         let mut k = 0;
@@ -749,21 +1476,33 @@ impl FlowLayouter<'_> {
                 continue;
             }
 
-            if !self.has_footnotes {
-                self.layout_footnote_separator(engine)?;
+            let stream = notes[k].stream(StyleChain::default()).clone();
+            if !self.footnote_streams.contains(&stream) {
+                self.layout_footnote_separator(engine, stream.clone())?;
             }
 
             self.regions.size.y -= self.footnote_config.gap;
-            let frames = FootnoteEntry::new(notes[k].clone())
-                .pack()
+            let entry = FootnoteEntry::new(notes[k].clone()).pack();
+            let locator = Locator::synthesize(notes[k].location().unwrap());
+            let mut frames = entry
                 .layout(
                     engine,
-                    Locator::synthesize(notes[k].location().unwrap()),
+                    locator.relayout(),
                     self.styles,
                     self.regions.with_root(false),
                 )?
                 .into_frames();
 
+            // If the entry is split, lay it out again with the continuation
+            // markers.
+            if frames.len() > 1
+                && (self.footnote_config.continued.is_some()
+                    || self.footnote_config.continuation.is_some())
+            {
+                let location = notes[k].location().unwrap();
+                frames = self.layout_split_footnote(engine, &entry, location)?;
+            }
+
             // If the entries didn't fit, abort (to keep footnote and entry
             // together).
             if !force
@@ -774,7 +1513,7 @@ impl FlowLayouter<'_> {
                 notes.truncate(prev_notes_len);
                 self.items.truncate(prev_items_len);
                 self.regions.size = prev_size;
-                self.has_footnotes = prev_has_footnotes;
+                self.footnote_streams.truncate(prev_streams_len);
                 return Ok(false);
             }
 
@@ -783,11 +1522,11 @@ impl FlowLayouter<'_> {
                 find_footnotes(notes, &frame);
                 if i > 0 {
                     self.finish_region(engine, false)?;
-                    self.layout_footnote_separator(engine)?;
+                    self.layout_footnote_separator(engine, stream.clone())?;
                     self.regions.size.y -= self.footnote_config.gap;
                 }
                 self.regions.size.y -= frame.height();
-                self.items.push(FlowItem::Footnote(frame));
+                self.items.push(FlowItem::Footnote(frame, stream.clone()));
             }
 
             k += 1;
@@ -803,8 +1542,91 @@ impl FlowLayouter<'_> {
         Ok(true)
     }
 
-    /// Layout and save the footnote separator, typically a line.
-    fn layout_footnote_separator(&mut self, engine: &mut Engine) -> SourceResult<()> {
+    /// Layout a footnote entry that is split across regions, appending the
+    /// `continued` marker to all parts but the last and prepending the
+    /// `continuation` marker to all parts but the first.
+    ///
+    /// The markers are located at variants of the footnote's location, just
+    /// like the entry's number.
+    fn layout_split_footnote(
+        &mut self,
+        engine: &mut Engine,
+        entry: &Content,
+        location: Location,
+    ) -> SourceResult<Vec<Frame>> {
+        // The markers are styled like the entry itself and separated from it
+        // by the leading of its lines.
+        let styles = entry.to_packed::<FootnoteEntry>().unwrap().show_set(self.styles);
+        let leading = ParElem::leading_in(self.styles.chain(&styles));
+        let pod = Regions::one(self.regions.base(), Axes::new(true, false));
+        let mut marker =
+            |content: &Option<Content>, above: bool| -> SourceResult<Option<Frame>> {
+                let Some(content) = content else { return Ok(None) };
+                let content = content.clone().styled_with_map(styles.clone());
+                let variant = if above { 3 } else { 2 };
+                let locator = Locator::synthesize(location.variant(variant));
+                let mut frame =
+                    content.layout(engine, locator, self.styles, pod)?.into_frame();
+                frame.size_mut().y += leading;
+                if !above {
+                    frame.translate(Point::with_y(leading));
+                }
+                Ok(Some(frame))
+            };
+        let continued = marker(&self.footnote_config.continued, false)?;
+        let continuation = marker(&self.footnote_config.continuation, true)?;
+        let height = |marker: &Option<Frame>| {
+            marker.as_ref().map(Frame::height).unwrap_or_default()
+        };
+        let continued_height = height(&continued);
+        let continuation_height = height(&continuation);
+
+        // Leave room for the markers in the regions.
+        let mut backlog = vec![];
+        let mut regions = self.regions.with_root(false).map(&mut backlog, |size| {
+            Size::new(size.x, size.y - continued_height - continuation_height)
+        });
+        regions.size.y = self.regions.size.y - continued_height;
+
+        // Parts that are empty because the entry didn't fit don't count.
+        let locator = Locator::synthesize(location);
+        let frames = entry.layout(engine, locator, self.styles, regions)?.into_frames();
+        let first = frames.iter().position(|frame| !frame.is_empty()).unwrap_or(0);
+        let count = frames.len();
+        Ok(frames
+            .into_iter()
+            .enumerate()
+            .map(|(i, frame)| {
+                if i < first {
+                    return frame;
+                }
+
+                let mut output = Frame::soft(Size::new(frame.width(), Abs::zero()));
+                let push = |output: &mut Frame, frame: Frame| {
+                    let y = output.height();
+                    output.size_mut().x.set_max(frame.width());
+                    output.size_mut().y += frame.height();
+                    output.push_frame(Point::with_y(y), frame);
+                };
+                if let Some(marker) = continuation.clone().filter(|_| i > first) {
+                    push(&mut output, marker);
+                }
+                push(&mut output, frame);
+                if let Some(marker) = continued.clone().filter(|_| i + 1 < count) {
+                    push(&mut output, marker);
+                }
+                output
+            })
+            .collect())
+    }
+
+    /// Layout and save the footnote separator of a note stream, typically a
+    /// line.
+    fn layout_footnote_separator(
+        &mut self,
+        engine: &mut Engine,
+        stream: Option<Str>,
+    ) -> SourceResult<()> {
         let expand = Axes::new(self.regions.expand.x, false);
         let pod = Regions::one(self.regions.base(), expand);
         let separator = &self.footnote_config.separator;
@@ -816,16 +1638,21 @@ impl FlowLayouter<'_> {
         frame.size_mut().y += self.footnote_config.clearance;
         frame.translate(Point::with_y(self.footnote_config.clearance));
 
-        self.has_footnotes = true;
+        // The areas of multiple streams are separated by the gap.
+        if !self.footnote_streams.is_empty() {
+            self.regions.size.y -= self.footnote_config.gap;
+        }
+
+        self.footnote_streams.push(stream.clone());
         self.regions.size.y -= frame.height();
-        self.items.push(FlowItem::Footnote(frame));
+        self.items.push(FlowItem::Footnote(frame, stream));
 
         Ok(())
     }
 }
 
 /// Finds all footnotes in the frame.
-fn find_footnotes(notes: &mut Vec<Packed<FootnoteElem>>, frame: &Frame) {
+pub(crate) fn find_footnotes(notes: &mut Vec<Packed<FootnoteElem>>, frame: &Frame) {
     for (_, item) in frame.items() {
         match item {
             FrameItem::Group(group) => find_footnotes(notes, &group.frame),
@@ -841,3 +1668,47 @@ fn find_footnotes(notes: &mut Vec<Packed<FootnoteElem>>, frame: &Frame) {
         }
     }
 }
+
+/// Finds the markers of the numbered lines in a frame, along with the
+/// positions of the lines' baselines.
+fn find_line_markers(
+    frame: &Frame,
+) -> impl Iterator<Item = (Abs, Packed<ParLineMarker>)> + '_ {
+    frame.items().filter_map(|(pos, item)| match item {
+        FrameItem::Tag(tag) if tag.elem.location().is_some() => tag
+            .elem
+            .to_packed::<ParLineMarker>()
+            .map(|marker| (pos.y, marker.clone())),
+        _ => None,
+    })
+}
+
+/// Determines the number of the line with the marker at the given location.
+fn line_number(engine: &Engine, loc: Location, scope: LineNumberingScope) -> usize {
+    let introspector = engine.introspector;
+    let selector = ParLineMarker::elem().select();
+    let count = introspector.query_count_before(&selector, loc);
+
+    // The number of marked lines before the numbering was last restarted.
+    let restart = match scope {
+        LineNumberingScope::Document => 0,
+        LineNumberingScope::Page => {
+            let page = introspector.page(loc);
+            introspector.query(&selector).partition_point(|marker| {
+                introspector.page(marker.location().unwrap()) < page
+            })
+        }
+        LineNumberingScope::Section => {
+            let headings = introspector.query(&Selector::Before {
+                selector: Arc::new(HeadingElem::elem().select()),
+                end: Arc::new(Selector::Location(loc)),
+                inclusive: false,
+            });
+            headings.last().map_or(0, |heading| {
+                introspector.query_count_before(&selector, heading.location().unwrap())
+            })
+        }
+    };
+
+    count.saturating_sub(restart)
+}
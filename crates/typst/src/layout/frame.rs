@@ -9,8 +9,8 @@ use smallvec::SmallVec;
 use crate::foundations::{cast, dict, Dict, StyleChain, Value};
 use crate::introspection::Tag;
 use crate::layout::{
-    Abs, Axes, Corners, FixedAlignment, HideElem, Length, Point, Rel, Sides, Size,
-    Transform,
+    Abs, Axes, Corners, FixedAlignment, HideElem, Length, Point, RedactElem, Rel, Sides,
+    Size, Transform,
 };
 use crate::model::{Destination, LinkElem};
 use crate::syntax::Span;
@@ -89,6 +89,13 @@ impl Frame {
         self.items.is_empty()
     }
 
+    /// Whether this frame's items and the other one's are the same instance,
+    /// i.e. the frames are clones of each other whose items weren't modified
+    /// since.
+    pub fn is_same(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.items, &other.items)
+    }
+
     /// The size of the frame.
     pub fn size(&self) -> Size {
         self.size
@@ -310,6 +317,7 @@ impl Frame {
     /// Apply late-stage properties from the style chain to this frame. This
     /// includes:
     /// - `HideElem::hidden`
+    /// - `RedactElem::redaction`
     /// - `LinkElem::dests`
     ///
     /// This must be called on all frames produced by elements
@@ -325,12 +333,18 @@ impl Frame {
             self.post_process_raw(
                 LinkElem::dests_in(styles),
                 HideElem::hidden_in(styles),
+                RedactElem::redaction_in(styles),
             );
         }
     }
 
     /// Apply raw late-stage properties from the raw data.
-    pub fn post_process_raw(&mut self, dests: SmallVec<[Destination; 1]>, hide: bool) {
+    pub fn post_process_raw(
+        &mut self,
+        dests: SmallVec<[Destination; 1]>,
+        hide: bool,
+        redaction: Option<Paint>,
+    ) {
         if !self.is_empty() {
             let size = self.size;
             self.push_multiple(
@@ -340,6 +354,8 @@ impl Frame {
             );
             if hide {
                 self.hide();
+            } else if let Some(fill) = redaction {
+                self.redact(&fill);
             }
         }
     }
@@ -356,6 +372,108 @@ impl Frame {
         });
     }
 
+    /// Replace all content in the frame by boxes of the same size, but keep
+    /// metadata.
+    ///
+    /// Each frame that directly holds content is covered by one box. Redacting
+    /// a frame twice yields the same result as redacting it once.
+    pub fn redact(&mut self, fill: &Paint) {
+        let mut visible = false;
+        Arc::make_mut(&mut self.items).retain_mut(|(_, item)| match item {
+            FrameItem::Group(group) => {
+                group.frame.redact(fill);
+                !group.frame.is_empty()
+            }
+            FrameItem::Tag(_) => true,
+            _ => {
+                visible = true;
+                false
+            }
+        });
+
+        if visible {
+            self.prepend(
+                Point::zero(),
+                FrameItem::Shape(
+                    Geometry::Rect(self.size).filled(fill.clone()),
+                    Span::detached(),
+                ),
+            );
+        }
+    }
+
+    /// Remove all content that extends beyond the horizontal position `limit`
+    /// and return the right edge of the remaining content.
+    ///
+    /// Text runs are cut glyph-wise and untransformed groups are truncated
+    /// recursively. Links are clipped to the limit. All other items, including
+    /// transformed groups, are dropped if they cross the limit.
+    pub fn truncate(&mut self, limit: Abs) -> Abs {
+        let mut end = Abs::zero();
+        Arc::make_mut(&mut self.items).retain_mut(|(pos, item)| {
+            let width = match item {
+                FrameItem::Group(group) if group.transform.is_identity() => {
+                    let inner = group.frame.truncate(limit - pos.x);
+                    if group.frame.is_empty() {
+                        return false;
+                    }
+                    end.set_max(pos.x + inner);
+                    return true;
+                }
+                FrameItem::Text(text) => {
+                    let mut x = pos.x;
+                    let kept = text
+                        .glyphs
+                        .iter()
+                        .take_while(|glyph| {
+                            let next = x + glyph.x_advance.at(text.size);
+                            let fits = limit.fits(next);
+                            if fits {
+                                x = next;
+                            }
+                            fits
+                        })
+                        .count();
+                    if kept == 0 {
+                        return false;
+                    }
+                    text.glyphs.truncate(kept);
+                    end.set_max(x);
+                    return true;
+                }
+                FrameItem::Link(_, size) => {
+                    size.x.set_min(limit - pos.x);
+                    return size.x > Abs::zero();
+                }
+                FrameItem::Tag(_) => return true,
+                FrameItem::Group(group) => {
+                    let size = group.frame.size();
+                    let corners = [
+                        Point::zero(),
+                        Point::with_x(size.x),
+                        Point::with_y(size.y),
+                        size.to_point(),
+                    ];
+                    corners
+                        .into_iter()
+                        .map(|corner| corner.transform(group.transform).x)
+                        .max()
+                        .unwrap_or_default()
+                }
+                FrameItem::Shape(shape, _) => shape.geometry.bbox_size().x,
+                FrameItem::Image(_, size, _) => size.x,
+            };
+
+            let right = pos.x + width;
+            let fits = limit.fits(right);
+            if fits {
+                end.set_max(right);
+            }
+            fits
+        });
+        end
+    }
+
     /// Add a background fill.
     pub fn fill(&mut self, fill: Paint) {
         self.prepend(
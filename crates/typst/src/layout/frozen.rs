@@ -0,0 +1,106 @@
+use comemo::{Track, Tracked, TrackedMut};
+
+use crate::diag::SourceResult;
+use crate::engine::{Budget, Engine, Route, Sink, Traced};
+use crate::foundations::{elem, Content, NativeElement, Packed, Show, StyleChain};
+use crate::introspection::{Introspector, Locator};
+use crate::layout::{strip_tags, Abs, Axes, BlockElem, Frame, Region, Regions, Size};
+use crate::utils::Numeric;
+use crate::World;
+
+/// Lays out content once and keeps its layout across compilations.
+///
+/// Layout is normally repeated whenever the document changes in a way that
+/// could affect the content, for instance, because it shows a page number or
+/// because introspections elsewhere in the document changed. Content wrapped
+/// in `frozen` is laid out independently of the rest of the document, such
+/// that its layout is only repeated if the content itself, its styles, or the
+/// width available to it change. This speeds up the compilation of documents
+/// with expensive static artwork like title pages or large diagrams.
+///
+/// Frozen content is laid out as a single, unbreakable block that is as tall
+/// as it needs to be. Just like with a [stamp]($stamp), its content is not
+/// visible to introspection: It can't be [queried]($query) or
+/// [located]($locate), and [context]($context) expressions within it don't
+/// know their location or anything about the rest of the document.
+///
+/// # Example
+/// ```example
+/// #frozen(
+///   grid(
+///     columns: 8,
+///     gutter: 2pt,
+///     ..range(32).map(i => square(
+///       size: 8pt,
+///       fill: blue.lighten(calc.rem(i * 7, 10) * 8%),
+///     )),
+///   ),
+/// )
+/// ```
+#[elem(Show)]
+pub struct FrozenElem {
+    /// The content to freeze.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for Packed<FrozenElem> {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(BlockElem::single_layouter(self.clone(), layout_frozen)
+            .pack()
+            .spanned(self.span()))
+    }
+}
+
+/// Layout the frozen content.
+#[typst_macros::time(span = elem.span())]
+fn layout_frozen(
+    elem: &Packed<FrozenElem>,
+    engine: &mut Engine,
+    _: Locator,
+    styles: StyleChain,
+    region: Region,
+) -> SourceResult<Frame> {
+    let expand = region.expand.x && region.size.x.is_finite();
+    freeze(
+        engine.world,
+        TrackedMut::reborrow_mut(&mut engine.sink),
+        engine.route.track(),
+        elem.body(),
+        styles,
+        region.size.x,
+        expand,
+    )
+}
+
+/// Lay out content without access to the rest of the document.
+///
+/// The layout neither depends on the introspector nor on a locator, so it is
+/// reused from the cache as long as the content, styles, and width stay the
+/// same.
+#[comemo::memoize]
+fn freeze(
+    world: Tracked<dyn World + '_>,
+    sink: TrackedMut<Sink>,
+    route: Tracked<Route>,
+    body: &Content,
+    styles: StyleChain,
+    width: Abs,
+    expand: bool,
+) -> SourceResult<Frame> {
+    let introspector = Introspector::default();
+    let traced = Traced::default();
+    let budget = Budget::default();
+    let mut engine = Engine {
+        world,
+        introspector: introspector.track(),
+        traced: traced.track(),
+        budget: budget.track(),
+        sink,
+        route: Route::extend(route),
+    };
+
+    let pod = Regions::one(Size::new(width, Abs::inf()), Axes::new(expand, false));
+    let frame = body.layout(&mut engine, Locator::root(), styles, pod)?.into_frame();
+    Ok(strip_tags(&frame))
+}
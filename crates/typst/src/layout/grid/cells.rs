@@ -4,8 +4,9 @@ use std::sync::Arc;
 use comemo::Track;
 use ecow::eco_format;
 
+use super::formula::compute_formulas;
 use super::lines::Line;
-use super::repeated::{Footer, Header, Repeatable};
+use super::repeated::{Footer, Header, Repeatable, RowGroup};
 use crate::diag::{bail, At, Hint, HintedStrResult, HintedString, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
@@ -16,6 +17,7 @@ use crate::introspection::Locator;
 use crate::layout::{
     Abs, Alignment, Axes, Fragment, Length, LinePosition, Regions, Rel, Sides, Sizing,
 };
+use crate::model::TableHeaderScope;
 use crate::syntax::Span;
 use crate::utils::NonZeroExt;
 use crate::visualize::{Paint, Stroke};
@@ -237,7 +239,7 @@ pub(super) enum Entry<'a> {
 
 impl<'a> Entry<'a> {
     /// Obtains the cell inside this entry, if this is not a merged cell.
-    fn as_cell(&self) -> Option<&Cell<'a>> {
+    pub(super) fn as_cell(&self) -> Option<&Cell<'a>> {
         match self {
             Self::Cell(cell) => Some(cell),
             Self::Merged { .. } => None,
@@ -278,10 +280,12 @@ pub enum ResolvableGridItem<T: ResolvableCell> {
     Cell(T),
 }
 
-/// Any grid child, which can be either a header or an item.
+/// Any grid child, which can be either a header, a footer, a row group or an
+/// item.
 pub enum ResolvableGridChild<T: ResolvableCell, I> {
     Header { repeat: bool, span: Span, items: I },
     Footer { repeat: bool, span: Span, items: I },
+    Group { header: usize, span: Span, items: I },
     Item(ResolvableGridItem<T>),
 }
 
@@ -320,6 +324,43 @@ pub trait ResolvableCell {
 
     /// The cell's span, for errors.
     fn span(&self) -> Span;
+
+    /// Completes a resolved cell with information that is only available once
+    /// the whole grid has been resolved: the grid's dimensions and whether
+    /// the cell is part of the header or footer.
+    fn finalize_cell(
+        cell: &mut Cell,
+        column_count: usize,
+        row_count: usize,
+        header: bool,
+        footer: bool,
+    );
+
+    /// Returns the formula of a resolved cell, if it has one.
+    fn cell_formula(cell: &Cell, styles: StyleChain) -> Option<Func>;
+
+    /// Returns the body of a resolved cell, as given by the user.
+    fn cell_body<'a>(cell: &'a Cell) -> Option<&'a Content>;
+
+    /// Replaces the body of a resolved cell, e.g. by the result of its
+    /// formula.
+    fn set_cell_body(cell: &mut Cell, body: Content);
+
+    /// Returns which cells a resolved cell is a header for, given whether it
+    /// is part of the grid's header. Returns `None` for data cells.
+    fn cell_scope(
+        cell: &Cell,
+        header: bool,
+        styles: StyleChain,
+    ) -> Option<TableHeaderScope>;
+
+    /// Records a resolved cell's header scope and the positions of the header
+    /// cells which apply to it.
+    fn set_cell_headers(
+        cell: &mut Cell,
+        scope: Option<TableHeaderScope>,
+        headers: &[Axes<usize>],
+    );
 }
 
 /// A grid of cells, including the columns, rows, and cell data.
@@ -342,6 +383,8 @@ pub struct CellGrid<'a> {
     pub(super) header: Option<Repeatable<Header>>,
     /// The repeatable footer of this grid.
     pub(super) footer: Option<Repeatable<Footer>>,
+    /// The groups of rows which should be kept together.
+    pub(super) groups: Vec<RowGroup>,
     /// Whether this grid has gutters.
     pub(super) has_gutter: bool,
 }
@@ -354,7 +397,7 @@ impl<'a> CellGrid<'a> {
         cells: impl IntoIterator<Item = Cell<'a>>,
     ) -> Self {
         let entries = cells.into_iter().map(Entry::Cell).collect();
-        Self::new_internal(tracks, gutter, vec![], vec![], None, None, entries)
+        Self::new_internal(tracks, gutter, vec![], vec![], None, None, vec![], entries)
     }
 
     /// Resolves and positions all cells in the grid before creating it.
@@ -410,6 +453,9 @@ impl<'a> CellGrid<'a> {
         let mut footer: Option<(usize, Span, Footer)> = None;
         let mut repeat_footer = false;
 
+        // Stores the span of each row group alongside the group itself.
+        let mut groups: Vec<(Span, RowGroup)> = vec![];
+
         // Resolve the breakability of a cell, based on whether or not it spans
         // an auto row.
         let resolve_breakable = |y, rowspan| {
@@ -460,6 +506,8 @@ impl<'a> CellGrid<'a> {
         for child in children {
             let mut is_header = false;
             let mut is_footer = false;
+            let mut is_group = false;
+            let mut group_header_rows = 0;
             let mut child_start = usize::MAX;
             let mut child_end = 0;
             let mut child_span = Span::detached();
@@ -512,6 +560,22 @@ impl<'a> CellGrid<'a> {
 
                     (Some(items), None)
                 }
+                ResolvableGridChild::Group { header, span, items } => {
+                    is_group = true;
+                    child_span = span;
+                    group_header_rows = header;
+
+                    // Like headers and footers, row groups always start at a
+                    // new row, such that they don't share any rows with the
+                    // cells before them.
+                    start_new_row = true;
+
+                    // Any hlines at the top of the group will start at this
+                    // index.
+                    first_index_of_top_hlines = pending_hlines.len();
+
+                    (Some(items), None)
+                }
                 ResolvableGridChild::Item(item) => (None, Some(item)),
             };
 
@@ -761,9 +825,9 @@ impl<'a> CellGrid<'a> {
                     }
                 }
 
-                if is_header || is_footer {
-                    // Ensure each cell in a header or footer is fully
-                    // contained within it.
+                if is_header || is_footer || is_group {
+                    // Ensure each cell in a header, footer or row group is
+                    // fully contained within it.
                     child_start = child_start.min(y);
                     child_end = child_end.max(y + rowspan);
 
@@ -784,9 +848,9 @@ impl<'a> CellGrid<'a> {
                 }
             }
 
-            if (is_header || is_footer) && child_start == usize::MAX {
-                // Empty header/footer: consider the header/footer to be
-                // at the next empty row after the latest auto index.
+            if (is_header || is_footer || is_group) && child_start == usize::MAX {
+                // Empty header/footer/group: consider it to be at the next
+                // empty row after the latest auto index.
                 auto_index = find_next_empty_row(&resolved_cells, auto_index, c);
                 child_start = auto_index.div_ceil(c);
                 child_end = child_start + 1;
@@ -833,7 +897,57 @@ impl<'a> CellGrid<'a> {
                 ));
             }
 
-            if is_header || is_footer {
+            if is_group {
+                if groups
+                    .iter()
+                    .any(|(_, group)| group.start < child_end && child_start < group.end)
+                {
+                    bail!(child_span, "row groups must not have common rows");
+                }
+
+                let header_end = child_start.saturating_add(group_header_rows);
+                if header_end > child_end {
+                    bail!(
+                        child_span,
+                        "row group header cannot have more rows than the group";
+                        hint: "try reducing the amount of header rows"
+                    );
+                }
+
+                // The header is repeated on its own when the group is split,
+                // so its cells must not span the rows after it.
+                if resolved_cells
+                    .iter()
+                    .enumerate()
+                    .skip(c * child_start)
+                    .take(c * group_header_rows)
+                    .any(|(i, entry)| {
+                        entry
+                            .as_ref()
+                            .and_then(Entry::as_cell)
+                            .is_some_and(|cell| i / c + cell.rowspan.get() > header_end)
+                    })
+                {
+                    bail!(
+                        child_span,
+                        "row group header would conflict with a cell spanning the rows after it";
+                        hint: "try reducing that cell's rowspan or increasing the amount of header rows"
+                    );
+                }
+
+                groups.push((
+                    child_span,
+                    RowGroup {
+                        // Later on, we have to correct these numbers in case
+                        // there is gutter.
+                        start: child_start,
+                        header_end,
+                        end: child_end,
+                    },
+                ));
+            }
+
+            if is_header || is_footer || is_group {
                 let amount_hlines = pending_hlines.len();
                 for (_, top_hline, has_auto_y) in pending_hlines
                     .get_mut(
@@ -850,7 +964,8 @@ impl<'a> CellGrid<'a> {
                     }
                 }
 
-                // Next automatically positioned cell goes under this header.
+                // Next automatically positioned cell goes under this header
+                // (or footer, or row group).
                 // FIXME: Consider only doing this if the header has any fully
                 // automatically positioned cells. Otherwise,
                 // `resolve_cell_position` should be smart enough to skip
@@ -884,7 +999,7 @@ impl<'a> CellGrid<'a> {
         // creation, ensure the header expands enough to accommodate them
         // across all of their spanned rows. Same for the footer.
         // 4. If any cells before the footer try to span it, error.
-        let resolved_cells = resolved_cells
+        let mut resolved_cells = resolved_cells
             .into_iter()
             .chain(std::iter::repeat_with(|| None).take(missing_cells))
             .enumerate()
@@ -965,6 +1080,34 @@ impl<'a> CellGrid<'a> {
         let mut hlines: Vec<Vec<Line>> = vec![];
         let row_amount = resolved_cells.len().div_ceil(c);
 
+        // Now that the grid's dimensions as well as its header and footer are
+        // final, let the cells know about them.
+        let header_end = header.as_ref().map_or(0, |header| header.end);
+        let footer_start =
+            footer.as_ref().map_or(row_amount, |(_, _, footer)| footer.start);
+        for (i, entry) in resolved_cells.iter_mut().enumerate() {
+            if let Entry::Cell(cell) = entry {
+                let y = i / c;
+                T::finalize_cell(cell, c, row_amount, y < header_end, y >= footer_start);
+            }
+        }
+
+        associate_headers::<T>(&mut resolved_cells, c, header_end, styles);
+
+        // With all cells in place, the values of cells with formulas can be
+        // computed.
+        compute_formulas::<T>(&mut resolved_cells, c, engine, styles)?;
+
+        // Row groups are laid out between the header and the footer.
+        for (group_span, group) in &groups {
+            if group.start < header_end || group.end > footer_start {
+                bail!(
+                    *group_span,
+                    "row group must not have common rows with the header or footer"
+                );
+            }
+        }
+
         for (line_span, line, _) in pending_hlines {
             let y = line.index;
             if y > row_amount {
@@ -1112,6 +1255,22 @@ impl<'a> CellGrid<'a> {
                 }
             });
 
+        let groups = groups
+            .into_iter()
+            .map(|(_, mut group)| {
+                if has_gutter {
+                    // Convert the group's rows to post-gutter coordinates.
+                    // The gutter row after the group's last row isn't part of
+                    // it, but the gutter row after its header is, as it is
+                    // repeated together with the header.
+                    group.start *= 2;
+                    group.end = 2 * group.end - 1;
+                    group.header_end = (2 * group.header_end).min(group.end);
+                }
+                group
+            })
+            .collect();
+
         Ok(Self::new_internal(
             tracks,
             gutter,
@@ -1119,11 +1278,13 @@ impl<'a> CellGrid<'a> {
             hlines,
             header,
             footer,
+            groups,
             resolved_cells,
         ))
     }
 
     /// Generates the cell grid, given the tracks and resolved entries.
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn new_internal(
         tracks: Axes<&[Sizing]>,
         gutter: Axes<&[Sizing]>,
@@ -1131,6 +1292,7 @@ impl<'a> CellGrid<'a> {
         hlines: Vec<Vec<Line>>,
         header: Option<Repeatable<Header>>,
         footer: Option<Repeatable<Footer>>,
+        groups: Vec<RowGroup>,
         entries: Vec<Entry<'a>>,
     ) -> Self {
         let mut cols = vec![];
@@ -1185,6 +1347,7 @@ impl<'a> CellGrid<'a> {
             hlines,
             header,
             footer,
+            groups,
             has_gutter,
         }
     }
@@ -1476,3 +1639,62 @@ fn skip_auto_index_through_fully_merged_rows(
         }
     }
 }
+
+/// Associates each cell with the header cells which apply to it: the column
+/// headers above it in its columns and the row headers before it in its rows.
+/// With multiple levels of headers, a cell is associated with all of them.
+fn associate_headers<T: ResolvableCell>(
+    entries: &mut [Entry],
+    columns: usize,
+    header_end: usize,
+    styles: StyleChain,
+) {
+    let scopes: Vec<_> = entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            entry
+                .as_cell()
+                .and_then(|cell| T::cell_scope(cell, i / columns < header_end, styles))
+        })
+        .collect();
+
+    // The position, spans and scope of each header cell, in row-major order.
+    let headers: Vec<_> = entries
+        .iter()
+        .zip(&scopes)
+        .enumerate()
+        .filter_map(|(i, (entry, scope))| {
+            let cell = entry.as_cell()?;
+            let pos = Axes::new(i % columns, i / columns);
+            Some((pos, Axes::new(cell.colspan.get(), cell.rowspan.get()), (*scope)?))
+        })
+        .collect();
+
+    if headers.is_empty() {
+        return;
+    }
+
+    for (i, (entry, scope)) in entries.iter_mut().zip(scopes).enumerate() {
+        let Entry::Cell(cell) = entry else { continue };
+        let (x, y) = (i % columns, i / columns);
+        let (x_end, y_end) = (x + cell.colspan.get(), y + cell.rowspan.get());
+
+        let column_headers = headers.iter().filter(|(pos, span, scope)| {
+            scope.applies_to_columns()
+                && pos.y + span.y <= y
+                && pos.x < x_end
+                && x < pos.x + span.x
+        });
+        let row_headers = headers.iter().filter(|(pos, span, scope)| {
+            scope.applies_to_rows()
+                && pos.x + span.x <= x
+                && pos.y < y_end
+                && y < pos.y + span.y
+        });
+
+        let associated: Vec<_> =
+            column_headers.chain(row_headers).map(|(pos, ..)| *pos).collect();
+        T::set_cell_headers(cell, scope, &associated);
+    }
+}
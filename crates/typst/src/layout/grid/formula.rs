@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex};
+
+use comemo::{Track, Tracked};
+use ecow::{eco_format, EcoString, EcoVec};
+
+use super::cells::{Entry, ResolvableCell};
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::eval::ops;
+use crate::foundations::{
+    func, repr, scope, ty, Array, Content, Context, Func, Repr, Str, StyleChain, Value,
+};
+use crate::layout::Axes;
+use crate::syntax::Span;
+
+/// The values of a grid's or table's cells, as seen by cell formulas.
+///
+/// A sheet is passed to the [`formula`]($table.cell.formula) of a cell and
+/// gives access to the values of the other cells. Cells are referenced like in
+/// a spreadsheet: by their column's letter followed by their row's number,
+/// starting at one. For instance, `{"A1"}` is the first cell of the first row
+/// and `{"C2:C10"}` is the range of the second to the tenth cell of the third
+/// column.
+///
+/// The value of a cell is a number if its body is a number, a string if it
+/// contains other text and `{none}` if it is empty. Cells with a formula have
+/// the value computed by their formula.
+#[ty(scope)]
+#[derive(Debug, Clone)]
+pub struct Sheet {
+    /// The amount of columns.
+    columns: usize,
+    /// The cells in row-major order.
+    cells: EcoVec<SheetCell>,
+    /// The formula cells which are currently being evaluated. Used to detect
+    /// cyclic references.
+    pending: EcoVec<usize>,
+    /// The values of the formula cells which were already evaluated. Shared
+    /// between all copies of the sheet, so that each formula runs only once.
+    results: Arc<Mutex<HashMap<usize, Value>>>,
+}
+
+/// A cell in a sheet.
+#[derive(Debug, Clone, PartialEq, Hash)]
+enum SheetCell {
+    /// A cell with a fixed value.
+    Value(Value),
+    /// A cell whose value is computed by a formula.
+    Formula(Func),
+}
+
+impl Sheet {
+    /// Evaluates the value of the cell with the given index.
+    fn eval(
+        &self,
+        engine: &mut Engine,
+        context: Tracked<Context>,
+        span: Span,
+        index: usize,
+    ) -> SourceResult<Value> {
+        match &self.cells[index] {
+            SheetCell::Value(value) => Ok(value.clone()),
+            SheetCell::Formula(formula) => {
+                if let Some(value) = self.results.lock().unwrap().get(&index) {
+                    return Ok(value.clone());
+                }
+                if self.pending.contains(&index) {
+                    let name = self.name(index);
+                    bail!(span, "cell formula has a cyclic reference to cell {name}");
+                }
+                let mut sheet = self.clone();
+                sheet.pending.push(index);
+                let value = formula.call(engine, context, [sheet])?;
+                self.results.lock().unwrap().insert(index, value.clone());
+                Ok(value)
+            }
+        }
+    }
+
+    /// The amount of rows.
+    fn rows(&self) -> usize {
+        self.cells.len().div_ceil(self.columns)
+    }
+
+    /// The indices of the cells in the given range, in row-major order.
+    ///
+    /// Parts of the range outside of the sheet are ignored.
+    fn range(&self, range: &str) -> StrResult<impl Iterator<Item = usize> + '_> {
+        let (first, last) = match range.split_once(':') {
+            Some((first, last)) => (parse_reference(first)?, parse_reference(last)?),
+            None => {
+                let cell = parse_reference(range)?;
+                (cell, cell)
+            }
+        };
+        let clamp = |range: RangeInclusive<usize>, len: usize| {
+            *range.start()..len.min(range.end() + 1)
+        };
+        let xs = clamp(first.x.min(last.x)..=first.x.max(last.x), self.columns);
+        let ys = clamp(first.y.min(last.y)..=first.y.max(last.y), self.rows());
+        Ok(ys.flat_map(move |y| xs.clone().map(move |x| y * self.columns + x)))
+    }
+
+    /// The spreadsheet-style name of the cell with the given index.
+    fn name(&self, index: usize) -> EcoString {
+        let mut letters = vec![];
+        let mut x = index % self.columns + 1;
+        while x > 0 {
+            x -= 1;
+            letters.push(char::from(b'A' + (x % 26) as u8));
+            x /= 26;
+        }
+        let column: EcoString = letters.into_iter().rev().collect();
+        eco_format!("{column}{}", index / self.columns + 1)
+    }
+}
+
+#[scope]
+impl Sheet {
+    /// Retrieves the value of a single cell.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 2,
+    ///   [Net], [100],
+    ///   [Gross], table.cell(formula: r => r.at("B1") * 1.2)[],
+    /// )
+    /// ```
+    #[func]
+    pub fn at(
+        &self,
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
+        /// The reference to the cell, e.g. `{"B3"}`.
+        reference: Str,
+    ) -> SourceResult<Value> {
+        let cell = parse_reference(&reference).at(span)?;
+        if cell.x >= self.columns || cell.y >= self.rows() {
+            bail!(span, "cell {reference} does not exist");
+        }
+        self.eval(engine, context, span, cell.y * self.columns + cell.x)
+    }
+
+    /// Retrieves the values of all cells in a range, row by row.
+    #[func]
+    pub fn values(
+        &self,
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
+        /// The range of cells, e.g. `{"A2:C4"}`.
+        range: Str,
+    ) -> SourceResult<Array> {
+        self.range(&range)
+            .at(span)?
+            .map(|index| self.eval(engine, context, span, index))
+            .collect()
+    }
+
+    /// Sums the numeric values of all cells in a range.
+    ///
+    /// Cells which don't hold a number, like empty cells or cells with text,
+    /// are skipped. The sum of a range without any numbers is zero.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 2,
+    ///   [Apples], [12],
+    ///   [Pears], [7],
+    ///   [Plums], [],
+    ///   [*Total*], table.cell(formula: r => r.sum("B1:B3"))[],
+    /// )
+    /// ```
+    #[func]
+    pub fn sum(
+        &self,
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
+        /// The range of cells, e.g. `{"C2:C10"}`.
+        range: Str,
+    ) -> SourceResult<Value> {
+        let mut acc = Value::Int(0);
+        for index in self.range(&range).at(span)? {
+            let value = self.eval(engine, context, span, index)?;
+            if matches!(value, Value::Int(_) | Value::Float(_)) {
+                acc = ops::add(acc, value).at(span)?;
+            }
+        }
+        Ok(acc)
+    }
+}
+
+impl PartialEq for Sheet {
+    fn eq(&self, other: &Self) -> bool {
+        self.columns == other.columns
+            && self.cells == other.cells
+            && self.pending == other.pending
+    }
+}
+
+impl Hash for Sheet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.columns.hash(state);
+        self.cells.hash(state);
+        self.pending.hash(state);
+    }
+}
+
+impl Repr for Sheet {
+    fn repr(&self) -> EcoString {
+        eco_format!("sheet({} columns, {} rows)", self.columns, self.rows())
+    }
+}
+
+/// Replaces the bodies of all cells with a formula by the formula's result.
+pub(super) fn compute_formulas<T: ResolvableCell>(
+    entries: &mut [Entry],
+    columns: usize,
+    engine: &mut Engine,
+    styles: StyleChain,
+) -> SourceResult<()> {
+    let cells: EcoVec<SheetCell> = entries
+        .iter()
+        .map(|entry| {
+            match entry.as_cell().and_then(|cell| T::cell_formula(cell, styles)) {
+                Some(formula) => SheetCell::Formula(formula),
+                None => SheetCell::Value(
+                    entry
+                        .as_cell()
+                        .and_then(T::cell_body)
+                        .map_or(Value::None, cell_value),
+                ),
+            }
+        })
+        .collect();
+
+    if !cells.iter().any(|cell| matches!(cell, SheetCell::Formula(_))) {
+        return Ok(());
+    }
+
+    let sheet = Sheet {
+        columns,
+        cells,
+        pending: EcoVec::new(),
+        results: Arc::new(Mutex::new(HashMap::new())),
+    };
+    let context = Context::new(None, Some(styles));
+    for (index, entry) in entries.iter_mut().enumerate() {
+        if let (SheetCell::Formula(_), Entry::Cell(cell)) = (&sheet.cells[index], entry) {
+            let span = cell.body.span();
+            let value = sheet.eval(engine, context.track(), span, index)?;
+            T::set_cell_body(cell, value.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// The value of a cell without a formula, determined from its body.
+fn cell_value(body: &Content) -> Value {
+    let text = body.plain_text().replace(repr::MINUS_SIGN, "-");
+    let text = text.trim();
+    if text.is_empty() {
+        Value::None
+    } else if let Ok(int) = text.parse::<i64>() {
+        Value::Int(int)
+    } else if let Ok(float) = text.parse::<f64>() {
+        Value::Float(float)
+    } else {
+        Value::Str(text.into())
+    }
+}
+
+/// Parses a spreadsheet-style cell reference like `B3` into zero-based column
+/// and row indices.
+fn parse_reference(reference: &str) -> StrResult<Axes<usize>> {
+    let reference = reference.trim();
+    let split = reference
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(reference.len());
+    let (letters, digits) = reference.split_at(split);
+    let column = letters.bytes().try_fold(0_usize, |acc, letter| {
+        acc.checked_mul(26)?
+            .checked_add(usize::from(letter.to_ascii_uppercase() - b'A') + 1)
+    });
+    let row = digits.parse::<usize>().ok();
+    match (column, row) {
+        (Some(x), Some(y)) if x > 0 && y > 0 => Ok(Axes::new(x - 1, y - 1)),
+        _ => bail!("invalid cell reference: {}", reference.repr()),
+    }
+}
@@ -4,7 +4,7 @@ use super::lines::{
     generate_line_segments, hline_stroke_at_column, vline_stroke_at_row, LinePosition,
     LineSegment,
 };
-use super::repeated::Repeatable;
+use super::repeated::{Repeatable, RowGroup};
 use super::rowspans::{Rowspan, UnbreakableRowGroup};
 use crate::diag::{bail, SourceResult};
 use crate::engine::Engine;
@@ -57,6 +57,12 @@ pub struct GridLayouter<'a> {
     /// The simulated footer height for this region.
     /// The simulation occurs before any rows are laid out for a region.
     pub(super) footer_height: Abs,
+    /// The row group whose body is currently being laid out, if it has a
+    /// header which must be repeated on region breaks.
+    pub(super) current_group: Option<&'a RowGroup>,
+    /// The height of the current row group's header, which is included in
+    /// `header_height`.
+    pub(super) group_header_height: Abs,
     /// The span of the grid element.
     pub(super) span: Span,
 }
@@ -122,6 +128,8 @@ impl<'a> GridLayouter<'a> {
             is_rtl: TextElem::dir_in(styles) == Dir::RTL,
             header_height: Abs::zero(),
             footer_height: Abs::zero(),
+            current_group: None,
+            group_header_height: Abs::zero(),
             span,
         }
     }
@@ -161,6 +169,7 @@ impl<'a> GridLayouter<'a> {
                 }
             }
 
+            self.update_current_group(y, engine)?;
             self.layout_row(y, engine, 0)?;
         }
 
@@ -1530,6 +1539,11 @@ impl<'a> GridLayouter<'a> {
 
         if !last {
             let disambiguator = self.finished.len();
+
+            // The header of the current row group is laid out again below.
+            self.header_height -= self.group_header_height;
+            self.group_header_height = Abs::zero();
+
             if let Some(Repeatable::Repeated(footer)) = &self.grid.footer {
                 self.prepare_footer(footer, engine, disambiguator)?;
             }
@@ -1541,6 +1555,11 @@ impl<'a> GridLayouter<'a> {
 
             // Ensure rows don't try to overrun the footer.
             self.regions.size.y -= self.footer_height;
+
+            if let Some(group) = self.current_group {
+                // Repeat the header of the row group being split.
+                self.layout_group_header(group, engine, disambiguator)?;
+            }
         }
 
         Ok(())
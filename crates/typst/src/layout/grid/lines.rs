@@ -678,6 +678,7 @@ mod test {
             vec![],
             None,
             None,
+            vec![],
             entries,
         )
     }
@@ -1216,6 +1217,7 @@ mod test {
             vec![],
             None,
             None,
+            vec![],
             entries,
         )
     }
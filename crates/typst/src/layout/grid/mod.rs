@@ -1,4 +1,5 @@
 mod cells;
+mod formula;
 mod layout;
 mod lines;
 mod repeated;
@@ -7,6 +8,7 @@ mod rowspans;
 pub use self::cells::{
     Cell, CellGrid, Celled, ResolvableCell, ResolvableGridChild, ResolvableGridItem,
 };
+pub use self::formula::Sheet;
 pub use self::layout::GridLayouter;
 pub use self::lines::LinePosition;
 
@@ -19,7 +21,7 @@ use smallvec::{smallvec, SmallVec};
 use crate::diag::{bail, HintedStrResult, HintedString, SourceResult, Trace, Tracepoint};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, scope, Array, Content, Fold, NativeElement, Packed, Show, Smart,
+    cast, elem, scope, Array, Content, Fold, Func, NativeElement, Packed, Show, Smart,
     StyleChain, Value,
 };
 use crate::introspection::Locator;
@@ -27,7 +29,10 @@ use crate::layout::{
     Abs, Alignment, Axes, BlockElem, Dir, Fragment, Length, OuterHAlignment,
     OuterVAlignment, Regions, Rel, Sides, Sizing,
 };
-use crate::model::{TableCell, TableFooter, TableHLine, TableHeader, TableVLine};
+use crate::model::{
+    TableCell, TableFooter, TableGroup, TableHLine, TableHeader, TableHeaderScope,
+    TableVLine,
+};
 use crate::syntax::Span;
 use crate::text::TextElem;
 use crate::utils::NonZeroExt;
@@ -335,6 +340,9 @@ impl GridElem {
 
     #[elem]
     type GridFooter;
+
+    #[elem]
+    type GridGroup;
 }
 
 impl Show for Packed<GridElem> {
@@ -379,6 +387,11 @@ fn layout_grid(
             span: footer.span(),
             items: footer.children().iter().map(resolve_item),
         },
+        GridChild::Group(group) => ResolvableGridChild::Group {
+            header: group.header(styles),
+            span: group.span(),
+            items: group.children().iter().map(resolve_item),
+        },
         GridChild::Item(item) => ResolvableGridChild::Item(item.to_resolvable(styles)),
     });
     let grid = CellGrid::resolve(
@@ -419,6 +432,7 @@ cast! {
 pub enum GridChild {
     Header(Packed<GridHeader>),
     Footer(Packed<GridFooter>),
+    Group(Packed<GridGroup>),
     Item(GridItem),
 }
 
@@ -427,6 +441,7 @@ cast! {
     self => match self {
         Self::Header(header) => header.into_value(),
         Self::Footer(footer) => footer.into_value(),
+        Self::Group(group) => group.into_value(),
         Self::Item(item) => item.into_value(),
     },
     v: Content => {
@@ -449,11 +464,18 @@ impl TryFrom<Content> for GridChild {
                 hint: "use `grid.footer` instead"
             )
         }
+        if value.is::<TableGroup>() {
+            bail!(
+                "cannot use `table.group` as a grid row group";
+                hint: "use `grid.group` instead"
+            )
+        }
 
         value
             .into_packed::<GridHeader>()
             .map(Self::Header)
             .or_else(|value| value.into_packed::<GridFooter>().map(Self::Footer))
+            .or_else(|value| value.into_packed::<GridGroup>().map(Self::Group))
             .or_else(|value| GridItem::try_from(value).map(Self::Item))
     }
 }
@@ -531,6 +553,14 @@ impl TryFrom<Content> for GridItem {
         if value.is::<TableFooter>() {
             bail!("cannot place a table footer within another footer or header");
         }
+        if value.is::<GridGroup>() {
+            bail!("cannot place a grid row group within another group, header or footer");
+        }
+        if value.is::<TableGroup>() {
+            bail!(
+                "cannot place a table row group within another group, header or footer"
+            );
+        }
         if value.is::<TableCell>() {
             bail!(
                 "cannot use `table.cell` as a grid cell";
@@ -595,6 +625,27 @@ pub struct GridFooter {
     pub children: Vec<GridItem>,
 }
 
+/// A group of grid rows which is kept together.
+///
+/// The rows of a group are moved to the next page as a whole if they don't
+/// fit into the current one. Only if the group doesn't even fit into a full
+/// page, it is split across pages. In that case, its first `header` rows are
+/// repeated at the top of each page the group continues on. For an example,
+/// refer to the [`table.group`]($table.group) element.
+#[elem(name = "group", title = "Grid Group")]
+pub struct GridGroup {
+    /// The number of rows at the start of the group which make up its header.
+    ///
+    /// The header is kept together with the first row after it and repeated
+    /// whenever the group is split across pages.
+    #[default(0)]
+    pub header: usize,
+
+    /// The cells and lines within the group.
+    #[variadic]
+    pub children: Vec<GridItem>,
+}
+
 /// A horizontal line in the grid.
 ///
 /// Overrides any per-cell stroke, including stroke specified through the grid's
@@ -845,6 +896,68 @@ pub struct GridCell {
     /// unbreakable, while a cell spanning at least one `{auto}`-sized row is
     /// breakable.
     pub breakable: Smart<bool>,
+
+    /// A function computing the cell's content from the values of other
+    /// cells.
+    ///
+    /// You can find an example for this property at the
+    /// [`table.cell.formula`]($table.cell.formula) property.
+    pub formula: Option<Func>,
+
+    /// The total amount of columns in the grid.
+    ///
+    /// This field is filled in automatically and may be used in show rules,
+    /// together with `x`, to style a cell depending on its column. For
+    /// example, `{it.x == it.column-count - 1}` holds for cells in the last
+    /// column.
+    #[synthesized]
+    pub column_count: usize,
+
+    /// The total amount of rows in the grid.
+    ///
+    /// This field is filled in automatically and may be used in show rules,
+    /// together with `y`, to style a cell depending on its row.
+    ///
+    /// ```example
+    /// #show grid.cell: it => {
+    ///   if it.y == it.row-count - 1 { strong(it) } else { it }
+    /// }
+    ///
+    /// #grid(
+    ///   columns: 2,
+    ///   gutter: 3pt,
+    ///   [Apples], [2],
+    ///   [Pears], [3],
+    ///   [Total], [5],
+    /// )
+    /// ```
+    #[synthesized]
+    pub row_count: usize,
+
+    /// Whether the cell is part of the grid's [header]($grid.header).
+    ///
+    /// This field is filled in automatically and may be used in show rules
+    /// to style header cells.
+    ///
+    /// ```example
+    /// #show grid.cell.where(header: true): emph
+    ///
+    /// #grid(
+    ///   columns: 2,
+    ///   gutter: 3pt,
+    ///   grid.header[Fruit][Amount],
+    ///   [Apples], [2],
+    /// )
+    /// ```
+    #[synthesized]
+    pub header: bool,
+
+    /// Whether the cell is part of the grid's [footer]($grid.footer).
+    ///
+    /// This field is filled in automatically and may be used in show rules
+    /// to style footer cells.
+    #[synthesized]
+    pub footer: bool,
 }
 
 cast! {
@@ -951,6 +1064,41 @@ impl ResolvableCell for Packed<GridCell> {
     fn span(&self) -> Span {
         Packed::span(self)
     }
+
+    fn finalize_cell(
+        cell: &mut Cell,
+        column_count: usize,
+        row_count: usize,
+        header: bool,
+        footer: bool,
+    ) {
+        if let Some(cell) = cell.body.to_packed_mut::<GridCell>() {
+            cell.push_column_count(column_count);
+            cell.push_row_count(row_count);
+            cell.push_header(header);
+            cell.push_footer(footer);
+        }
+    }
+
+    fn cell_formula(cell: &Cell, styles: StyleChain) -> Option<Func> {
+        cell.body.to_packed::<GridCell>()?.formula(styles)
+    }
+
+    fn cell_body<'a>(cell: &'a Cell) -> Option<&'a Content> {
+        cell.body.to_packed::<GridCell>().map(|cell| cell.body())
+    }
+
+    fn set_cell_body(cell: &mut Cell, body: Content) {
+        if let Some(cell) = cell.body.to_packed_mut::<GridCell>() {
+            cell.push_body(body);
+        }
+    }
+
+    fn cell_scope(_: &Cell, _: bool, _: StyleChain) -> Option<TableHeaderScope> {
+        None
+    }
+
+    fn set_cell_headers(_: &mut Cell, _: Option<TableHeaderScope>, _: &[Axes<usize>]) {}
 }
 
 impl Show for Packed<GridCell> {
@@ -1,3 +1,4 @@
+use super::layout::Row;
 use super::rowspans::UnbreakableRowGroup;
 use crate::diag::SourceResult;
 use crate::engine::Engine;
@@ -15,6 +16,25 @@ pub(super) struct Footer {
     pub(super) start: usize,
 }
 
+/// A group of rows which is kept together if possible.
+/// Its header rows are repeated when it has to be split across regions.
+pub(super) struct RowGroup {
+    /// The first row included in this group.
+    pub(super) start: usize,
+    /// The index after the last row included in this group's header. Equal to
+    /// `start` if the group has no header.
+    pub(super) header_end: usize,
+    /// The index after the last row included in this group.
+    pub(super) end: usize,
+}
+
+impl RowGroup {
+    /// Whether this group has any header rows.
+    pub(super) fn has_header(&self) -> bool {
+        self.header_end > self.start
+    }
+}
+
 /// A possibly repeatable grid object.
 /// It still exists even when not repeatable, but must not have additional
 /// considerations by grid layout, other than for consistency (such as making
@@ -187,4 +207,89 @@ impl<'a> GridLayouter<'a> {
             disambiguator,
         )
     }
+
+    /// Updates the row group whose body is being laid out, given the next
+    /// row to lay out.
+    ///
+    /// While inside a group's body, the group's header is considered part of
+    /// the header height, as it is repeated on each region break.
+    pub(super) fn update_current_group(
+        &mut self,
+        y: usize,
+        engine: &mut Engine,
+    ) -> SourceResult<()> {
+        if self.current_group.is_some_and(|group| y >= group.end) {
+            // The group is done, so its header won't be repeated anymore.
+            self.header_height -= self.group_header_height;
+            self.group_header_height = Abs::zero();
+            self.current_group = None;
+        }
+
+        if self.current_group.is_none() {
+            let grid = self.grid;
+            if let Some(group) = grid.groups.iter().find(|group| {
+                group.has_header() && y == group.header_end && y < group.end
+            }) {
+                self.group_header_height = self
+                    .simulate_group_header(
+                        group,
+                        &self.regions,
+                        engine,
+                        self.finished.len(),
+                    )?
+                    .height;
+                self.header_height += self.group_header_height;
+                self.current_group = Some(group);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Lays out the header rows of a row group again, at the top of a new
+    /// region the group continues in.
+    pub(super) fn layout_group_header(
+        &mut self,
+        group: &RowGroup,
+        engine: &mut Engine,
+        disambiguator: usize,
+    ) -> SourceResult<()> {
+        let first = self.lrows.len();
+
+        // The group header is unbreakable, just like the grid's header.
+        self.unbreakable_rows_left += group.header_end - group.start;
+        for y in group.start..group.header_end {
+            self.layout_row(y, engine, disambiguator)?;
+        }
+
+        // Track the height of the repeated header in this region.
+        self.group_header_height = self.lrows[first..]
+            .iter()
+            .map(|row| match row {
+                Row::Frame(frame, _, _) => frame.height(),
+                Row::Fr(..) => Abs::zero(),
+            })
+            .sum();
+        self.header_height += self.group_header_height;
+
+        Ok(())
+    }
+
+    /// Simulate the header rows of a row group.
+    pub(super) fn simulate_group_header(
+        &self,
+        group: &RowGroup,
+        regions: &Regions<'_>,
+        engine: &mut Engine,
+        disambiguator: usize,
+    ) -> SourceResult<UnbreakableRowGroup> {
+        // Cells in the group's header never span the rows after it.
+        self.simulate_unbreakable_row_group(
+            group.start,
+            Some(group.header_end - group.start),
+            regions,
+            engine,
+            disambiguator,
+        )
+    }
 }
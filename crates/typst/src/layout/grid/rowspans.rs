@@ -240,13 +240,40 @@ impl<'a> GridLayouter<'a> {
                 }
             }
 
-            let row_group = self.simulate_unbreakable_row_group(
-                current_row,
-                amount_unbreakable_rows,
-                &self.regions,
-                engine,
-                0,
-            )?;
+            let mut simulated_group = None;
+            if let Some(group) =
+                self.grid.groups.iter().find(|group| group.start == current_row)
+            {
+                // Keep the whole row group together if it fits into the
+                // current region or, after skipping, into a fresh one.
+                // Otherwise, it will be split, but at least keep its header
+                // together with the first row after it.
+                let whole = self.simulate_unbreakable_row_group(
+                    current_row,
+                    Some(group.end - group.start),
+                    &self.regions,
+                    engine,
+                    0,
+                )?;
+                let fresh = self.regions.full - self.header_height - self.footer_height;
+                if self.regions.size.y.fits(whole.height) || fresh.fits(whole.height) {
+                    simulated_group = Some(whole);
+                } else if group.has_header() {
+                    amount_unbreakable_rows =
+                        Some((group.header_end + 1).min(group.end) - group.start);
+                }
+            }
+
+            let row_group = match simulated_group {
+                Some(row_group) => row_group,
+                None => self.simulate_unbreakable_row_group(
+                    current_row,
+                    amount_unbreakable_rows,
+                    &self.regions,
+                    engine,
+                    0,
+                )?,
+            };
 
             // Skip to fitting region.
             while !self.regions.size.y.fits(row_group.height)
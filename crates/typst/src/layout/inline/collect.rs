@@ -3,9 +3,10 @@ use crate::diag::bail;
 use crate::foundations::{Packed, Resolve};
 use crate::introspection::{Tag, TagElem};
 use crate::layout::{
-    Abs, AlignElem, BoxElem, Dir, Fr, Frame, HElem, InlineElem, InlineItem, Sizing,
-    Spacing,
+    Abs, AlignElem, BoxElem, Dir, Em, Fr, Frame, HElem, InlineElem, InlineItem,
+    LeaderElem, Sizing, Spacing,
 };
+use crate::model::BlankElem;
 use crate::syntax::Span;
 use crate::text::{
     LinebreakElem, SmartQuoteElem, SmartQuoter, SmartQuotes, SpaceElem, TextElem,
@@ -18,6 +19,7 @@ const SPACING_REPLACE: &str = " "; // Space
 const OBJ_REPLACE: &str = "\u{FFFC}"; // Object Replacement Character
 const SPACING_REPLACE_CHAR: char = ' ';
 const OBJ_REPLACE_CHAR: char = '\u{FFFC}';
+const TAB: &str = "\t";
 
 // Unicode BiDi control characters.
 const LTR_EMBEDDING: &str = "\u{202A}";
@@ -35,6 +37,14 @@ pub enum Item<'a> {
     Absolute(Abs, bool),
     /// Fractional spacing between other items.
     Fractional(Fr, Option<(&'a Packed<BoxElem>, Locator<'a>, StyleChain<'a>)>),
+    /// A leader that fills one fraction of the remaining space.
+    Leader(&'a Packed<LeaderElem>, Locator<'a>, StyleChain<'a>),
+    /// A blank that fills a fraction of the remaining space.
+    Blank(Fr, &'a Packed<BlankElem>, Locator<'a>, StyleChain<'a>),
+    /// A tab that advances to the next tab stop, with the width it has beyond
+    /// the last stop and its index among the paragraph's tabs. Its actual
+    /// advance is determined per line.
+    Tab(Abs, usize),
     /// Layouted inline-level content.
     Frame(Frame, StyleChain<'a>),
     /// A tag.
@@ -66,8 +76,12 @@ impl<'a> Item<'a> {
     pub fn textual(&self) -> &str {
         match self {
             Self::Text(shaped) => shaped.text,
-            Self::Absolute(_, _) | Self::Fractional(_, _) => SPACING_REPLACE,
+            Self::Absolute(_, _)
+            | Self::Fractional(_, _)
+            | Self::Leader(..)
+            | Self::Blank(..) => SPACING_REPLACE,
             Self::Frame(_, _) => OBJ_REPLACE,
+            Self::Tab(..) => TAB,
             Self::Tag(_) => "",
             Self::Skip(s) => s,
         }
@@ -84,7 +98,8 @@ impl<'a> Item<'a> {
             Self::Text(shaped) => shaped.width,
             Self::Absolute(v, _) => *v,
             Self::Frame(frame, _) => frame.width(),
-            Self::Fractional(_, _) | Self::Tag(_) => Abs::zero(),
+            Self::Fractional(_, _) | Self::Leader(..) | Self::Blank(..) => Abs::zero(),
+            Self::Tab(..) | Self::Tag(_) => Abs::zero(),
             Self::Skip(_) => Abs::zero(),
         }
     }
@@ -123,18 +138,19 @@ pub fn collect<'a>(
     styles: &'a StyleChain<'a>,
     region: Size,
     consecutive: bool,
-) -> SourceResult<(String, Vec<Segment<'a>>, SpanMapper)> {
+) -> SourceResult<(String, Vec<Segment<'a>>, SpanMapper, Vec<Range>)> {
     let mut collector = Collector::new(2 + children.len());
     let mut iter = children.chain(styles).peekable();
     let mut locator = locator.split();
 
     let first_line_indent = ParElem::first_line_indent_in(*styles);
-    if !first_line_indent.is_zero()
-        && consecutive
+    if !first_line_indent.amount.is_zero()
+        && (consecutive || first_line_indent.all)
         && AlignElem::alignment_in(*styles).resolve(*styles).x
             == TextElem::dir_in(*styles).start().into()
     {
-        collector.push_item(Item::Absolute(first_line_indent.resolve(*styles), false));
+        collector
+            .push_item(Item::Absolute(first_line_indent.amount.resolve(*styles), false));
         collector.spans.push(1, Span::detached());
     }
 
@@ -145,35 +161,29 @@ pub fn collect<'a>(
     }
 
     let outer_dir = TextElem::dir_in(*styles);
+    let tabbed = !ParElem::tab_stops_in(*styles).is_empty();
 
+    let mut tabs = 0;
     while let Some((child, styles)) = iter.next() {
         let prev_len = collector.full.len();
 
         if child.is::<SpaceElem>() {
             collector.push_text(" ", styles);
         } else if let Some(elem) = child.to_packed::<TextElem>() {
-            collector.build_text(styles, |full| {
-                let dir = TextElem::dir_in(styles);
-                if dir != outer_dir {
-                    // Insert "Explicit Directional Embedding".
-                    match dir {
-                        Dir::LTR => full.push_str(LTR_EMBEDDING),
-                        Dir::RTL => full.push_str(RTL_EMBEDDING),
-                        _ => {}
+            if tabbed && elem.text().contains('\t') {
+                let space = Em::new(0.25).resolve(styles);
+                for (i, run) in elem.text().split('\t').enumerate() {
+                    if i > 0 {
+                        collector.push_item(Item::Tab(space, tabs));
+                        tabs += 1;
+                    }
+                    if !run.is_empty() {
+                        collector.push_run(run, styles, outer_dir);
                     }
                 }
-
-                if let Some(case) = TextElem::case_in(styles) {
-                    full.push_str(&case.apply(elem.text()));
-                } else {
-                    full.push_str(elem.text());
-                }
-
-                if dir != outer_dir {
-                    // Insert "Pop Directional Formatting".
-                    full.push_str(POP_EMBEDDING);
-                }
-            });
+            } else {
+                collector.push_run(elem.text(), styles, outer_dir);
+            }
         } else if let Some(elem) = child.to_packed::<HElem>() {
             let amount = elem.amount();
             if amount.is_zero() {
@@ -245,6 +255,17 @@ pub fn collect<'a>(
                 let frame = elem.layout(engine, loc, styles, region)?;
                 collector.push_item(Item::Frame(frame, styles));
             }
+        } else if let Some(elem) = child.to_packed::<LeaderElem>() {
+            let loc = locator.next(&elem.span());
+            collector.push_item(Item::Leader(elem, loc, styles));
+        } else if let Some(elem) = child.to_packed::<BlankElem>() {
+            let loc = locator.next(&elem.span());
+            if let Sizing::Fr(v) = elem.width(styles) {
+                collector.push_item(Item::Blank(v, elem, loc, styles));
+            } else {
+                let frame = elem.layout(engine, loc, styles, region)?;
+                collector.push_item(Item::Frame(frame, styles));
+            }
         } else if let Some(elem) = child.to_packed::<TagElem>() {
             collector.push_item(Item::Tag(&elem.tag));
         } else {
@@ -253,9 +274,12 @@ pub fn collect<'a>(
 
         let len = collector.full.len() - prev_len;
         collector.spans.push(len, child.span());
+        if len > 0 && TextElem::nobreak_in(styles) {
+            collector.push_nobreak(prev_len..collector.full.len());
+        }
     }
 
-    Ok((collector.full, collector.segments, collector.spans))
+    Ok((collector.full, collector.segments, collector.spans, collector.nobreak))
 }
 
 /// Collects segments.
@@ -263,6 +287,7 @@ struct Collector<'a> {
     full: String,
     segments: Vec<Segment<'a>>,
     spans: SpanMapper,
+    nobreak: Vec<Range>,
     quoter: SmartQuoter,
 }
 
@@ -272,6 +297,7 @@ impl<'a> Collector<'a> {
             full: String::new(),
             segments: Vec::with_capacity(capacity),
             spans: SpanMapper::new(),
+            nobreak: vec![],
             quoter: SmartQuoter::new(),
         }
     }
@@ -291,6 +317,31 @@ impl<'a> Collector<'a> {
         self.push_segment(Segment::Text(len, styles), false);
     }
 
+    fn push_run(&mut self, text: &str, styles: StyleChain<'a>, outer_dir: Dir) {
+        self.build_text(styles, |full| {
+            let dir = TextElem::dir_in(styles);
+            if dir != outer_dir {
+                // Insert "Explicit Directional Embedding".
+                match dir {
+                    Dir::LTR => full.push_str(LTR_EMBEDDING),
+                    Dir::RTL => full.push_str(RTL_EMBEDDING),
+                    _ => {}
+                }
+            }
+
+            if let Some(case) = TextElem::case_in(styles) {
+                full.push_str(&case.apply(text));
+            } else {
+                full.push_str(text);
+            }
+
+            if dir != outer_dir {
+                // Insert "Pop Directional Formatting".
+                full.push_str(POP_EMBEDDING);
+            }
+        });
+    }
+
     fn push_quote(&mut self, quote: &str, styles: StyleChain<'a>) {
         self.full.push_str(quote);
         self.push_segment(Segment::Text(quote.len(), styles), true);
@@ -301,6 +352,15 @@ impl<'a> Collector<'a> {
         self.push_segment(Segment::Item(item), false);
     }
 
+    /// Mark a range of the text as unbreakable, merging it with the previous
+    /// one if they are adjacent.
+    fn push_nobreak(&mut self, range: Range) {
+        match self.nobreak.last_mut() {
+            Some(last) if last.end == range.start => last.end = range.end,
+            _ => self.nobreak.push(range),
+        }
+    }
+
     fn push_segment(&mut self, segment: Segment<'a>, is_quote: bool) {
         if let Some(last) = self.full.chars().last() {
             self.quoter.last(last, is_quote);
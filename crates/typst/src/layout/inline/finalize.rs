@@ -1,13 +1,26 @@
 use super::*;
-use crate::layout::{Abs, Frame, Point};
+use crate::diag::warning;
+use crate::foundations::{NativeElement, Repr};
+use crate::introspection::Tag;
+use crate::layout::{Abs, Frame, FrameItem, PageElem, Point, Ratio};
+use crate::model::{ParLineMarker, ParLineNumbering};
+use crate::syntax::Span;
 use crate::utils::Numeric;
 
+/// The number of lines a river of whitespace must run through to be
+/// reported.
+const RIVER_LINES: usize = 3;
+
+/// The number of consecutive hyphenated lines that are reported.
+const LADDER_LINES: usize = 3;
+
 /// Turns the selected lines into frames.
 #[typst_macros::time]
 pub fn finalize(
     engine: &mut Engine,
     p: &Preparation,
     lines: &[Line],
+    spans: &[LineSpan],
     styles: StyleChain,
     region: Size,
     expand: bool,
@@ -26,10 +39,52 @@ pub fn finalize(
 
     // Stack the lines into one frame per region.
     let shrink = ParElem::shrink_in(styles);
-    let mut frames: Vec<Frame> = lines
-        .iter()
-        .map(|line| commit(engine, p, line, width, region.y, shrink))
-        .collect::<SourceResult<_>>()?;
+    let mut frames: Vec<Frame> = if spans.is_empty() {
+        lines
+            .iter()
+            .map(|line| commit(engine, p, line, width, region.y, shrink))
+            .collect::<SourceResult<_>>()?
+    } else {
+        // Lines that flow around exclusions are built with the width of their
+        // span and then moved into it within a frame of the full width.
+        lines
+            .iter()
+            .zip(spans)
+            .map(|(line, span)| {
+                let frame = commit(engine, p, line, span.width, region.y, shrink)?;
+                let mut output =
+                    Frame::soft(Size::new(region.x, span.skip + frame.height()));
+                output.set_baseline(span.skip + frame.baseline());
+                output.push_frame(Point::new(span.offset, span.skip), frame);
+                Ok(output)
+            })
+            .collect::<SourceResult<_>>()?
+    };
+
+    // Report poorly set lines and rivers if requested. This must happen
+    // before orphan and widow prevention merges some of the lines.
+    if ParElem::diagnose_in(styles) {
+        diagnose(engine, p, lines, spans, &frames, width, shrink);
+    }
+
+    if PageElem::diagnose_in(styles) {
+        diagnose_overflow(engine, p, lines, spans, width, shrink);
+    }
+
+    // Mark the lines for line numbering before orphan and widow prevention
+    // merges some of them. The markers precede the line's content, such that
+    // the line counts as being before elements in it, like headings.
+    if let Some(numbering) = ParLineNumbering::numbering_in(styles) {
+        let scope = ParLineNumbering::scope_in(styles);
+        let side = ParLineNumbering::side_in(styles);
+        let clearance = ParLineNumbering::clearance_in(styles);
+        for frame in &mut frames {
+            let marker =
+                ParLineMarker::new(numbering.clone(), scope, side, clearance.into());
+            let tag = Tag::new(marker.pack(), 0);
+            frame.prepend(Point::with_y(frame.baseline()), FrameItem::Tag(tag));
+        }
+    }
 
     // Positive ratios enable prevention, while zero and negative ratios disable
     // it.
@@ -61,3 +116,182 @@ fn merge(first: &mut Frame, second: Frame, leading: Abs) {
     first.push_frame(Point::with_y(offset), second);
     first.size_mut().y = total;
 }
+
+/// Warns about lines whose spaces had to be stretched or shrunk beyond their
+/// limits or the tolerance, about ladders of hyphenated lines, and about
+/// rivers of whitespace running through the paragraph.
+fn diagnose(
+    engine: &mut Engine,
+    p: &Preparation,
+    lines: &[Line],
+    spans: &[LineSpan],
+    frames: &[Frame],
+    width: Abs,
+    shrink: bool,
+) {
+    let mut ladder = 0;
+    for (i, line) in lines.iter().enumerate() {
+        // Count the consecutive lines that end with a hyphen.
+        if matches!(line.dash, Some(Dash::SoftHyphen | Dash::HardHyphen)) {
+            ladder += 1;
+        } else {
+            ladder = 0;
+        }
+
+        let Some(span) = line_span(line) else { continue };
+        let width = spans.get(i).map_or(width, |span| span.width);
+        let remaining = width - line.width - p.hang;
+        let justified = line.justify && line.fr().is_zero();
+        let expandability =
+            if justified { line.expandability(p.expansion) } else { Abs::zero() };
+        let shrinkability = if shrink { line.shrinkability() } else { Abs::zero() };
+        let ratio = raw_ratio(
+            p,
+            width - p.hang,
+            line.width,
+            line.stretchability(),
+            line.shrinkability(),
+            line.justifiables(),
+        );
+
+        if ladder == LADDER_LINES {
+            engine.sink.warn(warning!(
+                span, "three consecutive lines end with a hyphen";
+                hint: "consider rewording the paragraph or raising the ladder cost",
+            ));
+        }
+
+        if justified && !(line.stretchability() + expandability).fits(remaining) {
+            engine.sink.warn(warning!(
+                span, "line is too loose";
+                hint: "its spaces had to be stretched beyond their limit";
+                hint: "consider rewording the paragraph or enabling hyphenation",
+            ));
+        } else if let Some(tolerance) =
+            p.tolerance.filter(|tolerance| justified && ratio > tolerance.get())
+        {
+            engine.sink.warn(warning!(
+                span, "line is stretched beyond the tolerance";
+                hint: "its spaces had to be stretched to {} of their limit, but the \
+                       tolerance is {}", Ratio::new(ratio).repr(), tolerance.repr();
+                hint: "consider rewording the paragraph or enabling hyphenation",
+            ));
+        } else if !(remaining + shrinkability + expandability).fits(Abs::zero()) {
+            engine.sink.warn(warning!(
+                span, "line is too tight";
+                hint: "it does not fit even with its spaces shrunk as far as possible";
+                hint: "consider rewording the paragraph or enabling hyphenation",
+            ));
+        }
+    }
+
+    // Follow chains of overlapping spaces from line to line. Each chain
+    // remembers its length and the span of its topmost space.
+    let mut chains: Vec<(Abs, Abs, usize, Span)> = vec![];
+    for frame in frames {
+        let mut next = vec![];
+        for (start, end, span) in gaps(frame) {
+            let (len, origin) = chains
+                .iter()
+                .filter(|&&(s, e, _, _)| overlap(start, end, s, e))
+                .map(|&(_, _, len, origin)| (len + 1, origin))
+                .max_by_key(|&(len, _)| len)
+                .unwrap_or((1, span));
+
+            if len == RIVER_LINES {
+                engine.sink.warn(warning!(
+                    origin, "spaces form a river through the paragraph";
+                    hint: "consider rewording the paragraph or adjusting the hyphenation",
+                ));
+            }
+
+            next.push((start, end, len, origin));
+        }
+        chains = next;
+    }
+}
+
+/// Warns about lines that overflow their measure even with their spaces
+/// shrunk as far as possible.
+fn diagnose_overflow(
+    engine: &mut Engine,
+    p: &Preparation,
+    lines: &[Line],
+    spans: &[LineSpan],
+    width: Abs,
+    shrink: bool,
+) {
+    for (i, line) in lines.iter().enumerate() {
+        let width = spans.get(i).map_or(width, |span| span.width);
+        if !width.is_finite() {
+            continue;
+        }
+
+        let justified = line.justify && line.fr().is_zero();
+        let expandability =
+            if justified { line.expandability(p.expansion) } else { Abs::zero() };
+        let shrinkability = if shrink { line.shrinkability() } else { Abs::zero() };
+        let overflow = line.width + p.hang - shrinkability - expandability - width;
+        if overflow.fits(Abs::zero()) {
+            continue;
+        }
+
+        let Some(span) = line_span(line) else { continue };
+        engine.sink.warn(warning!(
+            span, "line overflows its measure by {}", overflow.repr();
+            hint: "the line needs {} but only {} are available",
+                (overflow + width).repr(), width.repr();
+            hint: "consider enabling hyphenation or allowing a break in long words",
+        ));
+    }
+}
+
+/// The span of the first text in a line.
+fn line_span(line: &Line) -> Option<Span> {
+    line.items()
+        .filter_map(Item::text)
+        .flat_map(|shaped| shaped.glyphs.iter())
+        .map(|glyph| glyph.span.0)
+        .find(|span| !span.is_detached())
+}
+
+/// The horizontal extents of the spaces in a committed line.
+fn gaps(frame: &Frame) -> Vec<(Abs, Abs, Span)> {
+    let mut gaps = vec![];
+    collect_gaps(frame, Abs::zero(), 1.0, &mut gaps);
+    gaps
+}
+
+/// Collect the spaces in a frame, descending into the groups that wrap
+/// expanded text.
+fn collect_gaps(
+    frame: &Frame,
+    origin: Abs,
+    scale: f64,
+    gaps: &mut Vec<(Abs, Abs, Span)>,
+) {
+    for (pos, item) in frame.items() {
+        let mut x = origin + pos.x * scale;
+        match item {
+            FrameItem::Text(text) => {
+                for glyph in &text.glyphs {
+                    let advance = glyph.x_advance.at(text.size) * scale;
+                    if matches!(&text.text[glyph.range()], " " | "\u{a0}") {
+                        gaps.push((x, x + advance, glyph.span.0));
+                    }
+                    x += advance;
+                }
+            }
+            FrameItem::Group(group) if group.frame.kind().is_soft() => {
+                collect_gaps(&group.frame, x, scale * group.transform.sx.get(), gaps);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether two spaces overlap by at least half of the narrower one.
+fn overlap(a_start: Abs, a_end: Abs, b_start: Abs, b_end: Abs) -> bool {
+    let shared = a_end.min(b_end) - a_start.max(b_start);
+    shared > (a_end - a_start).min(b_end - b_start) / 2.0
+}
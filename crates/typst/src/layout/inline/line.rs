@@ -2,7 +2,9 @@ use unicode_bidi::BidiInfo;
 
 use super::*;
 use crate::engine::Engine;
-use crate::layout::{Abs, Em, Fr, Frame, FrameItem, Point};
+use crate::foundations::Smart;
+use crate::layout::{Abs, Em, Fr, Frame, FrameItem, HAlignment, Point, Ratio, Transform};
+use crate::model::TabAlign;
 use crate::text::{Lang, TextElem};
 use crate::utils::Numeric;
 
@@ -36,6 +38,8 @@ pub struct Line<'a> {
     /// Whether the line ends with a hyphen or dash, either naturally or through
     /// hyphenation.
     pub dash: Option<Dash>,
+    /// The advances of the line's tabs, in logical order.
+    pub tabs: Vec<Abs>,
 }
 
 impl<'a> Line<'a> {
@@ -99,6 +103,13 @@ impl<'a> Line<'a> {
         self.items().filter_map(Item::text).map(|s| s.shrinkability()).sum()
     }
 
+    /// How much can the line's glyphs be expanded or condensed with the given
+    /// maximum horizontal scaling.
+    pub fn expandability(&self, expansion: Ratio) -> Abs {
+        self.items().filter_map(Item::text).map(|s| s.width).sum::<Abs>()
+            * expansion.get()
+    }
+
     /// Whether the line has items with negative width.
     pub fn has_negative_width_items(&self) -> bool {
         self.items().any(|item| match item {
@@ -113,10 +124,32 @@ impl<'a> Line<'a> {
         self.items()
             .filter_map(|item| match item {
                 Item::Fractional(fr, _) => Some(*fr),
+                Item::Leader(..) => Some(Fr::one()),
+                Item::Blank(fr, ..) => Some(*fr),
                 _ => None,
             })
             .sum()
     }
+
+    /// An estimate of the line's height before its frame is built.
+    ///
+    /// Inline content with fractional sizing is not taken into account.
+    pub fn height(&self, engine: &Engine) -> Abs {
+        let mut top = Abs::zero();
+        let mut bottom = Abs::zero();
+        for item in self.items() {
+            let (ascent, descent) = match item {
+                Item::Text(shaped) => shaped.measure(engine),
+                Item::Frame(frame, _) => {
+                    (frame.baseline(), frame.height() - frame.baseline())
+                }
+                _ => continue,
+            };
+            top.set_max(ascent);
+            bottom.set_max(descent);
+        }
+        top + bottom
+    }
 }
 
 /// A dash at the end of a line.
@@ -155,6 +188,7 @@ pub fn line<'a>(
             width: Abs::zero(),
             justify,
             dash: None,
+            tabs: vec![],
         };
     }
 
@@ -340,7 +374,7 @@ pub fn line<'a>(
         width += item.width();
     }
 
-    Line {
+    let mut line = Line {
         bidi: &p.bidi,
         trimmed: range,
         end,
@@ -350,7 +384,78 @@ pub fn line<'a>(
         width,
         justify,
         dash,
+        tabs: vec![],
+    };
+
+    // Advance the tabs to their stops.
+    if line.items().any(|item| matches!(item, Item::Tab(..))) {
+        line.tabs = tabs(p, &line);
+        line.width += line.tabs.iter().copied().sum::<Abs>();
+    }
+
+    line
+}
+
+/// Determines how far each tab in the line advances to reach its tab stop.
+fn tabs(p: &Preparation, line: &Line) -> Vec<Abs> {
+    let items: Vec<_> = line.items().collect();
+    let mut tabs = vec![];
+    // Stops are measured from the start of the paragraph. Later lines start
+    // at the hanging indent, while the first line starts with an item that
+    // cancels it out.
+    let mut x = p.hang;
+    for (i, item) in items.iter().enumerate() {
+        let Item::Tab(space, _) = item else {
+            x += item.width();
+            continue;
+        };
+
+        // The text aligned at the stop extends to the next tab.
+        let rest = &items[i + 1..];
+        let run = &rest[..rest
+            .iter()
+            .position(|item| matches!(item, Item::Tab(..)))
+            .unwrap_or(rest.len())];
+        let width = || run.iter().map(|item| item.width()).sum::<Abs>();
+        let advance = p
+            .tab_stops
+            .iter()
+            .find(|(pos, _)| *pos > x)
+            .map(|&(pos, align)| {
+                let before = match align {
+                    TabAlign::Align(HAlignment::Center) => width() / 2.0,
+                    TabAlign::Align(HAlignment::End) => width(),
+                    TabAlign::Align(_) => Abs::zero(),
+                    TabAlign::Char(c) => width_before(run, c),
+                };
+                (pos - x - before).max(Abs::zero())
+            })
+            .unwrap_or(*space);
+
+        x += advance;
+        tabs.push(advance);
     }
+    tabs
+}
+
+/// The width of the items up to the first occurrence of the character, or of
+/// all items if it doesn't occur.
+fn width_before(items: &[&Item], c: char) -> Abs {
+    let mut width = Abs::zero();
+    for item in items {
+        let Item::Text(shaped) = item else {
+            width += item.width();
+            continue;
+        };
+
+        for glyph in shaped.glyphs.iter() {
+            if glyph.c == c {
+                return width;
+            }
+            width += glyph.x_advance.at(shaped.size);
+        }
+    }
+    width
 }
 
 /// Commit to a line and build its frame.
@@ -367,6 +472,16 @@ pub fn commit(
 
     // Reorder the line from logical to visual order.
     let (reordered, starts_rtl) = reorder(line);
+
+    // The index of the line's first tab among the paragraph's tabs, to look up
+    // the advances of its tabs.
+    let first_tab = line
+        .items()
+        .find_map(|item| match item {
+            Item::Tab(_, index) => Some(*index),
+            _ => None,
+        })
+        .unwrap_or_default();
     if !starts_rtl {
         offset += p.hang;
     }
@@ -407,17 +522,27 @@ pub fn commit(
     let mut justification_ratio = 0.0;
     let mut extra_justification = Abs::zero();
 
-    let shrinkability = line.shrinkability();
+    // The glyphs of justified lines can be scaled horizontally, which
+    // contributes to the line's adjustability in proportion to its width.
+    let expandability = if line.justify && fr.is_zero() {
+        line.expandability(p.expansion)
+    } else {
+        Abs::zero()
+    };
+
+    let shrinkability = if shrink { line.shrinkability() } else { Abs::zero() };
     let stretch = line.stretchability();
-    if remaining < Abs::zero() && shrinkability > Abs::zero() && shrink {
+    if remaining < Abs::zero() && shrinkability + expandability > Abs::zero() {
         // Attempt to reduce the length of the line, using shrinkability.
-        justification_ratio = (remaining / shrinkability).max(-1.0);
-        remaining = (remaining + shrinkability).min(Abs::zero());
+        let adjust = shrinkability + expandability;
+        justification_ratio = (remaining / adjust).max(-1.0);
+        remaining = (remaining + adjust).min(Abs::zero());
     } else if line.justify && fr.is_zero() {
         // Attempt to increase the length of the line, using stretchability.
-        if stretch > Abs::zero() {
-            justification_ratio = (remaining / stretch).min(1.0);
-            remaining = (remaining - stretch).max(Abs::zero());
+        let adjust = stretch + expandability;
+        if adjust > Abs::zero() {
+            justification_ratio = (remaining / adjust).min(1.0);
+            remaining = (remaining - adjust).max(Abs::zero());
         }
 
         let justifiables = line.justifiables();
@@ -459,9 +584,28 @@ pub fn commit(
                     offset += amount;
                 }
             }
+            Item::Leader(elem, loc, styles) => {
+                let amount = Fr::one().share(fr, remaining);
+                let region = Size::new(amount, full);
+                let mut frame =
+                    elem.layout(engine, loc.relayout(), *styles, offset, region)?;
+                frame.post_process(*styles);
+                frame.translate(Point::with_y(TextElem::baseline_in(*styles)));
+                push(&mut offset, frame);
+            }
+            Item::Blank(v, elem, loc, styles) => {
+                let region = Size::new(v.share(fr, remaining), full);
+                let mut frame = elem.layout(engine, loc.relayout(), *styles, region)?;
+                frame.post_process(*styles);
+                frame.translate(Point::with_y(TextElem::baseline_in(*styles)));
+                push(&mut offset, frame);
+            }
             Item::Text(shaped) => {
                 let mut frame =
                     shaped.build(engine, justification_ratio, extra_justification);
+                if !expandability.is_zero() {
+                    expand(&mut frame, 1.0 + justification_ratio * p.expansion.get());
+                }
                 frame.post_process(shaped.styles);
                 push(&mut offset, frame);
             }
@@ -471,6 +615,9 @@ pub fn commit(
                 frame.translate(Point::with_y(TextElem::baseline_in(*styles)));
                 push(&mut offset, frame);
             }
+            Item::Tab(_, index) => {
+                offset += line.tabs.get(index - first_tab).copied().unwrap_or_default();
+            }
             Item::Tag(tag) => {
                 let mut frame = Frame::soft(Size::zero());
                 frame.push(Point::zero(), FrameItem::Tag((*tag).clone()));
@@ -499,6 +646,14 @@ pub fn commit(
     Ok(output)
 }
 
+/// Scale a text frame horizontally by the given factor, keeping its left edge
+/// in place.
+fn expand(frame: &mut Frame, factor: f64) {
+    let width = frame.width() * factor;
+    frame.transform(Transform::scale(Ratio::new(factor), Ratio::one()));
+    frame.size_mut().x = width;
+}
+
 /// Return a line's items in visual order.
 fn reorder<'a>(line: &'a Line<'a>) -> (Vec<&Item<'a>>, bool) {
     let mut reordered = vec![];
@@ -558,16 +713,23 @@ fn should_insert_hyphen(pred_line: &Line) -> bool {
     // For more information see the discussion at https://github.com/typst/typst/issues/3235
     let Some(Item::Text(shape)) = pred_line.last.as_ref() else { return false };
 
+    // The repetition can also be configured explicitly.
+    if let Smart::Custom(repeat) = TextElem::repeat_hyphen_in(shape.styles) {
+        return repeat;
+    }
+
     match shape.lang {
         // - Lower Sorbian: see https://dolnoserbski.de/ortografija/psawidla/K3
         // - Czech: see https://prirucka.ujc.cas.cz/?id=164
         // - Croatian: see http://pravopis.hr/pravilo/spojnica/68/
+        // - Hungarian: see https://helyesiras.mta.hu/helyesiras/default/akh12
         // - Polish: see https://www.ortograf.pl/zasady-pisowni/lacznik-zasady-pisowni
         // - Portuguese: see https://www2.senado.leg.br/bdsf/bitstream/handle/id/508145/000997415.pdf (Base XX)
         // - Slovak: see https://www.zones.sk/studentske-prace/gramatika/10620-pravopis-rozdelovanie-slov/
         Lang::LOWER_SORBIAN
         | Lang::CZECH
         | Lang::CROATIAN
+        | Lang::HUNGARIAN
         | Lang::POLISH
         | Lang::PORTUGUESE
         | Lang::SLOVAK => true,
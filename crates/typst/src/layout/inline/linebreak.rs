@@ -10,10 +10,10 @@ use once_cell::sync::Lazy;
 
 use super::*;
 use crate::engine::Engine;
-use crate::layout::{Abs, Em};
+use crate::layout::{Abs, Em, Ratio};
 use crate::model::Linebreaks;
 use crate::syntax::link_prefix;
-use crate::text::{Lang, TextElem};
+use crate::text::{hyphenate_exception, Lang, TextElem};
 
 /// The cost of a line or paragraph layout.
 type Cost = f64;
@@ -22,6 +22,9 @@ type Cost = f64;
 const DEFAULT_HYPH_COST: Cost = 0.5;
 const DEFAULT_RUNT_COST: Cost = 0.5;
 const CONSECUTIVE_DASH_COST: Cost = 0.3;
+const LOOSE_COST: Cost = 100.0;
+const SHORT_LAST_LINE_COST: Cost = 10.0;
+const TOO_SHORT_LAST_LINE_COST: Cost = 1000.0;
 const MAX_COST: Cost = 1_000_000.0;
 const MIN_RATIO: f64 = -1.0;
 const MIN_APPROX_RATIO: f64 = -0.5;
@@ -131,6 +134,113 @@ fn linebreak_simple<'a>(
     lines
 }
 
+/// Performs line breaking in first-fit style while flowing the lines around
+/// the given exclusions. Each line gets the widest horizontal span that is not
+/// covered by an exclusion at its height. If a line doesn't even fit beside an
+/// exclusion with a single segment, it moves below the exclusion instead.
+///
+/// Before exclusions are considered, each line is narrowed by the left and
+/// right indents that `indents` returns for the line's index.
+///
+/// Returns the lines along with their spans.
+#[typst_macros::time]
+#[allow(clippy::type_complexity)]
+pub fn linebreak_wrapped<'a>(
+    engine: &mut Engine,
+    p: &'a Preparation<'a>,
+    width: Abs,
+    exclusions: &[Exclusion],
+    indents: &mut dyn FnMut(&mut Engine, usize) -> SourceResult<(Abs, Abs)>,
+) -> SourceResult<(Vec<Line<'a>>, Vec<LineSpan>)> {
+    let mut lines = Vec::with_capacity(16);
+    let mut spans = Vec::with_capacity(16);
+    let mut start = 0;
+    let mut last: Option<(Line, usize)> = None;
+
+    // The top of the next line and the span of the current one.
+    let mut y = Abs::zero();
+    let mut current: Option<LineSpan> = None;
+
+    // Determines the span of the line with the given index which starts at
+    // `y`, moving the line below narrowing exclusions as long as it doesn't
+    // fit beside them.
+    let mut locate = |engine: &mut Engine,
+                      index: usize,
+                      y: Abs,
+                      attempt: &Line|
+     -> SourceResult<LineSpan> {
+        let (left, right) = indents(engine, index)?;
+        let height = attempt.height(engine);
+        let mut top = y;
+        loop {
+            let (offset, free, clear) =
+                free_span(exclusions, left, width - right, top, top + height);
+            match clear {
+                Some(clear) if clear > top && !(free - p.hang).fits(attempt.width) => {
+                    top = clear;
+                }
+                _ => return Ok(LineSpan { skip: top - y, offset, width: free }),
+            }
+        }
+    };
+
+    let mut step = |end: usize, breakpoint: Breakpoint| -> SourceResult<()> {
+        // Compute the line and its size.
+        let mut attempt = line(engine, p, start..end, breakpoint, lines.last());
+        let mut span = match current {
+            Some(span) => span,
+            None => *current.insert(locate(engine, lines.len(), y, &attempt)?),
+        };
+
+        // If the line doesn't fit anymore, we push the last fitting attempt
+        // into the stack and rebuild the line from the attempt's end.
+        if !(span.width - p.hang).fits(attempt.width) {
+            if let Some((last_attempt, last_end)) = last.take() {
+                y += span.skip + last_attempt.height(engine) + p.leading;
+                lines.push(last_attempt);
+                spans.push(span);
+                start = last_end;
+                attempt = line(engine, p, start..end, breakpoint, lines.last());
+                span = locate(engine, lines.len(), y, &attempt)?;
+                current = Some(span);
+            }
+        }
+
+        // Finish the current line if there is a mandatory line break or if the
+        // line doesn't fit horizontally already.
+        if breakpoint == Breakpoint::Mandatory
+            || !(span.width - p.hang).fits(attempt.width)
+        {
+            y += span.skip + attempt.height(engine) + p.leading;
+            lines.push(attempt);
+            spans.push(span);
+            start = end;
+            last = None;
+            current = None;
+        } else {
+            last = Some((attempt, end));
+        }
+
+        Ok(())
+    };
+
+    // Stop at the first error of the indent callback.
+    let mut result = Ok(());
+    breakpoints(p, |end, breakpoint| {
+        if result.is_ok() {
+            result = step(end, breakpoint);
+        }
+    });
+    result?;
+
+    if let (Some((line, _)), Some(span)) = (last, current) {
+        lines.push(line);
+        spans.push(span);
+    }
+
+    Ok((lines, spans))
+}
+
 /// Performs line breaking in optimized Knuth-Plass style. Here, we use more
 /// context to determine the line breaks than in the simple first-fit style. For
 /// example, we might choose to cut a line short even though there is still a
@@ -154,7 +264,7 @@ fn linebreak_optimized<'a>(
     p: &'a Preparation<'a>,
     width: Abs,
 ) -> Vec<Line<'a>> {
-    let metrics = CostMetrics::compute(p);
+    let metrics = CostMetrics::compute(p, width);
 
     // Determines the exact costs of a likely good layout through Knuth-Plass
     // with approximate metrics. We can use this cost as an upper bound to prune
@@ -363,15 +473,16 @@ fn linebreak_optimized_approximate(
             // account trailing spaces. This is, again, only an approximation of
             // the real behaviour of `line`.
             let trimmed_end = start + p.bidi.text[start..end].trim_end().len();
+            let line_width = estimates.widths.estimate(start..trimmed_end)
+                + if breakpoint == Breakpoint::Hyphen {
+                    metrics.approx_hyphen_width
+                } else {
+                    Abs::zero()
+                };
             let line_ratio = raw_ratio(
                 p,
                 width,
-                estimates.widths.estimate(start..trimmed_end)
-                    + if breakpoint == Breakpoint::Hyphen {
-                        metrics.approx_hyphen_width
-                    } else {
-                        Abs::zero()
-                    },
+                line_width,
                 estimates.stretchability.estimate(start..trimmed_end),
                 estimates.shrinkability.estimate(start..trimmed_end),
                 estimates.justifiables.estimate(start..trimmed_end),
@@ -382,6 +493,7 @@ fn linebreak_optimized_approximate(
                 metrics,
                 breakpoint,
                 line_ratio,
+                line_width,
                 at_end,
                 justify,
                 unbreakable,
@@ -474,6 +586,7 @@ fn ratio_and_cost(
         metrics,
         breakpoint,
         ratio,
+        attempt.width,
         attempt.end == p.bidi.text.len(),
         attempt.justify,
         unbreakable,
@@ -485,7 +598,7 @@ fn ratio_and_cost(
 }
 
 /// Determine the stretch ratio for a line given raw metrics.
-fn raw_ratio(
+pub(super) fn raw_ratio(
     p: &Preparation,
     available_width: Abs,
     line_width: Abs,
@@ -497,8 +610,11 @@ fn raw_ratio(
     // to make it the desired width.
     let delta = available_width - line_width;
 
-    // Determine how much stretch is permitted.
-    let adjust = if delta >= Abs::zero() { stretchability } else { shrinkability };
+    // Determine how much stretch is permitted. The glyphs of justified lines
+    // can be expanded or condensed in addition to the spaces.
+    let expand = if p.justify { line_width * p.expansion.get() } else { Abs::zero() };
+    let adjust =
+        expand + if delta >= Abs::zero() { stretchability } else { shrinkability };
 
     // Ideally, the ratio should between -1.0 and 1.0.
     //
@@ -529,17 +645,21 @@ fn raw_cost(
     metrics: &CostMetrics,
     breakpoint: Breakpoint,
     ratio: f64,
+    line_width: Abs,
     at_end: bool,
     justify: bool,
     unbreakable: bool,
     consecutive_dash: bool,
     approx: bool,
 ) -> Cost {
+    // Whether the line ends the paragraph or is followed by a forced break.
+    let last = breakpoint == Breakpoint::Mandatory || at_end;
+
     // Determine the cost of the line.
     let mut cost = if ratio < metrics.min_ratio(approx) {
         // Overfull line always has maximum cost.
         MAX_COST
-    } else if breakpoint == Breakpoint::Mandatory || at_end {
+    } else if last {
         // - If ratio < 0, we always need to shrink the line (even the last one).
         // - If ratio > 0, we need to stretch the line only when it is justified
         //   (last line is not justified by default even if `p.justify` is true).
@@ -553,6 +673,23 @@ fn raw_cost(
         ratio.powi(3).abs()
     };
 
+    // Penalize lines that are stretched beyond the tolerance, the more the
+    // further they exceed it, such that a single very loose line is not
+    // preferred over a few that are only slightly too loose.
+    if ratio > metrics.tolerance && (!last || justify) {
+        cost += LOOSE_COST * (1.0 + ratio - metrics.tolerance);
+    }
+
+    // Penalize a last line that is shorter than preferred.
+    if at_end && line_width < metrics.last_line_fill {
+        cost += SHORT_LAST_LINE_COST * (1.0 - line_width / metrics.last_line_fill);
+    }
+
+    // Avoid a last line that is shorter than the minimum at almost any cost.
+    if at_end && line_width < metrics.last_line_min {
+        cost += TOO_SHORT_LAST_LINE_COST;
+    }
+
     // Penalize runts (lone words in the last line).
     if unbreakable && at_end {
         cost += metrics.runt_cost;
@@ -571,7 +708,7 @@ fn raw_cost(
 
     // Penalize two consecutive dashes (not necessarily hyphens) extra.
     if consecutive_dash {
-        cost += CONSECUTIVE_DASH_COST;
+        cost += metrics.ladder_cost;
     }
 
     cost
@@ -587,6 +724,14 @@ fn raw_cost(
 /// code much simpler and the consumers of this function don't need the
 /// composability and flexibility of external iteration anyway.
 fn breakpoints<'a>(p: &'a Preparation<'a>, mut f: impl FnMut(usize, Breakpoint)) {
+    // Drop opportunities within unbreakable spans. Explicit line breaks are
+    // still honored.
+    let mut f = |end, breakpoint| {
+        if breakpoint == Breakpoint::Mandatory || !p.is_nobreak(end) {
+            f(end, breakpoint);
+        }
+    };
+
     let text = p.bidi.text;
     let hyphenate = p.hyphenate != Some(false);
     let lb = LINEBREAK_DATA.as_borrowed();
@@ -649,10 +794,10 @@ fn breakpoints<'a>(p: &'a Preparation<'a>, mut f: impl FnMut(usize, Breakpoint))
             let end = last + word.len();
             let mut offset = last;
 
-            // Determine the language to hyphenate this word in.
-            let Some(lang) = lang_at(p, last) else { break 'hyphenate };
+            // Split the word into syllables.
+            let Some(syllables) = syllables(p, last, word) else { break 'hyphenate };
 
-            for syllable in hypher::hyphenate(word, lang) {
+            for syllable in syllables {
                 // Don't hyphenate after the final syllable.
                 offset += syllable.len();
                 if offset == end {
@@ -678,8 +823,15 @@ fn breakpoints<'a>(p: &'a Preparation<'a>, mut f: impl FnMut(usize, Breakpoint))
             }
         }
 
-        // Call `f` for the UAX #14 break opportunity.
-        f(point, breakpoint);
+        // Call `f` for the UAX #14 break opportunity unless it would strand
+        // a single letter at the end of the line or violate the line breaking
+        // rules of CJK typesetting.
+        if breakpoint != Breakpoint::Normal
+            || !(strands_letter(p, last, &text[last..point])
+                || violates_kinsoku(p, point))
+        {
+            f(point, breakpoint);
+        }
 
         last = point;
     }
@@ -743,6 +895,59 @@ fn linebreak_link(link: &str, mut f: impl FnMut(usize)) {
     }
 }
 
+/// Whether breaking after the segment at the given offset would leave a word of
+/// a single letter at the end of a line although that is forbidden.
+fn strands_letter(p: &Preparation, offset: usize, segment: &str) -> bool {
+    let mut chars = segment.trim_end().chars();
+    if !chars.next().is_some_and(char::is_alphabetic) || chars.next().is_some() {
+        return false;
+    }
+
+    p.find(offset)
+        .and_then(Item::text)
+        .is_some_and(|shaped| !TextElem::single_letter_breaks_in(shaped.styles))
+}
+
+/// Whether a break at the given offset would start a line with a character
+/// that must not start one or end a line with a character that must not end
+/// one, according to the rules of Japanese and Chinese typesetting (kinsoku
+/// shori).
+///
+/// The Unicode line breaking algorithm already forbids almost all of these
+/// breaks, but not all of them. For instance, it allows a break before a
+/// full-width tilde.
+fn violates_kinsoku(p: &Preparation, offset: usize) -> bool {
+    let text = p.bidi.text;
+    let (Some(before), Some(after)) =
+        (text[..offset].chars().next_back(), text[offset..].chars().next())
+    else {
+        return false;
+    };
+
+    if !NO_LINE_START.contains(after) && !NO_LINE_END.contains(before) {
+        return false;
+    }
+
+    let Some(shaped) = p.find(offset - before.len_utf8()).and_then(Item::text) else {
+        return false;
+    };
+
+    TextElem::cjk_in(shaped.styles).kinsoku()
+}
+
+/// Full-width characters that must not start a line: closing brackets,
+/// hyphens, dividing punctuation, middle dots, full stops, commas, iteration
+/// marks, the prolonged sound mark, and small kana. See Requirements for
+/// Japanese Text Layout, Appendix A.
+const NO_LINE_START: &str = "）〕］｝〉》」』】〙〗〟｠\
+    ゠〜～？！・：；、。，．\
+    ヽヾゝゞ々〻ー\
+    ぁぃぅぇぉっゃゅょゎゕゖァィゥェォッャュョヮヵヶ\
+    ㇰㇱㇲㇳㇴㇵㇶㇷㇸㇹㇺㇻㇼㇽㇾㇿ";
+
+/// Full-width characters that must not end a line: opening brackets.
+const NO_LINE_END: &str = "（〔［｛〈《「『【〘〖〝｟";
+
 /// Whether hyphenation is enabled at the given offset.
 fn hyphenate_at(p: &Preparation, offset: usize) -> bool {
     p.hyphenate
@@ -753,6 +958,27 @@ fn hyphenate_at(p: &Preparation, offset: usize) -> bool {
         .unwrap_or(false)
 }
 
+/// Split a word at the given offset into syllables, preferring the
+/// hyphenation exceptions and patterns of its text over the built-in patterns.
+fn syllables<'s>(p: &Preparation, offset: usize, word: &'s str) -> Option<Vec<&'s str>> {
+    if let Some(shaped) = p.find(offset).and_then(Item::text) {
+        let exceptions = TextElem::hyphenation_exceptions_in(shaped.styles);
+        if let Some(syllables) = exceptions
+            .iter()
+            .find_map(|exception| hyphenate_exception(word, exception))
+        {
+            return Some(syllables);
+        }
+
+        if let Some(patterns) = TextElem::hyphenation_patterns_in(shaped.styles) {
+            return Some(patterns.hyphenate(word));
+        }
+    }
+
+    let lang = lang_at(p, offset)?;
+    Some(hypher::hyphenate(word, lang).collect())
+}
+
 /// The text language at the given offset.
 fn lang_at(p: &Preparation, offset: usize) -> Option<hypher::Lang> {
     let lang = p.lang.or_else(|| {
@@ -770,18 +996,26 @@ struct CostMetrics {
     min_approx_ratio: f64,
     hyph_cost: Cost,
     runt_cost: Cost,
+    ladder_cost: Cost,
+    tolerance: f64,
+    last_line_fill: Abs,
+    last_line_min: Abs,
     approx_hyphen_width: Abs,
 }
 
 impl CostMetrics {
     /// Compute shared metrics for paragraph optimization.
-    fn compute(p: &Preparation) -> Self {
+    fn compute(p: &Preparation, width: Abs) -> Self {
         Self {
             // When justifying, we may stretch spaces below their natural width.
             min_ratio: if p.justify { MIN_RATIO } else { 0.0 },
             min_approx_ratio: if p.justify { MIN_APPROX_RATIO } else { 0.0 },
             hyph_cost: DEFAULT_HYPH_COST * p.costs.hyphenation().get(),
             runt_cost: DEFAULT_RUNT_COST * p.costs.runt().get(),
+            ladder_cost: CONSECUTIVE_DASH_COST * p.costs.ladder().get(),
+            tolerance: p.tolerance.map_or(f64::INFINITY, Ratio::get),
+            last_line_fill: p.last_line_fill.of(width),
+            last_line_min: p.last_line_min.relative_to(width),
             // Approximate hyphen width for estimates.
             approx_hyphen_width: Em::new(0.33).at(p.size),
         }
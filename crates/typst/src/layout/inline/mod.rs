@@ -4,31 +4,39 @@ mod line;
 mod linebreak;
 mod prepare;
 mod shaping;
+mod wrap;
 
 use comemo::{Track, Tracked, TrackedMut};
 
+pub use self::wrap::Exclusion;
+
+pub(crate) use self::shaping::shape_text;
+
 use self::collect::{collect, Item, Segment, SpanMapper};
 use self::finalize::finalize;
-use self::line::{commit, line, Line};
-use self::linebreak::{linebreak, Breakpoint};
+use self::line::{commit, line, Dash, Line};
+use self::linebreak::{linebreak, linebreak_wrapped, raw_ratio, Breakpoint};
 use self::prepare::{prepare, Preparation};
 use self::shaping::{
     cjk_punct_style, is_of_cj_script, shape_range, ShapedGlyph, ShapedText,
     BEGIN_PUNCT_PAT, END_PUNCT_PAT,
 };
-use crate::diag::SourceResult;
-use crate::engine::{Engine, Route, Sink, Traced};
-use crate::foundations::StyleChain;
+use self::wrap::{free_span, LineSpan};
+use crate::diag::{At, SourceResult};
+use crate::engine::{Budget, Engine, Route, Sink, Traced};
+use crate::foundations::{Context, Func, IntoValue, Resolve, StyleChain};
 use crate::introspection::{Introspector, Locator, LocatorLink};
-use crate::layout::{Fragment, Size};
+use crate::layout::{Abs, Fragment, Length, Rel, Sides, Size};
 use crate::model::ParElem;
 use crate::realize::StyleVec;
+use crate::utils::Numeric;
 use crate::World;
 
 /// Range of a substring of text.
 type Range = std::ops::Range<usize>;
 
 /// Layouts content inline.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn layout_inline(
     children: &StyleVec,
     engine: &mut Engine,
@@ -37,6 +45,7 @@ pub(crate) fn layout_inline(
     consecutive: bool,
     region: Size,
     expand: bool,
+    exclusions: &[Exclusion],
 ) -> SourceResult<Fragment> {
     #[comemo::memoize]
     #[allow(clippy::too_many_arguments)]
@@ -52,29 +61,43 @@ pub(crate) fn layout_inline(
         consecutive: bool,
         region: Size,
         expand: bool,
+        exclusions: &[Exclusion],
     ) -> SourceResult<Fragment> {
         let link = LocatorLink::new(locator);
         let locator = Locator::link(&link);
+        // Paragraphs contain no root flow, so they don't need the budget.
+        let budget = Budget::default();
         let mut engine = Engine {
             world,
             introspector,
             traced,
+            budget: budget.track(),
             sink,
             route: Route::extend(route),
         };
 
         // Collect all text into one string for BiDi analysis.
-        let (text, segments, spans) =
+        let (text, segments, spans, nobreak) =
             collect(children, &mut engine, locator, &styles, region, consecutive)?;
 
         // Perform BiDi analysis and then prepares paragraph layout.
-        let p = prepare(&mut engine, children, &text, segments, spans, styles)?;
+        let p = prepare(&mut engine, children, &text, segments, spans, nobreak, styles)?;
 
-        // Break the paragraph into lines.
-        let lines = linebreak(&engine, &p, region.x - p.hang);
+        // Break the paragraph into lines, flowing them around exclusions and
+        // following the paragraph's shape if there are any.
+        let shape = ParElem::shape_in(styles).filter(|_| region.x.is_finite());
+        let (lines, spans) = if exclusions.is_empty() && shape.is_none() {
+            (linebreak(&engine, &p, region.x - p.hang), vec![])
+        } else {
+            let mut indents = |engine: &mut Engine, index: usize| match &shape {
+                Some(shape) => line_indents(engine, shape, styles, region.x, index),
+                None => Ok((Abs::zero(), Abs::zero())),
+            };
+            linebreak_wrapped(&mut engine, &p, region.x, exclusions, &mut indents)?
+        };
 
         // Turn the selected lines into frames.
-        finalize(&mut engine, &p, &lines, styles, region, expand)
+        finalize(&mut engine, &p, &lines, &spans, styles, region, expand)
     }
 
     cached(
@@ -89,5 +112,29 @@ pub(crate) fn layout_inline(
         consecutive,
         region,
         expand,
+        exclusions,
     )
 }
+
+/// Determines the left and right indent of the line with the given index
+/// through the paragraph's shape function.
+fn line_indents(
+    engine: &mut Engine,
+    shape: &Func,
+    styles: StyleChain,
+    width: Abs,
+    index: usize,
+) -> SourceResult<(Abs, Abs)> {
+    let indents = shape
+        .call(
+            engine,
+            Context::new(None, Some(styles)).track(),
+            [index.into_value(), width.into_value()],
+        )?
+        .cast::<Sides<Option<Rel<Length>>>>()
+        .at(shape.span())?;
+    let resolve = |indent: Option<Rel<Length>>| {
+        indent.map_or(Abs::zero(), |indent| indent.resolve(styles).relative_to(width))
+    };
+    Ok((resolve(indents.left), resolve(indents.right)))
+}
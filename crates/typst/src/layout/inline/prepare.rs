@@ -2,8 +2,8 @@ use unicode_bidi::{BidiInfo, Level as BidiLevel};
 
 use super::*;
 use crate::foundations::{Resolve, Smart};
-use crate::layout::{Abs, AlignElem, Dir, Em, FixedAlignment};
-use crate::model::Linebreaks;
+use crate::layout::{Abs, AlignElem, Dir, Em, FixedAlignment, Length, Ratio, Rel};
+use crate::model::{Linebreaks, TabAlign};
 use crate::text::{Costs, Lang, TextElem};
 
 /// A paragraph representation in which children are already layouted and text
@@ -19,6 +19,8 @@ pub struct Preparation<'a> {
     pub items: Vec<Item<'a>>,
     /// The span mapper.
     pub spans: SpanMapper,
+    /// Sorted, disjoint text ranges within which lines must not be broken.
+    pub nobreak: Vec<Range>,
     /// Whether to hyphenate if it's the same for all children.
     pub hyphenate: Option<bool>,
     /// Costs for various layout decisions.
@@ -39,8 +41,18 @@ pub struct Preparation<'a> {
     pub leading: Abs,
     /// How to determine line breaks.
     pub linebreaks: Smart<Linebreaks>,
+    /// The maximum stretch ratio of an acceptable line.
+    pub tolerance: Option<Ratio>,
+    /// How much the glyphs of justified lines may be scaled horizontally.
+    pub expansion: Ratio,
+    /// How much of the width the last line should fill.
+    pub last_line_fill: Ratio,
+    /// The minimum length of the last line.
+    pub last_line_min: Rel<Abs>,
     /// The text size.
     pub size: Abs,
+    /// The resolved tab stops with logical alignments.
+    pub tab_stops: Vec<(Abs, TabAlign)>,
 }
 
 impl<'a> Preparation<'a> {
@@ -57,6 +69,13 @@ impl<'a> Preparation<'a> {
         None
     }
 
+    /// Whether breaking a line at the given `text_offset` is forbidden because
+    /// it falls within an unbreakable span.
+    pub fn is_nobreak(&self, text_offset: usize) -> bool {
+        let i = self.nobreak.partition_point(|range| range.end <= text_offset);
+        self.nobreak.get(i).is_some_and(|range| range.start < text_offset)
+    }
+
     /// Return the items that intersect the given `text_range`.
     ///
     /// Returns the expanded range around the items and the items.
@@ -97,11 +116,13 @@ pub fn prepare<'a>(
     text: &'a str,
     segments: Vec<Segment<'a>>,
     spans: SpanMapper,
+    nobreak: Vec<Range>,
     styles: StyleChain<'a>,
 ) -> SourceResult<Preparation<'a>> {
+    let dir = TextElem::dir_in(styles);
     let bidi = BidiInfo::new(
         text,
-        match TextElem::dir_in(styles) {
+        match dir {
             Dir::LTR => Some(BidiLevel::ltr()),
             Dir::RTL => Some(BidiLevel::rtl()),
             _ => None,
@@ -124,15 +145,26 @@ pub fn prepare<'a>(
         cursor = end;
     }
 
-    let cjk_latin_spacing = TextElem::cjk_latin_spacing_in(styles).is_auto();
-    if cjk_latin_spacing {
-        add_cjk_latin_spacing(&mut items);
+    // The spacing configured in the `cjk` group takes precedence over the
+    // `cjk-latin-spacing` property.
+    let spacing = match TextElem::cjk_in(styles).spacing() {
+        Some(Smart::Auto) => Some(Em::new(0.25).into()),
+        Some(Smart::Custom(spacing)) => spacing,
+        None => TextElem::cjk_latin_spacing_in(styles)
+            .is_auto()
+            .then(|| Em::new(0.25).into()),
+    };
+
+    let cjk_latin_spacing = spacing.is_some();
+    if let Some(spacing) = spacing {
+        add_cjk_latin_spacing(&mut items, spacing);
     }
 
     Ok(Preparation {
         bidi,
         items,
         spans,
+        nobreak,
         hyphenate: children.shared_get(styles, TextElem::hyphenate_in),
         costs: TextElem::costs_in(styles),
         lang: children.shared_get(styles, TextElem::lang_in),
@@ -140,17 +172,25 @@ pub fn prepare<'a>(
         justify: ParElem::justify_in(styles),
         hang: ParElem::hanging_indent_in(styles),
         cjk_latin_spacing,
-        fallback: TextElem::fallback_in(styles),
+        fallback: TextElem::fallback_in(styles).last_resort,
         leading: ParElem::leading_in(styles),
         linebreaks: ParElem::linebreaks_in(styles),
+        tolerance: ParElem::tolerance_in(styles),
+        expansion: ParElem::expansion_in(styles),
+        last_line_fill: ParElem::last_line_fill_in(styles),
+        last_line_min: ParElem::last_line_min_in(styles),
         size: TextElem::size_in(styles),
+        tab_stops: ParElem::tab_stops_in(styles)
+            .into_iter()
+            .map(|stop| (stop.pos.resolve(styles), stop.align.logical(dir)))
+            .collect(),
     })
 }
 
 /// Add some spacing between Han characters and western characters. See
 /// Requirements for Chinese Text Layout, Section 3.2.2 Mixed Text Composition
 /// in Horizontal Written Mode
-fn add_cjk_latin_spacing(items: &mut [Item]) {
+fn add_cjk_latin_spacing(items: &mut [Item], spacing: Length) {
     let mut items = items.iter_mut().filter(|x| !matches!(x, Item::Tag(_))).peekable();
     let mut prev: Option<&ShapedGlyph> = None;
     while let Some(item) = items.next() {
@@ -172,20 +212,22 @@ fn add_cjk_latin_spacing(items: &mut [Item]) {
                     .and_then(|shaped| shaped.glyphs.first())
             });
 
+            // The spacing defaults to 1/4 em and can be shrunk to half of it.
+            let amount = spacing.em + Em::from_length(spacing.abs, text.size);
+
             // Case 1: CJ followed by a Latin character
             if glyph.is_cj_script() && next.is_some_and(|g| g.is_letter_or_number()) {
-                // The spacing is default to 1/4 em, and can be shrunk to 1/8 em.
-                glyph.x_advance += Em::new(0.25);
-                glyph.adjustability.shrinkability.1 += Em::new(0.125);
-                text.width += Em::new(0.25).at(text.size);
+                glyph.x_advance += amount;
+                glyph.adjustability.shrinkability.1 += amount / 2.0;
+                text.width += amount.at(text.size);
             }
 
             // Case 2: Latin followed by a CJ character
             if glyph.is_cj_script() && prev.is_some_and(|g| g.is_letter_or_number()) {
-                glyph.x_advance += Em::new(0.25);
-                glyph.x_offset += Em::new(0.25);
-                glyph.adjustability.shrinkability.0 += Em::new(0.125);
-                text.width += Em::new(0.25).at(text.size);
+                glyph.x_advance += amount;
+                glyph.x_offset += amount;
+                glyph.adjustability.shrinkability.0 += amount / 2.0;
+                text.width += amount.at(text.size);
             }
 
             prev = Some(glyph);
@@ -16,8 +16,8 @@ use crate::foundations::{Smart, StyleChain};
 use crate::layout::{Abs, Dir, Em, Frame, FrameItem, Point, Size};
 use crate::syntax::Span;
 use crate::text::{
-    decorate, families, features, variant, Font, FontVariant, Glyph, Lang, Region,
-    TextElem, TextItem,
+    decorate, families, features, script_families, variant, Font, FontVariant, Glyph,
+    Lang, Region, TextElem, TextItem,
 };
 use crate::utils::SliceExt;
 use crate::World;
@@ -331,7 +331,7 @@ impl<'a> ShapedText<'a> {
     }
 
     /// Measure the top and bottom extent of this text.
-    fn measure(&self, engine: &Engine) -> (Abs, Abs) {
+    pub fn measure(&self, engine: &Engine) -> (Abs, Abs) {
         let mut top = Abs::zero();
         let mut bottom = Abs::zero();
 
@@ -652,6 +652,24 @@ pub fn shape_range<'a>(
     process(cursor..range.end, prev_level);
 }
 
+/// Shape a standalone piece of text, outside of any paragraph.
+pub fn shape_text<'a>(
+    engine: &Engine,
+    text: &'a str,
+    styles: StyleChain<'a>,
+) -> ShapedText<'a> {
+    shape(
+        engine,
+        0,
+        text,
+        &SpanMapper::new(),
+        styles,
+        TextElem::dir_in(styles),
+        TextElem::lang_in(styles),
+        TextElem::region_in(styles),
+    )
+}
+
 /// Whether this is not a specific script.
 fn is_generic_script(script: Script) -> bool {
     matches!(script, Script::Unknown | Script::Common | Script::Inherited)
@@ -684,12 +702,24 @@ fn shape<'a>(
         styles,
         variant: variant(styles),
         features: features(styles),
-        fallback: TextElem::fallback_in(styles),
+        fallback: TextElem::fallback_in(styles).last_resort,
         dir,
+        acronyms: if TextElem::acronyms_in(styles) {
+            find_acronyms(base, text)
+        } else {
+            vec![]
+        },
     };
 
     if !text.is_empty() {
-        shape_segment(&mut ctx, base, text, families(styles));
+        // Runs are split by script, so the first specific script determines
+        // which script-specific fallback families apply.
+        let script = text
+            .chars()
+            .map(|c| c.script())
+            .find(|&script| !is_generic_script(script))
+            .unwrap_or(Script::Unknown);
+        shape_segment(&mut ctx, base, text, script_families(styles, script));
     }
 
     track_and_space(&mut ctx);
@@ -726,6 +756,7 @@ struct ShapingContext<'a, 'v> {
     features: Vec<rustybuzz::Feature>,
     fallback: bool,
     dir: Dir,
+    acronyms: Vec<Range>,
 }
 
 /// Shape text with font fallback using the `families` iterator.
@@ -784,6 +815,18 @@ fn shape_segment<'a>(
     });
     buffer.guess_segment_properties();
 
+    // Turn the capitals of acronyms in this segment into small capitals.
+    let mut features = Cow::Borrowed(ctx.features.as_slice());
+    for range in &ctx.acronyms {
+        let start = range.start.max(base);
+        let end = range.end.min(base + text.len());
+        if start < end {
+            let tag = Tag::from_bytes(b"c2sc");
+            let feature = rustybuzz::Feature::new(tag, 1, start - base..end - base);
+            features.to_mut().push(feature);
+        }
+    }
+
     // Prepare the shape plan. This plan depends on direction, script, language,
     // and features, but is independent from the text and can thus be memoized.
     let plan = create_shape_plan(
@@ -791,7 +834,7 @@ fn shape_segment<'a>(
         buffer.direction(),
         buffer.script(),
         buffer.language().as_ref(),
-        &ctx.features,
+        &features,
     );
 
     // Shape!
@@ -938,11 +981,38 @@ fn shape_tofus(ctx: &mut ShapingContext, base: usize, text: &str, font: Font) {
     }
 }
 
+/// The extra tracking between the small capitals of an acronym.
+const ACRONYM_TRACKING: Em = Em::new(0.05);
+
+/// Find the runs of two or more uppercase letters in the text.
+fn find_acronyms(base: usize, text: &str) -> Vec<Range> {
+    let mut acronyms = vec![];
+    let mut start = None;
+    let mut count = 0;
+    for (i, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        if c.is_uppercase() {
+            start.get_or_insert(i);
+            count += 1;
+        } else if let Some(start) = start.take() {
+            if count >= 2 {
+                acronyms.push(base + start..base + i);
+            }
+            count = 0;
+        }
+    }
+    acronyms
+}
+
 /// Apply tracking and spacing to the shaped glyphs.
 fn track_and_space(ctx: &mut ShapingContext) {
     let tracking = Em::from_length(TextElem::tracking_in(ctx.styles), ctx.size);
     let spacing =
         TextElem::spacing_in(ctx.styles).map(|abs| Em::from_length(abs, ctx.size));
+    let acronym = |glyph: &ShapedGlyph| {
+        ctx.acronyms
+            .iter()
+            .position(|range| range.contains(&glyph.range.start))
+    };
 
     let mut glyphs = ctx.glyphs.iter_mut().peekable();
     while let Some(glyph) = glyphs.next() {
@@ -955,11 +1025,15 @@ fn track_and_space(ctx: &mut ShapingContext) {
             glyph.x_advance = spacing.relative_to(glyph.x_advance);
         }
 
-        if glyphs
-            .peek()
-            .is_some_and(|next| glyph.range.start != next.range.start)
-        {
-            glyph.x_advance += tracking;
+        if let Some(next) = glyphs.peek() {
+            if glyph.range.start != next.range.start {
+                glyph.x_advance += tracking;
+
+                // Space the small capitals of acronyms slightly apart.
+                if acronym(glyph).is_some_and(|i| acronym(next) == Some(i)) {
+                    glyph.x_advance += ACRONYM_TRACKING;
+                }
+            }
         }
     }
 }
@@ -973,6 +1047,10 @@ fn calculate_adjustability(ctx: &mut ShapingContext, lang: Lang, region: Option<
         glyph.adjustability = glyph.base_adjustability(style);
     }
 
+    if !TextElem::cjk_in(ctx.styles).compress() {
+        return;
+    }
+
     let mut glyphs = ctx.glyphs.iter_mut().peekable();
     while let Some(glyph) = glyphs.next() {
         // CNS style needs not further adjustment.
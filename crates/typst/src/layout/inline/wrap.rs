@@ -0,0 +1,110 @@
+use crate::layout::{Abs, Point};
+
+/// An area that the lines of a paragraph flow around.
+///
+/// Exclusions are created by placed elements that text [wraps]($place.wrap)
+/// around. They are given in the coordinate system of the paragraph, with the
+/// origin at the top-left corner of its first line.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Exclusion {
+    /// The vertices of the polygon that outlines the area.
+    pub vertices: Vec<Point>,
+    /// The gap to keep between the area and the text.
+    pub gap: Abs,
+}
+
+impl Exclusion {
+    /// The lowest point of the area, including the gap.
+    pub fn bottom(&self) -> Abs {
+        self.vertices.iter().map(|p| p.y).max().unwrap_or_default() + self.gap
+    }
+
+    /// The horizontal extent of the area (including the gap) within the
+    /// vertical band between `top` and `bottom`, if it intersects the band.
+    pub fn extent(&self, top: Abs, bottom: Abs) -> Option<(Abs, Abs)> {
+        let top = top - self.gap;
+        let bottom = bottom + self.gap;
+        let mut extent: Option<(Abs, Abs)> = None;
+        let mut include = |x: Abs| {
+            let (min, max) = extent.get_or_insert((x, x));
+            min.set_min(x);
+            max.set_max(x);
+        };
+
+        // The extent within the band is attained where the polygon's edges
+        // cross the band's boundaries or at vertices inside of it.
+        let len = self.vertices.len();
+        for (i, &a) in self.vertices.iter().enumerate() {
+            let b = self.vertices[(i + 1) % len];
+            let (lo, hi) = (a.y.min(b.y), a.y.max(b.y));
+            if hi <= top || lo >= bottom {
+                continue;
+            }
+
+            if a.y.approx_eq(b.y) {
+                include(a.x);
+                include(b.x);
+                continue;
+            }
+
+            for y in [lo.max(top), hi.min(bottom)] {
+                include(a.x + (b.x - a.x) * ((y - a.y) / (b.y - a.y)));
+            }
+        }
+
+        extent.map(|(min, max)| (min - self.gap, max + self.gap))
+    }
+}
+
+/// The horizontal space available to a line that wraps around exclusions.
+#[derive(Debug, Copy, Clone)]
+pub struct LineSpan {
+    /// The vertical space to skip before the line to get past exclusions that
+    /// leave no room for it.
+    pub skip: Abs,
+    /// The horizontal offset of the line.
+    pub offset: Abs,
+    /// The width available to the line.
+    pub width: Abs,
+}
+
+/// The widest horizontal span between `start` and `end` that is not covered by
+/// any exclusion within the vertical band between `top` and `bottom`.
+///
+/// Also returns where the exclusions narrowing the span end, if there are any.
+pub fn free_span(
+    exclusions: &[Exclusion],
+    start: Abs,
+    end: Abs,
+    top: Abs,
+    bottom: Abs,
+) -> (Abs, Abs, Option<Abs>) {
+    let mut free = vec![(start, end.max(start))];
+    let mut clear: Option<Abs> = None;
+
+    for exclusion in exclusions {
+        let Some((min, max)) = exclusion.extent(top, bottom) else { continue };
+        if max <= start || min >= end {
+            continue;
+        }
+
+        free = free
+            .into_iter()
+            .flat_map(|(start, end)| {
+                [(start, end.min(min)), (start.max(max), end)]
+                    .into_iter()
+                    .filter(|(start, end)| start < end)
+            })
+            .collect();
+
+        let bottom = exclusion.bottom();
+        clear = Some(clear.map_or(bottom, |clear| clear.min(bottom)));
+    }
+
+    let (start, end) = free
+        .into_iter()
+        .max_by(|a, b| (a.1 - a.0).cmp(&(b.1 - b.0)))
+        .unwrap_or_default();
+
+    (start, end - start, clear)
+}
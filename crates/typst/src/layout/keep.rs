@@ -0,0 +1,70 @@
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, NativeElement, Packed, Show, StyleChain};
+use crate::introspection::Locator;
+use crate::layout::{Abs, Axes, BlockElem, Fragment, Regions, Size};
+
+/// Keeps a group of blocks together in one region.
+///
+/// The content of `keep` is never split across pages or columns. If it doesn't
+/// fit into the rest of the current region, the whole group moves to the next
+/// one. Only if the group is taller than a full region, it is broken up like
+/// any other content, since it wouldn't fit anywhere in one piece.
+///
+/// To just prevent a page break right after a single block, use the block's
+/// [`keep-with-next`]($block.keep-with-next) property instead.
+///
+/// # Example
+/// ```example
+/// #set page(height: 100pt)
+/// #lorem(15)
+///
+/// #keep[
+///   - Flour
+///   - Eggs
+///   - Milk
+/// ]
+/// ```
+#[elem(Show)]
+pub struct KeepElem {
+    /// The content to keep together.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for Packed<KeepElem> {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(BlockElem::multi_layouter(self.clone(), layout_keep)
+            .pack()
+            .spanned(self.span()))
+    }
+}
+
+/// Layout the kept content.
+#[typst_macros::time(span = elem.span())]
+fn layout_keep(
+    elem: &Packed<KeepElem>,
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    regions: Regions,
+) -> SourceResult<Fragment> {
+    // Lay the content out in one piece. The height is unbounded because
+    // layout clamps the frame size to the region.
+    let pod = Regions::one(
+        Size::new(regions.size.x, Abs::inf()),
+        Axes::new(regions.expand.x, false),
+    );
+    let frame = elem
+        .body()
+        .layout(engine, locator.relayout(), styles, pod)?
+        .into_frame();
+
+    // If it fits into a full region, the flow moves it to the next region if
+    // necessary. Otherwise, fall back to breaking it.
+    if regions.full.fits(frame.height()) {
+        Ok(Fragment::frame(frame))
+    } else {
+        elem.body().layout(engine, locator, styles, regions)
+    }
+}
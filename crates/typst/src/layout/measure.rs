@@ -3,11 +3,15 @@ use comemo::Tracked;
 use crate::diag::{At, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    dict, func, Content, Context, Dict, Resolve, Smart, StyleChain, Styles,
+    dict, func, Array, Content, Context, Dict, IntoValue, Resolve, Smart, Str,
+    StyleChain, Styles,
 };
 use crate::introspection::{Locator, LocatorLink};
-use crate::layout::{Abs, Axes, Length, Regions, Size};
+use crate::layout::inline::shape_text;
+use crate::layout::{Abs, Axes, Em, Length, Regions, Size};
 use crate::syntax::Span;
+use crate::text::{FontList, TextElem, TextSize};
+use crate::utils::SliceExt;
 
 /// Measures the layouted size of content.
 ///
@@ -107,3 +111,101 @@ pub fn measure(
     let Size { x, y } = frame.size();
     Ok(dict! { "width" => x, "height" => y })
 }
+
+/// Measures the glyph clusters of a piece of text.
+///
+/// While [`measure`] only tells you the size of some text as a whole, this
+/// function shapes the text with the current text settings and reports the
+/// metrics of each of its clusters. A cluster is a group of characters that
+/// form an inseparable unit after shaping, like a single letter, a ligature,
+/// or a letter with its combining accents. This is useful to build effects
+/// that position characters individually, without having to reimplement text
+/// shaping.
+///
+/// The function returns an array with one dictionary per cluster, in visual
+/// order. Each dictionary has the following entries:
+///
+/// - `text` ([`str`]): The characters making up the cluster.
+/// - `font` ([`str`]): The family of the font the cluster was shaped with,
+///   which may differ from the requested one due to font fallback.
+/// - `x` ([`length`]): The horizontal position of the cluster on the baseline,
+///   relative to the start of the text.
+/// - `advance` ([`length`]): How far the cluster advances the pen.
+/// - `kerning` ([`length`]): The difference between the cluster's advance and
+///   the default advances of its glyphs, for instance due to kerning or
+///   [tracking]($text.tracking).
+/// - `ascender` and `descender` ([`length`]): The distance from the baseline
+///   to the ascender and descender of the cluster's font. The descender is
+///   typically negative.
+///
+/// # Example
+/// ```example
+/// #context {
+///   let clusters = measure-glyphs("AVA", size: 20pt)
+///   for cluster in clusters [
+///     #cluster.text: #cluster.advance
+///     (#cluster.kerning) \
+///   ]
+/// }
+/// ```
+#[func(contextual)]
+pub fn measure_glyphs(
+    /// The engine.
+    engine: &mut Engine,
+    /// The callsite context.
+    context: Tracked<Context>,
+    /// The callsite span.
+    span: Span,
+    /// The text to measure.
+    text: Str,
+    /// The font family or families to shape the text with.
+    ///
+    /// Defaults to the [font]($text.font) of the context.
+    #[named]
+    font: Option<FontList>,
+    /// The font size to measure the text at.
+    ///
+    /// Defaults to the [size]($text.size) of the context.
+    #[named]
+    size: Option<Length>,
+) -> SourceResult<Array> {
+    let mut local = Styles::new();
+    if let Some(font) = font {
+        local.set(TextElem::set_font(font));
+    }
+    if let Some(size) = size {
+        local.set(TextElem::set_size(TextSize(size)));
+    }
+
+    let outer = context.styles().at(span)?;
+    let styles = outer.chain(&local);
+    let shaped = shape_text(engine, &text, styles);
+    let size = shaped.size;
+
+    let mut clusters = Array::new();
+    let mut x = Abs::zero();
+    for (range, group) in shaped.glyphs.group_by_key(|glyph| glyph.range.clone()) {
+        let font = &group[0].font;
+        let advance = group.iter().map(|glyph| glyph.x_advance).sum::<Em>();
+        let default = group
+            .iter()
+            .map(|glyph| glyph.font.advance(glyph.glyph_id).unwrap_or(glyph.x_advance))
+            .sum::<Em>();
+        let metrics = font.metrics();
+        clusters.push(
+            dict! {
+                "text" => &text[range],
+                "font" => font.info().family.clone(),
+                "x" => x,
+                "advance" => advance.at(size),
+                "kerning" => (advance - default).at(size),
+                "ascender" => metrics.ascender.at(size),
+                "descender" => metrics.descender.at(size),
+            }
+            .into_value(),
+        );
+        x += advance.at(size);
+    }
+
+    Ok(clusters)
+}
@@ -2,20 +2,25 @@
 
 mod abs;
 mod align;
+mod anchor;
 mod angle;
 mod axes;
 mod columns;
 mod container;
 mod corners;
+mod debug;
 mod dir;
 mod em;
+mod fit;
 mod flow;
 mod fr;
 mod fragment;
 mod frame;
+mod frozen;
 mod grid;
 mod hide;
 mod inline;
+mod keep;
 #[path = "layout.rs"]
 mod layout_;
 mod length;
@@ -23,20 +28,26 @@ mod length;
 mod measure_;
 mod pad;
 mod page;
+mod parallel;
 mod place;
 mod point;
 mod ratio;
+mod redact;
 mod regions;
 mod rel;
 mod repeat;
 mod sides;
+mod sideways;
 mod size;
 mod spacing;
 mod stack;
+mod stamp;
 mod transform;
+mod watermark;
 
 pub use self::abs::*;
 pub use self::align::*;
+pub use self::anchor::*;
 pub use self::angle::*;
 pub use self::axes::*;
 pub use self::columns::*;
@@ -44,35 +55,44 @@ pub use self::container::*;
 pub use self::corners::*;
 pub use self::dir::*;
 pub use self::em::*;
+pub use self::fit::*;
 pub use self::flow::*;
 pub use self::fr::*;
 pub use self::fragment::*;
 pub use self::frame::*;
+pub use self::frozen::*;
 pub use self::grid::*;
 pub use self::hide::*;
+pub use self::keep::*;
 pub use self::layout_::*;
 pub use self::length::*;
 pub use self::measure_::*;
 pub use self::pad::*;
 pub use self::page::*;
+pub use self::parallel::*;
 pub use self::place::*;
 pub use self::point::*;
 pub use self::ratio::*;
+pub use self::redact::*;
 pub use self::regions::*;
 pub use self::rel::*;
 pub use self::repeat::*;
 pub use self::sides::*;
+pub use self::sideways::*;
 pub use self::size::*;
 pub use self::spacing::*;
 pub use self::stack::*;
+pub use self::stamp::*;
 pub use self::transform::*;
+pub use self::watermark::*;
 
+pub use self::inline::Exclusion;
 pub(crate) use self::inline::*;
 
 use comemo::{Track, Tracked, TrackedMut};
 
 use crate::diag::{bail, SourceResult};
-use crate::engine::{Engine, Route, Sink, Traced};
+use crate::engine::{Budget, Engine, Route, Sink, Traced};
 use crate::foundations::{category, Category, Content, Scope, StyleChain};
 use crate::introspection::{Introspector, Locator, LocatorLink};
 use crate::model::Document;
@@ -95,26 +115,38 @@ pub fn define(global: &mut Scope) {
     global.define_type::<Fr>();
     global.define_type::<Dir>();
     global.define_type::<Alignment>();
+    global.define_type::<Sheet>();
     global.define_elem::<PageElem>();
     global.define_elem::<PagebreakElem>();
+    global.define_elem::<WatermarkElem>();
     global.define_elem::<VElem>();
     global.define_elem::<HElem>();
     global.define_elem::<BoxElem>();
     global.define_elem::<BlockElem>();
+    global.define_elem::<KeepElem>();
     global.define_elem::<StackElem>();
     global.define_elem::<GridElem>();
     global.define_elem::<ColumnsElem>();
     global.define_elem::<ColbreakElem>();
+    global.define_elem::<ParallelElem>();
     global.define_elem::<PlaceElem>();
     global.define_elem::<FlushElem>();
+    global.define_elem::<AnchorElem>();
     global.define_elem::<AlignElem>();
     global.define_elem::<PadElem>();
     global.define_elem::<RepeatElem>();
+    global.define_elem::<LeaderElem>();
     global.define_elem::<MoveElem>();
     global.define_elem::<ScaleElem>();
+    global.define_elem::<FitElem>();
     global.define_elem::<RotateElem>();
+    global.define_elem::<SidewaysElem>();
     global.define_elem::<HideElem>();
+    global.define_elem::<RedactElem>();
+    global.define_elem::<StampElem>();
+    global.define_elem::<FrozenElem>();
     global.define_func::<measure>();
+    global.define_func::<measure_glyphs>();
     global.define_func::<layout>();
 }
 
@@ -131,28 +163,46 @@ impl Content {
         engine: &mut Engine,
         styles: StyleChain,
     ) -> SourceResult<Document> {
+        self.layout_document_pages(engine, styles, None)
+    }
+
+    /// Layout the content into a document, stopping once the given number of
+    /// pages is finished.
+    ///
+    /// If layout stopped early, the document is marked as
+    /// [truncated](crate::model::Incomplete::Truncated).
+    pub fn layout_document_pages(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        pages: Option<usize>,
+    ) -> SourceResult<Document> {
+        #[allow(clippy::too_many_arguments)]
         #[comemo::memoize]
         fn cached(
             content: &Content,
             world: Tracked<dyn World + '_>,
             introspector: Tracked<Introspector>,
             traced: Tracked<Traced>,
+            budget: Tracked<Budget>,
             sink: TrackedMut<Sink>,
             route: Tracked<Route>,
             styles: StyleChain,
+            pages: Option<usize>,
         ) -> SourceResult<Document> {
             let mut locator = Locator::root().split();
             let mut engine = Engine {
                 world,
                 introspector,
                 traced,
+                budget,
                 sink,
                 route: Route::extend(route).unnested(),
             };
             let arenas = Arenas::default();
             let (document, styles) =
                 realize_doc(&mut engine, locator.next(&()), &arenas, content, styles)?;
-            document.layout(&mut engine, locator.next(&()), styles)
+            document.layout(&mut engine, locator.next(&()), styles, pages)
         }
 
         cached(
@@ -160,9 +210,11 @@ impl Content {
             engine.world,
             engine.introspector,
             engine.traced,
+            engine.budget,
             TrackedMut::reborrow_mut(&mut engine.sink),
             engine.route.track(),
             styles,
+            pages,
         )
     }
 
@@ -181,6 +233,7 @@ impl Content {
             world: Tracked<dyn World + '_>,
             introspector: Tracked<Introspector>,
             traced: Tracked<Traced>,
+            budget: Tracked<Budget>,
             sink: TrackedMut<Sink>,
             route: Tracked<Route>,
             locator: Tracked<Locator>,
@@ -193,6 +246,7 @@ impl Content {
                 world,
                 introspector,
                 traced,
+                budget,
                 sink,
                 route: Route::extend(route),
             };
@@ -223,6 +277,7 @@ impl Content {
             engine.world,
             engine.introspector,
             engine.traced,
+            engine.budget,
             TrackedMut::reborrow_mut(&mut engine.sink),
             engine.route.track(),
             locator.track(),
@@ -1,30 +1,41 @@
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
 use std::num::NonZeroUsize;
 use std::ops::RangeInclusive;
-use std::ptr;
 use std::str::FromStr;
+use std::sync::Arc;
 
-use comemo::Track;
+use comemo::{Track, Tracked, TrackedMut};
+use smallvec::smallvec;
 
-use crate::diag::{bail, SourceResult};
-use crate::engine::Engine;
+use crate::diag::{bail, At, HintedStrResult, SourceResult};
+use crate::engine::{Budget, Engine, Route, Sink, Traced};
 use crate::foundations::{
-    cast, elem, AutoValue, Cast, Content, Context, Dict, Fold, Func, NativeElement,
-    Packed, Resolve, Smart, StyleChain, Value,
+    cast, dict, elem, func, scope, Args, Array, AutoValue, Cast, Construct, Content,
+    Context, Dict, Fold, Func, LocatableSelector, NativeElement, NoneValue, Packed,
+    Resolve, Show, Smart, Str, StyleChain, Value,
 };
 use crate::introspection::{
-    Counter, CounterDisplayElem, CounterKey, Locator, ManualPageCounter, SplitLocator,
+    Counter, CounterDisplayElem, CounterKey, Introspector, Locator, LocatorLink,
+    ManualPageCounter,
 };
+use crate::layout::debug::{self, debugging, DebugArea};
 use crate::layout::{
-    Abs, AlignElem, Alignment, Axes, ColumnsElem, Dir, Frame, HAlignment, Length,
-    OuterVAlignment, Point, Ratio, Regions, Rel, Sides, Size, SpecificAlignment,
-    VAlignment,
+    Abs, AlignElem, Alignment, Axes, ColumnsElem, Dir, Em, Fr, Frame, FrameItem,
+    GridCell, GridChild, GridElem, GridItem, HAlignment, Length, OuterVAlignment, Point,
+    Ratio, Regions, Rel, Sides, Size, Sizing, SpecificAlignment, TrackSizings, Transform,
+    VAlignment, WatermarkElem,
 };
-
-use crate::model::Numbering;
+use crate::model::{layout_margin_notes, Numbering};
+use crate::syntax::{Span, Spanned};
 use crate::text::TextElem;
 use crate::utils::{NonZeroExt, Numeric, Scalar};
-use crate::visualize::Paint;
+use crate::visualize::{
+    ellipse, Color, FixedStroke, Geometry, Paint, Path, Shape, Stroke,
+};
+use crate::World;
 
 /// Layouts its child onto one or multiple pages.
 ///
@@ -45,7 +56,7 @@ use crate::visualize::Paint;
 ///
 /// There you go, US friends!
 /// ```
-#[elem]
+#[elem(scope)]
 pub struct PageElem {
     /// A standard paper size to set width and height.
     #[external]
@@ -158,6 +169,31 @@ pub struct PageElem {
     /// margins.
     pub binding: Smart<Binding>,
 
+    /// Additional space at the bound edge of each page that is lost to the
+    /// binding.
+    ///
+    /// The offset is added to the margin at the edge where the page is bound,
+    /// which alternates between left and right for two-sided documents. The
+    /// page's content, header, and footer move away from the binding by this
+    /// amount, while margin notes in the inside margin keep their distance
+    /// to the content, so that none of them disappear in the binding.
+    ///
+    /// ```example
+    /// #set page(
+    ///   width: 100pt,
+    ///   height: 60pt,
+    ///   margin: (inside: 10pt, outside: 20pt),
+    ///   binding-offset: 10pt,
+    ///   header: (inside: [In], outside: [Out]),
+    /// )
+    ///
+    /// #rect(width: 100%)[Right]
+    /// #pagebreak()
+    /// #rect(width: 100%)[Left]
+    /// ```
+    #[resolve]
+    pub binding_offset: Length,
+
     /// How many columns the page has.
     ///
     /// If you need to insert columns into a page or other container, you can
@@ -178,6 +214,76 @@ pub struct PageElem {
     #[default(NonZeroUsize::ONE)]
     pub columns: NonZeroUsize,
 
+    /// Named frames on the page through which the page's content flows.
+    ///
+    /// Each frame is a rectangle given by its `x` and `y` position and its
+    /// `width` and `height`, all relative to the page's content area, i.e. the
+    /// page minus its margins. The content fills the frames in the order in
+    /// which they are listed and then continues in the frames of the next page.
+    /// This way, text can be threaded through the boxes of a newsletter or
+    /// magazine layout. When frames are given, the page's
+    /// [columns]($page.columns) are ignored.
+    ///
+    /// The frames' positions and heights are arbitrary, but they must all have
+    /// the same width, since the content is broken into lines only once.
+    ///
+    /// ```example:single
+    /// #set page(height: 120pt, frames: (
+    ///   lead: (x: 0%, y: 0%, width: 48%, height: 45%),
+    ///   rest: (x: 52%, y: 25%, width: 48%, height: 75%),
+    /// ))
+    /// #lorem(40)
+    /// ```
+    #[borrowed]
+    pub frames: Option<TextFrames>,
+
+    /// The distance between the lines of a baseline grid.
+    ///
+    /// When set, the baselines of the lines of text on the page are moved down
+    /// to the next multiple of this distance, measured from the top of the
+    /// page's content area. Since all columns and pages share the same grid,
+    /// their lines then register, also across the spine of a two-sided
+    /// document. The grid is typically set to the distance between two
+    /// consecutive baselines of body text.
+    ///
+    /// Only text in the main flow of the page is snapped to the grid. Text
+    /// within containers like blocks or figures keeps its natural position.
+    ///
+    /// ```example:single
+    /// #set page(columns: 2, height: 4cm, baseline-grid: 13pt)
+    /// #set par(leading: 5pt)
+    ///
+    /// = Results
+    /// The measurements confirm the
+    /// expected trend.
+    ///
+    /// #text(0.8em)[Smaller text also
+    /// lands on the grid.]
+    ///
+    /// #colbreak()
+    /// In the second column, every line
+    /// registers with a line in the
+    /// first column.
+    /// ```
+    #[resolve]
+    pub baseline_grid: Option<Length>,
+
+    /// Whether to warn about content that doesn't fit into the page.
+    ///
+    /// When enabled, Typst emits a warning for each line of text that
+    /// overflows its measure, each block that overflows its region because
+    /// it cannot be broken, and each unbreakable block that moves on to the
+    /// next region although more than half of the current one is still
+    /// empty. The warnings state the affected amounts, which helps to track
+    /// down layout problems in long documents.
+    ///
+    /// ```example
+    /// #set page(height: 80pt, diagnose: true)
+    /// #block(height: 70pt, width: 100%, fill: aqua)
+    /// ```
+    #[default(false)]
+    pub diagnose: bool,
+
     /// The page's background color.
     ///
     /// This instructs the printer to color the complete page with the given
@@ -193,6 +299,98 @@ pub struct PageElem {
     #[borrowed]
     pub fill: Option<Paint>,
 
+    /// A decorative border around the page.
+    ///
+    /// Takes a [stroke]($stroke) for all sides or a dictionary with the following
+    /// optional keys:
+    /// - `top`, `bottom`, `left`, `right`: The stroke of the given side.
+    /// - `inside`, `outside`: The stroke of the side closer to and further
+    ///   away from the spine, respectively. These keys are mutually exclusive
+    ///   with `left` and `right` and adapt to the page's side of the spread.
+    /// - `x`, `y`: The stroke of both horizontal or vertical sides.
+    /// - `stroke`: The stroke of all sides that are not otherwise specified.
+    /// - `inset`: How far the border is from the edges of the trimmed page.
+    ///   Defaults to `{auto}`, which centers it within the margins.
+    /// - `corner`: An ornament that is centered on each corner of the border.
+    ///   It is given for the top left corner and mirrored for the others.
+    ///
+    /// ```example
+    /// #set page(
+    ///   width: 100pt,
+    ///   height: 80pt,
+    ///   margin: 20pt,
+    ///   border: (
+    ///     stroke: 0.5pt,
+    ///     outside: 2pt,
+    ///     corner: square(size: 4pt, fill: black),
+    ///   ),
+    /// )
+    ///
+    /// Right page
+    /// #pagebreak()
+    /// Left page
+    /// ```
+    #[borrowed]
+    pub border: Option<PageBorder>,
+
+    /// How far the page extends beyond its trimmed edges.
+    ///
+    /// Pages that are printed to the edge are printed larger than their final
+    /// size and then trimmed, such that no white edge remains when the cut is
+    /// slightly off. The page's [fill]($page.fill), [background]($page.background),
+    /// [foreground]($page.foreground), and [spread]($page.spread) extend into
+    /// this bleed area, as does content that is placed beyond the page's
+    /// edges.
+    ///
+    /// The page's width, height, and margins still refer to the trimmed page.
+    /// In PDF export, the trimmed page and the bleed area are recorded as the
+    /// page's trim and bleed boxes.
+    ///
+    /// ```example
+    /// #set page(
+    ///   width: 80pt,
+    ///   height: 60pt,
+    ///   bleed: 6pt,
+    ///   fill: aqua,
+    ///   marks: (crop: true),
+    /// )
+    ///
+    /// Bleeding.
+    /// ```
+    #[parse({
+        let bleed: Option<Spanned<Length>> = args.named("bleed")?;
+        if let Some(Spanned { v, span }) = &bleed {
+            if v.abs < Abs::zero() || v.em < Em::zero() {
+                bail!(*span, "bleed must not be negative");
+            }
+        }
+        bleed.map(|bleed| bleed.v)
+    })]
+    #[resolve]
+    pub bleed: Length,
+
+    /// Which printer's marks to draw around the page.
+    ///
+    /// Takes a dictionary with the following optional keys:
+    /// - `crop`: Whether to draw crop marks at the corners of the page, which
+    ///   show where to trim it.
+    /// - `registration`: Whether to draw registration marks at the middle of
+    ///   each side, which show whether the colors were printed in register.
+    ///
+    /// The marks are drawn outside of the [bleed]($page.bleed) area, which
+    /// makes the page's frame larger by the space they take up.
+    ///
+    /// ```example
+    /// #set page(
+    ///   width: 80pt,
+    ///   height: 60pt,
+    ///   marks: (crop: true, registration: true),
+    /// )
+    ///
+    /// Ready to print.
+    /// ```
+    pub marks: PageMarks,
+
     /// How to [number]($numbering) the pages.
     ///
     /// If an explicit `footer` (or `header` for top-aligned numbering) is
@@ -210,6 +408,36 @@ pub struct PageElem {
     #[borrowed]
     pub numbering: Option<Numbering>,
 
+    /// The range of pages whose logical page numbers are counted together.
+    ///
+    /// Each named scope has its own page counter. When the scope changes from
+    /// one page to the next, the page counter switches to the new scope's
+    /// counter. It starts at 1 when a scope is entered for the first time and
+    /// continues where it left off when a scope is entered again. Pages
+    /// without a scope share one counter, too.
+    ///
+    /// This makes it easy to number the front matter of a book with roman
+    /// numerals and the main matter with arabic numerals starting at 1,
+    /// without updating the page counter manually. Page numbers in the
+    /// [outline], in [references]($ref), and in the page labels of PDF export
+    /// all follow the scopes.
+    ///
+    /// ```example
+    /// #set page(
+    ///   height: 60pt,
+    ///   numbering: "i",
+    ///   numbering-scope: "front",
+    /// )
+    /// Preface
+    ///
+    /// #set page(
+    ///   numbering: "1",
+    ///   numbering-scope: "main",
+    /// )
+    /// Introduction
+    /// ```
+    pub numbering_scope: Option<Str>,
+
     /// The alignment of the page numbering.
     ///
     /// If the vertical component is `top`, the numbering is placed into the
@@ -232,10 +460,20 @@ pub struct PageElem {
     /// The page's header. Fills the top margin of each page.
     ///
     /// - Content: Shows the content as the header.
+    /// - Dictionary: Shows content in slots at the start, center, and end of
+    ///   the header, see below.
     /// - `{auto}`: Shows the page number if a `numbering` is set and
     ///   `number-align` is `top`.
     /// - `{none}`: Suppresses the header.
     ///
+    /// A dictionary can either have the keys `left`, `center`, and `right` or
+    /// the keys `inside`, `center`, and `outside`, all of which are optional.
+    /// The `inside` slot is at the edge closer to the spine of a bound
+    /// document and the `outside` slot at the opposite edge, so they mirror
+    /// between right-hand and left-hand pages (see
+    /// [`page.side`]($page.side)). The center slot stays centered on the page
+    /// regardless of the width of the other slots.
+    ///
     /// ```example
     /// #set par(justify: true)
     /// #set page(
@@ -249,7 +487,22 @@ pub struct PageElem {
     ///
     /// #lorem(19)
     /// ```
+    ///
+    /// ```example
+    /// #set page(
+    ///   height: 80pt,
+    ///   header: (
+    ///     inside: smallcaps[Typst Academy],
+    ///     outside: context counter(page).display(),
+    ///   ),
+    /// )
+    ///
+    /// #lorem(10)
+    /// #pagebreak()
+    /// #lorem(10)
+    /// ```
     #[borrowed]
+    #[parse(parse_marginal(args, "header")?)]
     pub header: Smart<Option<Content>>,
 
     /// The amount the header is raised into the top margin.
@@ -260,6 +513,8 @@ pub struct PageElem {
     /// The page's footer. Fills the bottom margin of each page.
     ///
     /// - Content: Shows the content as the footer.
+    /// - Dictionary: Shows content in slots at the start, center, and end of
+    ///   the footer, like for the [`header`]($page.header).
     /// - `{auto}`: Shows the page number if a `numbering` is set and
     ///   `number-align` is `bottom`.
     /// - `{none}`: Suppresses the footer.
@@ -286,6 +541,7 @@ pub struct PageElem {
     /// #lorem(48)
     /// ```
     #[borrowed]
+    #[parse(parse_marginal(args, "footer")?)]
     pub footer: Smart<Option<Content>>,
 
     /// The amount the footer is lowered into the bottom margin.
@@ -312,6 +568,45 @@ pub struct PageElem {
     #[borrowed]
     pub background: Option<Content>,
 
+    /// Content that spans the page's spread.
+    ///
+    /// A spread consists of two facing pages. This content is laid out across
+    /// both of them, as if they were one page of twice the width, and then
+    /// split at the spine, such that each page shows its half. This way,
+    /// images and colored areas can span across the gutter.
+    ///
+    /// Given content is placed in the background, behind the page's
+    /// [`background`]($page.background). Alternatively, a dictionary can
+    /// contain any of the following keys:
+    /// - `background`: Placed behind the page's background.
+    /// - `foreground`: Placed in front of the page's
+    ///   [`foreground`]($page.foreground).
+    /// - `header`: Placed in the top margin like the page's
+    ///   [`header`]($page.header), but across the full width of the spread.
+    /// - `footer`: Placed in the bottom margin like the page's
+    ///   [`footer`]($page.footer), but across the full width of the spread.
+    ///
+    /// With left [binding]($page.binding), odd pages are right-hand pages and
+    /// even pages are left-hand pages. With right binding, it is the other way
+    /// around. The first page thus forms a spread on its own.
+    ///
+    /// ```example
+    /// #set page(
+    ///   width: 100pt,
+    ///   height: 80pt,
+    ///   spread: place(
+    ///     center + horizon,
+    ///     circle(radius: 30pt, fill: aqua),
+    ///   ),
+    /// )
+    ///
+    /// Left page
+    /// #pagebreak()
+    /// Right page
+    /// ```
+    #[borrowed]
+    pub spread: Option<PageSpread>,
+
     /// Content in the page's foreground.
     ///
     /// This content will overlay the page's body.
@@ -326,6 +621,24 @@ pub struct PageElem {
     #[borrowed]
     pub foreground: Option<Content>,
 
+    /// A watermark on the page.
+    ///
+    /// The watermark is centered on the page, regardless of its margins. It
+    /// is placed behind the page's body, but above its
+    /// [`background`]($page.background), unless it is configured to be
+    /// placed above the body. Plain content is turned into a
+    /// [watermark]($watermark) with the default settings.
+    ///
+    /// ```example
+    /// #set page(watermark: [CONFIDENTIAL])
+    ///
+    /// = Typst's secret plans
+    /// In the year 2023, we plan to take
+    /// over the world (of typesetting).
+    /// ```
+    #[borrowed]
+    pub watermark: Option<Content>,
+
     /// The contents of the page(s).
     ///
     /// Multiple pages will be created if the content does not fit on a single
@@ -334,10 +647,217 @@ pub struct PageElem {
     #[required]
     pub body: Content,
 
-    /// Whether the page should be aligned to an even or odd page.
+    /// Which kind of page the page should be aligned to.
     #[internal]
     #[synthesized]
-    pub clear_to: Option<Parity>,
+    pub clear_to: Option<PageTarget>,
+
+    /// A number of pages that the count of preceding pages should be a
+    /// multiple of.
+    #[internal]
+    #[synthesized]
+    pub clear_to_multiple_of: Option<NonZeroUsize>,
+
+    /// Whether the page is turned by [`page.single`]($page.single), swapping
+    /// its width and height once more.
+    #[ghost]
+    #[internal]
+    #[default(false)]
+    pub turned: bool,
+
+    /// How many pages the page run may still fill. The root flow stops after
+    /// it has filled this many pages.
+    #[ghost]
+    #[internal]
+    pub limit: Option<usize>,
+}
+
+#[scope]
+impl PageElem {
+    /// Puts content on pages of their own with a different page setup.
+    ///
+    /// Takes the same arguments as the [`page`]($page) function. The given
+    /// settings only apply to the pages produced by the body. All others are
+    /// taken over from the surrounding page setup, which is in effect again
+    /// on the pages after the body.
+    ///
+    /// If no size is given through `paper`, `width`, `height`, or `flipped`,
+    /// the pages are turned instead: They are landscape pages in a portrait
+    /// document and portrait pages in a landscape document. This is useful
+    /// for wide tables and figures.
+    ///
+    /// ```example
+    /// >>> #set page(width: 60pt, height: 80pt)
+    /// #set page(numbering: "1")
+    /// Portrait
+    ///
+    /// #page.single[Landscape]
+    ///
+    /// Portrait again
+    /// ```
+    #[func]
+    pub fn single(
+        /// The engine.
+        engine: &mut Engine,
+        /// The real arguments (the other arguments are just for the docs, this
+        /// function is a bit involved, so we parse the arguments manually).
+        args: &mut Args,
+        /// The page settings for the body's pages.
+        #[external]
+        #[variadic]
+        settings: Vec<Value>,
+        /// The contents of the pages.
+        #[external]
+        body: Content,
+    ) -> SourceResult<Content> {
+        let sized = args.items.iter().any(|arg| {
+            matches!(arg.name.as_deref(), Some("paper" | "width" | "height" | "flipped"))
+        });
+
+        let page = <Self as Construct>::construct(engine, args)?;
+        Ok(if sized { page } else { page.styled(Self::set_turned(true)) })
+    }
+
+    /// Whether the current page is a right-hand or a left-hand page.
+    ///
+    /// Returns `{"recto"}` for right-hand pages and `{"verso"}` for left-hand
+    /// pages. This depends on the physical page number and the page
+    /// [binding]($page.binding): With left binding, odd pages are recto
+    /// pages.
+    ///
+    /// This is useful for headers, footers, and other content that should
+    /// differ between the pages of a spread.
+    ///
+    /// ```example
+    /// #set page(
+    ///   height: 60pt,
+    ///   header: context {
+    ///     if page.side() == "recto" [
+    ///       #h(1fr) Chapter
+    ///     ] else [
+    ///       Book #h(1fr)
+    ///     ]
+    ///   },
+    /// )
+    /// #lorem(8)
+    /// ```
+    #[func(contextual)]
+    pub fn side(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
+    ) -> SourceResult<PageSide> {
+        let location = context.location().at(span)?;
+        let styles = context.styles().at(span)?;
+        let number = engine.introspector.page(location);
+        Ok(PageSide::of(Binding::resolve_in(styles), number))
+    }
+
+    /// Finds the elements on the current page.
+    ///
+    /// This works like [`query`], but only returns the elements on the same
+    /// physical page as the current [location]($here). Used in a header or
+    /// footer, this makes it possible to show the first and last entry on
+    /// each page, like the running heads of a dictionary.
+    ///
+    /// ```example
+    /// #set page(
+    ///   height: 90pt,
+    ///   header: context {
+    ///     let entries = page.query(heading)
+    ///     if entries != () [
+    ///       #entries.first().body
+    ///       #h(1fr)
+    ///       #entries.last().body
+    ///     ]
+    ///   },
+    /// )
+    ///
+    /// = Aardvark
+    /// = Abacus
+    /// = Abbey
+    /// = Abbot
+    /// ```
+    #[func(contextual)]
+    pub fn query(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
+        /// Which elements to find, like the `target` of [`query`].
+        target: LocatableSelector,
+    ) -> SourceResult<Array> {
+        let location = context.location().at(span)?;
+        let page = engine.introspector.page(location);
+        let elems = engine.introspector.query_page(&target.0, page);
+        Ok(elems.into_iter().map(Value::Content).collect())
+    }
+
+    /// Whether the current page contains an element matching the selector.
+    ///
+    /// This is a shorthand for checking whether
+    /// [`page.query`]($page.query) finds anything. It is useful to change the
+    /// header or footer on specific pages, for instance to hide the header on
+    /// pages where a chapter starts.
+    ///
+    /// ```example
+    /// #set page(
+    ///   height: 80pt,
+    ///   header: context {
+    ///     if not page.contains(heading.where(level: 1)) [
+    ///       _Chapter Title_ #h(1fr) Header
+    ///     ]
+    ///   },
+    /// )
+    ///
+    /// = Chapter
+    /// #lorem(12)
+    /// ```
+    #[func(contextual)]
+    pub fn contains(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
+        /// Which elements to look for.
+        target: LocatableSelector,
+    ) -> SourceResult<bool> {
+        let location = context.location().at(span)?;
+        let page = engine.introspector.page(location);
+        Ok(!engine.introspector.query_page(&target.0, page).is_empty())
+    }
+
+    /// Whether the current page has an even or odd physical page number.
+    ///
+    /// Returns `{"even"}` or `{"odd"}`. Unlike the page
+    /// [counter]($counter), this doesn't depend on the page numbering.
+    ///
+    /// ```example
+    /// #set page(height: 40pt)
+    /// This page is #context page.parity().
+    /// ```
+    #[func(contextual)]
+    pub fn parity(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
+    ) -> SourceResult<Parity> {
+        let location = context.location().at(span)?;
+        Ok(match engine.introspector.page(location).get() % 2 {
+            0 => Parity::Even,
+            _ => Parity::Odd,
+        })
+    }
 }
 
 impl Packed<PageElem> {
@@ -353,7 +873,7 @@ impl Packed<PageElem> {
         engine: &mut Engine,
         locator: Locator<'a>,
         styles: StyleChain<'a>,
-        extend_to: Option<Parity>,
+        extend_to: PageClear,
     ) -> SourceResult<PageLayout<'a>> {
         let mut locator = locator.split();
 
@@ -362,7 +882,7 @@ impl Packed<PageElem> {
         let width = self.width(styles).unwrap_or(Abs::inf());
         let height = self.height(styles).unwrap_or(Abs::inf());
         let mut size = Size::new(width, height);
-        if self.flipped(styles) {
+        if self.flipped(styles) != PageElem::turned_in(styles) {
             std::mem::swap(&mut size.x, &mut size.y);
         }
 
@@ -391,18 +911,31 @@ impl Packed<PageElem> {
                 .spanned(self.span());
         }
 
-        let area = size - margin.sum_by_axis();
+        let area =
+            size - margin.sum_by_axis() - Size::with_x(self.binding_offset(styles));
         let mut regions = Regions::repeat(area, area.map(Abs::is_finite));
         regions.root = true;
 
-        // Layout the child.
-        let frames = child
-            .layout(engine, locator.next(&self.span()), styles, regions)?
-            .into_frames();
+        // Layout the child, threading it through the page's frames if there
+        // are any.
+        let frames = match self.frames(styles) {
+            Some(frames) if !frames.0.is_empty() => layout_threaded(
+                engine,
+                locator.next(&self.span()),
+                styles,
+                self.body(),
+                area,
+                frames,
+                self.span(),
+            )?,
+            _ => child
+                .layout(engine, locator.next(&self.span()), styles, regions)?
+                .into_frames(),
+        };
 
         Ok(PageLayout {
             page: self,
-            locator,
+            locator: locator.next(&()),
             styles,
             extend_to,
             area,
@@ -413,13 +946,101 @@ impl Packed<PageElem> {
     }
 }
 
+/// Lays out the body of a page run through the page's linked frames, returning
+/// one frame per page.
+fn layout_threaded(
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    body: &Content,
+    area: Size,
+    frames: &TextFrames,
+    span: Span,
+) -> SourceResult<Vec<Frame>> {
+    let rects: Vec<(Point, Size)> = frames
+        .0
+        .iter()
+        .map(|(_, frame)| {
+            let pos = Point::new(
+                frame.x.resolve(styles).relative_to(area.x),
+                frame.y.resolve(styles).relative_to(area.y),
+            );
+            let size = Size::new(
+                frame.width.resolve(styles).relative_to(area.x),
+                frame.height.resolve(styles).relative_to(area.y),
+            );
+            (pos, size)
+        })
+        .collect();
+
+    let width = rects[0].1.x;
+    if rects.iter().any(|(_, size)| !size.x.approx_eq(width)) {
+        bail!(
+            span, "all frames of a page must have the same width";
+            hint: "the frames' positions and heights may differ",
+        );
+    }
+    if rects.iter().any(|(_, size)| !size.is_finite()) {
+        bail!(
+            span, "frames must have a finite size";
+            hint: "try giving the page a fixed width and height",
+        );
+    }
+
+    // The regions cycle through the frames. Since that can't be expressed with
+    // a single repeated last region, we provide the frames for as many pages
+    // as needed, growing the count until the content fits.
+    let count = rects.len();
+    let mut pages = 1;
+    let fragment = loop {
+        let heights: Vec<Abs> = rects
+            .iter()
+            .map(|(_, size)| size.y)
+            .cycle()
+            .take(count * pages)
+            .collect();
+        let regions = Regions {
+            size: Size::new(width, heights[0]),
+            full: heights[0],
+            backlog: &heights[1..],
+            last: Some(heights[0]),
+            expand: Axes::splat(true),
+            root: true,
+        };
+
+        let fragment = body.layout(engine, locator.relayout(), styles, regions)?;
+        if fragment.len() <= heights.len() {
+            break fragment;
+        }
+
+        pages = (pages + 1).max(fragment.len().div_ceil(count));
+    };
+
+    // Since the regions expand, the flow fills all of them. We drop the pages
+    // after the one in which the content ends.
+    let mut frames = fragment.into_frames();
+    let used = frames.iter().rposition(|frame| !frame.is_empty()).unwrap_or(0) + 1;
+    frames.truncate(used.div_ceil(count) * count);
+
+    Ok(frames
+        .chunks(count)
+        .map(|chunk| {
+            let mut page = Frame::hard(area);
+            for ((pos, _), frame) in rects.iter().zip(chunk) {
+                page.push_frame(*pos, frame.clone());
+            }
+            page
+        })
+        .collect())
+}
+
 /// A prepared layout of a page run that can be finalized with access to the
 /// page counter.
 pub struct PageLayout<'a> {
     page: &'a Packed<PageElem>,
-    locator: SplitLocator<'a>,
+    locator: Locator<'a>,
     styles: StyleChain<'a>,
-    extend_to: Option<Parity>,
+    extend_to: PageClear,
     area: Size,
     margin: Sides<Abs>,
     two_sided: bool,
@@ -428,38 +1049,123 @@ pub struct PageLayout<'a> {
 
 impl PageLayout<'_> {
     /// Finalize the layout with access to the next page counter.
+    ///
+    /// This is memoized over the laid out body of the page run and the state
+    /// of the page counter. After an edit, a page run whose body was reused
+    /// from the layout cache thus only has its marginals laid out again if it
+    /// now starts at a different page number or if introspections in the
+    /// marginals changed.
     #[typst_macros::time(name = "finalize page", span = self.page.span())]
     pub fn finalize(
+        self,
+        engine: &mut Engine,
+        page_counter: &mut ManualPageCounter,
+    ) -> SourceResult<Vec<Page>> {
+        #[allow(clippy::too_many_arguments)]
+        #[comemo::memoize]
+        fn cached(
+            page: &Packed<PageElem>,
+            world: Tracked<dyn World + '_>,
+            introspector: Tracked<Introspector>,
+            traced: Tracked<Traced>,
+            budget: Tracked<Budget>,
+            sink: TrackedMut<Sink>,
+            route: Tracked<Route>,
+            locator: Tracked<Locator>,
+            styles: StyleChain,
+            geometry: (PageClear, Size, Sides<Abs>, bool),
+            frames: Vec<Frame>,
+            mut page_counter: ManualPageCounter,
+        ) -> SourceResult<(Vec<Page>, ManualPageCounter)> {
+            let (extend_to, area, margin, two_sided) = geometry;
+            let link = LocatorLink::new(locator);
+            let layout = PageLayout {
+                page,
+                locator: Locator::link(&link),
+                styles,
+                extend_to,
+                area,
+                margin,
+                two_sided,
+                frames,
+            };
+            let mut engine = Engine {
+                world,
+                introspector,
+                traced,
+                budget,
+                sink,
+                route: Route::extend(route),
+            };
+            let pages = layout.finalize_impl(&mut engine, &mut page_counter)?;
+            Ok((pages, page_counter))
+        }
+
+        let (mut pages, counter) = cached(
+            self.page,
+            engine.world,
+            engine.introspector,
+            engine.traced,
+            engine.budget,
+            TrackedMut::reborrow_mut(&mut engine.sink),
+            engine.route.track(),
+            self.locator.track(),
+            self.styles,
+            (self.extend_to, self.area, self.margin, self.two_sided),
+            self.frames,
+            page_counter.clone(),
+        )?;
+
+        // Let the host post-process the finished pages.
+        for hook in &engine.world.library().page_hooks {
+            for page in &mut pages {
+                hook.apply(page);
+            }
+        }
+
+        *page_counter = counter;
+        Ok(pages)
+    }
+
+    /// Finalize the layout without memoization.
+    fn finalize_impl(
         mut self,
         engine: &mut Engine,
         page_counter: &mut ManualPageCounter,
     ) -> SourceResult<Vec<Page>> {
         let styles = self.styles;
+        let mut locator = self.locator.split();
 
-        // Align the child to the pagebreak's parity.
-        // Check for page count after adding the pending frames
-        if self.extend_to.is_some_and(|p| {
-            !p.matches(page_counter.physical().get() + self.frames.len())
-        }) {
-            // Insert empty page after the current pages.
-            let size = self.area.map(Abs::is_finite).select(self.area, Size::zero());
+        // Align the next page run to the pagebreak's target by inserting
+        // empty pages after the current pages.
+        let next = page_counter.physical().get() + self.frames.len();
+        let size = self.area.map(Abs::is_finite).select(self.area, Size::zero());
+        for _ in 0..self.extend_to.padding(next) {
             self.frames.push(Frame::hard(size));
         }
 
         let fill = self.page.fill(styles);
+        let border = self.page.border(styles);
+        let bleed = self.page.bleed(styles);
+        let marks = self.page.marks(styles);
         let foreground = self.page.foreground(styles);
         let background = self.page.background(styles);
+        let watermark = self.page.watermark(styles).clone().map(|content| {
+            if content.is::<WatermarkElem>() {
+                content
+            } else {
+                let span = content.span();
+                WatermarkElem::new(content).pack().spanned(span)
+            }
+        });
+        let spread = self.page.spread(styles).as_ref();
         let header_ascent = self.page.header_ascent(styles);
         let footer_descent = self.page.footer_descent(styles);
         let numbering = self.page.numbering(styles);
+        let numbering_scope = self.page.numbering_scope(styles);
         let number_align = self.page.number_align(styles);
-        let binding =
-            self.page
-                .binding(styles)
-                .unwrap_or_else(|| match TextElem::dir_in(styles) {
-                    Dir::LTR => Binding::Left,
-                    _ => Binding::Right,
-                });
+        let binding = Binding::resolve_in(styles);
+        let binding_offset = self.page.binding_offset(styles);
 
         // Construct the numbering (for header or footer).
         let numbering_marginal = numbering.as_ref().map(|numbering| {
@@ -499,71 +1205,115 @@ impl PageLayout<'_> {
             )
         };
 
-        // Post-process pages.
-        let mut pages = Vec::with_capacity(self.frames.len());
-        for mut frame in self.frames {
-            // The padded width of the page's content without margins.
-            let pw = frame.width();
+        // The border's corner ornament doesn't depend on the page and is thus
+        // laid out only once per page run.
+        let border = match border {
+            Some(border) => {
+                let ornament = match &border.corner {
+                    Some(corner) => {
+                        let pod =
+                            Regions::one(Size::splat(Abs::inf()), Axes::splat(false));
+                        let ornament = corner
+                            .layout(engine, locator.next(&corner.span()), styles, pod)?
+                            .into_frame();
+                        Some(ornament)
+                    }
+                    None => None,
+                };
+                Some((border, ornament))
+            }
+            None => None,
+        };
+
+        let marginals = Marginals {
+            contents: [
+                header,
+                footer,
+                spread.map_or(&None, |spread| &spread.header),
+                spread.map_or(&None, |spread| &spread.footer),
+                &watermark,
+                background,
+                foreground,
+                spread.map_or(&None, |spread| &spread.background),
+                spread.map_or(&None, |spread| &spread.foreground),
+            ],
+            header_ascent,
+            footer_descent,
+            fill: fill.as_ref(),
+            border,
+            bleed,
+            binding_offset,
+            marks,
+            styles,
+        };
 
+        // Determine the margins of all pages and pre-generate the locators for
+        // their marginals. Both only depend on the order of the pages, so that
+        // the pages can then be finished in parallel.
+        let first = page_counter.physical();
+        let mut work = Vec::with_capacity(self.frames.len());
+        for (i, frame) in self.frames.into_iter().enumerate() {
             // If two sided, left becomes inside and right becomes outside.
             // Thus, for left-bound pages, we want to swap on even pages and
             // for right-bound pages, we want to swap on odd pages.
             let mut margin = self.margin;
-            if self.two_sided && binding.swap(page_counter.physical()) {
+            let bound = binding.on_page(first.saturating_add(i), self.two_sided);
+            if bound != binding {
                 std::mem::swap(&mut margin.left, &mut margin.right);
             }
 
-            // Realize margins.
-            frame.set_size(frame.size() + margin.sum_by_axis());
-            frame.translate(Point::new(margin.left, margin.top));
-
-            // The page size with margins.
-            let size = frame.size();
-
-            // Realize overlays.
-            for marginal in [header, footer, background, foreground] {
-                let Some(content) = marginal.as_ref() else { continue };
-
-                let (pos, area, align);
-                if ptr::eq(marginal, header) {
-                    let ascent = header_ascent.relative_to(margin.top);
-                    pos = Point::with_x(margin.left);
-                    area = Size::new(pw, margin.top - ascent);
-                    align = Alignment::BOTTOM;
-                } else if ptr::eq(marginal, footer) {
-                    let descent = footer_descent.relative_to(margin.bottom);
-                    pos = Point::new(margin.left, size.y - margin.bottom + descent);
-                    area = Size::new(pw, margin.bottom - descent);
-                    align = Alignment::TOP;
-                } else {
-                    pos = Point::zero();
-                    area = size;
-                    align = HAlignment::Center + VAlignment::Horizon;
-                };
+            let locators = marginals.contents.map(|marginal| {
+                marginal.as_ref().map(|content| locator.next(&content.span()))
+            });
 
-                let pod = Regions::one(area, Axes::splat(true));
-                let sub = content
-                    .clone()
-                    .styled(AlignElem::set_alignment(align))
-                    .layout(engine, self.locator.next(&content.span()), styles, pod)?
-                    .into_frame();
+            let spine = binding.spine(first.saturating_add(i));
+            work.push((frame, margin, bound, spine, locators));
+        }
 
-                if ptr::eq(marginal, header) || ptr::eq(marginal, background) {
-                    frame.prepend_frame(pos, sub);
-                } else {
-                    frame.push_frame(pos, sub);
-                }
-            }
+        // Marginals whose layout doesn't depend on their context are laid out
+        // only once per page run and then reused, keyed by their index and
+        // area. Such marginals don't contain any introspection tags. The cache
+        // is filled by the first page and then shared by the others.
+        let mut cache = HashMap::new();
+        let mut pages = Vec::with_capacity(work.len());
+        let mut work = work.into_iter();
+        let mut frames = vec![];
+        if let Some((mut frame, margin, bound, spine, locators)) = work.next() {
+            let reusable = marginals
+                .realize(engine, &mut frame, margin, bound, spine, locators, &cache)?;
+            cache.extend(reusable);
+            frames.push((frame, spine));
+        }
 
-            if let Some(fill) = fill {
-                frame.fill(fill.clone());
+        let rest = engine.parallelize(
+            work,
+            |engine, (mut frame, margin, bound, spine, locators)| {
+                marginals
+                    .realize(engine, &mut frame, margin, bound, spine, locators, &cache)
+                    .map(|_| (frame, spine))
+            },
+        );
+
+        // Switch to the run's numbering scope. The resulting counter update
+        // goes first on the first page, so that all of the page sees it.
+        let mut scope_update = page_counter
+            .enter(numbering_scope, || locator.next_location(engine.introspector, 0));
+
+        // Count the pages, which requires the finished frames since counter
+        // updates may be part of the marginals.
+        for result in frames.into_iter().map(Ok).chain(rest) {
+            let (mut frame, spine) = result?;
+            if let Some(tag) = scope_update.take() {
+                frame.prepend(Point::zero(), FrameItem::Tag(tag));
             }
-
             page_counter.visit(engine, &frame)?;
             pages.push(Page {
                 frame,
                 numbering: numbering.clone(),
                 number: page_counter.logical(),
+                spine,
+                trim: bleed + marks.extent(),
+                bleed,
             });
 
             page_counter.step();
@@ -573,6 +1323,354 @@ impl PageLayout<'_> {
     }
 }
 
+/// The overlays of the pages of a page run.
+struct Marginals<'a> {
+    /// The header and footer of the page and the spread, the watermark, the
+    /// background and foreground of the page, and those of the spread, in this
+    /// order. Overlays that are prepended in this order end up stacked in
+    /// reverse.
+    contents: [&'a Option<Content>; 9],
+    header_ascent: Rel<Abs>,
+    footer_descent: Rel<Abs>,
+    fill: Option<&'a Paint>,
+    /// The page's border and the layout of its corner ornament.
+    border: Option<(&'a PageBorder, Option<Frame>)>,
+    bleed: Abs,
+    binding_offset: Abs,
+    marks: PageMarks,
+    styles: StyleChain<'a>,
+}
+
+impl Marginals<'_> {
+    const HEADER: usize = 0;
+    const FOOTER: usize = 1;
+    const SPREAD_HEADER: usize = 2;
+    const SPREAD_FOOTER: usize = 3;
+    const WATERMARK: usize = 4;
+    const BACKGROUND: usize = 5;
+    const SPREAD_BACKGROUND: usize = 7;
+    const SPREAD_FOREGROUND: usize = 8;
+
+    /// Realizes the margin notes, margins, and overlays of a page, given the
+    /// page's margins, the edge at which it is bound, the side of its spine,
+    /// and the locators for its marginals.
+    ///
+    /// Marginals in the cache are reused. Returns the newly laid out marginals
+    /// that can be reused for other pages.
+    #[allow(clippy::too_many_arguments)]
+    fn realize(
+        &self,
+        engine: &mut Engine,
+        frame: &mut Frame,
+        margin: Sides<Abs>,
+        bound: Binding,
+        spine: Binding,
+        locators: [Option<Locator>; 9],
+        cache: &HashMap<MarginalKey, Frame>,
+    ) -> SourceResult<Vec<(MarginalKey, Frame)>> {
+        // The padded width of the page's content without margins.
+        let pw = frame.width();
+
+        // Realize margin notes. They are placed relative to the content and
+        // thus keep clear of the binding offset.
+        layout_margin_notes(engine, frame, margin, bound, self.styles)?;
+
+        // Realize margins, widening the one at the bound edge by the binding
+        // offset.
+        let mut margin = margin;
+        match bound {
+            Binding::Left => margin.left += self.binding_offset,
+            Binding::Right => margin.right += self.binding_offset,
+        }
+
+        frame.set_size(frame.size() + margin.sum_by_axis());
+        frame.translate(Point::new(margin.left, margin.top));
+
+        // The page size with margins.
+        let size = frame.size();
+
+        // Realize the bleed. From here on, positions are relative to the
+        // bleed area instead of the trimmed page.
+        let bleed = self.bleed;
+        frame.set_size(size + Size::splat(2.0 * bleed));
+        frame.translate(Point::splat(bleed));
+        let full = frame.size();
+
+        // Realize the border. It is placed behind the page's body and above
+        // its background.
+        if let Some((border, ornament)) = &self.border {
+            let decor =
+                border.layout(margin, size, spine, ornament.as_ref(), self.styles);
+            frame.prepend_frame(Point::splat(bleed), decor);
+        }
+
+        // Realize overlays.
+        let mut reusable = vec![];
+        for (i, (marginal, locator)) in self.contents.iter().zip(locators).enumerate() {
+            let (Some(content), Some(locator)) = (marginal.as_ref(), locator) else {
+                continue;
+            };
+
+            // A page whose spine is on the left shows the right half of the
+            // spread.
+            let spread = matches!(
+                i,
+                Self::SPREAD_HEADER
+                    | Self::SPREAD_FOOTER
+                    | Self::SPREAD_BACKGROUND
+                    | Self::SPREAD_FOREGROUND
+            );
+            let shift = match spine {
+                Binding::Left if spread => -size.x,
+                _ => Abs::zero(),
+            };
+
+            let (pos, area, align);
+            if i == Self::HEADER || i == Self::SPREAD_HEADER {
+                let ascent = self.header_ascent.relative_to(margin.top);
+                let (x, width) = if spread {
+                    (bleed + shift, 2.0 * size.x)
+                } else {
+                    (bleed + margin.left, pw)
+                };
+                pos = Point::new(x, bleed);
+                area = Size::new(width, margin.top - ascent);
+                align = Alignment::BOTTOM;
+            } else if i == Self::FOOTER || i == Self::SPREAD_FOOTER {
+                let descent = self.footer_descent.relative_to(margin.bottom);
+                let (x, width) = if spread {
+                    (bleed + shift, 2.0 * size.x)
+                } else {
+                    (bleed + margin.left, pw)
+                };
+                pos = Point::new(x, bleed + size.y - margin.bottom + descent);
+                area = Size::new(width, margin.bottom - descent);
+                align = Alignment::TOP;
+            } else if spread {
+                // The spread is twice as wide as the page, plus the bleed on
+                // its outer edges.
+                pos = Point::with_x(shift);
+                area = Size::new(2.0 * (size.x + bleed), full.y);
+                align = HAlignment::Center + VAlignment::Horizon;
+            } else {
+                pos = Point::zero();
+                area = full;
+                align = HAlignment::Center + VAlignment::Horizon;
+            };
+
+            // Mirrored slots are arranged for the page's side and their
+            // layout can only be reused on pages with the same side.
+            let (content, side) = match content.to_packed::<MarginalSlotsElem>() {
+                Some(slots) if *slots.mirrored() => (slots.arrange(spine), Some(spine)),
+                _ => (content.clone(), None),
+            };
+
+            // Watermarks can be placed above the page's body.
+            let above = content
+                .to_packed::<WatermarkElem>()
+                .is_some_and(|watermark| watermark.above(self.styles));
+
+            let key = (i, area, side);
+            let sub = match cache.get(&key) {
+                Some(sub) => sub.clone(),
+                None => {
+                    let sub = match content.to_packed::<WatermarkElem>() {
+                        Some(watermark) if i == Self::WATERMARK => {
+                            watermark.layout(engine, locator, self.styles, size, bleed)?
+                        }
+                        _ => {
+                            let pod = Regions::one(area, Axes::splat(true));
+                            content
+                                .styled(AlignElem::set_alignment(align))
+                                .layout(engine, locator, self.styles, pod)?
+                                .into_frame()
+                        }
+                    };
+                    if !has_tags(&sub) {
+                        reusable.push((key, sub.clone()));
+                    }
+                    sub
+                }
+            };
+
+            // The spread's content is clipped to its half.
+            let (pos, sub) = if spread {
+                let mut half = Frame::hard(full);
+                half.push_frame(pos, sub);
+                half.clip(Path::rect(full));
+                (Point::zero(), half)
+            } else {
+                (pos, sub)
+            };
+
+            if i == Self::HEADER
+                || i == Self::BACKGROUND
+                || i == Self::SPREAD_BACKGROUND
+                || (i == Self::WATERMARK && !above)
+            {
+                frame.prepend_frame(pos, sub);
+            } else {
+                frame.push_frame(pos, sub);
+            }
+        }
+
+        if let Some(fill) = self.fill {
+            frame.fill(fill.clone());
+        }
+
+        if debugging(engine) {
+            self.debug(frame, margin, size);
+        }
+
+        self.marks.draw(frame, size, bleed);
+
+        Ok(reusable)
+    }
+
+    /// Draw the debug overlay of a page with the given margins and size
+    /// (without bleed): a coordinate grid starting at the page's top left
+    /// corner, the area within the margins, and the areas of the header and
+    /// footer.
+    fn debug(&self, frame: &mut Frame, margin: Sides<Abs>, size: Size) {
+        let bleed = self.bleed;
+        let origin = Point::splat(bleed);
+        debug::coordinates(frame, origin);
+
+        let inner = Size::new(
+            size.x - margin.left - margin.right,
+            size.y - margin.top - margin.bottom,
+        );
+        let ascent = self.header_ascent.relative_to(margin.top);
+        let descent = self.footer_descent.relative_to(margin.bottom);
+        let areas = [
+            (Point::new(margin.left, margin.top), inner, DebugArea::Margin),
+            (
+                Point::with_x(margin.left),
+                Size::new(inner.x, margin.top - ascent),
+                DebugArea::Marginal,
+            ),
+            (
+                Point::new(margin.left, size.y - margin.bottom + descent),
+                Size::new(inner.x, margin.bottom - descent),
+                DebugArea::Marginal,
+            ),
+        ];
+
+        for (pos, area, kind) in areas {
+            debug::outline(frame, origin + pos, area, kind);
+        }
+    }
+}
+
+/// Identifies a reusable marginal layout by the marginal's index, its area,
+/// and the side of the spine it was arranged for, if it is mirrored.
+type MarginalKey = (usize, Size, Option<Binding>);
+
+/// A header or footer with content in slots at its start, center, and end.
+///
+/// Created from a dictionary given as the page's `header` or `footer`.
+#[elem(Show)]
+pub struct MarginalSlotsElem {
+    /// The content at the left edge, or at the inside edge if mirrored.
+    #[required]
+    pub left: Option<Content>,
+
+    /// The content in the center.
+    #[required]
+    pub center: Option<Content>,
+
+    /// The content at the right edge, or at the outside edge if mirrored.
+    #[required]
+    pub right: Option<Content>,
+
+    /// Whether the slots are inside and outside instead of left and right.
+    #[required]
+    pub mirrored: bool,
+}
+
+impl Packed<MarginalSlotsElem> {
+    /// Arrange the slots in a row for a page whose spine is at the given
+    /// edge.
+    fn arrange(&self, spine: Binding) -> Content {
+        let (mut left, mut right) = (self.left(), self.right());
+        if *self.mirrored() && spine == Binding::Right {
+            std::mem::swap(&mut left, &mut right);
+        }
+
+        let slots = [
+            (left, HAlignment::Left),
+            (self.center(), HAlignment::Center),
+            (right, HAlignment::Right),
+        ];
+
+        let cells = slots
+            .into_iter()
+            .map(|(body, align)| {
+                let body = body.clone().unwrap_or_default();
+                let cell = GridCell::new(body).with_align(Smart::Custom(align.into()));
+                GridChild::Item(GridItem::Cell(Packed::new(cell).spanned(self.span())))
+            })
+            .collect();
+
+        GridElem::new(cells)
+            .with_columns(TrackSizings(smallvec![
+                Sizing::Fr(Fr::one()),
+                Sizing::Auto,
+                Sizing::Fr(Fr::one()),
+            ]))
+            .pack()
+            .spanned(self.span())
+    }
+}
+
+impl Show for Packed<MarginalSlotsElem> {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(self.arrange(Binding::Left))
+    }
+}
+
+/// The content of a header or footer, which can also be given as a dictionary
+/// of slots.
+struct MarginalContent(Content);
+
+cast! {
+    MarginalContent,
+    v: Content => Self(v),
+    mut dict: Dict => {
+        let mut take = |key| dict.take(key).ok().map(Value::cast).transpose();
+        let center = take("center")?;
+        let (left, right) = (take("left")?, take("right")?);
+        let (inside, outside) = (take("inside")?, take("outside")?);
+        dict.finish(&["left", "center", "right", "inside", "outside"])?;
+        let mirrored = inside.is_some() || outside.is_some();
+        if mirrored && (left.is_some() || right.is_some()) {
+            bail!("cannot combine `left` or `right` with `inside` or `outside`");
+        }
+        let (left, right) = if mirrored { (inside, outside) } else { (left, right) };
+        Self(MarginalSlotsElem::new(left, center, right, mirrored).pack())
+    },
+}
+
+/// Parses the header or footer argument.
+fn parse_marginal(
+    args: &mut Args,
+    name: &str,
+) -> SourceResult<Option<Smart<Option<Content>>>> {
+    Ok(args
+        .named::<Smart<Option<MarginalContent>>>(name)?
+        .map(|v| v.map(|v| v.map(|v| v.0))))
+}
+
+/// Whether a frame contains introspection tags, which indicate that its layout
+/// may depend on its context.
+fn has_tags(frame: &Frame) -> bool {
+    frame.items().any(|(_, item)| match item {
+        FrameItem::Group(group) => has_tags(&group.frame),
+        FrameItem::Tag(_) => true,
+        _ => false,
+    })
+}
+
 /// A finished page.
 #[derive(Debug, Clone)]
 pub struct Page {
@@ -583,6 +1681,52 @@ pub struct Page {
     /// The logical page number (controlled by `counter(page)` and may thus not
     /// match the physical number).
     pub number: usize,
+    /// The edge of the page that faces the spine when the pages are bound
+    /// into a book.
+    pub spine: Binding,
+    /// The distance from the edges of the frame to the edges of the trimmed
+    /// page. This is zero unless the page has a bleed or printer's marks.
+    pub trim: Abs,
+    /// How far the page's content extends beyond the edges of the trimmed
+    /// page.
+    pub bleed: Abs,
+}
+
+/// A hook that post-processes each finished page before export.
+///
+/// Hooks are registered with
+/// [`LibraryBuilder::with_page_hook`](crate::LibraryBuilder::with_page_hook)
+/// and run in the order of registration, right after a page's marginals,
+/// margin notes, and printer's marks were added. They can add items to the
+/// page's frame or transform it, for example, to stamp a watermark, draw a
+/// coordinate grid for debugging, or number the physical lines. Because they
+/// run during layout, tags and links they add take part in introspection.
+#[derive(Clone)]
+pub struct PageHook(Arc<dyn Fn(&mut Page) + Send + Sync>);
+
+impl PageHook {
+    /// Create a new hook from a function.
+    pub fn new(f: impl Fn(&mut Page) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    /// Apply the hook to a page.
+    pub fn apply(&self, page: &mut Page) {
+        (self.0)(page)
+    }
+}
+
+impl Debug for PageHook {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad("PageHook(..)")
+    }
+}
+
+impl Hash for PageHook {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Hooks are opaque, so they are distinguished by identity.
+        (Arc::as_ptr(&self.0) as *const () as usize).hash(state);
+    }
 }
 
 /// Specification of the page's margins.
@@ -695,6 +1839,400 @@ cast! {
     }
 }
 
+/// Content that spans both pages of a spread.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct PageSpread {
+    /// Content behind the page's background.
+    pub background: Option<Content>,
+    /// Content in front of the page's foreground.
+    pub foreground: Option<Content>,
+    /// Content in the top margins of the spread.
+    pub header: Option<Content>,
+    /// Content in the bottom margins of the spread.
+    pub footer: Option<Content>,
+}
+
+cast! {
+    PageSpread,
+    self => {
+        let Self { background, foreground, header, footer } = self;
+        if foreground.is_none() && header.is_none() && footer.is_none() {
+            return background.into_value();
+        }
+
+        let mut dict = Dict::new();
+        let parts = [
+            ("background", background),
+            ("foreground", foreground),
+            ("header", header),
+            ("footer", footer),
+        ];
+        for (key, content) in parts {
+            if let Some(content) = content {
+                dict.insert(key.into(), content.into_value());
+            }
+        }
+
+        Value::Dict(dict)
+    },
+    mut dict: Dict => {
+        let mut take = |key| dict.take(key).ok().map(Value::cast).transpose();
+        let spread = Self {
+            background: take("background")?,
+            foreground: take("foreground")?,
+            header: take("header")?,
+            footer: take("footer")?,
+        };
+        dict.finish(&["background", "foreground", "header", "footer"])?;
+        spread
+    },
+    v: Content => Self { background: Some(v), ..Default::default() },
+}
+
+/// A decorative border around a page.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct PageBorder {
+    /// The strokes of each side.
+    pub sides: Sides<Option<Stroke>>,
+    /// Whether `left` and `right` are `inside` and `outside` (and thus swapped
+    /// on pages whose spine is on the right).
+    pub two_sided: bool,
+    /// The distance of the border from the edges of the trimmed page.
+    pub inset: Smart<Length>,
+    /// An ornament for the top left corner, mirrored for the other corners.
+    pub corner: Option<Content>,
+}
+
+impl PageBorder {
+    /// Lay out the border of a trimmed page of the given size and margins,
+    /// given the side of its spine and the layout of the corner ornament.
+    fn layout(
+        &self,
+        margin: Sides<Abs>,
+        size: Size,
+        spine: Binding,
+        ornament: Option<&Frame>,
+        styles: StyleChain,
+    ) -> Frame {
+        let mut frame = Frame::soft(size);
+
+        // Without an explicit inset, the border is centered within the margins.
+        let inset = match self.inset {
+            Smart::Custom(inset) => Sides::splat(inset.resolve(styles)),
+            Smart::Auto => margin.map(|m| m / 2.0),
+        };
+
+        let (x0, y0) = (inset.left, inset.top);
+        let (x1, y1) = (size.x - inset.right, size.y - inset.bottom);
+
+        let mut sides = self.sides.clone();
+        if self.two_sided && spine == Binding::Right {
+            std::mem::swap(&mut sides.left, &mut sides.right);
+        }
+
+        let lines = [
+            (sides.top, Point::new(x0, y0), Point::new(x1, y0)),
+            (sides.right, Point::new(x1, y0), Point::new(x1, y1)),
+            (sides.bottom, Point::new(x0, y1), Point::new(x1, y1)),
+            (sides.left, Point::new(x0, y0), Point::new(x0, y1)),
+        ];
+
+        for (stroke, from, to) in lines {
+            let Some(stroke) = stroke else { continue };
+            let stroke = stroke.resolve(styles).unwrap_or_default();
+            let shape = Geometry::Line(to - from).stroked(stroke);
+            frame.push(from, FrameItem::Shape(shape, Span::detached()));
+        }
+
+        // The ornament is centered on each corner and mirrored such that it
+        // always faces the inside of the border.
+        if let Some(ornament) = ornament {
+            let half = ornament.size() / 2.0;
+            for (x, sx) in [(x0, 1.0), (x1, -1.0)] {
+                for (y, sy) in [(y0, 1.0), (y1, -1.0)] {
+                    let mut corner = ornament.clone();
+                    corner.transform(Transform::scale(Ratio::new(sx), Ratio::new(sy)));
+                    let pos = Point::new(x - sx * half.x, y - sy * half.y);
+                    frame.push_frame(pos, corner);
+                }
+            }
+        }
+
+        frame
+    }
+}
+
+cast! {
+    PageBorder,
+    self => {
+        let Sides { left, top, right, bottom } = self.sides;
+        let uniform = left == top && top == right && right == bottom;
+        let plain = !self.two_sided && self.inset.is_auto() && self.corner.is_none();
+        if uniform && plain && left.is_some() {
+            return left.into_value();
+        }
+
+        let mut dict = Dict::new();
+        let mut handle = |key: &str, stroke: Option<Stroke>| {
+            if let Some(stroke) = stroke {
+                dict.insert(key.into(), stroke.into_value());
+            }
+        };
+
+        if uniform && !self.two_sided {
+            handle("stroke", top);
+        } else {
+            if top == bottom {
+                handle("y", top);
+            } else {
+                handle("top", top);
+                handle("bottom", bottom);
+            }
+
+            if self.two_sided {
+                handle("inside", left);
+                handle("outside", right);
+            } else if left == right {
+                handle("x", left);
+            } else {
+                handle("left", left);
+                handle("right", right);
+            }
+        }
+
+        if let Smart::Custom(inset) = self.inset {
+            dict.insert("inset".into(), inset.into_value());
+        }
+        if let Some(corner) = self.corner {
+            dict.insert("corner".into(), corner.into_value());
+        }
+
+        Value::Dict(dict)
+    },
+    mut dict: Dict => {
+        let keys = [
+            "left", "top", "right", "bottom", "outside", "inside", "x", "y", "stroke",
+            "inset", "corner",
+        ];
+
+        // A dictionary without any of the border's keys describes the stroke
+        // of all sides.
+        if !dict.iter().any(|(key, _)| keys.contains(&key.as_str())) {
+            let stroke = Value::Dict(dict).cast()?;
+            return Ok(Self { sides: Sides::splat(Some(stroke)), ..Default::default() });
+        }
+
+        let mut take = |key| dict.take(key).ok().map(Value::cast).transpose();
+
+        let stroke = take("stroke")?;
+        let x = take("x")?.or(stroke.clone());
+        let y = take("y")?.or(stroke);
+        let top = take("top")?.or(y.clone());
+        let bottom = take("bottom")?.or(y);
+        let outside = take("outside")?;
+        let inside = take("inside")?;
+        let left = take("left")?;
+        let right = take("right")?;
+
+        let two_sided = outside.is_some() || inside.is_some();
+        if two_sided && (left.is_some() || right.is_some()) {
+            bail!("`inside` and `outside` are mutually exclusive with `left` and `right`");
+        }
+
+        let inset = dict.take("inset").ok().map(Value::cast).transpose()?;
+        let corner = dict.take("corner").ok().map(Value::cast).transpose()?;
+
+        dict.finish(&keys)?;
+
+        PageBorder {
+            sides: Sides {
+                left: inside.or(left).or(x.clone()),
+                top,
+                right: outside.or(right).or(x),
+                bottom,
+            },
+            two_sided,
+            inset: inset.unwrap_or(Smart::Auto),
+            corner,
+        }
+    },
+    v: Stroke => Self { sides: Sides::splat(Some(v)), ..Default::default() },
+}
+
+/// Named frames through which the content of a page flows, in order.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct TextFrames(pub Vec<(Str, TextFrame)>);
+
+cast! {
+    TextFrames,
+    self => self
+        .0
+        .into_iter()
+        .map(|(name, frame)| (name, frame.into_value()))
+        .collect::<Dict>()
+        .into_value(),
+    v: Dict => Self(
+        v.into_iter()
+            .map(|(name, value)| Ok((name, value.cast()?)))
+            .collect::<HintedStrResult<_>>()?,
+    ),
+}
+
+/// A rectangle on a page, relative to the page's content area.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct TextFrame {
+    /// The horizontal position of the frame.
+    pub x: Rel<Length>,
+    /// The vertical position of the frame.
+    pub y: Rel<Length>,
+    /// The width of the frame.
+    pub width: Rel<Length>,
+    /// The height of the frame.
+    pub height: Rel<Length>,
+}
+
+cast! {
+    TextFrame,
+    self => dict! {
+        "x" => self.x,
+        "y" => self.y,
+        "width" => self.width,
+        "height" => self.height,
+    }.into_value(),
+    mut dict: Dict => {
+        let frame = TextFrame {
+            x: dict.take("x")?.cast()?,
+            y: dict.take("y")?.cast()?,
+            width: dict.take("width")?.cast()?,
+            height: dict.take("height")?.cast()?,
+        };
+        dict.finish(&["x", "y", "width", "height"])?;
+        frame
+    }
+}
+
+/// A side of a spread.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum PageSide {
+    /// A right-hand page, whose spine is on the left.
+    Recto,
+    /// A left-hand page, whose spine is on the right.
+    Verso,
+}
+
+impl PageSide {
+    /// The side of the page with the given physical number.
+    pub(crate) fn of(binding: Binding, number: NonZeroUsize) -> Self {
+        match binding.spine(number) {
+            Binding::Left => Self::Recto,
+            Binding::Right => Self::Verso,
+        }
+    }
+}
+
+/// Which printer's marks to draw around a page.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct PageMarks {
+    /// Whether to draw crop marks.
+    pub crop: bool,
+    /// Whether to draw registration marks.
+    pub registration: bool,
+}
+
+impl PageMarks {
+    /// The distance between the bleed area and the marks.
+    fn offset() -> Abs {
+        Abs::pt(3.0)
+    }
+
+    /// The length of the marks.
+    fn length() -> Abs {
+        Abs::pt(18.0)
+    }
+
+    /// How far the marks extend beyond the bleed area.
+    pub fn extent(self) -> Abs {
+        if self.crop || self.registration {
+            Self::offset() + Self::length()
+        } else {
+            Abs::zero()
+        }
+    }
+
+    /// Draws the marks around a frame that contains a trimmed page of the
+    /// given size and its bleed, growing the frame to make room for them.
+    fn draw(self, frame: &mut Frame, size: Size, bleed: Abs) {
+        let extent = self.extent();
+        if extent.is_zero() {
+            return;
+        }
+
+        frame.set_size(frame.size() + Size::splat(2.0 * extent));
+        frame.translate(Point::splat(extent));
+
+        // The edges of the trimmed page and where the marks start and end,
+        // measured outwards from them.
+        let (x0, y0) = (extent + bleed, extent + bleed);
+        let (x1, y1) = (x0 + size.x, y0 + size.y);
+        let near = bleed + Self::offset();
+        let far = near + Self::length();
+
+        let stroke = FixedStroke::from_pair(Color::BLACK, Abs::pt(0.25));
+        let line = |from: Point, to: Point| {
+            (from, Geometry::Line(to - from).stroked(stroke.clone()))
+        };
+        let mut push = |(pos, shape): (Point, Shape)| {
+            frame.push(pos, FrameItem::Shape(shape, Span::detached()));
+        };
+
+        if self.crop {
+            for (x, dx) in [(x0, -1.0), (x1, 1.0)] {
+                for (y, dy) in [(y0, -1.0), (y1, 1.0)] {
+                    push(line(Point::new(x + dx * near, y), Point::new(x + dx * far, y)));
+                    push(line(Point::new(x, y + dy * near), Point::new(x, y + dy * far)));
+                }
+            }
+        }
+
+        if self.registration {
+            let radius = Self::length() / 4.0;
+            let half = Self::length() / 2.0;
+            let center = (near + far) / 2.0;
+            let (xm, ym) = ((x0 + x1) / 2.0, (y0 + y1) / 2.0);
+            for mid in [
+                Point::new(xm, y0 - center),
+                Point::new(xm, y1 + center),
+                Point::new(x0 - center, ym),
+                Point::new(x1 + center, ym),
+            ] {
+                let circle =
+                    ellipse(Size::splat(2.0 * radius), None, Some(stroke.clone()));
+                push((mid - Point::splat(radius), circle));
+                push(line(mid - Point::with_x(half), mid + Point::with_x(half)));
+                push(line(mid - Point::with_y(half), mid + Point::with_y(half)));
+            }
+        }
+    }
+}
+
+cast! {
+    PageMarks,
+    self => dict! {
+        "crop" => self.crop,
+        "registration" => self.registration,
+    }.into_value(),
+    _: NoneValue => Self::default(),
+    mut dict: Dict => {
+        let mut take = |key| dict.take(key).ok().map(Value::cast).transpose();
+        let marks = PageMarks {
+            crop: take("crop")?.unwrap_or(false),
+            registration: take("registration")?.unwrap_or(false),
+        };
+        dict.finish(&["crop", "registration"])?;
+        marks
+    }
+}
+
 /// Specification of the page's binding.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Binding {
@@ -706,7 +2244,7 @@ pub enum Binding {
 
 impl Binding {
     /// Whether to swap left and right margin for the page with this number.
-    fn swap(self, number: NonZeroUsize) -> bool {
+    pub(crate) fn swap(self, number: NonZeroUsize) -> bool {
         match self {
             // Left-bound must swap on even pages
             // (because it is correct on the first page).
@@ -716,6 +2254,36 @@ impl Binding {
             Self::Right => number.get() % 2 == 1,
         }
     }
+
+    /// The edge at which the page with this number is actually bound, which
+    /// alternates if the pages are two-sided.
+    pub(crate) fn on_page(self, number: NonZeroUsize, two_sided: bool) -> Self {
+        match self {
+            _ if !two_sided || !self.swap(number) => self,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+
+    /// The page binding in the style chain, which defaults to the start edge
+    /// of the text direction.
+    pub(crate) fn resolve_in(styles: StyleChain) -> Self {
+        PageElem::binding_in(styles).unwrap_or_else(|| match TextElem::dir_in(styles) {
+            Dir::LTR => Self::Left,
+            _ => Self::Right,
+        })
+    }
+
+    /// The edge of the page with this number that faces the spine.
+    ///
+    /// With left binding, the odd pages are right-hand pages, whose spine is
+    /// on the left. With right binding, they are left-hand pages.
+    pub(crate) fn spine(self, number: NonZeroUsize) -> Self {
+        match (self, number.get() % 2 == 1) {
+            (Self::Left, true) | (Self::Right, false) => Self::Left,
+            (Self::Left, false) | (Self::Right, true) => Self::Right,
+        }
+    }
 }
 
 cast! {
@@ -771,6 +2339,7 @@ cast! {
 /// A list of page ranges to be exported. The ranges are one-indexed.
 /// For example, `1..=3` indicates the first, second and third pages should be
 /// exported.
+#[derive(Debug, Clone)]
 pub struct PageRanges(Vec<PageRange>);
 
 pub type PageRange = RangeInclusive<Option<NonZeroUsize>>;
@@ -821,8 +2390,12 @@ pub struct PagebreakElem {
     #[default(false)]
     pub weak: bool,
 
-    /// If given, ensures that the next page will be an even/odd page, with an
-    /// empty page in between if necessary.
+    /// If given, ensures that the next page will be an even/odd or a
+    /// recto/verso page, with an empty page in between if necessary.
+    ///
+    /// Whether the next page is a recto (right-hand) or verso (left-hand)
+    /// page depends on the page [binding]($page.binding) of the next page,
+    /// see [`page.side`]($page.side).
     ///
     /// ```example
     /// #set page(height: 30pt)
@@ -831,7 +2404,91 @@ pub struct PagebreakElem {
     /// #pagebreak(to: "odd")
     /// Third.
     /// ```
-    pub to: Option<Parity>,
+    pub to: Option<PageTarget>,
+
+    /// If given, ensures that the number of pages before the next page is a
+    /// multiple of this number, with empty pages in between if necessary.
+    ///
+    /// This is useful to start chapters on a new signature when a book is
+    /// printed on folded sheets, each holding the same number of pages. If
+    /// combined with `to` and both can't be satisfied at once, `to` takes
+    /// precedence.
+    ///
+    /// ```example
+    /// #set page(height: 30pt)
+    ///
+    /// First.
+    /// #pagebreak(to-multiple-of: 4)
+    /// Fifth.
+    /// ```
+    pub to_multiple_of: Option<NonZeroUsize>,
+}
+
+/// Which kind of page a page break should clear to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PageTarget {
+    /// An even or odd page.
+    Parity(Parity),
+    /// A recto or verso page.
+    Side(PageSide),
+}
+
+impl PageTarget {
+    /// The parity of the targeted page under the given binding.
+    fn parity(self, binding: Binding) -> Parity {
+        match self {
+            Self::Parity(parity) => parity,
+            Self::Side(side) if PageSide::of(binding, NonZeroUsize::ONE) == side => {
+                Parity::Odd
+            }
+            Self::Side(_) => Parity::Even,
+        }
+    }
+}
+
+cast! {
+    PageTarget,
+    self => match self {
+        Self::Parity(parity) => parity.into_value(),
+        Self::Side(side) => side.into_value(),
+    },
+    parity: Parity => Self::Parity(parity),
+    side: PageSide => Self::Side(side),
+}
+
+/// The empty pages to insert after a page run such that the next one starts
+/// on the requested kind of page.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct PageClear {
+    /// The parity the number of the next page should have.
+    parity: Option<Parity>,
+    /// A number that the count of pages before the next page should be a
+    /// multiple of.
+    multiple_of: Option<NonZeroUsize>,
+}
+
+impl PageClear {
+    /// Determine how the page run before this page should be padded.
+    pub(crate) fn of(page: &Packed<PageElem>, styles: StyleChain) -> Self {
+        let binding = Binding::resolve_in(styles);
+        Self {
+            parity: page.clear_to().copied().flatten().map(|to| to.parity(binding)),
+            multiple_of: page.clear_to_multiple_of().copied().flatten(),
+        }
+    }
+
+    /// The number of empty pages to insert before the page with the given
+    /// number.
+    fn padding(self, next: usize) -> usize {
+        let mut count = 0;
+        if let Some(n) = self.multiple_of {
+            count += (n.get() - (next - 1) % n.get()) % n.get();
+        }
+        if self.parity.is_some_and(|parity| !parity.matches(next + count)) {
+            count += 1;
+        }
+        count
+    }
 }
 
 /// Whether something should be even or odd.
@@ -0,0 +1,458 @@
+use std::collections::HashSet;
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, Cast, Content, NativeElement, Packed, SequenceElem, Show, Str, StyleChain,
+    StyledElem,
+};
+use crate::introspection::Locator;
+use crate::layout::{
+    Abs, AnchorElem, Axes, BlockElem, Fragment, Frame, Length, Point, Ratio, Regions,
+    Rel, Size,
+};
+use crate::model::{ParElem, ParbreakElem};
+use crate::text::SpaceElem;
+use crate::utils::Numeric;
+
+/// Lays out two synchronized streams of content on facing pages or in
+/// alternating blocks.
+///
+/// Both streams are broken into chunks at their paragraph breaks or at
+/// [matching anchors]($parallel.sync). The corresponding chunks of the two
+/// streams start at the same vertical position: Whenever one chunk is longer
+/// than its counterpart, the next chunk of the shorter stream waits for the
+/// longer one. This is useful for critical editions and bilingual texts, where
+/// a text and its translation are printed next to each other.
+///
+/// On facing pages, the left stream is placed into the first region (typically
+/// the current page) and the right stream into the one after it, then the left
+/// stream continues on the third region, and so on. To get true facing pages,
+/// start the parallel text on a left page, for instance with
+/// [`{pagebreak(to: "even")}`]($pagebreak.to). If there are no further regions
+/// to break into, for instance in a [box] with a fixed height, the streams are
+/// placed side by side instead.
+///
+/// # Example
+/// ```example
+/// #set page(height: 80pt)
+/// #parallel[
+///   Gallia est omnis divisa in
+///   partes tres.
+///
+///   Hi omnes lingua inter se
+///   differunt.
+/// ][
+///   All Gaul is divided into
+///   three parts.
+///
+///   All these differ from each
+///   other in language.
+/// ]
+/// ```
+#[elem(Show)]
+pub struct ParallelElem {
+    /// How the two streams are arranged.
+    ///
+    /// - `{"facing"}`: The streams are placed on facing pages.
+    /// - `{"alternating"}`: The chunks of the streams alternate in a single
+    ///   flow, with each chunk of the left stream followed by its counterpart
+    ///   from the right stream. This is typical for interlinear translations.
+    ///
+    /// ```example
+    /// #parallel(mode: "alternating")[
+    ///   Veni, vidi, vici.
+    ///
+    ///   Alea iacta est.
+    /// ][
+    ///   #set text(style: "italic")
+    ///   I came, I saw, I conquered.
+    ///
+    ///   The die is cast.
+    /// ]
+    /// ```
+    #[default(ParallelMode::Facing)]
+    pub mode: ParallelMode,
+
+    /// Where the streams are broken into corresponding chunks.
+    ///
+    /// - `{"paragraphs"}`: At each paragraph break.
+    /// - `{"anchors"}`: At each [anchor] whose key occurs in both streams. The
+    ///   chunk then starts with the anchor. This way, several paragraphs of
+    ///   one stream can correspond to a single paragraph of the other one.
+    ///
+    /// ```example
+    /// #set page(height: 80pt)
+    /// #parallel(sync: "anchors")[
+    ///   Omnia mea mecum porto.
+    ///
+    ///   #anchor("b")
+    ///   Tempus fugit.
+    /// ][
+    ///   All that is mine I carry
+    ///   with me.
+    ///
+    ///   Or so they say.
+    ///
+    ///   #anchor("b")
+    ///   Time flies.
+    /// ]
+    /// ```
+    #[default(ParallelSync::Paragraphs)]
+    pub sync: ParallelSync,
+
+    /// The gap between the streams when they are placed side by side.
+    #[resolve]
+    #[default(Ratio::new(0.04).into())]
+    pub gutter: Rel<Length>,
+
+    /// The content of the left stream.
+    #[required]
+    pub left: Content,
+
+    /// The content of the right stream.
+    #[required]
+    pub right: Content,
+}
+
+impl Show for Packed<ParallelElem> {
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        if self.mode(styles) == ParallelMode::Alternating {
+            let [left, right] = split_streams(self, styles);
+            let mut seq = vec![];
+            for i in 0..left.len().max(right.len()) {
+                for chunk in [left.get(i), right.get(i)].into_iter().flatten() {
+                    seq.push(chunk.clone());
+                    seq.push(ParbreakElem::new().pack());
+                }
+            }
+            return Ok(Content::sequence(seq));
+        }
+
+        Ok(BlockElem::multi_layouter(self.clone(), layout_parallel)
+            .pack()
+            .spanned(self.span()))
+    }
+}
+
+/// How the streams of a [`ParallelElem`] are arranged.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum ParallelMode {
+    /// On facing pages.
+    Facing,
+    /// In alternating blocks.
+    Alternating,
+}
+
+/// Where the streams of a [`ParallelElem`] are broken into chunks.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum ParallelSync {
+    /// At paragraph breaks.
+    Paragraphs,
+    /// At anchors shared by both streams.
+    Anchors,
+}
+
+/// Layout the two streams.
+#[typst_macros::time(span = elem.span())]
+fn layout_parallel(
+    elem: &Packed<ParallelElem>,
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    regions: Regions,
+) -> SourceResult<Fragment> {
+    let streams = split_streams(elem, styles);
+    let spacing = ParElem::spacing_in(styles);
+
+    // Without follow-up regions, there are no facing pages and the streams
+    // are placed side by side.
+    let facing = !regions.backlog.is_empty() || regions.last.is_some();
+    let width = if facing || !regions.size.x.is_finite() {
+        regions.size.x
+    } else {
+        let gutter = elem.gutter(styles).relative_to(regions.base().x);
+        (regions.size.x - gutter) / 2.0
+    };
+
+    // The first region may already be partially filled. To align the streams
+    // on facing pages, all positions are measured from the top of the page.
+    let offset = if regions.full.is_finite() {
+        (regions.full - regions.size.y).max(Abs::zero())
+    } else {
+        Abs::zero()
+    };
+
+    let layouter = ParallelLayouter {
+        styles,
+        regions,
+        facing,
+        width,
+        offset,
+        heights: std::iter::once(offset + regions.size.y)
+            .chain(regions.backlog.iter().copied())
+            .collect(),
+    };
+
+    // The frames placed into each region, at positions measured from the top
+    // of the page.
+    let mut placed: Vec<Vec<(Point, Frame)>> = vec![];
+
+    // The region pair and vertical position at which the next chunks start.
+    let mut pair = 0;
+    let mut y = offset;
+    let mut locator = locator.split();
+
+    let count = streams.iter().map(Vec::len).max().unwrap_or(0);
+    for i in 0..count {
+        if y > layouter.top(pair) {
+            y += spacing;
+        }
+
+        let locators = [locator.next(&()), locator.next(&())];
+        let mut fragments =
+            layouter.layout_chunks(engine, &locators, &streams, i, pair, y)?;
+
+        // If a chunk doesn't fit into the rest of the current region at all,
+        // both chunks start at the top of the next one instead.
+        let moved = fragments
+            .iter()
+            .flatten()
+            .any(|fragment| fragment.len() > 1 && fragment.as_slice()[0].is_empty());
+        if moved && y > layouter.top(pair) {
+            pair += 1;
+            y = layouter.top(pair);
+            fragments =
+                layouter.layout_chunks(engine, &locators, &streams, i, pair, y)?;
+        }
+
+        // Place the chunks and continue after the one that ends last.
+        let (mut end_pair, mut end_y) = (pair, y);
+        for (side, fragment) in fragments.into_iter().enumerate() {
+            let Some(fragment) = fragment else { continue };
+            for (j, frame) in fragment.into_iter().enumerate() {
+                let top = if j == 0 { y } else { layouter.top(pair + j) };
+                let bottom = top + frame.height();
+                if (pair + j, bottom) > (end_pair, end_y) {
+                    (end_pair, end_y) = (pair + j, bottom);
+                }
+
+                let region = layouter.region(pair + j, side);
+                if placed.len() <= region {
+                    placed.resize_with(region + 1, Vec::new);
+                }
+                placed[region].push((Point::new(layouter.x(side), top), frame));
+            }
+        }
+
+        (pair, y) = (end_pair, end_y);
+    }
+
+    // Both sides of the last pair are emitted, even if one stays empty.
+    let total = layouter.region(pair, 1).max(placed.len().saturating_sub(1)) + 1;
+    placed.resize_with(total, Vec::new);
+
+    let mut finished = vec![];
+    for (region, items) in placed.into_iter().enumerate() {
+        let top = if region == 0 { offset } else { Abs::zero() };
+        let mut height =
+            if regions.expand.y { layouter.height(region) - top } else { Abs::zero() };
+        for (pos, frame) in &items {
+            height.set_max(pos.y - top + frame.height());
+        }
+
+        let mut output = Frame::hard(Size::new(regions.size.x, height));
+        for (pos, frame) in items {
+            output.push_frame(Point::new(pos.x, pos.y - top), frame);
+        }
+        finished.push(output);
+    }
+
+    Ok(Fragment::frames(finished))
+}
+
+/// Lays out the chunks of two streams into pairs of regions.
+struct ParallelLayouter<'a> {
+    /// The styles of the parallel element.
+    styles: StyleChain<'a>,
+    /// The regions the streams are laid out into.
+    regions: Regions<'a>,
+    /// Whether the streams are on facing pages rather than side by side.
+    facing: bool,
+    /// The width of each stream.
+    width: Abs,
+    /// How much of the first region is already filled.
+    offset: Abs,
+    /// The full heights of the known regions.
+    heights: Vec<Abs>,
+}
+
+impl ParallelLayouter<'_> {
+    /// The index of the region in which a side of a region pair is placed.
+    fn region(&self, pair: usize, side: usize) -> usize {
+        if self.facing {
+            2 * pair + side
+        } else {
+            pair
+        }
+    }
+
+    /// The horizontal position of a side within its region.
+    fn x(&self, side: usize) -> Abs {
+        if self.facing || side == 0 {
+            Abs::zero()
+        } else {
+            self.regions.size.x - self.width
+        }
+    }
+
+    /// The position at which content starts in a region pair.
+    fn top(&self, pair: usize) -> Abs {
+        if pair == 0 {
+            self.offset
+        } else {
+            Abs::zero()
+        }
+    }
+
+    /// The full height of a region.
+    fn height(&self, region: usize) -> Abs {
+        self.heights
+            .get(region)
+            .copied()
+            .or(self.regions.last)
+            .unwrap_or_else(|| self.heights.last().copied().unwrap_or_default())
+    }
+
+    /// Lays out the `i`-th chunk of both streams, starting at `y` in the
+    /// given region pair. Streams without such a chunk yield `None`.
+    fn layout_chunks(
+        &self,
+        engine: &mut Engine,
+        locators: &[Locator; 2],
+        streams: &[Vec<Content>; 2],
+        i: usize,
+        pair: usize,
+        y: Abs,
+    ) -> SourceResult<[Option<Fragment>; 2]> {
+        let mut fragments = [None, None];
+        for (side, stream) in streams.iter().enumerate() {
+            let Some(chunk) = stream.get(i) else { continue };
+
+            // The follow-up regions of this side, up to where the regions
+            // start repeating.
+            let backlog: Vec<Abs> = (pair + 1..)
+                .map(|pair| self.region(pair, side))
+                .take_while(|&region| region < self.heights.len())
+                .map(|region| self.height(region))
+                .collect();
+
+            let region = self.region(pair, side);
+            let full = self.height(region);
+            let pod = Regions {
+                size: Size::new(self.width, (full - y).max(Abs::zero())),
+                full,
+                backlog: &backlog,
+                last: self.regions.last,
+                expand: Axes::new(self.regions.expand.x, false),
+                root: false,
+            };
+
+            fragments[side] = Some(chunk.layout(
+                engine,
+                locators[side].relayout(),
+                self.styles,
+                pod,
+            )?);
+        }
+        Ok(fragments)
+    }
+}
+
+/// Breaks both streams of a parallel text into corresponding chunks.
+fn split_streams(elem: &Packed<ParallelElem>, styles: StyleChain) -> [Vec<Content>; 2] {
+    let streams = [elem.left(), elem.right()];
+    match elem.sync(styles) {
+        ParallelSync::Paragraphs => streams.map(|stream| {
+            split(stream, &|child| child.is::<ParbreakElem>().then_some(false))
+                .into_iter()
+                .filter(|chunk| !is_blank(chunk))
+                .collect()
+        }),
+        ParallelSync::Anchors => {
+            let [mut left, mut right] = [HashSet::new(), HashSet::new()];
+            anchor_keys(streams[0], &mut left);
+            anchor_keys(streams[1], &mut right);
+
+            // In alternating blocks, the anchors would try to align the
+            // chunks with each other, so they are removed.
+            let keep = elem.mode(styles) == ParallelMode::Facing;
+            streams.map(|stream| {
+                split(stream, &|child| {
+                    let anchor = child.to_packed::<AnchorElem>()?;
+                    let key = anchor.key();
+                    (left.contains(key) && right.contains(key)).then_some(keep)
+                })
+            })
+        }
+    }
+}
+
+/// Splits content at the children for which `breaks` returns `Some(_)`, also
+/// looking into styled content. Depending on the returned flag, the breaking
+/// child is kept at the start of the next chunk or dropped.
+fn split(content: &Content, breaks: &dyn Fn(&Content) -> Option<bool>) -> Vec<Content> {
+    if let Some(styled) = content.to_packed::<StyledElem>() {
+        return split(&styled.child, breaks)
+            .into_iter()
+            .map(|chunk| chunk.styled_with_map(styled.styles.clone()))
+            .collect();
+    }
+
+    let Some(sequence) = content.to_packed::<SequenceElem>() else {
+        return vec![content.clone()];
+    };
+
+    let mut parts = vec![vec![]];
+    for child in &sequence.children {
+        if let Some(keep) = breaks(child) {
+            parts.push(if keep { vec![child.clone()] } else { vec![] });
+        } else if child.is::<SequenceElem>() || child.is::<StyledElem>() {
+            let mut chunks = split(child, breaks).into_iter();
+            parts.last_mut().unwrap().extend(chunks.next());
+            parts.extend(chunks.map(|chunk| vec![chunk]));
+        } else {
+            parts.last_mut().unwrap().push(child.clone());
+        }
+    }
+
+    parts.into_iter().map(Content::sequence).collect()
+}
+
+/// Collects the keys of the anchors in content.
+fn anchor_keys(content: &Content, keys: &mut HashSet<Str>) {
+    if let Some(styled) = content.to_packed::<StyledElem>() {
+        return anchor_keys(&styled.child, keys);
+    }
+
+    content.sequence_recursive_for_each(&mut |child| {
+        if let Some(anchor) = child.to_packed::<AnchorElem>() {
+            keys.insert(anchor.key().clone());
+        } else if child.is::<StyledElem>() {
+            anchor_keys(child, keys);
+        }
+    });
+}
+
+/// Whether content consists only of spaces.
+fn is_blank(content: &Content) -> bool {
+    if let Some(styled) = content.to_packed::<StyledElem>() {
+        return is_blank(&styled.child);
+    }
+
+    let mut blank = true;
+    content.sequence_recursive_for_each(&mut |child| {
+        blank &= child.is::<SpaceElem>() || child.is::<StyledElem>() && is_blank(child);
+    });
+    blank
+}
@@ -1,9 +1,11 @@
 use crate::diag::{bail, At, Hint, SourceResult};
 use crate::engine::Engine;
-use crate::foundations::{elem, scope, Content, Packed, Smart, StyleChain, Unlabellable};
+use crate::foundations::{
+    elem, scope, Cast, Content, Packed, Smart, StyleChain, Unlabellable,
+};
 use crate::introspection::Locator;
 use crate::layout::{
-    Alignment, Axes, Em, Fragment, Length, Regions, Rel, Size, VAlignment,
+    Alignment, Axes, Em, Fragment, Length, PageSide, Regions, Rel, Size, VAlignment,
 };
 use crate::realize::{Behave, Behaviour};
 
@@ -66,11 +68,132 @@ pub struct PlaceElem {
     /// ```
     pub float: bool,
 
-    /// The amount of clearance the placed element has in a floating layout.
+    /// Relative to which containing scope the content is placed.
+    ///
+    /// - `{"column"}`: The content is placed within the current column.
+    /// - `{"parent"}`: The content spans all columns of a multi-column
+    ///   layout, like a page with multiple [`columns`]($page.columns). The
+    ///   text of the columns flows around the space reserved for it.
+    ///
+    /// Parent-scoped placement is only available for floating placement. A
+    /// float that spans all columns is placed at the top or bottom of the
+    /// columns in which it occurs, unless the first of these columns is
+    /// already complete. In that case, it is deferred to the next page.
+    ///
+    /// ```example
+    /// #set page(height: 150pt, columns: 2)
+    /// #place(
+    ///   top + center,
+    ///   float: true,
+    ///   scope: "parent",
+    ///   clearance: 8pt,
+    ///   text(1.4em)[*A Title Across Columns*],
+    /// )
+    /// #lorem(40)
+    /// ```
+    pub scope: PlacementScope,
+
+    /// The amount of clearance the placed element has in a floating layout or
+    /// to text which [wraps]($place.wrap) around it.
     #[default(Em::new(1.5).into())]
     #[resolve]
     pub clearance: Length,
 
+    /// Whether and how the text of subsequent paragraphs wraps around the
+    /// placed element.
+    ///
+    /// - `{none}`: The text is not affected by the placed element.
+    /// - `{auto}`: The text flows around the placed element's bounding box.
+    /// - An array of points: The text flows around the polygon with these
+    ///   vertices. Like the vertices of a [polygon]($polygon), the points are
+    ///   relative to the top-left corner of the placed element and relative
+    ///   lengths are resolved against its size.
+    ///
+    /// Text that wraps around a floating element is not displaced by it.
+    /// Instead, the float is placed at the current position in the flow or at
+    /// the bottom of the page. Wrapping is not supported for floats with
+    /// [parent scope]($place.scope).
+    ///
+    /// Since the width of each line depends on its vertical position, text that
+    /// wraps around a placed element is always broken into lines in simple
+    /// first-fit style, regardless of the paragraph's
+    /// [`linebreaks`]($par.linebreaks) setting.
+    ///
+    /// ```example
+    /// #set page(height: 140pt)
+    /// #place(
+    ///   top + right,
+    ///   wrap: auto,
+    ///   clearance: 6pt,
+    ///   rect(width: 40pt, height: 50pt),
+    /// )
+    /// #lorem(40)
+    /// ```
+    pub wrap: Option<Smart<Vec<Axes<Rel<Length>>>>>,
+
+    /// Where the placed content is read relative to the other content of
+    /// its container.
+    ///
+    /// - `{auto}`: The content is read where the `place` call appears in the
+    ///   document.
+    /// - `{"start"}`: The content is read before all other content of the
+    ///   region of its container it ends up in, for example the page or
+    ///   column.
+    /// - `{"end"}`: The content is read after all other content of that
+    ///   region, but before its footnotes.
+    ///
+    /// The reading order is what screen readers and text extraction (such as
+    /// copying text out of a PDF) follow. It is independent of where the
+    /// content is displayed: A sidebar at the top of the page can be read
+    /// after the main text and a title at the bottom before it. Elements in
+    /// the placed content are also counted and [queried]($query) in reading
+    /// order.
+    ///
+    /// Content that is read earlier is drawn earlier, so content that is read
+    /// at the start can be covered by other content of the region.
+    ///
+    /// ```example
+    /// #set page(height: 80pt)
+    /// #place(
+    ///   bottom + right,
+    ///   order: "start",
+    ///   text(1.4em)[*Title*],
+    /// )
+    /// The title is read first.
+    /// ```
+    pub order: Smart<PlacementOrder>,
+
+    /// On which pages the content is placed.
+    ///
+    /// - `{none}`: On all pages.
+    /// - `{"recto"}`: Only on right-hand pages.
+    /// - `{"verso"}`: Only on left-hand pages.
+    ///
+    /// Which pages are right-hand pages depends on the page
+    /// [binding]($page.binding), see [`page.side`]($page.side). On other
+    /// pages, the content is dropped and takes up no space, even if it
+    /// floats. This is most useful in a page's
+    /// [background]($page.background) or [foreground]($page.foreground),
+    /// which are laid out anew for every page.
+    ///
+    /// ```example
+    /// #let tab(side, alignment) = place(
+    ///   alignment + horizon,
+    ///   on: side,
+    ///   rect(width: 6pt, height: 20pt, fill: aqua),
+    /// )
+    ///
+    /// #set page(
+    ///   height: 60pt,
+    ///   background: tab("recto", right) + tab("verso", left),
+    /// )
+    ///
+    /// Recto
+    /// #pagebreak()
+    /// Verso
+    /// ```
+    pub on: Option<PageSide>,
+
     /// The horizontal displacement of the placed content.
     ///
     /// ```example
@@ -124,6 +247,12 @@ impl Packed<PlaceElem> {
             })
         {
             bail!(self.span(), "floating placement must be `auto`, `top`, or `bottom`");
+        } else if !float && self.scope(styles) == PlacementScope::Parent {
+            return Err(
+                "parent-scoped placement is only available for floating placement",
+            )
+            .hint("you can enable floating placement with `place(float: true, ..)`")
+            .at(self.span());
         } else if !float && alignment.is_auto() {
             return Err("automatic positioning is only available for floating placement")
                 .hint("you can enable floating placement with `place(float: true, ..)`")
@@ -141,6 +270,30 @@ impl Packed<PlaceElem> {
     }
 }
 
+/// Relative to which containing scope something is placed.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum PlacementScope {
+    /// Place into the current column.
+    #[default]
+    Column,
+    /// Place relative to the parent, letting the content span all columns.
+    Parent,
+}
+
+/// Marks the position of content that is only placed on one side of a spread,
+/// such that the page on which it ends up can be determined.
+#[elem]
+pub(crate) struct PlaceMarker {}
+
+/// Where placed content is read relative to the other content of its region.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum PlacementOrder {
+    /// Read before all other content of the region.
+    Start,
+    /// Read after all other content of the region.
+    End,
+}
+
 impl Behave for Packed<PlaceElem> {
     fn behaviour(&self) -> Behaviour {
         Behaviour::Ignorant
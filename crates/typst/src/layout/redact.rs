@@ -0,0 +1,52 @@
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, Packed, Show, StyleChain};
+use crate::visualize::{Color, Paint};
+
+/// Redacts content, removing it from the output.
+///
+/// Like [hidden]($hide) content, redacted content still takes up its space.
+/// In its place, boxes of the same size are drawn: one for each run of text,
+/// image, or shape. The redacted content is not part of the output at all, so
+/// it can't be recovered by copying text from an exported PDF or by
+/// extracting it with other tools. Links in redacted content are removed,
+/// too.
+///
+/// Only the visible content is redacted. Content that also appears elsewhere
+/// in the document, like a redacted heading in the outline or in a PDF's
+/// bookmarks, must be redacted there as well.
+///
+/// # Example
+/// ```example
+/// The informant's name is
+/// #redact[John Doe] and they
+/// live in #redact[Springfield].
+///
+/// #redact(fill: gray)[
+///   This paragraph was redacted
+///   in its entirety.
+/// ]
+/// ```
+#[elem(Show)]
+pub struct RedactElem {
+    /// How to fill the boxes that replace the redacted content.
+    #[default(Color::BLACK.into())]
+    pub fill: Paint,
+
+    /// The content to redact.
+    #[required]
+    pub body: Content,
+
+    /// This style is set on the content contained in the `redact` element.
+    #[internal]
+    #[ghost]
+    pub redaction: Option<Paint>,
+}
+
+impl Show for Packed<RedactElem> {
+    #[typst_macros::time(name = "redact", span = self.span())]
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let fill = self.fill(styles);
+        Ok(self.body().clone().styled(RedactElem::set_redaction(Some(fill))))
+    }
+}
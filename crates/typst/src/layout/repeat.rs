@@ -7,6 +7,7 @@ use crate::introspection::Locator;
 use crate::layout::{
     Abs, AlignElem, Axes, BlockElem, Fragment, Frame, Point, Regions, Size,
 };
+use crate::text::TextElem;
 use crate::utils::Numeric;
 
 /// Repeats content to the available space.
@@ -19,6 +20,9 @@ use crate::utils::Numeric;
 /// Errors if there no bounds on the available space, as it would create
 /// infinite content.
 ///
+/// If the instances should line up across consecutive lines, use a
+/// [`leader`] instead.
+///
 /// # Example
 /// ```example
 /// Sign on the dotted line:
@@ -90,3 +94,101 @@ fn layout_repeat(
 
     Ok(Fragment::frame(frame))
 }
+
+/// Fills the remaining space in a line with repeated content whose instances
+/// line up across lines.
+///
+/// In contrast to a [`repeat`] in a fractionally sized [`box`], which spreads
+/// its instances evenly over the available space, a leader places its
+/// instances on a fixed grid that starts at the left edge of the paragraph.
+/// Leaders in consecutive lines thus produce straight columns of dots, like
+/// the leaders known from TeX. This is useful for tables of contents, menus,
+/// and forms.
+///
+/// A leader always takes up one fraction (`{1fr}`) of the line's remaining
+/// space. Since the grid is anchored to the paragraph, the instances only line
+/// up between lines that share the same paragraph origin.
+///
+/// # Example
+/// ```example
+/// #set text(10pt)
+/// Introduction #leader() 1 \
+/// Approach #leader() 14 \
+/// Related Work #leader() 159
+/// ```
+#[elem]
+pub struct LeaderElem {
+    /// The content to repeat.
+    ///
+    /// ```example
+    /// Price #leader(fill: [-]) 5€
+    /// ```
+    #[default(TextElem::packed("."))]
+    pub fill: Content,
+
+    /// Whether the instances should be aligned to a grid that is shared by all
+    /// lines of the paragraph.
+    ///
+    /// If this is `{false}`, the instances are distributed over the available
+    /// space like with [`repeat`].
+    #[default(true)]
+    pub align: bool,
+}
+
+impl Packed<LeaderElem> {
+    /// Layout the leader as part of a paragraph line.
+    ///
+    /// The `offset` is the horizontal position of the leader within its line,
+    /// which is used to align the instances to the paragraph's grid.
+    #[typst_macros::time(name = "leader", span = self.span())]
+    pub fn layout(
+        &self,
+        engine: &mut Engine,
+        locator: Locator,
+        styles: StyleChain,
+        offset: Abs,
+        region: Size,
+    ) -> SourceResult<Frame> {
+        let pod = Regions::one(region, Axes::new(false, false));
+        let piece = self.fill(styles).layout(engine, locator, styles, pod)?.into_frame();
+
+        let width = piece.width();
+        let mut frame = Frame::soft(Size::new(region.x, piece.height()));
+        if piece.has_baseline() {
+            frame.set_baseline(piece.baseline());
+        }
+
+        if width <= Abs::zero() || !region.x.is_finite() {
+            return Ok(frame);
+        }
+
+        // Determine where the first instance goes and how much space to put
+        // between consecutive instances.
+        let (mut x, apart) = if self.align(styles) {
+            // Snap the first instance to the next multiple of the piece width,
+            // measured from the start of the line.
+            ((offset / width).ceil() * width - offset, Abs::zero())
+        } else {
+            let count = (region.x / width).floor();
+            let remaining = region.x % width;
+            let apart = if count > 1.0 { remaining / (count - 1.0) } else { Abs::zero() };
+            (Abs::zero(), apart)
+        };
+
+        // Determine up front how many instances fit into the region, with the
+        // same limit as for `repeat`.
+        let count = if region.x.fits(x + width) {
+            ((region.x - x - width) / (width + apart) + 1e-6).floor() as usize + 1
+        } else {
+            0
+        }
+        .min(1000);
+
+        for _ in 0..count {
+            frame.push_frame(Point::with_x(x), piece.clone());
+            x += width + apart;
+        }
+
+        Ok(frame)
+    }
+}
@@ -0,0 +1,155 @@
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, NativeElement, Packed, Show, Smart, StyleChain};
+use crate::introspection::{Locatable, Location, Locator};
+use crate::layout::{
+    Abs, Angle, Axes, Binding, BlockElem, Dir, Fragment, Frame, FrameItem, PageElem,
+    Regions, Size, Transform,
+};
+use crate::text::TextElem;
+use crate::utils::Numeric;
+
+/// Turns content sideways to make use of a page's full height.
+///
+/// Wide figures and tables often don't fit onto a portrait page. The
+/// `sideways` function lays its content out as if the page were in landscape
+/// orientation and then rotates it by a quarter turn. In contrast to setting
+/// up a landscape page, the page's header, footer, and number stay in place and
+/// upright.
+///
+/// If the current page already has content on it, the sideways content starts
+/// on the next page. It always takes up the remaining height of its page, so
+/// that content after it continues on a new page.
+///
+/// # Example
+/// ```example
+/// >>> #set page(width: 120pt, height: 180pt)
+/// #set page(numbering: "1")
+///
+/// #sideways(figure(
+///   table(
+///     columns: 4,
+///     [Year], [North], [South], [West],
+///     [2022], [120], [96], [104],
+///     [2023], [131], [101], [110],
+///   ),
+///   caption: [Sales by region],
+/// ))
+/// ```
+#[elem(Show)]
+pub struct SidewaysElem {
+    /// Whether to turn the content clockwise.
+    ///
+    /// - `{auto}`: Turns the content such that its top faces the page's
+    ///   [binding]($page.binding). If the page has [two-sided]($page.margin)
+    ///   margins, the direction thus alternates between left and right pages.
+    /// - `{true}`: Turns the content clockwise, so that its top faces the right
+    ///   edge of the page.
+    /// - `{false}`: Turns the content counterclockwise, so that its top faces
+    ///   the left edge of the page.
+    ///
+    /// ```example
+    /// >>> #set page(width: 120pt, height: 120pt)
+    /// #sideways(clockwise: true)[
+    ///   This text reads from top
+    ///   to bottom.
+    /// ]
+    /// ```
+    pub clockwise: Smart<bool>,
+
+    /// The content to turn sideways.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for Packed<SidewaysElem> {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(BlockElem::multi_layouter(self.clone(), layout_sideways)
+            .pack()
+            .spanned(self.span()))
+    }
+}
+
+/// Layout the sideways content.
+#[typst_macros::time(span = elem.span())]
+fn layout_sideways(
+    elem: &Packed<SidewaysElem>,
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    mut regions: Regions,
+) -> SourceResult<Fragment> {
+    let mut frames = vec![];
+
+    // Move on to the next region if there already is content in this one, so
+    // that the rotated content can use the region's full height.
+    if regions.size.y < regions.full && !regions.in_last() {
+        frames.push(Frame::soft(Size::zero()));
+        regions.next();
+    }
+
+    // The region's height becomes the width of the rotated content. If it is
+    // unbounded, the content takes up its natural width instead.
+    let Size { x: width, y: height } = regions.size;
+    let expand = Axes::new(height.is_finite(), width.is_finite());
+    let pod = Regions::one(Size::new(height, width), expand);
+    let body = SidewaysMarker::new().pack().spanned(elem.span()) + elem.body().clone();
+    let mut frame = body.layout(engine, locator, styles, pod)?.into_frame();
+
+    let clockwise = elem.clockwise(styles).unwrap_or_else(|| {
+        let binding = PageElem::binding_in(styles).unwrap_or_else(|| {
+            match TextElem::dir_in(styles) {
+                Dir::LTR => Binding::Left,
+                _ => Binding::Right,
+            }
+        });
+
+        // On two-sided pages, the binding alternates between the page's left
+        // and right edge.
+        let two_sided = PageElem::margin_in(styles).two_sided.unwrap_or(false);
+        let swap = two_sided
+            && find_marker(&frame)
+                .is_some_and(|loc| binding.swap(engine.introspector.page(loc)));
+        (binding == Binding::Right) != swap
+    });
+
+    // Rotate by a quarter turn around the top-left corner and move the
+    // content back into the frame.
+    let size = frame.size();
+    let ts = if clockwise {
+        Transform::translate(size.y, Abs::zero())
+            .pre_concat(Transform::rotate(Angle::deg(90.0)))
+    } else {
+        Transform::translate(Abs::zero(), size.x)
+            .pre_concat(Transform::rotate(Angle::deg(-90.0)))
+    };
+
+    frame.transform(ts);
+    frame.set_size(Size::new(size.y, size.x));
+    frames.push(frame);
+
+    Ok(Fragment::frames(frames))
+}
+
+/// Marks the start of sideways content.
+///
+/// The page of the sideways content is determined from the position of this
+/// marker instead of the [`SidewaysElem`] itself, since the latter's position
+/// may still be on the previous page if the content moved on to the next one.
+#[elem(Locatable, Show)]
+struct SidewaysMarker {}
+
+impl Show for Packed<SidewaysMarker> {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
+/// Finds the location of the sideways marker in a frame.
+fn find_marker(frame: &Frame) -> Option<Location> {
+    frame.items().find_map(|(_, item)| match item {
+        FrameItem::Group(group) => find_marker(&group.frame),
+        FrameItem::Tag(tag) if tag.elem.is::<SidewaysMarker>() => tag.elem.location(),
+        _ => None,
+    })
+}
@@ -0,0 +1,100 @@
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, NativeElement, Packed, Show, StyleChain};
+use crate::introspection::Locator;
+use crate::layout::{Axes, Frame, FrameItem, InlineElem, InlineItem, Regions, Size};
+
+/// Lays out content once and reuses the result wherever it is used.
+///
+/// Some content is expensive to lay out, but repeated many times in the same
+/// form, like a complex logo in the header of every page. When wrapped in
+/// `stamp`, such content is laid out only once and the result is stamped
+/// onto each place where it is used. To reuse a stamp, store it in a variable
+/// or return it from a function.
+///
+/// A stamp is laid out again if the styles it is used with differ, for
+/// instance because a different text size applies. Stamps created by a
+/// function with different arguments are laid out separately, too.
+///
+/// Since its layout doesn't depend on where it is used, the content of a
+/// stamp is not visible to introspection: It can't be [queried]($query) or
+/// [located]($locate) and [context]($context) expressions within it don't know
+/// their location. For this reason, a stamp shouldn't contain a page number.
+///
+/// # Example
+/// ```example
+/// #let logo = stamp(box(
+///   stack(
+///     dir: ltr,
+///     spacing: 2pt,
+///     ..range(6).map(i => circle(
+///       radius: 3pt,
+///       fill: blue.lighten(i * 15%),
+///     )),
+///   ),
+/// ))
+///
+/// #set page(header: [
+///   Quarterly Report #h(1fr) #logo
+/// ])
+///
+/// Our company's logo is in the
+/// header of every page.
+/// ```
+#[elem(Show)]
+pub struct StampElem {
+    /// The content to lay out once.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for Packed<StampElem> {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(InlineElem::layouter(self.clone(), layout_stamp)
+            .pack()
+            .spanned(self.span()))
+    }
+}
+
+/// Layout the stamped content.
+#[typst_macros::time(span = elem.span())]
+fn layout_stamp(
+    elem: &Packed<StampElem>,
+    engine: &mut Engine,
+    _: Locator,
+    styles: StyleChain,
+    region: Size,
+) -> SourceResult<Vec<InlineItem>> {
+    // Laying out with the same locator in each place makes the layout
+    // independent of the stamp's position, so that it is reused from the
+    // layout cache.
+    let pod = Regions::one(region, Axes::splat(false));
+    let frame = elem.body().layout(engine, Locator::root(), styles, pod)?.into_frame();
+    Ok(vec![InlineItem::Frame(strip_tags(&frame))])
+}
+
+/// Removes all introspection tags from a frame.
+///
+/// The elements within the stamp would otherwise have the same location in
+/// each place the stamp is used at.
+#[comemo::memoize]
+pub(crate) fn strip_tags(frame: &Frame) -> Frame {
+    let mut output = Frame::new(frame.size(), frame.kind());
+    if frame.has_baseline() {
+        output.set_baseline(frame.baseline());
+    }
+
+    for (pos, item) in frame.items() {
+        match item {
+            FrameItem::Tag(_) => {}
+            FrameItem::Group(group) => {
+                let mut group = group.clone();
+                group.frame = strip_tags(&group.frame);
+                output.push(*pos, FrameItem::Group(group));
+            }
+            _ => output.push(*pos, item.clone()),
+        }
+    }
+
+    output
+}
@@ -0,0 +1,116 @@
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, NativeElement, Packed, Smart, StyleChain};
+use crate::introspection::Locator;
+use crate::layout::{
+    strip_tags, Abs, Angle, Axes, Em, Frame, Length, Point, Regions, RotateElem, Size,
+};
+use crate::text::TextElem;
+use crate::visualize::{Color, Paint, Path};
+
+/// A watermark on each page.
+///
+/// A watermark is rotated content that is centered on the page, regardless of
+/// the page's margins, and optionally repeated across all of it. It is used as
+/// the page's [`watermark`]($page.watermark). Content that is set as the
+/// page's watermark directly is treated like a watermark with the default
+/// settings.
+///
+/// The text of the watermark is filled with a semi-transparent paint, so that
+/// the page's body stays readable.
+///
+/// # Example
+/// ```example
+/// #set page(watermark: watermark(
+///   tile: true,
+///   text(14pt)[DRAFT],
+/// ))
+///
+/// = Typst's secret plans
+/// In the year 2023, we plan to take
+/// over the world (of typesetting).
+/// ```
+#[elem]
+pub struct WatermarkElem {
+    /// The angle by which the watermark is rotated.
+    ///
+    /// If set to `{auto}`, the watermark runs along the diagonal from the
+    /// page's bottom left to its top right corner.
+    pub angle: Smart<Angle>,
+
+    /// Whether to repeat the watermark across the whole page.
+    #[default(false)]
+    pub tile: bool,
+
+    /// The gap between repetitions of a tiled watermark.
+    #[resolve]
+    #[default(Em::new(2.0).into())]
+    pub gap: Length,
+
+    /// How to fill the text of the watermark.
+    #[default(Color::BLACK.with_alpha(0.15).into())]
+    pub fill: Paint,
+
+    /// Whether the watermark is placed above the page's body instead of
+    /// behind it.
+    #[default(false)]
+    pub above: bool,
+
+    /// The content of the watermark.
+    #[required]
+    pub body: Content,
+}
+
+impl Packed<WatermarkElem> {
+    /// Lay out the watermark for a page of the given size with the given
+    /// bleed on all sides.
+    #[typst_macros::time(name = "watermark", span = self.span())]
+    pub(crate) fn layout(
+        &self,
+        engine: &mut Engine,
+        locator: Locator,
+        styles: StyleChain,
+        size: Size,
+        bleed: Abs,
+    ) -> SourceResult<Frame> {
+        let angle = self
+            .angle(styles)
+            .unwrap_or_else(|| Angle::rad(-(size.y / size.x).atan()));
+
+        let body = self.body().clone().styled(TextElem::set_fill(self.fill(styles)));
+        let rotated = RotateElem::new(body)
+            .with_angle(angle)
+            .with_reflow(true)
+            .pack()
+            .spanned(self.span());
+
+        let full = size + Size::splat(2.0 * bleed);
+        let pod = Regions::one(full, Axes::splat(false));
+        let mark = rotated.layout(engine, locator, styles, pod)?.into_frame();
+        let center = (full.to_point() - mark.size().to_point()) / 2.0;
+
+        let mut frame = Frame::hard(full);
+        let step = mark.size() + Size::splat(self.gap(styles));
+        if !self.tile(styles) || step.x <= Abs::zero() || step.y <= Abs::zero() {
+            frame.push_frame(center, mark);
+            return Ok(frame);
+        }
+
+        // The number of repetitions on each side of the central watermark
+        // that are needed to cover the page. Only the central one keeps its
+        // introspection tags, so that its elements have unique locations.
+        let nx = ((full.x + mark.width()) / (2.0 * step.x)).ceil() as i64;
+        let ny = ((full.y + mark.height()) / (2.0 * step.y)).ceil() as i64;
+        let copy = strip_tags(&mark);
+        for y in -ny..=ny {
+            for x in -nx..=nx {
+                let pos = center + Point::new(x as f64 * step.x, y as f64 * step.y);
+                let sub = if x == 0 && y == 0 { mark.clone() } else { copy.clone() };
+                frame.push_frame(pos, sub);
+            }
+        }
+
+        frame.clip(Path::rect(full));
+        Ok(frame)
+    }
+}
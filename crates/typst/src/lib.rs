@@ -51,133 +51,37 @@ pub mod symbols;
 pub mod text;
 pub mod visualize;
 
+mod compile;
+mod iter;
+mod partial;
+mod snippet;
+mod testing;
+
 #[doc(inline)]
 pub use typst_syntax as syntax;
 #[doc(inline)]
 pub use typst_utils as utils;
 
-use std::collections::HashSet;
+pub use self::compile::{compile, compile_within, trace};
+pub use self::iter::{layout_document_iter, PageIter};
+pub use self::partial::compile_partial;
+pub use self::snippet::layout_snippet;
+pub use self::testing::{test, TestResult};
+
 use std::ops::{Deref, Range};
 
-use comemo::{Track, Tracked, Validate};
-use ecow::{EcoString, EcoVec};
-use typst_timing::{timed, TimingScope};
-
-use crate::diag::{warning, FileResult, SourceDiagnostic, SourceResult, Warned};
-use crate::engine::{Engine, Route, Sink, Traced};
-use crate::foundations::{
-    Array, Bytes, Datetime, Dict, Module, Scope, StyleChain, Styles, Value,
-};
-use crate::introspection::Introspector;
-use crate::layout::{Alignment, Dir};
-use crate::model::Document;
-use crate::syntax::package::PackageSpec;
+use ecow::EcoString;
+
+use crate::diag::{FileResult, PackageError, PackageResult};
+use crate::foundations::sys::Capabilities;
+use crate::foundations::{Array, Bytes, Datetime, Dict, Module, Scope, Styles, Value};
+use crate::layout::{Alignment, Dir, Page, PageHook};
+use crate::syntax::package::{BoundedPackageSpec, PackageSpec, PackageVersion};
 use crate::syntax::{FileId, Source, Span};
 use crate::text::{Font, FontBook};
 use crate::utils::LazyHash;
 use crate::visualize::Color;
 
-/// Compile sources into a fully layouted document.
-///
-/// - Returns `Ok(document)` if there were no fatal errors.
-/// - Returns `Err(errors)` if there were fatal errors.
-#[typst_macros::time]
-pub fn compile(world: &dyn World) -> Warned<SourceResult<Document>> {
-    let mut sink = Sink::new();
-    let output = compile_inner(world.track(), Traced::default().track(), &mut sink)
-        .map_err(deduplicate);
-    Warned { output, warnings: sink.warnings() }
-}
-
-/// Compiles sources and returns all values and styles observed at the given
-/// `span` during compilation.
-#[typst_macros::time]
-pub fn trace(world: &dyn World, span: Span) -> EcoVec<(Value, Option<Styles>)> {
-    let mut sink = Sink::new();
-    let traced = Traced::new(span);
-    compile_inner(world.track(), traced.track(), &mut sink).ok();
-    sink.values()
-}
-
-/// Relayout until introspection converges.
-fn compile_inner(
-    world: Tracked<dyn World + '_>,
-    traced: Tracked<Traced>,
-    sink: &mut Sink,
-) -> SourceResult<Document> {
-    let library = world.library();
-    let styles = StyleChain::new(&library.styles);
-
-    // First evaluate the main source file into a module.
-    let content = crate::eval::eval(
-        world,
-        traced,
-        sink.track_mut(),
-        Route::default().track(),
-        &world.main(),
-    )?
-    .content();
-
-    let mut iter = 0;
-    let mut document = Document::default();
-
-    // Relayout until all introspections stabilize.
-    // If that doesn't happen within five attempts, we give up.
-    loop {
-        // The name of the iterations for timing scopes.
-        const ITER_NAMES: &[&str] =
-            &["layout (1)", "layout (2)", "layout (3)", "layout (4)", "layout (5)"];
-        let _scope = TimingScope::new(ITER_NAMES[iter], None);
-
-        // Clear delayed errors.
-        sink.delayed();
-
-        let constraint = <Introspector as Validate>::Constraint::new();
-        let mut engine = Engine {
-            world,
-            introspector: document.introspector.track_with(&constraint),
-            traced,
-            sink: sink.track_mut(),
-            route: Route::default(),
-        };
-
-        // Layout!
-        document = content.layout_document(&mut engine, styles)?;
-        document.introspector.rebuild(&document.pages);
-        iter += 1;
-
-        if timed!("check stabilized", document.introspector.validate(&constraint)) {
-            break;
-        }
-
-        if iter >= 5 {
-            sink.warn(warning!(
-                Span::detached(), "layout did not converge within 5 attempts";
-                hint: "check if any states or queries are updating themselves"
-            ));
-            break;
-        }
-    }
-
-    // Promote delayed errors.
-    let delayed = sink.delayed();
-    if !delayed.is_empty() {
-        return Err(delayed);
-    }
-
-    Ok(document)
-}
-
-/// Deduplicate diagnostics.
-fn deduplicate(mut diags: EcoVec<SourceDiagnostic>) -> EcoVec<SourceDiagnostic> {
-    let mut unique = HashSet::new();
-    diags.retain(|diag| {
-        let hash = crate::utils::hash128(&(&diag.span, &diag.message));
-        unique.insert(hash)
-    });
-    diags
-}
-
 /// The environment in which typesetting occurs.
 ///
 /// All loading functions (`main`, `source`, `file`, `font`) should perform
@@ -233,6 +137,21 @@ pub trait World: Send + Sync {
     fn packages(&self) -> &[(PackageSpec, Option<EcoString>)] {
         &[]
     }
+
+    /// Determine the exact version of a package imported with a version bound
+    /// like `@local/letter:1.2`.
+    ///
+    /// This function is optional to implement. Implementations should
+    /// typically pick the highest available version that matches the bound.
+    /// To make builds reproducible, they can record the choice in a
+    /// [lockfile](crate::syntax::package::PackageLock) and reuse it in later
+    /// compilations. If this returns an error, the import fails with it.
+    fn resolve_package(
+        &self,
+        spec: &BoundedPackageSpec,
+    ) -> PackageResult<PackageVersion> {
+        Err(PackageError::NoMatchingVersion(spec.clone()))
+    }
 }
 
 macro_rules! delegate_for_ptr {
@@ -269,6 +188,13 @@ macro_rules! delegate_for_ptr {
             fn packages(&self) -> &[(PackageSpec, Option<EcoString>)] {
                 self.deref().packages()
             }
+
+            fn resolve_package(
+                &self,
+                spec: &BoundedPackageSpec,
+            ) -> PackageResult<PackageVersion> {
+                self.deref().resolve_package(spec)
+            }
         }
     };
 }
@@ -304,6 +230,12 @@ pub struct Library {
     /// The standard library as a value.
     /// Used to provide the `std` variable.
     pub std: Value,
+    /// The capabilities the host grants to documents.
+    pub capabilities: Capabilities,
+    /// Hooks that post-process each finished page.
+    pub page_hooks: Vec<PageHook>,
+    /// Whether to draw the layout debug overlay onto the pages.
+    pub layout_debug: bool,
 }
 
 impl Library {
@@ -326,6 +258,9 @@ impl Default for Library {
 #[derive(Debug, Clone, Default)]
 pub struct LibraryBuilder {
     inputs: Option<Dict>,
+    capabilities: Option<Capabilities>,
+    page_hooks: Vec<PageHook>,
+    layout_debug: bool,
 }
 
 impl LibraryBuilder {
@@ -335,20 +270,59 @@ impl LibraryBuilder {
         self
     }
 
+    /// Configure the capabilities visible through `sys.capabilities`.
+    ///
+    /// By default, all capabilities are granted.
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    /// Register a hook that post-processes each finished page.
+    ///
+    /// See [`PageHook`] for details.
+    pub fn with_page_hook(
+        mut self,
+        hook: impl Fn(&mut Page) + Send + Sync + 'static,
+    ) -> Self {
+        self.page_hooks.push(PageHook::new(hook));
+        self
+    }
+
+    /// Configure whether to draw the layout debug overlay onto the pages.
+    ///
+    /// The overlay shows a coordinate grid with lines every centimeter, the
+    /// pages' margins, headers, and footers, the regions of flows with their
+    /// baseline grid, and the areas of floats, footnotes, and chains of
+    /// sticky blocks. It is disabled by default.
+    pub fn with_layout_debug(mut self, enabled: bool) -> Self {
+        self.layout_debug = enabled;
+        self
+    }
+
     /// Consumes the builder and returns a `Library`.
     pub fn build(self) -> Library {
         let math = math::module();
         let inputs = self.inputs.unwrap_or_default();
-        let global = global(math.clone(), inputs);
+        let capabilities = self.capabilities.unwrap_or_default();
+        let global = global(math.clone(), inputs, &capabilities);
         let std = Value::Module(global.clone());
-        Library { global, math, styles: Styles::new(), std }
+        Library {
+            global,
+            math,
+            styles: Styles::new(),
+            std,
+            capabilities,
+            page_hooks: self.page_hooks,
+            layout_debug: self.layout_debug,
+        }
     }
 }
 
 /// Construct the module with global definitions.
-fn global(math: Module, inputs: Dict) -> Module {
+fn global(math: Module, inputs: Dict, capabilities: &Capabilities) -> Module {
     let mut global = Scope::deduplicating();
-    self::foundations::define(&mut global, inputs);
+    self::foundations::define(&mut global, inputs, capabilities);
     self::model::define(&mut global);
     self::text::define(&mut global);
     global.reset_category();
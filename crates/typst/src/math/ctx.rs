@@ -18,7 +18,7 @@ use crate::math::{
     scaled_font_size, styled_char, EquationElem, FrameFragment, GlyphFragment,
     LayoutMath, MathFragment, MathRun, MathSize, THICK,
 };
-use crate::model::ParElem;
+use crate::model::{ParElem, ParLineNumbering};
 use crate::realize::StyleVec;
 use crate::syntax::{is_newline, Span};
 use crate::text::{
@@ -302,15 +302,19 @@ impl<'a, 'b, 'v> MathContext<'a, 'b, 'v> {
         let spaced = text.graphemes(true).nth(1).is_some();
         let text = TextElem::packed(text).spanned(span);
         let par = ParElem::new(StyleVec::wrap(eco_vec![text]));
+
+        // Lines of text in math are never numbered.
+        let local = ParLineNumbering::set_numbering(None).wrap();
         let frame = Packed::new(par)
             .spanned(span)
             .layout(
                 self.engine,
                 self.locator.next(&span),
-                styles,
+                styles.chain(&local),
                 false,
                 Size::splat(Abs::inf()),
                 false,
+                &[],
             )?
             .into_frame();
 
@@ -7,7 +7,7 @@ use unicode_math_class::MathClass;
 
 use crate::foundations::StyleChain;
 use crate::layout::{
-    Abs, Corner, Em, Frame, FrameItem, HideElem, Point, Size, VAlignment,
+    Abs, Corner, Em, Frame, FrameItem, HideElem, Point, RedactElem, Size, VAlignment,
 };
 use crate::math::{
     scaled_font_size, EquationElem, Limits, MathContext, MathSize, Scaled,
@@ -222,6 +222,7 @@ pub struct GlyphFragment {
     pub span: Span,
     pub dests: SmallVec<[Destination; 1]>,
     pub hidden: bool,
+    pub redaction: Option<Paint>,
     pub limits: Limits,
 }
 
@@ -278,6 +279,7 @@ impl GlyphFragment {
             span,
             dests: LinkElem::dests_in(styles),
             hidden: HideElem::hidden_in(styles),
+            redaction: RedactElem::redaction_in(styles),
         };
         fragment.set_id(ctx, id);
         fragment
@@ -361,7 +363,7 @@ impl GlyphFragment {
         let mut frame = Frame::soft(size);
         frame.set_baseline(self.ascent);
         frame.push(Point::with_y(self.ascent + self.shift), FrameItem::Text(item));
-        frame.post_process_raw(self.dests, self.hidden);
+        frame.post_process_raw(self.dests, self.hidden, self.redaction);
         frame
     }
 
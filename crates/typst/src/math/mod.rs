@@ -49,7 +49,7 @@ use crate::foundations::{
 };
 use crate::introspection::TagElem;
 use crate::layout::{BoxElem, Frame, FrameItem, HElem, Point, Size, Spacing, VAlignment};
-use crate::realize::{process, BehavedBuilder};
+use crate::realize::{process, BehavedBuilder, FirstMatches};
 use crate::text::{LinebreakElem, SpaceElem, TextElem};
 
 /// Typst has special [syntax]($syntax/#math) and library functions to typeset
@@ -232,7 +232,10 @@ impl LayoutMath for Content {
             return elem.layout_math(ctx, styles);
         }
 
-        if let Some(realized) = process(ctx.engine, &mut ctx.locator, self, styles)? {
+        let mut firsts = FirstMatches::default();
+        if let Some(realized) =
+            process(ctx.engine, &mut ctx.locator, &mut firsts, self, styles)?
+        {
             return realized.layout_math(ctx, styles);
         }
 
@@ -249,7 +252,8 @@ impl LayoutMath for Content {
 
         if let Some(styled) = self.to_packed::<StyledElem>() {
             let outer = styles;
-            let styles = outer.chain(&styled.styles);
+            let local = styled.styles.resolve_contextual(ctx.engine, None, outer)?;
+            let styles = outer.chain(&local);
 
             if TextElem::font_in(styles) != TextElem::font_in(outer) {
                 let frame = ctx.layout_content(&styled.child, styles)?;
@@ -167,7 +167,7 @@ fn assemble(
     let mut frame = Frame::soft(size);
     let mut offset = Abs::zero();
     frame.set_baseline(baseline);
-    frame.post_process_raw(base.dests, base.hidden);
+    frame.post_process_raw(base.dests, base.hidden, base.redaction);
 
     for (fragment, advance) in selected {
         let pos = if horizontal {
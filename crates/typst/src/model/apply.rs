@@ -0,0 +1,41 @@
+use crate::foundations::{elem, Selector, Styles};
+
+/// Applies set and show rules to a section of the document.
+///
+/// A section starts with a heading matching the `target` and extends up to,
+/// but not including, the next heading of the same or a higher level. The
+/// rules apply to the heading itself and everything in its section, just as
+/// if they were written directly below the heading. Unlike a set rule, this
+/// also works when the section's content comes from an
+/// [included]($scripting/#modules) file, without wrapping it in braces.
+///
+/// The `apply` call must come before the section it targets. Rules written
+/// within the section take precedence over the applied ones.
+///
+/// # Example
+/// ```example
+/// #apply(<appendix>, set text(fill: gray))
+/// #apply(<appendix>, show heading: set text(blue))
+///
+/// = Results <results>
+/// Everything went well.
+///
+/// = Appendix <appendix>
+/// Some raw data.
+///
+/// == Measurements
+/// More raw data.
+///
+/// = Conclusion
+/// Back to normal.
+/// ```
+#[elem]
+pub struct ApplyElem {
+    /// The heading that starts the section, typically given by its label.
+    #[required]
+    pub target: Selector,
+
+    /// The set or show rules to apply to the section.
+    #[required]
+    pub rules: Styles,
+}
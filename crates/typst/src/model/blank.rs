@@ -0,0 +1,153 @@
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{
+    cast, elem, Content, NativeElement, Packed, Resolve, Smart, StyleChain,
+};
+use crate::introspection::Locator;
+use crate::layout::{
+    Abs, Alignment, Axes, Em, Fr, Frame, FrameItem, HideElem, Point, Regions, Size,
+    Sizing,
+};
+use crate::model::first_baseline;
+use crate::text::{TextElem, TextSize};
+use crate::visualize::{DashPattern, Geometry, Stroke};
+
+/// A blank to fill in, as found on exams, worksheets, and forms.
+///
+/// A blank is a line in the text on which an answer can be written. By
+/// default, it takes up one fraction (`{1fr}`) of the remaining space in its
+/// line, just like [`h(1fr)`]($h), such that blanks in justified text stretch
+/// instead of the spaces between words.
+///
+/// A blank can hold the expected answer, which is only shown when
+/// [`reveal`]($blank.reveal) is enabled. Since a hidden answer still takes up
+/// its space, the answer sheet and the blank exam have exactly the same
+/// layout.
+///
+/// # Example
+/// ```example
+/// #set par(justify: true)
+///
+/// Name: #blank(caption: [Family name])
+/// #blank(width: 40pt, caption: [Class])
+///
+/// The capital of France is
+/// #blank(width: 60pt, rule: "dotted", answer: [Paris]).
+///
+/// #set blank(reveal: true)
+/// The capital of France is
+/// #blank(width: 60pt, rule: "dotted", answer: [Paris]).
+/// ```
+#[elem]
+pub struct BlankElem {
+    /// How wide the blank is.
+    ///
+    /// If this is `{auto}`, the blank is as wide as its answer.
+    #[default(Sizing::Fr(Fr::one()))]
+    pub width: Sizing,
+
+    /// How to draw the line of the blank.
+    ///
+    /// This can be a [stroke] or, for convenience, just the name of a dash
+    /// pattern like `{"dotted"}` or `{"dashed"}`. If set to `{none}`, no line
+    /// is drawn.
+    ///
+    /// ```example
+    /// #blank(rule: "dashed") \
+    /// #blank(rule: 0.5pt + blue)
+    /// ```
+    #[default(Some(BlankRule(Stroke::default())))]
+    pub rule: Option<BlankRule>,
+
+    /// A caption below the line that says what to fill in.
+    ///
+    /// The caption is set in smaller text and makes the blank taller, pushing
+    /// the next line down.
+    pub caption: Option<Content>,
+
+    /// The expected answer.
+    pub answer: Option<Content>,
+
+    /// Whether to show the answer.
+    ///
+    /// This is typically set document-wide to produce an answer sheet, for
+    /// example based on an input: `{set blank(reveal: "answers" in
+    /// sys.inputs)}`.
+    #[default(false)]
+    pub reveal: bool,
+}
+
+impl Packed<BlankElem> {
+    /// Layout the blank as part of a paragraph line.
+    ///
+    /// The width of the `region` is the space available to the line or, for a
+    /// fractionally sized blank, its share of the line's remaining space.
+    #[typst_macros::time(name = "blank", span = self.span())]
+    pub fn layout(
+        &self,
+        engine: &mut Engine,
+        locator: Locator,
+        styles: StyleChain,
+        region: Size,
+    ) -> SourceResult<Frame> {
+        let mut locator = locator.split();
+        let pod = Regions::one(region, Axes::splat(false));
+
+        let answer = match self.answer(styles) {
+            Some(answer) if self.reveal(styles) => {
+                answer.layout(engine, locator.next(&()), styles, pod)?.into_frame()
+            }
+            Some(answer) => HideElem::new(answer)
+                .pack()
+                .layout(engine, locator.next(&()), styles, pod)?
+                .into_frame(),
+            None => Frame::soft(Size::zero()),
+        };
+
+        let width = match self.width(styles) {
+            Sizing::Auto => answer.width(),
+            Sizing::Rel(rel) => rel.resolve(styles).relative_to(region.x),
+            Sizing::Fr(_) => region.x,
+        };
+
+        // The answer sits on the baseline and the line slightly below it, like
+        // an underline.
+        let baseline = first_baseline(&answer).unwrap_or(answer.height());
+        let line_y = baseline + Em::new(0.2).resolve(styles);
+
+        let mut frame = Frame::soft(Size::new(width, answer.height()));
+        frame.set_baseline(baseline);
+        frame.push_frame(Point::zero(), answer);
+
+        if let Some(rule) = self.rule(styles) {
+            let stroke = rule.0.resolve(styles).unwrap_or_default();
+            let shape = Geometry::Line(Point::with_x(width)).stroked(stroke);
+            frame.push(Point::with_y(line_y), FrameItem::Shape(shape, self.span()));
+        }
+
+        if let Some(caption) = self.caption(styles) {
+            let caption = caption
+                .aligned(Alignment::CENTER)
+                .styled(TextElem::set_size(TextSize(Em::new(0.7).into())));
+            let pod = Regions::one(Size::new(width, Abs::inf()), Axes::new(true, false));
+            let caption =
+                caption.layout(engine, locator.next(&()), styles, pod)?.into_frame();
+            let y = line_y + Em::new(0.3).resolve(styles);
+            frame.size_mut().y = y + caption.height();
+            frame.push_frame(Point::with_y(y), caption);
+        }
+
+        Ok(frame)
+    }
+}
+
+/// The line of a blank.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct BlankRule(pub Stroke);
+
+cast! {
+    BlankRule,
+    self => self.0.into_value(),
+    v: Stroke => Self(v),
+    v: DashPattern => Self(Stroke { dash: Smart::Custom(Some(v)), ..Stroke::default() }),
+}
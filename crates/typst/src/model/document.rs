@@ -1,13 +1,18 @@
-use ecow::EcoString;
+use std::num::NonZeroUsize;
 
-use crate::diag::{bail, HintedStrResult, SourceResult};
+use ecow::{eco_vec, EcoString};
+
+use crate::diag::{bail, error, HintedStrResult, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
     cast, elem, Args, Array, Construct, Content, Datetime, Packed, Smart, StyleChain,
     Value,
 };
-use crate::introspection::{Introspector, Locator, ManualPageCounter};
-use crate::layout::{Page, PageElem};
+use crate::introspection::{Divergence, Introspector, Locator, ManualPageCounter};
+use crate::layout::{
+    Abs, Binding, Frame, FrameItem, Page, PageClear, PageElem, PageLayout, PageRanges,
+    Point, Size,
+};
 use crate::realize::StyleVec;
 
 /// The root element of a document and its metadata.
@@ -58,6 +63,31 @@ pub struct DocumentElem {
     #[ghost]
     pub date: Smart<Option<Datetime>>,
 
+    /// How many times the document may be laid out at most until all
+    /// introspections converge.
+    ///
+    /// Counters, states, and queries can depend on the layout that they
+    /// themselves influence. Typst thus lays out the document repeatedly
+    /// until their results stop changing. If that doesn't happen within this
+    /// many attempts, it gives up and warns about the counters and states
+    /// whose values changed in the last attempt. If this is `{auto}`, Typst
+    /// makes five attempts.
+    ///
+    /// Raising this can help with documents that just need a few more
+    /// attempts to stabilize, but it won't fix ones that oscillate.
+    #[ghost]
+    pub iterations: Smart<NonZeroUsize>,
+
+    /// Whether the document is meant to be viewed as spreads of facing pages.
+    ///
+    /// Viewers and exporters that support it then show facing pages side by
+    /// side. The pages are grouped like for the [`spread`]($page.spread) of a
+    /// page: The first page stands on its own and is followed by pairs of a
+    /// left-hand and a right-hand page.
+    #[ghost]
+    #[default(false)]
+    pub spreads: bool,
+
     /// The page runs.
     #[internal]
     #[variadic]
@@ -72,52 +102,113 @@ impl Construct for DocumentElem {
 
 impl Packed<DocumentElem> {
     /// Layout this document.
+    ///
+    /// If a number of `pages` is given, layout stops once that many pages are
+    /// finished and the document is marked as truncated. As the pages of the
+    /// last laid out run may have been cut short, this also happens if the
+    /// document has exactly that many pages.
     #[typst_macros::time(name = "document", span = self.span())]
     pub fn layout(
         &self,
         engine: &mut Engine,
         locator: Locator,
         styles: StyleChain,
+        pages: Option<usize>,
     ) -> SourceResult<Document> {
         let children = self.children();
         let mut peekable = children.chain(&styles).peekable();
         let mut locator = locator.split();
+        let introspector = engine.introspector;
 
         let iter = std::iter::from_fn(|| {
             let (child, styles) = peekable.next()?;
             let extend_to = peekable
                 .peek()
-                .and_then(|(next, _)| *next.to_packed::<PageElem>()?.clear_to()?);
+                .and_then(|(next, styles)| {
+                    Some(PageClear::of(next.to_packed::<PageElem>()?, *styles))
+                })
+                .unwrap_or_default();
+            let checkpoint = locator.next_location(introspector, 0).hash();
             let locator = locator.next(&child.span());
-            Some((child, styles, extend_to, locator))
+            Some((child, styles, extend_to, locator, checkpoint))
         });
 
-        let layouts =
-            engine.parallelize(iter, |engine, (child, styles, extend_to, locator)| {
-                if let Some(page) = child.to_packed::<PageElem>() {
-                    page.layout(engine, locator, styles, extend_to)
-                } else {
-                    bail!(child.span(), "unexpected document child");
+        let mut page_counter = ManualPageCounter::new();
+        let mut finished = Vec::with_capacity(self.children().len());
+        let mut incomplete = None;
+
+        if let Some(limit) = pages {
+            // How many pages a run may fill depends on the runs before it, so
+            // they are laid out one after another.
+            for (child, styles, extend_to, locator, checkpoint) in iter {
+                if finished.len() >= limit {
+                    break;
                 }
-            });
 
-        let mut page_counter = ManualPageCounter::new();
-        let mut pages = Vec::with_capacity(self.children().len());
-        for result in layouts {
-            pages.extend(result?.finalize(engine, &mut page_counter)?);
+                let local = PageElem::set_limit(Some(limit - finished.len())).wrap();
+                let styles = styles.chain(&local);
+                let layout = layout_run(engine, child, styles, extend_to, locator)?;
+                finished.extend(layout.finalize(engine, &mut page_counter)?);
+                if engine.budget.exceeded(checkpoint) {
+                    incomplete = Some(Incomplete::Truncated);
+                    break;
+                }
+            }
+
+            if finished.len() >= limit {
+                incomplete = Some(Incomplete::Truncated);
+            }
+        } else {
+            let layouts = engine.parallelize(
+                iter,
+                |engine, (child, styles, extend_to, locator, checkpoint)| {
+                    let result = layout_run(engine, child, styles, extend_to, locator);
+                    (result, engine.budget.exceeded(checkpoint))
+                },
+            );
+
+            for (result, exceeded) in layouts {
+                finished.extend(result?.finalize(engine, &mut page_counter)?);
+
+                // A page run that ended after the budget ran out may have been
+                // cut short, so the pages of the following runs would be out of
+                // place.
+                if exceeded {
+                    incomplete = Some(Incomplete::Truncated);
+                    break;
+                }
+            }
         }
 
         Ok(Document {
-            pages,
+            pages: finished,
             title: DocumentElem::title_in(styles).map(|content| content.plain_text()),
             author: DocumentElem::author_in(styles).0,
             keywords: DocumentElem::keywords_in(styles).0,
             date: DocumentElem::date_in(styles),
+            iterations: DocumentElem::iterations_in(styles),
+            spreads: DocumentElem::spreads_in(styles),
             introspector: Introspector::default(),
+            incomplete,
+            divergences: vec![],
         })
     }
 }
 
+/// Layout a single page run.
+fn layout_run<'a>(
+    engine: &mut Engine,
+    child: &'a Content,
+    styles: StyleChain<'a>,
+    extend_to: PageClear,
+    locator: Locator<'a>,
+) -> SourceResult<PageLayout<'a>> {
+    match child.to_packed::<PageElem>() {
+        Some(page) => page.layout(engine, locator, styles, extend_to),
+        None => Err(eco_vec![error!(child.span(), "unexpected document child")]),
+    }
+}
+
 /// A list of authors.
 #[derive(Debug, Default, Clone, PartialEq, Hash)]
 pub struct Author(Vec<EcoString>);
@@ -153,8 +244,146 @@ pub struct Document {
     pub keywords: Vec<EcoString>,
     /// The document's creation date.
     pub date: Smart<Option<Datetime>>,
+    /// How many times the document may be laid out at most.
+    pub iterations: Smart<NonZeroUsize>,
+    /// Whether the document is meant to be viewed as spreads.
+    pub spreads: bool,
     /// Provides the ability to execute queries on the document.
     pub introspector: Introspector,
+    /// Whether the document was left unfinished because the time budget of
+    /// its compilation ran out.
+    pub incomplete: Option<Incomplete>,
+    /// The counters and states that changed in the last layout attempt if the
+    /// introspections did not converge.
+    pub divergences: Vec<Divergence>,
+}
+
+impl Document {
+    /// Groups the pages into spreads of facing pages.
+    ///
+    /// Each page is placed on the side opposite to its spine. A page whose
+    /// facing page doesn't exist, like the first one, forms a spread on its
+    /// own.
+    pub fn group_spreads(&self) -> Vec<Spread> {
+        let mut spreads: Vec<Spread> = vec![];
+        for (i, page) in self.pages.iter().enumerate() {
+            // The second page starts a new spread, the third one joins it.
+            let joins = i % 2 == 0
+                && spreads.last_mut().is_some_and(|last| last.slot(page.spine).is_none());
+            if !joins {
+                spreads.push(Spread::default());
+            }
+            *spreads.last_mut().unwrap().slot(page.spine) = Some(i);
+        }
+        spreads
+    }
+
+    /// Imposes the pages 2-up, producing one frame per spread with its pages
+    /// side by side.
+    ///
+    /// The missing page of a spread on its own is left blank, such that the
+    /// existing one stays on its side.
+    pub fn impose(&self) -> Vec<Frame> {
+        self.group_spreads()
+            .into_iter()
+            .map(|spread| {
+                let left = spread.left.map(|i| &self.pages[i].frame);
+                let right = spread.right.map(|i| &self.pages[i].frame);
+                let width =
+                    |frame: Option<&Frame>| frame.map_or(Abs::zero(), Frame::width);
+                let offset = width(left.or(right));
+                let height = left.into_iter().chain(right).map(Frame::height).max();
+
+                let size =
+                    Size::new(offset + width(right.or(left)), height.unwrap_or_default());
+                let mut frame = Frame::hard(size);
+                if let Some(left) = left {
+                    frame.push_frame(Point::zero(), left.clone());
+                }
+                if let Some(right) = right {
+                    frame.push_frame(Point::with_x(offset), right.clone());
+                }
+                frame
+            })
+            .collect()
+    }
+
+    /// Extracts the plain text of the given pages, or of all pages if `None`.
+    ///
+    /// The text of each page is collected in the order it appears in the
+    /// frames, with a line break whenever the baseline changes. Pages end with
+    /// a line break and are separated by form feeds.
+    pub fn plain_text(&self, pages: Option<&PageRanges>) -> EcoString {
+        let mut text = EcoString::new();
+        for (i, page) in self.pages.iter().enumerate() {
+            if pages.is_some_and(|pages| !pages.includes_page_index(i)) {
+                continue;
+            }
+            if !text.is_empty() {
+                text.push('\u{c}');
+            }
+            write_frame_text(&page.frame, Point::zero(), &mut None, &mut text);
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// Append the text in a frame to a string, starting a new line whenever the
+/// baseline changes.
+fn write_frame_text(
+    frame: &Frame,
+    offset: Point,
+    baseline: &mut Option<Abs>,
+    text: &mut EcoString,
+) {
+    for (pos, item) in frame.items() {
+        let pos = offset + *pos;
+        match item {
+            FrameItem::Group(group) => {
+                let ts = group.transform;
+                let pos = pos + Point::new(ts.tx, ts.ty);
+                write_frame_text(&group.frame, pos, baseline, text);
+            }
+            FrameItem::Text(item) => {
+                if baseline.is_some_and(|y| !y.approx_eq(pos.y)) {
+                    text.push('\n');
+                }
+                *baseline = Some(pos.y);
+                text.push_str(&item.text);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Two facing pages of a document.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Spread {
+    /// The index of the left-hand page.
+    pub left: Option<usize>,
+    /// The index of the right-hand page.
+    pub right: Option<usize>,
+}
+
+impl Spread {
+    /// The slot for a page with the given spine.
+    fn slot(&mut self, spine: Binding) -> &mut Option<usize> {
+        match spine {
+            Binding::Left => &mut self.right,
+            Binding::Right => &mut self.left,
+        }
+    }
+}
+
+/// Why the compilation of a document stopped before it was finished.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Incomplete {
+    /// Layout was cut short, so the document may lack pages at its end.
+    Truncated,
+    /// All pages were laid out, but introspections like page numbers or
+    /// references may not have stabilized yet.
+    Unstable,
 }
 
 #[cfg(test)]
@@ -166,4 +395,34 @@ mod tests {
         fn ensure_send_and_sync<T: Send + Sync>() {}
         ensure_send_and_sync::<Document>();
     }
+
+    #[test]
+    fn test_group_spreads() {
+        fn spreads(spines: &[Binding]) -> Vec<(Option<usize>, Option<usize>)> {
+            let pages = spines
+                .iter()
+                .map(|&spine| Page {
+                    frame: Frame::soft(Size::zero()),
+                    numbering: None,
+                    number: 1,
+                    spine,
+                    trim: Abs::zero(),
+                    bleed: Abs::zero(),
+                })
+                .collect();
+            let document = Document { pages, ..Default::default() };
+            document.group_spreads().iter().map(|s| (s.left, s.right)).collect()
+        }
+
+        use Binding::{Left as L, Right as R};
+        assert_eq!(
+            spreads(&[L, R, L, R]),
+            [(None, Some(0)), (Some(1), Some(2)), (Some(3), None)]
+        );
+        assert_eq!(spreads(&[R, L, R]), [(Some(0), None), (Some(2), Some(1))]);
+        assert_eq!(
+            spreads(&[L, L, L]),
+            [(None, Some(0)), (None, Some(1)), (None, Some(2))]
+        );
+    }
 }
@@ -276,9 +276,12 @@ fn layout_enum(
             content
         } else {
             match numbering {
-                Numbering::Pattern(pattern) => {
-                    TextElem::packed(pattern.apply_kth(parents.len(), number))
-                }
+                Numbering::Pattern(pattern) => TextElem::packed(pattern.apply_kth(
+                    parents.len(),
+                    number,
+                    TextElem::lang_in(styles),
+                    TextElem::region_in(styles),
+                )),
                 other => other.apply(engine, context.track(), &[number])?.display(),
             }
         };
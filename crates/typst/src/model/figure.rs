@@ -2,7 +2,8 @@ use std::borrow::Cow;
 use std::num::NonZeroUsize;
 use std::str::FromStr;
 
-use ecow::EcoString;
+use ecow::{eco_format, EcoString};
+use smallvec::smallvec;
 
 use crate::diag::{bail, SourceResult};
 use crate::engine::Engine;
@@ -14,8 +15,9 @@ use crate::introspection::{
     Count, Counter, CounterKey, CounterUpdate, Locatable, Location,
 };
 use crate::layout::{
-    AlignElem, Alignment, BlockChild, BlockElem, Em, HAlignment, Length, OuterVAlignment,
-    PlaceElem, VAlignment, VElem,
+    AlignElem, Alignment, BlockChild, BlockElem, Em, GridCell, GridChild, GridElem,
+    GridItem, HAlignment, Length, PlaceElem, PlacementScope, Side, Sizing, TrackSizings,
+    VAlignment, VElem,
 };
 use crate::model::{Numbering, NumberingPattern, Outlinable, Refable, Supplement};
 use crate::text::{Lang, Region, TextElem};
@@ -133,6 +135,27 @@ pub struct FigureElem {
     /// ```
     pub placement: Option<Smart<VAlignment>>,
 
+    /// Relative to which containing scope the figure is placed.
+    ///
+    /// Set this to `{"parent"}` to create a full-width figure in a
+    /// multi-column layout. Only has an effect if the figure has a
+    /// [`placement`]($figure.placement). See the [`scope`]($place.scope)
+    /// argument of the `place` function for more details.
+    ///
+    /// ```example
+    /// #set page(height: 250pt, columns: 2)
+    ///
+    /// = Introduction
+    /// #figure(
+    ///   placement: bottom,
+    ///   scope: "parent",
+    ///   caption: [A glacier],
+    ///   image("glacier.jpg", width: 60%),
+    /// )
+    /// #lorem(60)
+    /// ```
+    pub scope: PlacementScope,
+
     /// The figure's caption.
     pub caption: Option<Packed<FigureCaption>>,
 
@@ -291,6 +314,7 @@ impl Synthesize for Packed<FigureElem> {
             caption.push_numbering(numbering.clone());
             caption.push_counter(Some(counter.clone()));
             caption.push_figure_location(location);
+            caption.push_continued(false);
         }
 
         elem.push_kind(Smart::Custom(kind));
@@ -306,18 +330,50 @@ impl Show for Packed<FigureElem> {
     #[typst_macros::time(name = "figure", span = self.span())]
     fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
         let mut realized = self.body().clone();
+        let mut block = BlockElem::new();
 
         // Build the caption, if any.
         if let Some(caption) = self.caption(styles) {
-            let v = VElem::weak(self.gap(styles).into()).pack();
-            realized = match caption.position(styles) {
-                OuterVAlignment::Top => caption.pack() + v + realized,
-                OuterVAlignment::Bottom => realized + v + caption.pack(),
+            let gap = self.gap(styles);
+            let position = caption.position(styles);
+            let v = VElem::weak(gap.into()).pack();
+            realized = match position {
+                Side::Top => caption.clone().pack() + v + realized,
+                Side::Bottom => realized + v + caption.clone().pack(),
+                Side::Left | Side::Right => {
+                    let span = self.span();
+                    let cell = |body| {
+                        GridChild::Item(GridItem::Cell(
+                            Packed::new(GridCell::new(body)).spanned(span),
+                        ))
+                    };
+                    let mut cells = vec![cell(caption.clone().pack()), cell(realized)];
+                    if position == Side::Right {
+                        cells.reverse();
+                    }
+                    GridElem::new(cells)
+                        .with_columns(TrackSizings(smallvec![Sizing::Auto; 2]))
+                        .with_column_gutter(TrackSizings(smallvec![gap.into()]))
+                        .pack()
+                        .spanned(span)
+                }
             };
+
+            // Repeat the caption where the figure breaks across pages.
+            if caption.repeat(styles) {
+                let mut continued = caption;
+                continued.push_continued(true);
+                let v = VElem::new(gap.into()).pack();
+                match position {
+                    Side::Top => block.push_after_break(Some(continued.pack() + v)),
+                    Side::Bottom => block.push_before_break(Some(v + continued.pack())),
+                    Side::Left | Side::Right => {}
+                }
+            }
         }
 
         // Wrap the contents in a block.
-        realized = BlockElem::new()
+        realized = block
             .with_body(Some(BlockChild::Content(realized)))
             .pack()
             .spanned(self.span());
@@ -326,6 +382,7 @@ impl Show for Packed<FigureElem> {
         if let Some(align) = self.placement(styles) {
             realized = PlaceElem::new(realized)
                 .with_float(true)
+                .with_scope(self.scope(styles))
                 .with_alignment(align.map(|align| HAlignment::Center + align))
                 .pack()
                 .spanned(self.span());
@@ -392,7 +449,9 @@ impl Outlinable for Packed<FigureElem> {
             return Ok(None);
         };
 
-        let mut realized = caption.body().clone();
+        let mut realized = caption
+            .short(StyleChain::default())
+            .unwrap_or_else(|| caption.body().clone());
         if let (
             Smart::Custom(Some(Supplement::Content(mut supplement))),
             Some(Some(counter)),
@@ -415,7 +474,7 @@ impl Outlinable for Packed<FigureElem> {
 
             let separator = caption.get_separator(StyleChain::default());
 
-            realized = supplement + numbers + separator + caption.body();
+            realized = supplement + numbers + separator + realized;
         }
 
         Ok(Some(realized))
@@ -441,7 +500,11 @@ impl Outlinable for Packed<FigureElem> {
 /// ```
 #[elem(name = "caption", Synthesize, Show)]
 pub struct FigureCaption {
-    /// The caption's position in the figure. Either `{top}` or `{bottom}`.
+    /// The caption's position in the figure. Either `{top}`, `{bottom}`,
+    /// `{left}`, or `{right}`.
+    ///
+    /// A caption on the left or right is placed beside the figure's body,
+    /// which is useful for wide figures that are rotated on the page.
     ///
     /// ```example
     /// #show figure.where(
@@ -465,9 +528,17 @@ pub struct FigureCaption {
     ///     [I'm down here too!]
     ///   )
     /// )
+    ///
+    /// #figure(
+    ///   rect[Hi],
+    ///   caption: figure.caption(
+    ///     position: right,
+    ///     [I'm on the side],
+    ///   )
+    /// )
     /// ```
-    #[default(OuterVAlignment::Bottom)]
-    pub position: OuterVAlignment,
+    #[default(Side::Bottom)]
+    pub position: Side,
 
     /// The separator which will appear between the number and body.
     ///
@@ -504,6 +575,60 @@ pub struct FigureCaption {
     #[required]
     pub body: Content,
 
+    /// A short version of the caption, which is shown in the [`outline`]
+    /// instead of the caption's body.
+    ///
+    /// ```example
+    /// #outline(target: figure)
+    ///
+    /// #figure(
+    ///   rect[Hello],
+    ///   caption: figure.caption(
+    ///     short: [A rectangle],
+    ///     [A rectangle, with four right angles and a friendly greeting.],
+    ///   ),
+    /// )
+    /// ```
+    pub short: Option<Content>,
+
+    /// Whether to repeat the caption where a [breakable]($block.breakable)
+    /// figure breaks across pages.
+    ///
+    /// A caption at the top is repeated at the top of each page the figure
+    /// continues on, while a caption at the bottom is repeated at the bottom
+    /// of each page the figure breaks on. Repeated captions are marked as
+    /// continued.
+    ///
+    /// ```example
+    /// #set page(height: 100pt)
+    /// #show figure: set block(breakable: true)
+    /// #set figure.caption(position: top, repeat: true)
+    ///
+    /// #figure(
+    ///   table(columns: 2, ..range(12).map(str)),
+    ///   caption: [Numbers],
+    /// )
+    /// ```
+    #[default(false)]
+    pub repeat: bool,
+
+    /// Whether this is a repeated caption of a figure that breaks across
+    /// pages. See [`repeat`]($figure.caption.repeat).
+    ///
+    /// ```example
+    /// #set page(height: 100pt)
+    /// #show figure: set block(breakable: true)
+    /// #set figure.caption(position: top, repeat: true)
+    /// #show figure.caption.where(continued: true): set text(gray)
+    ///
+    /// #figure(
+    ///   table(columns: 2, ..range(12).map(str)),
+    ///   caption: [Numbers],
+    /// )
+    /// ```
+    #[synthesized]
+    pub continued: bool,
+
     /// The figure's supplement.
     #[synthesized]
     pub kind: FigureKind,
@@ -538,6 +663,19 @@ impl FigureCaption {
         }
     }
 
+    /// Gets the marker for continued captions in the given language and
+    /// (optionally) region.
+    fn local_continued(lang: Lang, _: Option<Region>) -> &'static str {
+        match lang {
+            Lang::CHINESE => "续",
+            Lang::FRENCH => "suite",
+            Lang::GERMAN => "Fortsetzung",
+            Lang::RUSSIAN => "продолжение",
+            Lang::SPANISH => "continuación",
+            Lang::ENGLISH | _ => "continued",
+        }
+    }
+
     fn get_separator(&self, styles: StyleChain) -> Content {
         self.separator(styles).unwrap_or_else(|| {
             TextElem::packed(Self::local_separator(
@@ -579,6 +717,14 @@ impl Show for Packed<FigureCaption> {
             realized = supplement + numbers + self.get_separator(styles) + realized;
         }
 
+        if self.continued().copied().unwrap_or(false) {
+            let marker = FigureCaption::local_continued(
+                TextElem::lang_in(styles),
+                TextElem::region_in(styles),
+            );
+            realized += TextElem::packed(eco_format!(" ({marker})"));
+        }
+
         Ok(realized)
     }
 }
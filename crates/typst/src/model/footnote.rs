@@ -4,10 +4,12 @@ use std::str::FromStr;
 use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, scope, Content, Label, NativeElement, Packed, Show, ShowSet, Smart,
-    StyleChain, Styles,
+    cast, elem, scope, select_where, Cast, Content, Label, NativeElement, Packed, Show,
+    ShowSet, Smart, Str, StyleChain, Styles,
+};
+use crate::introspection::{
+    Count, Counter, CounterKey, CounterUpdate, Locatable, Location,
 };
-use crate::introspection::{Count, Counter, CounterUpdate, Locatable, Location};
 use crate::layout::{Abs, Em, HElem, Length, Ratio};
 use crate::model::{Destination, Numbering, NumberingPattern, ParElem};
 use crate::text::{SuperElem, TextElem, TextSize};
@@ -70,6 +72,30 @@ pub struct FootnoteElem {
     #[default(Numbering::Pattern(NumberingPattern::from_str("1").unwrap()))]
     pub numbering: Numbering,
 
+    /// The note stream to which the footnote belongs.
+    ///
+    /// Critical editions often have several independent series of notes, for
+    /// instance an apparatus of variant readings next to the translator's
+    /// notes. Each named stream is numbered on its own and its entries are
+    /// listed in an area of their own with a separate separator. The areas
+    /// are stacked below the entries of the default stream (`{none}`), in the
+    /// alphabetical order of the streams' names. To number a stream
+    /// differently, use a show-set rule.
+    ///
+    /// The footnotes of a named stream are counted by
+    /// `{counter(footnote.where(stream: name))}`, so `{counter(footnote)}`
+    /// only counts the footnotes of the default stream.
+    ///
+    /// ```example
+    /// #show footnote.where(stream: "app"): set footnote(numbering: "a")
+    ///
+    /// Arma virumque cano,#footnote[Sing.]
+    /// Troiae qui primus ab
+    /// oris#footnote(stream: "app")[_oras_ in some manuscripts.]
+    /// ```
+    #[borrowed]
+    pub stream: Option<Str>,
+
     /// The content to put into the footnote. Can also be the label of another
     /// footnote this one should point to.
     #[required]
@@ -110,29 +136,55 @@ impl FootnoteElem {
 impl Packed<FootnoteElem> {
     /// Returns the location of the definition of this footnote.
     pub fn declaration_location(&self, engine: &Engine) -> StrResult<Location> {
+        Ok(self.declaration(engine)?.location().unwrap())
+    }
+
+    /// Returns the footnote that defines this one's content.
+    fn declaration(&self, engine: &Engine) -> StrResult<Packed<FootnoteElem>> {
         match self.body() {
             FootnoteBody::Reference(label) => {
                 let element = engine.introspector.query_label(*label)?;
                 let footnote = element
                     .to_packed::<FootnoteElem>()
                     .ok_or("referenced element should be a footnote")?;
-                footnote.declaration_location(engine)
+                footnote.declaration(engine)
             }
-            _ => Ok(self.location().unwrap()),
+            _ => Ok(self.clone()),
+        }
+    }
+
+    /// The counter that numbers the footnotes of this footnote's stream.
+    pub fn counter(&self) -> Counter {
+        match self.stream(StyleChain::default()) {
+            None => Counter::of(FootnoteElem::elem()),
+            Some(stream) => Counter::new(CounterKey::Selector(select_where!(
+                FootnoteElem,
+                Stream => Some(stream.clone()),
+            ))),
         }
     }
+
+    /// Displays the number of this footnote, which must be a declaration.
+    pub(crate) fn display_number(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        numbering: &Numbering,
+    ) -> SourceResult<Content> {
+        let loc = self.location().unwrap();
+        self.counter().display_at_loc(engine, loc, styles, numbering)
+    }
 }
 
 impl Show for Packed<FootnoteElem> {
     #[typst_macros::time(name = "footnote", span = self.span())]
     fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
         let span = self.span();
-        let loc = self.declaration_location(engine).at(span)?;
+        let declaration = self.declaration(engine).at(span)?;
         let numbering = self.numbering(styles);
-        let counter = Counter::of(FootnoteElem::elem());
-        let num = counter.display_at_loc(engine, loc, styles, numbering)?;
+        let num = declaration.display_number(engine, styles, numbering)?;
         let sup = SuperElem::new(num).pack().spanned(span);
-        let loc = loc.variant(1);
+        let loc = declaration.location().unwrap().variant(1);
         // Add zero-width weak spacing to make the footnote "sticky".
         Ok(HElem::hole().pack() + sup.linked(Destination::Location(loc)))
     }
@@ -140,7 +192,21 @@ impl Show for Packed<FootnoteElem> {
 
 impl Count for Packed<FootnoteElem> {
     fn update(&self) -> Option<CounterUpdate> {
-        (!self.is_ref()).then(|| CounterUpdate::Step(NonZeroUsize::ONE))
+        // The footnotes of named streams are counted by their own counters.
+        (!self.is_ref() && self.stream(StyleChain::default()).is_none())
+            .then(|| CounterUpdate::Step(NonZeroUsize::ONE))
+    }
+
+    fn update_for(&self, key: &CounterKey) -> Option<CounterUpdate> {
+        // Although `counter(footnote)` selects the footnotes of all streams,
+        // it only counts those of the default stream. Counters with more
+        // specific selectors count all footnotes they select.
+        let generic = Counter::new(key.clone()) == Counter::of(FootnoteElem::elem());
+        if generic {
+            self.update()
+        } else {
+            (!self.is_ref()).then(|| CounterUpdate::Step(NonZeroUsize::ONE))
+        }
     }
 }
 
@@ -263,6 +329,53 @@ pub struct FootnoteEntry {
     /// ```
     #[default(Em::new(1.0).into())]
     pub indent: Length,
+
+    /// Where the entries are placed in a page with multiple
+    /// [columns]($page.columns).
+    ///
+    /// - `{"column"}`: At the bottom of the column in which the footnote
+    ///   occurs.
+    /// - `{"page"}`: In a single area spanning all columns, below them. Such
+    ///   entries are never split across pages.
+    ///
+    /// ```example
+    /// #set page(columns: 2, height: 120pt)
+    /// #set footnote.entry(scope: "page")
+    ///
+    /// Left#footnote[One.]
+    /// #colbreak()
+    /// Right#footnote[Two.]
+    /// ```
+    #[default(FootnoteScope::Column)]
+    pub scope: FootnoteScope,
+
+    /// A marker appended to an entry that is split across pages, at the end
+    /// of every part except for the last one.
+    ///
+    /// ```example
+    /// #set page(height: 100pt)
+    /// #set footnote.entry(
+    ///   continued: align(end)[_(continued)_],
+    ///   continuation: [_(continuation)_ ],
+    /// )
+    ///
+    /// #v(3em)
+    /// A long note #footnote(lorem(14))
+    /// ```
+    pub continued: Option<Content>,
+
+    /// A prefix for the remainder of an entry that is split across pages,
+    /// placed at the start of every part except for the first one.
+    pub continuation: Option<Content>,
+}
+
+/// Where footnote entries are placed in a page with multiple columns.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum FootnoteScope {
+    /// At the bottom of the column in which the footnote occurs.
+    Column,
+    /// Below all columns of the page.
+    Page,
 }
 
 impl Show for Packed<FootnoteEntry> {
@@ -273,7 +386,6 @@ impl Show for Packed<FootnoteEntry> {
         let number_gap = Em::new(0.05);
         let default = StyleChain::default();
         let numbering = note.numbering(default);
-        let counter = Counter::of(FootnoteElem::elem());
         let Some(loc) = note.location() else {
             bail!(
                 span, "footnote entry must have a location";
@@ -281,7 +393,7 @@ impl Show for Packed<FootnoteEntry> {
             );
         };
 
-        let num = counter.display_at_loc(engine, loc, styles, numbering)?;
+        let num = note.display_number(engine, styles, numbering)?;
         let sup = SuperElem::new(num)
             .pack()
             .spanned(span)
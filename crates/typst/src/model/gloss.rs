@@ -0,0 +1,300 @@
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+
+use crate::diag::{HintedStrResult, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    cast, elem, Array, Content, NativeElement, Packed, Show, Smart, StyleChain,
+    StyledElem, Synthesize, Value,
+};
+use crate::introspection::{Count, Counter, CounterUpdate, Locatable, Locator};
+use crate::layout::{
+    Abs, Axes, BlockChild, BlockElem, BoxElem, Em, Frame, HElem, InlineElem, InlineItem,
+    Length, Point, Regions, Size, Sizing,
+};
+use crate::model::{
+    first_baseline, Numbering, NumberingPattern, ParElem, Refable, Supplement,
+};
+use crate::text::{LinebreakElem, LocalName, SpaceElem, TextElem};
+use crate::utils::NonZeroExt;
+
+/// An interlinear gloss.
+///
+/// Glosses are the standard way to present examples in linguistics: A
+/// sentence in the source language is annotated word by word with its
+/// morphemes, followed by a free translation. Each word is aligned with its
+/// annotation, and a word and its annotation are kept together when the
+/// example wraps across multiple lines.
+///
+/// Glosses are numbered and can be referenced with their label, just like
+/// [figures]($figure) and [equations]($math.equation).
+///
+/// # Example
+/// ```example
+/// #gloss(
+///   [Der Hund schläft],
+///   [the.#smallcaps[nom] dog sleep-3#smallcaps[sg]],
+///   ['The dog is sleeping.'],
+/// ) <dog>
+///
+/// In @dog, the verb is inflected.
+/// ```
+///
+/// The words of a line are separated at spaces. To group several words or to
+/// leave a word without an annotation, pass an array instead:
+///
+/// ```example
+/// #gloss(
+///   ("Ich", "hab's", "gesehen"),
+///   ("I", "have=it", []),
+///   ['I saw it.'],
+/// )
+/// ```
+#[elem(Locatable, Synthesize, Show, Count, LocalName, Refable)]
+pub struct GlossElem {
+    /// How to [number]($numbering) the gloss.
+    ///
+    /// ```example
+    /// #set gloss(numbering: "(a)")
+    /// #gloss([Veni vidi], [come.1SG see.1SG])
+    /// ```
+    #[borrowed]
+    #[default(Some(Numbering::Pattern(NumberingPattern::from_str("(1)").unwrap())))]
+    pub numbering: Option<Numbering>,
+
+    /// The width reserved for the number in front of the gloss. Wrapped lines
+    /// and the translation are indented by this amount.
+    #[resolve]
+    #[default(Em::new(2.5).into())]
+    pub indent: Length,
+
+    /// Additional space between the aligned words, on top of a normal word
+    /// space.
+    #[resolve]
+    #[default(Em::new(0.5).into())]
+    pub gap: Length,
+
+    /// A supplement for the gloss.
+    ///
+    /// For references to glosses, this is added before the referenced number.
+    ///
+    /// If a function is specified, it is passed the referenced gloss and should
+    /// return content.
+    pub supplement: Smart<Option<Supplement>>,
+
+    /// The sentence in the source language.
+    #[required]
+    pub source: GlossWords,
+
+    /// The annotations of the words in the source, typically their morphemes.
+    #[required]
+    pub morphemes: GlossWords,
+
+    /// A free translation of the sentence.
+    #[positional]
+    pub translation: Option<Content>,
+}
+
+impl Synthesize for Packed<GlossElem> {
+    fn synthesize(
+        &mut self,
+        engine: &mut Engine,
+        styles: StyleChain,
+    ) -> SourceResult<()> {
+        let supplement = match self.as_ref().supplement(styles) {
+            Smart::Auto => TextElem::packed(Self::local_name_in(styles)),
+            Smart::Custom(None) => Content::empty(),
+            Smart::Custom(Some(supplement)) => {
+                supplement.resolve(engine, styles, [self.clone().pack()])?
+            }
+        };
+
+        self.push_supplement(Smart::Custom(Some(Supplement::Content(supplement))));
+        Ok(())
+    }
+}
+
+impl Show for Packed<GlossElem> {
+    #[typst_macros::time(name = "gloss", span = self.span())]
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let span = self.span();
+        let mut seq = vec![];
+
+        // The number sits in a box that spans the indent, so that wrapped
+        // lines align with the first word.
+        let mut indent = Abs::zero();
+        if let Some(numbering) = (**self).numbering(styles) {
+            indent = self.indent(styles);
+            let loc = self.location().unwrap();
+            let num = self.counter().display_at_loc(engine, loc, styles, numbering)?;
+            seq.push(
+                BoxElem::new()
+                    .with_width(Sizing::Rel(indent.into()))
+                    .with_body(Some(num))
+                    .pack(),
+            );
+        }
+
+        // Each word is aligned with its annotation in a column that is laid
+        // out as a unit, such that the paragraph only breaks between columns.
+        let (source, morphemes) = (&self.source().0, &self.morphemes().0);
+        let gap = self.gap(styles);
+        for i in 0..source.len().max(morphemes.len()) {
+            if i > 0 {
+                seq.push(SpaceElem::new().pack());
+                seq.push(HElem::new(gap.into()).with_weak(true).pack());
+            }
+
+            let lines = [source.get(i), morphemes.get(i)]
+                .map(|word| word.cloned().unwrap_or_default())
+                .to_vec();
+            let column = Packed::new(GlossColumn::new(lines)).spanned(span);
+            seq.push(InlineElem::layouter(column, layout_column).pack());
+        }
+
+        if let Some(translation) = self.translation(styles) {
+            seq.push(LinebreakElem::new().pack());
+            seq.push(translation);
+        }
+
+        let body =
+            Content::sequence(seq).styled(ParElem::set_hanging_indent(indent.into()));
+        Ok(BlockElem::new()
+            .with_body(Some(BlockChild::Content(body)))
+            .pack()
+            .spanned(span))
+    }
+}
+
+impl Count for Packed<GlossElem> {
+    fn update(&self) -> Option<CounterUpdate> {
+        self.numbering()
+            .is_some()
+            .then(|| CounterUpdate::Step(NonZeroUsize::ONE))
+    }
+}
+
+impl LocalName for Packed<GlossElem> {
+    const KEY: &'static str = "gloss";
+}
+
+impl Refable for Packed<GlossElem> {
+    fn supplement(&self) -> Content {
+        // After synthesis, this should always be custom content.
+        match (**self).supplement(StyleChain::default()) {
+            Smart::Custom(Some(Supplement::Content(content))) => content,
+            _ => Content::empty(),
+        }
+    }
+
+    fn counter(&self) -> Counter {
+        Counter::of(GlossElem::elem())
+    }
+
+    fn numbering(&self) -> Option<&Numbering> {
+        (**self).numbering(StyleChain::default()).as_ref()
+    }
+}
+
+/// The words of one line of a gloss.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct GlossWords(pub Vec<Content>);
+
+cast! {
+    GlossWords,
+    self => self.0.into_value(),
+    v: Array => Self(v.into_iter().map(Value::cast).collect::<HintedStrResult<_>>()?),
+    v: Content => Self(split_words(&v)),
+}
+
+/// Splits content into words at its spaces, also looking into styled content.
+fn split_words(content: &Content) -> Vec<Content> {
+    if let Some(styled) = content.to_packed::<StyledElem>() {
+        return split_words(&styled.child)
+            .into_iter()
+            .map(|word| word.styled_with_map(styled.styles.clone()))
+            .collect();
+    }
+
+    let mut words = vec![vec![]];
+    let push = |words: &mut Vec<Vec<Content>>, parts: Vec<Content>| {
+        let mut parts = parts.into_iter();
+        words.last_mut().unwrap().extend(parts.next());
+        words.extend(parts.map(|part| vec![part]));
+    };
+
+    content.sequence_recursive_for_each(&mut |child| {
+        if child.is::<SpaceElem>() {
+            words.push(vec![]);
+        } else if let Some(text) = child.to_packed::<TextElem>() {
+            let parts = text.text().split_whitespace().map(TextElem::packed).collect();
+            if text.text().starts_with(char::is_whitespace) {
+                words.push(vec![]);
+            }
+            push(&mut words, parts);
+            if text.text().ends_with(char::is_whitespace) {
+                words.push(vec![]);
+            }
+        } else if child.is::<StyledElem>() {
+            push(&mut words, split_words(child));
+        } else {
+            words.last_mut().unwrap().push(child.clone());
+        }
+    });
+
+    words
+        .into_iter()
+        .filter(|word| !word.is_empty())
+        .map(Content::sequence)
+        .collect()
+}
+
+/// A word of a gloss aligned with its annotation.
+#[elem]
+struct GlossColumn {
+    /// The word and its annotation.
+    #[required]
+    lines: Vec<Content>,
+}
+
+/// Layout a column of a gloss.
+///
+/// The lines of the column are stacked with the paragraph's leading, and the
+/// column sits on the baseline of its first line.
+fn layout_column(
+    elem: &Packed<GlossColumn>,
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    region: Size,
+) -> SourceResult<Vec<InlineItem>> {
+    let leading = ParElem::leading_in(styles);
+    let pod = Regions::one(Size::new(region.x, Abs::inf()), Axes::splat(false));
+    let mut locator = locator.split();
+
+    let mut frames = vec![];
+    for line in elem.lines() {
+        let frame = line.layout(engine, locator.next(&line.span()), styles, pod)?;
+        frames.push(frame.into_frame());
+    }
+
+    let width = frames.iter().map(Frame::width).fold(Abs::zero(), Abs::max);
+    let mut output = Frame::soft(Size::with_x(width));
+    let mut baseline = None;
+    let mut y = Abs::zero();
+    for frame in frames {
+        let ascent = first_baseline(&frame).unwrap_or(frame.height());
+        if baseline.is_none() {
+            baseline = Some(ascent);
+        } else {
+            y += leading;
+        }
+        let height = frame.height();
+        output.push_frame(Point::with_y(y), frame);
+        y += height;
+    }
+
+    output.size_mut().y = y;
+    output.set_baseline(baseline.unwrap_or_default());
+    Ok(vec![InlineItem::Frame(output)])
+}
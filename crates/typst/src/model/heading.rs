@@ -177,6 +177,25 @@ pub struct HeadingElem {
     #[default(Smart::Auto)]
     pub hanging_indent: Smart<Length>,
 
+    /// How many lines of the following paragraph must fit below the heading.
+    ///
+    /// Headings are never left alone at the end of a page or column: If the
+    /// content following the heading doesn't fit anymore, the heading moves
+    /// to the next page along with it. With this property, the heading also
+    /// moves if less than the given number of lines of the following
+    /// paragraph fit below it.
+    ///
+    /// ```example
+    /// #set page(height: 120pt)
+    /// #set heading(keep-lines-after: 2)
+    /// #lorem(18)
+    ///
+    /// = Introduction
+    /// #lorem(10)
+    /// ```
+    #[default(NonZeroUsize::ONE)]
+    pub keep_lines_after: NonZeroUsize,
+
     /// The heading's title.
     #[required]
     pub body: Content,
@@ -280,7 +299,8 @@ impl ShowSet for Packed<HeadingElem> {
         out.set(TextElem::set_weight(FontWeight::BOLD));
         out.set(BlockElem::set_above(Smart::Custom(above.into())));
         out.set(BlockElem::set_below(Smart::Custom(below.into())));
-        out.set(BlockElem::set_sticky(true));
+        out.set(BlockElem::set_keep_with_next(true));
+        out.set(BlockElem::set_keep_lines_after(self.keep_lines_after(styles)));
         out
     }
 }
@@ -0,0 +1,194 @@
+use std::num::NonZeroUsize;
+
+use crate::introspection::Introspector;
+use crate::layout::{Abs, Angle, Binding, Frame, Page, Point, Size, Transform};
+use crate::model::Document;
+
+/// How to impose the pages of a document into printer signatures.
+///
+/// A signature is a stack of sheets that are printed on both sides, folded in
+/// the middle, and bound together. Each side of a sheet holds two pages, so a
+/// sheet holds four pages in total.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Imposition {
+    /// The size of the printed sheets. The pages are centered on them.
+    ///
+    /// If this is `None`, a sheet is exactly as large as two of the largest
+    /// pages side by side.
+    pub sheet: Option<Size>,
+    /// How many pages a signature holds. Rounded up to a multiple of four.
+    ///
+    /// If this is `None`, all pages go into a single signature, which yields a
+    /// saddle-stitched booklet.
+    pub signature: Option<NonZeroUsize>,
+    /// How far the pages of each sheet are moved towards the fold, relative
+    /// to the next sheet further out.
+    ///
+    /// The inner sheets of a folded signature stick out further than the
+    /// outer ones and are trimmed more. Moving their pages inwards keeps the
+    /// margins on the outside of the pages the same.
+    pub creep: Abs,
+    /// Whether to rotate the back sides of the sheets by 180 degrees, as
+    /// needed by printers that flip the sheets along their short edge.
+    pub rotate_back: bool,
+}
+
+impl Default for Imposition {
+    fn default() -> Self {
+        Self {
+            sheet: None,
+            signature: None,
+            creep: Abs::zero(),
+            rotate_back: false,
+        }
+    }
+}
+
+impl Document {
+    /// Imposes the pages into printer signatures, producing a new document
+    /// with one page per side of a sheet, fronts and backs alternating.
+    ///
+    /// The pages of each signature are reordered such that they end up in
+    /// reading order once the sheets are folded and stacked. Signatures whose
+    /// pages don't fill their sheets are padded with blank pages at the end.
+    pub fn impose_signatures(&self, imposition: &Imposition) -> Document {
+        let page = self
+            .pages
+            .iter()
+            .map(|page| page.frame.size())
+            .reduce(Size::max)
+            .unwrap_or_default();
+        let sheet = imposition.sheet.unwrap_or(Size::new(2.0 * page.x, page.y));
+        let origin = Point::new(sheet.x / 2.0 - page.x, (sheet.y - page.y) / 2.0);
+
+        let total = self.pages.len().next_multiple_of(4);
+        let size = imposition
+            .signature
+            .map_or(total, |size| size.get().next_multiple_of(4))
+            .max(4);
+
+        let mut pages = vec![];
+        for start in (0..total).step_by(size) {
+            let count = size.min(total - start);
+            let frame = |i: usize| self.pages.get(start + i).map(|page| &page.frame);
+
+            for k in 0..count / 4 {
+                let shift = k as f64 * imposition.creep;
+                let sides = [(count - 1 - 2 * k, 2 * k), (2 * k + 1, count - 2 - 2 * k)];
+                for (back, (left, right)) in sides.into_iter().enumerate() {
+                    let mut side = Frame::hard(sheet);
+                    if let Some(left) = frame(left) {
+                        let pos = origin + Point::with_x(page.x - left.width() + shift);
+                        side.push_frame(pos, left.clone());
+                    }
+                    if let Some(right) = frame(right) {
+                        let pos = origin + Point::with_x(page.x - shift);
+                        side.push_frame(pos, right.clone());
+                    }
+
+                    if back == 1 && imposition.rotate_back {
+                        let turn = Transform::rotate(Angle::deg(180.0));
+                        side.transform(
+                            Transform::translate(sheet.x, sheet.y).pre_concat(turn),
+                        );
+                    }
+
+                    pages.push(Page {
+                        frame: side,
+                        numbering: None,
+                        number: pages.len() + 1,
+                        spine: Binding::Left,
+                        trim: Abs::zero(),
+                        bleed: Abs::zero(),
+                    });
+                }
+            }
+        }
+
+        let mut introspector = Introspector::default();
+        introspector.rebuild(&pages);
+
+        Document {
+            pages,
+            title: self.title.clone(),
+            author: self.author.clone(),
+            keywords: self.keywords.clone(),
+            date: self.date,
+            iterations: self.iterations,
+            spreads: false,
+            introspector,
+            incomplete: self.incomplete,
+            divergences: self.divergences.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::FrameItem;
+
+    /// Imposes pages of distinct heights and identifies them on the sides of
+    /// the sheets by their heights.
+    fn impose(pages: usize, imposition: Imposition) -> Vec<Vec<(usize, Abs)>> {
+        let pages = (1..=pages)
+            .map(|i| Page {
+                frame: Frame::hard(Size::new(Abs::pt(10.0), Abs::pt(i as f64))),
+                numbering: None,
+                number: i,
+                spine: Binding::Left,
+                trim: Abs::zero(),
+                bleed: Abs::zero(),
+            })
+            .collect();
+        let document = Document { pages, ..Default::default() };
+        document
+            .impose_signatures(&imposition)
+            .pages
+            .iter()
+            .map(|page| {
+                page.frame
+                    .items()
+                    .map(|(pos, item)| match item {
+                        FrameItem::Group(group) => {
+                            (group.frame.height().to_pt() as usize, pos.x)
+                        }
+                        _ => panic!("unexpected item"),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_impose_booklet() {
+        let pt = Abs::pt;
+        let sides = impose(6, Imposition::default());
+        assert_eq!(
+            sides,
+            [
+                vec![(1, pt(10.0))],
+                vec![(2, pt(0.0))],
+                vec![(6, pt(0.0)), (3, pt(10.0))],
+                vec![(4, pt(0.0)), (5, pt(10.0))],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_impose_signatures_with_creep() {
+        let pt = Abs::pt;
+        let imposition = Imposition {
+            signature: Some(NonZeroUsize::new(4).unwrap()),
+            creep: pt(1.0),
+            ..Default::default()
+        };
+        let sides = impose(8, imposition);
+        assert_eq!(sides[2], [(8, pt(0.0)), (5, pt(10.0))]);
+        assert_eq!(sides[3], [(6, pt(0.0)), (7, pt(10.0))]);
+
+        let imposition = Imposition { creep: pt(1.0), ..Default::default() };
+        let sides = impose(8, imposition);
+        assert_eq!(sides[2], [(6, pt(1.0)), (3, pt(9.0))]);
+    }
+}
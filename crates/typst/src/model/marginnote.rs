@@ -0,0 +1,230 @@
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Cast, Content, NativeElement, Packed, Show, StyleChain};
+use crate::introspection::{Locatable, Locator, Tag};
+use crate::layout::{
+    Abs, Axes, Binding, Em, Frame, FrameItem, InlineElem, InlineItem, Length, Point,
+    Regions, Sides, Size, Transform,
+};
+
+/// A note in the page margin.
+///
+/// Margin notes hold remarks right next to the text they refer to. A margin
+/// note is placed into the left or right margin of the page, such that the
+/// baseline of its first line is aligned with the baseline of the line in which
+/// it is called. If a note would overlap with a previous note in the same
+/// margin, it is moved down.
+///
+/// The width of a margin note is determined by the width of the margin, minus
+/// the [clearance]($marginnote.clearance) on both sides of the note. You thus
+/// typically want to widen the margin in which you place notes.
+///
+/// # Example
+/// ```example
+/// #set page(margin: (right: 70pt))
+/// #set par(justify: true)
+///
+/// The cell is the basic unit of
+/// life.#marginnote[
+///   #set text(0.8em)
+///   From Latin _cella_, a small room.
+/// ] All living organisms are
+/// composed of cells.
+/// ```
+///
+/// Like a [footnote], a margin note attaches itself to the preceding word if
+/// there is a space before it in the markup.
+///
+/// _Note:_ Set and show rules in the scope where `marginnote` is called do not
+/// apply to the note's content, which is laid out with the page's styles.
+#[elem(name = "marginnote", title = "Margin Note", Locatable, Show)]
+pub struct MarginNoteElem {
+    /// In which margin to place the note.
+    ///
+    /// - `{"outside"}`: The margin opposite to the page's
+    ///   [binding]($page.binding), i.e. the right margin for left-bound pages.
+    /// - `{"inside"}`: The margin at the page's binding.
+    /// - `{"left"}`/`{"right"}`: Always the left or right margin.
+    ///
+    /// If the page has [two-sided]($page.margin) margins, the inside and
+    /// outside margins alternate between left and right pages.
+    ///
+    /// ```example
+    /// #set page(margin: (x: 60pt))
+    /// A note#marginnote(side: "left")[
+    ///   Left.
+    /// ] and another
+    /// one.#marginnote(side: "right")[
+    ///   Right.
+    /// ]
+    /// ```
+    #[default(MarginSide::Outside)]
+    pub side: MarginSide,
+
+    /// The amount of clearance between a note and the page's body as well as
+    /// between the note and the edge of the page.
+    #[default(Em::new(1.0).into())]
+    #[resolve]
+    pub clearance: Length,
+
+    /// The minimum gap between two consecutive notes in the same margin.
+    #[default(Em::new(0.5).into())]
+    #[resolve]
+    pub gap: Length,
+
+    /// The content to put into the margin.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for Packed<MarginNoteElem> {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(InlineElem::layouter(self.clone(), layout_anchor)
+            .pack()
+            .spanned(self.span()))
+    }
+}
+
+/// In which margin a note is placed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum MarginSide {
+    /// The margin at the page's binding.
+    Inside,
+    /// The margin opposite to the page's binding.
+    Outside,
+    /// The left margin.
+    Left,
+    /// The right margin.
+    Right,
+}
+
+impl MarginSide {
+    /// Whether this is the left margin of a page bound at `binding`.
+    pub(crate) fn is_left(self, binding: Binding) -> bool {
+        match self {
+            Self::Inside => binding == Binding::Left,
+            Self::Outside => binding == Binding::Right,
+            Self::Left => true,
+            Self::Right => false,
+        }
+    }
+}
+
+/// Layout the anchor of a margin note.
+///
+/// The anchor is an empty frame on the baseline of the line in which the note
+/// is called. It holds a tag from which the page picks up the note.
+fn layout_anchor(
+    elem: &Packed<MarginNoteElem>,
+    _: &mut Engine,
+    _: Locator,
+    _: StyleChain,
+    _: Size,
+) -> SourceResult<Vec<InlineItem>> {
+    let loc = elem.location().unwrap().variant(1);
+    let mut anchor = MarginNoteAnchor::new(elem.clone().pack()).pack();
+    anchor.set_location(loc);
+
+    let mut frame = Frame::soft(Size::zero());
+    frame.push(Point::zero(), FrameItem::Tag(Tag::new(anchor, loc.hash())));
+    Ok(vec![InlineItem::Frame(frame)])
+}
+
+/// Marks the position of a margin note in the page's body.
+///
+/// This is distinct from the note's own tag, since the latter is not always on
+/// the baseline of the note's line.
+#[elem]
+struct MarginNoteAnchor {
+    /// The margin note.
+    #[required]
+    note: Content,
+}
+
+/// Lays out the margin notes anchored in a page's body and adds them to it.
+///
+/// The `frame` is the page's body without the `margin`, into which the notes
+/// are placed. The `binding` is the edge at which this specific page is bound.
+pub(crate) fn layout_margin_notes(
+    engine: &mut Engine,
+    frame: &mut Frame,
+    margin: Sides<Abs>,
+    binding: Binding,
+    styles: StyleChain,
+) -> SourceResult<()> {
+    let mut notes = vec![];
+    collect_anchors(frame, Transform::identity(), &mut notes);
+    notes.sort_by_key(|&(y, _)| y);
+
+    // The bottom edges of the last notes in the left and right margins.
+    let mut bottoms = [None; 2];
+    for (y, note) in notes {
+        let left = note.side(styles).is_left(binding);
+        let clearance = note.clearance(styles);
+        let space = if left { margin.left } else { margin.right };
+        let width = (space - 2.0 * clearance).max(Abs::zero());
+        let pod = Regions::one(Size::new(width, Abs::inf()), Axes::new(true, false));
+        let locator = Locator::synthesize(note.location().unwrap());
+        let sub = note.body().layout(engine, locator, styles, pod)?.into_frame();
+
+        // Align the baselines and move the note below the previous one.
+        let mut top = y - first_baseline(&sub).unwrap_or_default();
+        let bottom: &mut Option<Abs> = &mut bottoms[usize::from(left)];
+        if let Some(prev) = *bottom {
+            top.set_max(prev + note.gap(styles));
+        }
+        *bottom = Some(top + sub.height());
+
+        let x = if left { clearance - margin.left } else { frame.width() + clearance };
+        frame.push_frame(Point::new(x, top), sub);
+    }
+
+    Ok(())
+}
+
+/// Collects the margin notes anchored in a frame along with the vertical
+/// positions of their anchors.
+fn collect_anchors(
+    frame: &Frame,
+    ts: Transform,
+    notes: &mut Vec<(Abs, Packed<MarginNoteElem>)>,
+) {
+    for (pos, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => {
+                let ts = ts
+                    .pre_concat(Transform::translate(pos.x, pos.y))
+                    .pre_concat(group.transform);
+                collect_anchors(&group.frame, ts, notes);
+            }
+            FrameItem::Tag(tag) => {
+                if let Some(note) = tag
+                    .elem
+                    .to_packed::<MarginNoteAnchor>()
+                    .and_then(|anchor| anchor.note().to_packed::<MarginNoteElem>())
+                {
+                    notes.push((pos.transform(ts).y, note.clone()));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The position of the first baseline in a frame, if it contains any text.
+pub(crate) fn first_baseline(frame: &Frame) -> Option<Abs> {
+    frame.items().find_map(|(pos, item)| match item {
+        FrameItem::Group(group) => first_baseline(&group.frame).map(|y| pos.y + y),
+        FrameItem::Text(_) => Some(pos.y),
+        _ => None,
+    })
+}
+
+/// The position of the last baseline in a frame, if it contains any text.
+pub(crate) fn last_baseline(frame: &Frame) -> Option<Abs> {
+    frame.items().rev().find_map(|(pos, item)| match item {
+        FrameItem::Group(group) => last_baseline(&group.frame).map(|y| pos.y + y),
+        FrameItem::Text(_) => Some(pos.y),
+        _ => None,
+    })
+}
@@ -1,6 +1,8 @@
 //! Structuring elements that define the document model.
 
+mod apply;
 mod bibliography;
+mod blank;
 mod cite;
 mod document;
 mod emph;
@@ -8,37 +10,49 @@ mod emph;
 mod enum_;
 mod figure;
 mod footnote;
+mod gloss;
 mod heading;
+mod impose;
 mod link;
 mod list;
+mod marginnote;
 #[path = "numbering.rs"]
 mod numbering_;
 mod outline;
 mod par;
 mod quote;
 mod reference;
+mod sidenote;
 mod strong;
 mod table;
 mod terms;
+mod verse;
 
+pub use self::apply::*;
 pub use self::bibliography::*;
+pub use self::blank::*;
 pub use self::cite::*;
 pub use self::document::*;
 pub use self::emph::*;
 pub use self::enum_::*;
 pub use self::figure::*;
 pub use self::footnote::*;
+pub use self::gloss::*;
 pub use self::heading::*;
+pub use self::impose::*;
 pub use self::link::*;
 pub use self::list::*;
+pub use self::marginnote::*;
 pub use self::numbering_::*;
 pub use self::outline::*;
 pub use self::par::*;
 pub use self::quote::*;
 pub use self::reference::*;
+pub use self::sidenote::*;
 pub use self::strong::*;
 pub use self::table::*;
 pub use self::terms::*;
+pub use self::verse::*;
 
 use crate::foundations::{category, Category, Scope};
 
@@ -58,17 +72,23 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<LinkElem>();
     global.define_elem::<OutlineElem>();
     global.define_elem::<HeadingElem>();
+    global.define_elem::<ApplyElem>();
     global.define_elem::<FigureElem>();
     global.define_elem::<FootnoteElem>();
+    global.define_elem::<MarginNoteElem>();
+    global.define_elem::<GlossElem>();
+    global.define_elem::<SidenoteElem>();
     global.define_elem::<QuoteElem>();
     global.define_elem::<CiteElem>();
     global.define_elem::<BibliographyElem>();
+    global.define_elem::<BlankElem>();
     global.define_elem::<EnumElem>();
     global.define_elem::<ListElem>();
     global.define_elem::<ParbreakElem>();
     global.define_elem::<ParElem>();
     global.define_elem::<TableElem>();
     global.define_elem::<TermsElem>();
+    global.define_elem::<VerseElem>();
     global.define_elem::<EmphElem>();
     global.define_elem::<StrongElem>();
     global.define_func::<numbering>();
@@ -8,7 +8,7 @@ use smallvec::{smallvec, SmallVec};
 use crate::diag::SourceResult;
 use crate::engine::Engine;
 use crate::foundations::{cast, func, Context, Func, Str, Value};
-use crate::text::Case;
+use crate::text::{localized_str, Case, Lang, Region, TextElem};
 
 /// Applies a numbering to a sequence of numbers.
 ///
@@ -42,8 +42,20 @@ pub fn numbering(
     /// Defines how the numbering works.
     ///
     /// **Counting symbols** are `1`, `a`, `A`, `i`, `I`, `一`, `壹`, `あ`, `い`, `ア`, `イ`, `א`, `가`,
-    /// `ㄱ`, and `*`. They are replaced by the number in the sequence, in the
-    /// given case.
+    /// `ㄱ`, `१`, `๑`, `first`, `First`, and `*`. They are replaced by the
+    /// number in the sequence, in the given case.
+    ///
+    /// The `first` and `First` symbols spell out the number as an ordinal word
+    /// in the [text language]($text.lang), like "second" in English or
+    /// "zweitens" in German. There are words for the numbers up to twelve in
+    /// English, French, German, Italian, and Spanish. Other languages use the
+    /// English words and larger numbers fall back to Arabic numerals. Outside
+    /// of a [context], the words are English.
+    ///
+    /// The Chinese symbols `一` and `壹` produce traditional instead of
+    /// simplified Chinese numerals if the text language is Chinese and the
+    /// [region]($text.region) is Taiwan (`TW`), Hong Kong (`HK`), or Macao
+    /// (`MO`).
     ///
     /// The `*` character means that symbols should be used to count, in the
     /// order of `*`, `†`, `‡`, `§`, `¶`, and `‖`. If there are more than six
@@ -91,7 +103,15 @@ impl Numbering {
         numbers: &[usize],
     ) -> SourceResult<Value> {
         Ok(match self {
-            Self::Pattern(pattern) => Value::Str(pattern.apply(numbers).into()),
+            Self::Pattern(pattern) => {
+                let (lang, region) = match context.styles() {
+                    Ok(styles) => {
+                        (TextElem::lang_in(styles), TextElem::region_in(styles))
+                    }
+                    Err(_) => (Lang::ENGLISH, None),
+                };
+                Value::Str(pattern.apply(numbers, lang, region).into())
+            }
             Self::Func(func) => func.call(engine, context, numbers.iter().copied())?,
         })
     }
@@ -124,8 +144,8 @@ cast! {
 /// How to turn a number into text.
 ///
 /// A pattern consists of a prefix, followed by one of
-/// `1`, `a`, `A`, `i`, `I`, `一`, `壹`, `あ`, `い`, `ア`, `イ`, `א`, `가`, `ㄱ`, `*`, `①`, or `⓵`,
-/// and then a suffix.
+/// `1`, `a`, `A`, `i`, `I`, `一`, `壹`, `あ`, `い`, `ア`, `イ`, `א`, `가`, `ㄱ`, `१`, `๑`,
+/// `first`, `First`, `*`, `①`, or `⓵`, and then a suffix.
 ///
 /// Examples of valid patterns:
 /// - `1)`
@@ -140,7 +160,15 @@ pub struct NumberingPattern {
 
 impl NumberingPattern {
     /// Apply the pattern to the given number.
-    pub fn apply(&self, numbers: &[usize]) -> EcoString {
+    ///
+    /// The language and region determine the words of an ordinal numbering
+    /// and the variant of Chinese numerals.
+    pub fn apply(
+        &self,
+        numbers: &[usize],
+        lang: Lang,
+        region: Option<Region>,
+    ) -> EcoString {
         let mut fmt = EcoString::new();
         let mut numbers = numbers.iter();
 
@@ -150,7 +178,7 @@ impl NumberingPattern {
             if i > 0 || !self.trimmed {
                 fmt.push_str(prefix);
             }
-            fmt.push_str(&kind.apply(n, *case));
+            fmt.push_str(&kind.apply(n, *case, lang, region));
         }
 
         for ((prefix, kind, case), &n) in
@@ -161,7 +189,7 @@ impl NumberingPattern {
             } else {
                 fmt.push_str(prefix);
             }
-            fmt.push_str(&kind.apply(n, *case));
+            fmt.push_str(&kind.apply(n, *case, lang, region));
         }
 
         if !self.trimmed {
@@ -172,7 +200,13 @@ impl NumberingPattern {
     }
 
     /// Apply only the k-th segment of the pattern to a number.
-    pub fn apply_kth(&self, k: usize, number: usize) -> EcoString {
+    pub fn apply_kth(
+        &self,
+        k: usize,
+        number: usize,
+        lang: Lang,
+        region: Option<Region>,
+    ) -> EcoString {
         let mut fmt = EcoString::new();
         if let Some((prefix, _, _)) = self.pieces.first() {
             fmt.push_str(prefix);
@@ -183,7 +217,7 @@ impl NumberingPattern {
             .chain(self.pieces.last().into_iter().cycle())
             .nth(k)
         {
-            fmt.push_str(&kind.apply(number, *case));
+            fmt.push_str(&kind.apply(number, *case, lang, region));
         }
         fmt.push_str(&self.suffix);
         fmt
@@ -203,7 +237,19 @@ impl FromStr for NumberingPattern {
         let mut handled = 0;
 
         for (i, c) in pattern.char_indices() {
-            let Some(kind) = NumberingKind::from_char(c.to_ascii_lowercase()) else {
+            // Skip the rest of a counting word.
+            if i < handled {
+                continue;
+            }
+
+            let word = pattern.get(i..i + ORDINAL_WORD.len());
+            let (kind, len) = if word
+                .is_some_and(|w| w.eq_ignore_ascii_case(ORDINAL_WORD))
+            {
+                (NumberingKind::OrdinalWord, ORDINAL_WORD.len())
+            } else if let Some(kind) = NumberingKind::from_char(c.to_ascii_lowercase()) {
+                (kind, c.len_utf8())
+            } else {
                 continue;
             };
 
@@ -211,7 +257,7 @@ impl FromStr for NumberingPattern {
             let case =
                 if c.is_uppercase() || c == '壹' { Case::Upper } else { Case::Lower };
             pieces.push((prefix, kind, case));
-            handled = c.len_utf8() + i;
+            handled = i + len;
         }
 
         let suffix = pattern[handled..].into();
@@ -229,11 +275,15 @@ cast! {
         let mut pat = EcoString::new();
         for (prefix, kind, case) in &self.pieces {
             pat.push_str(prefix);
-            let mut c = kind.to_char();
-            if *case == Case::Upper {
-                c = c.to_ascii_uppercase();
+            let symbol = kind.symbol();
+            let mut chars = symbol.chars();
+            match (case, chars.next()) {
+                (Case::Upper, Some(c)) => {
+                    pat.push(c.to_ascii_uppercase());
+                    pat.push_str(chars.as_str());
+                }
+                _ => pat.push_str(symbol),
             }
-            pat.push(c);
         }
         pat.push_str(&self.suffix);
         pat.into_value()
@@ -255,13 +305,11 @@ pub enum NumberingKind {
     /// Hebrew numerals.
     Hebrew,
     /// Simplified Chinese numerals. Uses standard numerals for lowercase and “banknote” numerals for uppercase.
+    ///
+    /// Since the counting symbols of simplified and traditional Chinese are
+    /// the same, this switches to traditional numerals in regions that use
+    /// them.
     SimplifiedChinese,
-    // TODO: Pick the numbering pattern based on languages choice.
-    // As the `1st` numbering character of Chinese (Simplified) and
-    // Chinese (Traditional) is same, we are unable to determine
-    // if the context is Simplified or Traditional by only this
-    // character.
-    #[allow(unused)]
     /// Traditional Chinese numerals. Uses standard numerals for lowercase and “banknote” numerals for uppercase.
     TraditionalChinese,
     /// Hiragana in the gojūon order. Includes n but excludes wi and we.
@@ -284,8 +332,18 @@ pub enum NumberingKind {
     CircledNumber,
     /// Double-circled numbers (⓵, ⓶, ⓷, etc.), up to 10.
     DoubleCircledNumber,
+    /// Devanagari numerals (१, २, ३, etc.).
+    Devanagari,
+    /// Thai numerals (๑, ๒, ๓, etc.).
+    Thai,
+    /// Ordinal words in the text language (first, second, third, etc.). Uses
+    /// both cases, where uppercase capitalizes the first letter.
+    OrdinalWord,
 }
 
+/// The counting word for ordinal words.
+const ORDINAL_WORD: &str = "first";
+
 impl NumberingKind {
     /// Create a numbering kind from a lowercase character.
     pub fn from_char(c: char) -> Option<Self> {
@@ -306,35 +364,49 @@ impl NumberingKind {
             '\u{06F1}' => NumberingKind::EasternArabicPersian,
             '①' => NumberingKind::CircledNumber,
             '⓵' => NumberingKind::DoubleCircledNumber,
+            '१' => NumberingKind::Devanagari,
+            '๑' => NumberingKind::Thai,
             _ => return None,
         })
     }
 
-    /// The lowercase character for this numbering kind.
-    pub fn to_char(self) -> char {
+    /// The lowercase counting symbol for this numbering kind.
+    pub fn symbol(self) -> &'static str {
         match self {
-            Self::Arabic => '1',
-            Self::Letter => 'a',
-            Self::Roman => 'i',
-            Self::Symbol => '*',
-            Self::Hebrew => 'א',
-            Self::SimplifiedChinese => '一',
-            Self::TraditionalChinese => '一',
-            Self::HiraganaAiueo => 'あ',
-            Self::HiraganaIroha => 'い',
-            Self::KatakanaAiueo => 'ア',
-            Self::KatakanaIroha => 'イ',
-            Self::KoreanJamo => 'ㄱ',
-            Self::KoreanSyllable => '가',
-            Self::EasternArabic => '\u{0661}',
-            Self::EasternArabicPersian => '\u{06F1}',
-            Self::CircledNumber => '①',
-            Self::DoubleCircledNumber => '⓵',
+            Self::Arabic => "1",
+            Self::Letter => "a",
+            Self::Roman => "i",
+            Self::Symbol => "*",
+            Self::Hebrew => "א",
+            Self::SimplifiedChinese => "一",
+            Self::TraditionalChinese => "一",
+            Self::HiraganaAiueo => "あ",
+            Self::HiraganaIroha => "い",
+            Self::KatakanaAiueo => "ア",
+            Self::KatakanaIroha => "イ",
+            Self::KoreanJamo => "ㄱ",
+            Self::KoreanSyllable => "가",
+            Self::EasternArabic => "\u{0661}",
+            Self::EasternArabicPersian => "\u{06F1}",
+            Self::CircledNumber => "①",
+            Self::DoubleCircledNumber => "⓵",
+            Self::Devanagari => "१",
+            Self::Thai => "๑",
+            Self::OrdinalWord => ORDINAL_WORD,
         }
     }
 
     /// Apply the numbering to the given number.
-    pub fn apply(self, mut n: usize, case: Case) -> EcoString {
+    ///
+    /// The language and region are only relevant for ordinal words and
+    /// Chinese numerals.
+    pub fn apply(
+        self,
+        mut n: usize,
+        case: Case,
+        lang: Lang,
+        region: Option<Region>,
+    ) -> EcoString {
         match self {
             Self::Arabic => {
                 eco_format!("{n}")
@@ -506,11 +578,14 @@ impl NumberingKind {
                     Case::Upper => ChineseCase::Upper,
                 };
 
+                let traditional = lang == Lang::CHINESE
+                    && region.is_some_and(|r| ["TW", "HK", "MO"].contains(&r.as_str()));
+
                 match (n as u64).to_chinese(
                     match l {
-                        Self::SimplifiedChinese => ChineseVariant::Simple,
                         Self::TraditionalChinese => ChineseVariant::Traditional,
-                        _ => unreachable!(),
+                        _ if traditional => ChineseVariant::Traditional,
+                        _ => ChineseVariant::Simple,
                     },
                     chinese_case,
                     ChineseCountMethod::TenThousand,
@@ -555,6 +630,22 @@ impl NumberingKind {
                 |x| ['⓵', '⓶', '⓷', '⓸', '⓹', '⓺', '⓻', '⓼', '⓽', '⓾'][x],
                 n,
             ),
+            Self::Devanagari => decimal('\u{0966}', n),
+            Self::Thai => decimal('\u{0E50}', n),
+            Self::OrdinalWord => {
+                let Some(word) = n.checked_sub(1).and_then(|i| {
+                    localized_str(lang, region, "ordinals").split(',').nth(i)
+                }) else {
+                    return eco_format!("{n}");
+                };
+
+                let word = word.trim();
+                let mut chars = word.chars();
+                match (case, chars.next()) {
+                    (Case::Upper, Some(c)) => c.to_uppercase().chain(chars).collect(),
+                    _ => word.into(),
+                }
+            }
         }
     }
 }
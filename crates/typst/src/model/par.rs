@@ -1,13 +1,14 @@
 use std::fmt::{self, Debug, Formatter};
 
-use crate::diag::SourceResult;
+use crate::diag::{bail, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    elem, Args, Cast, Construct, Content, NativeElement, Packed, Set, Smart, StyleChain,
-    Unlabellable,
+    cast, dict, elem, scope, Args, Cast, Construct, Content, Dict, Func, NativeElement,
+    Packed, Set, Smart, StyleChain, Unlabellable,
 };
 use crate::introspection::Locator;
-use crate::layout::{Em, Fragment, Length, Size};
+use crate::layout::{Dir, Em, Exclusion, Fragment, HAlignment, Length, Ratio, Rel, Size};
+use crate::model::{MarginSide, Numbering};
 use crate::realize::StyleVec;
 
 /// Arranges text, spacing and inline-level elements into a paragraph.
@@ -35,7 +36,7 @@ use crate::realize::StyleVec;
 /// let $a$ be the smallest of the
 /// three integers. Then, we ...
 /// ```
-#[elem(title = "Paragraph", Debug, Construct)]
+#[elem(scope, title = "Paragraph", Debug, Construct)]
 pub struct ParElem {
     /// The spacing between lines.
     ///
@@ -83,12 +84,35 @@ pub struct ParElem {
     #[default(false)]
     pub justify: bool,
 
+    /// How much the glyphs of a justified line may be expanded or condensed
+    /// horizontally.
+    ///
+    /// Slightly scaling the glyphs gives justification another degree of
+    /// freedom besides the spacing between words, similar to the font
+    /// expansion of pdfTeX. On narrow measures, this noticeably reduces
+    /// excessive word spacing. The expansion is spread over the glyphs and
+    /// spaces in proportion to how much each can adjust. Keep it small, as
+    /// values beyond a few percent distort the letterforms visibly.
+    ///
+    /// ```example
+    /// #set page(width: 160pt)
+    /// #set par(justify: true)
+    /// #lorem(16)
+    ///
+    /// #set par(expansion: 2%)
+    /// #lorem(16)
+    /// ```
+    #[ghost]
+    #[default(Ratio::zero())]
+    pub expansion: Ratio,
+
     /// How to determine line breaks.
     ///
     /// When this property is set to `{auto}`, its default value, optimized line
     /// breaks will be used for justified paragraphs. Enabling optimized line
     /// breaks for ragged paragraphs may also be worthwhile to improve the
-    /// appearance of the text.
+    /// appearance of the text. Paragraphs that [wrap]($place.wrap) around a
+    /// placed element always use simple line breaks.
     ///
     /// ```example
     /// #set page(width: 207pt)
@@ -107,24 +131,195 @@ pub struct ParElem {
     #[ghost]
     pub linebreaks: Smart<Linebreaks>,
 
+    /// How far the spaces of a line may be stretched before the line is
+    /// considered too loose, or `{none}` for no limit.
+    ///
+    /// At `{100%}`, the spaces of a line are stretched by the full amount they
+    /// are designed to stretch. Beyond that, the gaps between words become
+    /// increasingly noticeable. Lines that would have to be stretched more
+    /// than the tolerance are only chosen if there is no better way to break
+    /// the paragraph. A lower tolerance thus yields more even spacing, at the
+    /// expense of more hyphenation.
+    ///
+    /// This only affects [optimized]($par.linebreaks) line breaking. The
+    /// penalties for hyphenation, runts, and consecutive hyphens can be
+    /// adjusted through the text's [`costs`]($text.costs).
+    ///
+    /// ```example
+    /// #set page(width: 180pt)
+    /// #set par(justify: true)
+    /// #set text(hyphenate: true)
+    /// #lorem(20)
+    ///
+    /// #set par(tolerance: 100%)
+    /// #lorem(20)
+    /// ```
+    #[ghost]
+    pub tolerance: Option<Ratio>,
+
+    /// How much of the paragraph's width its last line should preferably
+    /// fill.
+    ///
+    /// A last line that is shorter than this is penalized the more, the
+    /// shorter it is. The line breaker then prefers to move more words to the
+    /// last line if this doesn't make the other lines too loose or too
+    /// tight.
+    ///
+    /// Like the [`tolerance`]($par.tolerance), this only affects
+    /// [optimized]($par.linebreaks) line breaking.
+    ///
+    /// ```example
+    /// #set page(width: 180pt)
+    /// #set par(justify: true)
+    /// #lorem(21)
+    ///
+    /// #set par(last-line-fill: 50%)
+    /// #lorem(21)
+    /// ```
+    #[ghost]
+    #[default(Ratio::zero())]
+    pub last_line_fill: Ratio,
+
+    /// The minimum length of the paragraph's last line.
+    ///
+    /// A last line that is shorter than this is avoided at almost any cost:
+    /// The line breaker rather makes the previous lines a bit looser or
+    /// tighter to move more words to the last line. Only if there is no other
+    /// way to break the paragraph, the last line stays shorter. Relative
+    /// lengths are relative to the width of the paragraph.
+    ///
+    /// In contrast to the [`last-line-fill`]($par.last-line-fill), which is
+    /// only a preference, this is meant to prevent _runts,_ i.e. lonely words
+    /// on the last line of a paragraph. The penalty for runts that are still
+    /// longer than the minimum can be adjusted through the text's
+    /// [`costs`]($text.costs). Like the other line breaking costs, this only
+    /// affects [optimized]($par.linebreaks) line breaking.
+    ///
+    /// ```example
+    /// #set page(width: 180pt)
+    /// #set par(justify: true)
+    /// #lorem(25)
+    ///
+    /// #set par(last-line-min: 25%)
+    /// #lorem(25)
+    /// ```
+    #[ghost]
+    #[resolve]
+    pub last_line_min: Rel<Length>,
+
     /// The indent the first line of a paragraph should have.
     ///
-    /// Only the first line of a consecutive paragraph will be indented (not
-    /// the first one in a block or on the page).
+    /// By default, only the first line of a consecutive paragraph will be
+    /// indented (not the first one in a block, on the page, or after a
+    /// heading). To indent all paragraphs, as is customary in French and
+    /// other continental typography, pass a dictionary with the following
+    /// keys instead of a plain length:
+    ///
+    /// - `amount`: The indent of the first line.
+    /// - `all`: Whether to indent all paragraphs, not just consecutive ones.
+    ///   Defaults to `{false}`.
     ///
     /// By typographic convention, paragraph breaks are indicated either by some
     /// space between paragraphs or by indented first lines. Consider reducing
     /// the [paragraph spacing]($block.spacing) to the [`leading`]($par.leading)
     /// when using this property (e.g. using
     /// `[#show par: set block(spacing: 0.65em)]`).
+    ///
+    /// ```example
+    /// #set par(first-line-indent: (
+    ///   amount: 1em,
+    ///   all: true,
+    /// ))
+    ///
+    /// = Introduction
+    /// The first paragraph after the
+    /// heading is indented, too.
+    ///
+    /// And so is the second one.
+    /// ```
     #[ghost]
-    pub first_line_indent: Length,
+    pub first_line_indent: FirstLineIndent,
 
     /// The indent all but the first line of a paragraph should have.
     #[ghost]
     #[resolve]
     pub hanging_indent: Length,
 
+    /// The tab stops of the paragraph.
+    ///
+    /// If any stops are given, a tab character in the paragraph advances the
+    /// text following it to the next stop. A stop can be given as a length,
+    /// which is its distance from the start of the paragraph, or as a
+    /// dictionary with the following keys:
+    ///
+    /// - `pos`: The distance of the stop from the start of the paragraph.
+    /// - `align`: How to align the text between the tab and the next tab or
+    ///   the end of the line at the stop. This can be a horizontal
+    ///   [alignment] or a string with a single character, like `{"."}`, at
+    ///   whose first occurrence the text is aligned. Defaults to `{start}`.
+    ///
+    /// In markup, a tab can be inserted with a string, as in `[#"\t"]`. A tab
+    /// beyond the last stop is as wide as a space. Without any stops, tabs are
+    /// treated like any other text.
+    ///
+    /// ```example
+    /// #set par(tab-stops: (
+    ///   2.5cm,
+    ///   (pos: 5cm, align: right),
+    ///   (pos: 6.5cm, align: "."),
+    /// ))
+    /// #let row(..cells) = cells.pos().join("\t")
+    ///
+    /// #row[Bread][bakery][2][4.50] \
+    /// #row[Cheese][dairy][12][17.125] \
+    /// #row[Apples][market][108][0.9]
+    /// ```
+    #[ghost]
+    pub tab_stops: Vec<TabStop>,
+
+    /// A function that varies the indents of the paragraph from line to line.
+    ///
+    /// The function receives the index of a line, starting at zero, and the
+    /// width available to the paragraph. It must return the line's indents as
+    /// a dictionary with `left` and `right` keys. Both are optional and
+    /// relative lengths are relative to the paragraph's width. With this, you
+    /// can, for instance, set a paragraph in a triangular or stepped shape or
+    /// keep room for an image next to its first lines.
+    ///
+    /// Lines of a shaped paragraph are always broken in a first-fit manner,
+    /// as if the [`linebreaks`]($par.linebreaks) were set to `{"simple"}`.
+    ///
+    /// ```example
+    /// #set page(width: 200pt)
+    /// #set par(
+    ///   justify: true,
+    ///   shape: (i, width) => (
+    ///     left: calc.min(i * 12pt, width / 3),
+    ///     right: calc.min(i * 12pt, width / 3),
+    ///   ),
+    /// )
+    /// #lorem(30)
+    /// ```
+    #[ghost]
+    pub shape: Option<Func>,
+
+    /// Whether to warn about typographic problems in the paragraph.
+    ///
+    /// When enabled, Typst reports lines whose spaces had to be stretched or
+    /// shrunk beyond their limits during justification, justified lines that
+    /// are stretched beyond the [`tolerance`]($par.tolerance), three or more
+    /// consecutive lines that end with a hyphen, and "rivers", that is,
+    /// spaces in three or more successive lines that line up and form a
+    /// conspicuous vertical gap. The warnings point to the affected text, so
+    /// that you can reword it or adjust the hyphenation. Together, they form
+    /// a report of the lines that need attention when proofreading.
+    ///
+    /// This is meant for proofreading and disabled by default. You can enable
+    /// it for the whole document with `{set par(diagnose: true)}`.
+    #[ghost]
+    #[default(false)]
+    pub diagnose: bool,
+
     /// Indicates wheter an overflowing line should be shrunk.
     ///
     /// This property is set to `false` on raw blocks, because shrinking a line
@@ -145,6 +340,12 @@ pub struct ParElem {
     pub children: StyleVec,
 }
 
+#[scope]
+impl ParElem {
+    #[elem]
+    type ParLineNumbering;
+}
+
 impl Construct for ParElem {
     fn construct(engine: &mut Engine, args: &mut Args) -> SourceResult<Content> {
         // The paragraph constructor is special: It doesn't create a paragraph
@@ -161,8 +362,10 @@ impl Construct for ParElem {
 }
 
 impl Packed<ParElem> {
-    /// Layout the paragraph into a collection of lines.
+    /// Layout the paragraph into a collection of lines, flowing them around
+    /// the given exclusions.
     #[typst_macros::time(name = "par", span = self.span())]
+    #[allow(clippy::too_many_arguments)]
     pub fn layout(
         &self,
         engine: &mut Engine,
@@ -171,6 +374,7 @@ impl Packed<ParElem> {
         consecutive: bool,
         region: Size,
         expand: bool,
+        exclusions: &[Exclusion],
     ) -> SourceResult<Fragment> {
         crate::layout::layout_inline(
             &self.children,
@@ -180,6 +384,7 @@ impl Packed<ParElem> {
             consecutive,
             region,
             expand,
+            exclusions,
         )
     }
 }
@@ -191,6 +396,223 @@ impl Debug for ParElem {
     }
 }
 
+/// Numbers the lines of paragraphs.
+///
+/// This element can only be used in set rules. Once a
+/// [numbering]($par.line-numbering.numbering) is set, each line of the
+/// paragraphs in the page's flow is numbered, with the numbers placed in the
+/// page margin next to the lines. Lines in containers, such as the cells of a
+/// table, are not numbered.
+///
+/// # Example
+/// ```example
+/// #set page(margin: (left: 30pt))
+/// #set par.line-numbering(numbering: "1")
+///
+/// Roses are red. \
+/// Violets are blue. \
+/// Typst is there for you.
+/// ```
+///
+/// Line numbers are commonly required for manuscripts submitted for review
+/// and in legal documents. To keep the lines of a certain part of the document
+/// from being numbered, you can disable the numbering for it:
+///
+/// ```example
+/// #set page(margin: (left: 30pt))
+/// #set par.line-numbering(numbering: "1")
+///
+/// This is numbered.
+///
+/// #[
+///   #set par.line-numbering(numbering: none)
+///   This is not.
+/// ]
+///
+/// This is numbered again.
+/// ```
+#[elem(name = "line-numbering", title = "Paragraph Line Numbering", Construct)]
+pub struct ParLineNumbering {
+    /// How to number the lines. Accepts a
+    /// [numbering pattern or function]($numbering).
+    ///
+    /// If set to `{none}`, lines are not numbered.
+    ///
+    /// ```example
+    /// #set page(margin: (left: 30pt))
+    /// #set par.line-numbering(
+    ///   numbering: n => if calc.rem(n, 2) == 0 [#n],
+    /// )
+    ///
+    /// #lorem(20)
+    /// ```
+    pub numbering: Option<Numbering>,
+
+    /// When to restart the line numbers.
+    ///
+    /// ```example
+    /// #set page(margin: (left: 30pt))
+    /// #set par.line-numbering(
+    ///   numbering: "1",
+    ///   scope: "section",
+    /// )
+    ///
+    /// = Introduction
+    /// Lines are numbered.
+    ///
+    /// = Background
+    /// From the start.
+    /// ```
+    #[default(LineNumberingScope::Document)]
+    pub scope: LineNumberingScope,
+
+    /// In which margin to place the line numbers.
+    ///
+    /// See the [`side`]($marginnote.side) of margin notes for the meaning of
+    /// the possible values.
+    #[default(MarginSide::Left)]
+    pub side: MarginSide,
+
+    /// The distance between the line numbers and the text.
+    #[default(Em::new(1.0).into())]
+    #[resolve]
+    pub clearance: Length,
+}
+
+impl Construct for ParLineNumbering {
+    fn construct(_: &mut Engine, args: &mut Args) -> SourceResult<Content> {
+        bail!(args.span, "cannot be constructed manually");
+    }
+}
+
+/// Marks a line of a paragraph for line numbering.
+///
+/// Paragraph layout adds a marker without a location on the baseline of each
+/// line if line numbering is enabled. The flow then assigns locations to the
+/// markers of the lines it numbers and removes the others.
+#[elem]
+pub(crate) struct ParLineMarker {
+    /// How to number the line.
+    #[required]
+    pub(crate) numbering: Numbering,
+    /// When to restart the line numbers.
+    #[required]
+    pub(crate) scope: LineNumberingScope,
+    /// In which margin to place the number.
+    #[required]
+    pub(crate) side: MarginSide,
+    /// The distance between the number and the line.
+    #[required]
+    pub(crate) clearance: Length,
+}
+
+/// When to restart the line numbers.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum LineNumberingScope {
+    /// Numbers the lines continuously throughout the document.
+    Document,
+    /// Restarts the numbering on each page.
+    Page,
+    /// Restarts the numbering after each heading.
+    Section,
+}
+
+/// The indent of the first line of a paragraph.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Hash)]
+pub struct FirstLineIndent {
+    /// The indent of the first line.
+    pub amount: Length,
+    /// Whether to indent all paragraphs, not just consecutive ones.
+    pub all: bool,
+}
+
+cast! {
+    FirstLineIndent,
+    self => if self.all {
+        dict! { "amount" => self.amount, "all" => self.all }.into_value()
+    } else {
+        self.amount.into_value()
+    },
+    amount: Length => Self { amount, all: false },
+    mut dict: Dict => {
+        let amount = dict.take("amount")?.cast()?;
+        let all = dict
+            .take("all")
+            .ok()
+            .map(bool::from_value)
+            .transpose()?
+            .unwrap_or(false);
+        dict.finish(&["amount", "all"])?;
+        Self { amount, all }
+    },
+}
+
+/// A tab stop in a paragraph.
+#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+pub struct TabStop {
+    /// The distance of the stop from the start of the paragraph.
+    pub pos: Length,
+    /// How the text following a tab is aligned at the stop.
+    pub align: TabAlign,
+}
+
+cast! {
+    TabStop,
+    self => dict! {
+        "pos" => self.pos,
+        "align" => self.align,
+    }.into_value(),
+    v: Length => Self { pos: v, align: TabAlign::Align(HAlignment::Start) },
+    mut dict: Dict => {
+        let pos = dict.take("pos")?.cast()?;
+        let align = dict
+            .take("align")
+            .ok()
+            .map(TabAlign::from_value)
+            .transpose()?
+            .unwrap_or(TabAlign::Align(HAlignment::Start));
+        dict.finish(&["pos", "align"])?;
+        Self { pos, align }
+    },
+}
+
+/// How the text following a tab is aligned at a tab stop.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TabAlign {
+    /// Aligns the text at the stop like a box with the alignment.
+    Align(HAlignment),
+    /// Aligns the first occurrence of the character at the stop.
+    Char(char),
+}
+
+impl TabAlign {
+    /// Turns `left` and `right` into `start` and `end` in the given text
+    /// direction.
+    pub fn logical(self, dir: Dir) -> Self {
+        match self {
+            Self::Align(HAlignment::Left) if dir.is_positive() => {
+                Self::Align(HAlignment::Start)
+            }
+            Self::Align(HAlignment::Right) if dir.is_positive() => {
+                Self::Align(HAlignment::End)
+            }
+            Self::Align(HAlignment::Left) => Self::Align(HAlignment::End),
+            Self::Align(HAlignment::Right) => Self::Align(HAlignment::Start),
+            other => other,
+        }
+    }
+}
+
+cast! {
+    TabAlign,
+    self => match self {
+        Self::Align(align) => align.into_value(),
+        Self::Char(c) => c.into_value(),
+    },
+    v: HAlignment => Self::Align(v),
+    v: char => Self::Char(v),
+}
+
 /// How to determine line breaks in a paragraph.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum Linebreaks {
@@ -0,0 +1,76 @@
+use std::num::NonZeroUsize;
+
+use comemo::Track;
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, Content, Context, NativeElement, Packed, Show, StyleChain,
+};
+use crate::introspection::{Counter, CounterUpdate, Locatable};
+use crate::layout::{Em, HElem};
+use crate::model::{FootnoteElem, MarginNoteElem};
+use crate::text::SuperElem;
+use crate::utils::NonZeroExt;
+
+/// A numbered note in the page margin.
+///
+/// A sidenote combines a [footnote] with a [margin note]($marginnote): It
+/// inserts a superscript number into the text, but its content is placed into
+/// the margin next to the line in which it is called instead of at the bottom
+/// of the page. Sidenotes share their numbering with footnotes, such that the
+/// two can be mixed freely.
+///
+/// The note is laid out just like a margin note. Its placement can therefore
+/// be configured with set rules on [`marginnote`], and a sidenote that would
+/// overlap with a previous note in the same margin is moved down. By default,
+/// sidenotes go into the outside margin and span its full width minus the
+/// margin note's [clearance]($marginnote.clearance).
+///
+/// # Example
+/// ```example
+/// #set page(margin: (right: 80pt))
+/// #set marginnote(clearance: 8pt)
+///
+/// Cells were first described by
+/// Hooke.#sidenote[
+///   #set text(0.8em)
+///   In _Micrographia_, 1665.
+/// ] He compared them to the rooms
+/// of monks.#footnote[Latin _cella_.]
+/// ```
+#[elem(Locatable, Show)]
+pub struct SidenoteElem {
+    /// The content to put into the margin.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for Packed<SidenoteElem> {
+    #[typst_macros::time(name = "sidenote", span = self.span())]
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let span = self.span();
+        let loc = self.location().unwrap();
+        let numbering = FootnoteElem::numbering_in(styles);
+
+        // The note steps the footnote counter right after itself, so its
+        // number is the counter's value at its location stepped once.
+        let counter = Counter::of(FootnoteElem::elem());
+        let mut state = counter.at_loc(engine, loc)?;
+        state.step(NonZeroUsize::ONE, 1);
+        let context = Context::new(Some(loc), Some(styles));
+        let num = state.display(engine, context.track(), numbering)?.display();
+
+        let sup = SuperElem::new(num.clone()).pack().spanned(span);
+        let entry = SuperElem::new(num).pack().spanned(span)
+            + HElem::new(Em::new(0.05).into()).pack()
+            + self.body().clone();
+        let note = MarginNoteElem::new(entry).pack().spanned(span);
+
+        // Add zero-width weak spacing to make the sidenote "sticky".
+        Ok(HElem::hole().pack()
+            + sup
+            + counter.update(span, CounterUpdate::Step(NonZeroUsize::ONE))
+            + note)
+    }
+}
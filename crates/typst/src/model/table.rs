@@ -6,18 +6,19 @@ use ecow::eco_format;
 use crate::diag::{bail, HintedStrResult, HintedString, SourceResult, Trace, Tracepoint};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, scope, Content, Fold, NativeElement, Packed, Show, Smart, StyleChain,
+    array, cast, elem, scope, Array, Cast, Content, Fold, Func, IntoValue, NativeElement,
+    Packed, Show, ShowSet, Smart, StyleChain, Styles,
 };
 use crate::introspection::Locator;
 use crate::layout::{
     show_grid_cell, Abs, Alignment, Axes, BlockElem, Cell, CellGrid, Celled, Dir,
-    Fragment, GridCell, GridFooter, GridHLine, GridHeader, GridLayouter, GridVLine,
-    Length, LinePosition, OuterHAlignment, OuterVAlignment, Regions, Rel, ResolvableCell,
-    ResolvableGridChild, ResolvableGridItem, Sides, TrackSizings,
+    Fragment, GridCell, GridFooter, GridGroup, GridHLine, GridHeader, GridLayouter,
+    GridVLine, Length, LinePosition, OuterHAlignment, OuterVAlignment, Regions, Rel,
+    ResolvableCell, ResolvableGridChild, ResolvableGridItem, Sides, TrackSizings,
 };
 use crate::model::Figurable;
 use crate::syntax::Span;
-use crate::text::{LocalName, TextElem};
+use crate::text::{LocalName, NumberType, NumberWidth, TextElem};
 use crate::utils::NonZeroExt;
 use crate::visualize::{Paint, Stroke};
 
@@ -50,6 +51,9 @@ use crate::visualize::{Paint, Stroke};
 /// Although the `table` and the `grid` share most properties, set and show
 /// rules on one of them do not affect the other.
 ///
+/// So that numbers line up in columns, tables can use tabular lining figures.
+/// Enable them with the [`tabular-figures`]($table.tabular-figures) property.
+///
 /// To give a table a caption and make it [referenceable]($ref), put it into a
 /// [figure].
 ///
@@ -121,7 +125,7 @@ use crate::visualize::{Paint, Stroke};
 ///   [Robert], b, a, b,
 /// )
 /// ```
-#[elem(scope, Show, LocalName, Figurable)]
+#[elem(scope, Show, ShowSet, LocalName, Figurable)]
 pub struct TableElem {
     /// The column sizes. See the [grid documentation]($grid) for more
     /// information on track sizing.
@@ -236,6 +240,24 @@ pub struct TableElem {
     #[default(Celled::Value(Sides::splat(Some(Abs::pt(5.0).into()))))]
     pub inset: Celled<Sides<Option<Rel<Length>>>>,
 
+    /// Whether to set numbers in the table with tabular lining figures.
+    ///
+    /// When enabled, all digits have the same width, so that numbers line up
+    /// in columns. This is the same as setting the text's
+    /// [`number-type`]($text.number-type) to `{"lining"}` and its
+    /// [`number-width`]($text.number-width) to `{"tabular"}` for the table.
+    ///
+    /// ```example
+    /// #set table(tabular-figures: true)
+    /// #table(
+    ///   columns: 2,
+    ///   [Apples], [1,111],
+    ///   [Pears], [8,888],
+    /// )
+    /// ```
+    #[default(false)]
+    pub tabular_figures: bool,
+
     /// The contents of the table cells, plus any extra table lines specified
     /// with the [`table.hline`]($table.hline) and
     /// [`table.vline`]($table.vline) elements.
@@ -259,6 +281,9 @@ impl TableElem {
 
     #[elem]
     type TableFooter;
+
+    #[elem]
+    type TableGroup;
 }
 
 impl Show for Packed<TableElem> {
@@ -303,6 +328,11 @@ fn layout_table(
             span: footer.span(),
             items: footer.children().iter().map(resolve_item),
         },
+        TableChild::Group(group) => ResolvableGridChild::Group {
+            header: group.header(styles),
+            span: group.span(),
+            items: group.children().iter().map(resolve_item),
+        },
         TableChild::Item(item) => ResolvableGridChild::Item(item.to_resolvable(styles)),
     });
     let grid = CellGrid::resolve(
@@ -324,6 +354,17 @@ fn layout_table(
     layouter.layout(engine)
 }
 
+impl ShowSet for Packed<TableElem> {
+    fn show_set(&self, styles: StyleChain) -> Styles {
+        let mut out = Styles::new();
+        if self.tabular_figures(styles) {
+            out.set(TextElem::set_number_type(Smart::Custom(NumberType::Lining)));
+            out.set(TextElem::set_number_width(Smart::Custom(NumberWidth::Tabular)));
+        }
+        out
+    }
+}
+
 impl LocalName for Packed<TableElem> {
     const KEY: &'static str = "table";
 }
@@ -335,6 +376,7 @@ impl Figurable for Packed<TableElem> {}
 pub enum TableChild {
     Header(Packed<TableHeader>),
     Footer(Packed<TableFooter>),
+    Group(Packed<TableGroup>),
     Item(TableItem),
 }
 
@@ -343,6 +385,7 @@ cast! {
     self => match self {
         Self::Header(header) => header.into_value(),
         Self::Footer(footer) => footer.into_value(),
+        Self::Group(group) => group.into_value(),
         Self::Item(item) => item.into_value(),
     },
     v: Content => {
@@ -366,11 +409,18 @@ impl TryFrom<Content> for TableChild {
                 hint: "use `table.footer` instead"
             )
         }
+        if value.is::<GridGroup>() {
+            bail!(
+                "cannot use `grid.group` as a table row group";
+                hint: "use `table.group` instead"
+            )
+        }
 
         value
             .into_packed::<TableHeader>()
             .map(Self::Header)
             .or_else(|value| value.into_packed::<TableFooter>().map(Self::Footer))
+            .or_else(|value| value.into_packed::<TableGroup>().map(Self::Group))
             .or_else(|value| TableItem::try_from(value).map(Self::Item))
     }
 }
@@ -449,6 +499,14 @@ impl TryFrom<Content> for TableItem {
         if value.is::<TableFooter>() {
             bail!("cannot place a table footer within another footer or header");
         }
+        if value.is::<GridGroup>() {
+            bail!("cannot place a grid row group within another group, header or footer");
+        }
+        if value.is::<TableGroup>() {
+            bail!(
+                "cannot place a table row group within another group, header or footer"
+            );
+        }
         if value.is::<GridCell>() {
             bail!(
                 "cannot use `grid.cell` as a table cell";
@@ -554,6 +612,51 @@ pub struct TableFooter {
     pub children: Vec<TableItem>,
 }
 
+/// A group of table rows which is kept together.
+///
+/// When the rows of a group don't fit into the rest of the current page, the
+/// whole group is moved to the next page instead of being split. This keeps
+/// related rows, like the items of one project on an invoice or the
+/// transactions of one month on a statement, in one place.
+///
+/// Groups which are too tall to fit into a full page are still split. Their
+/// first `header` rows are then repeated at the top of each page the group
+/// continues on.
+///
+/// ```example
+/// #set page(height: 10em)
+/// #table(
+///   columns: 2,
+///   table.header[*Item*][*Hours*],
+///   table.group(
+///     header: 1,
+///     table.cell(colspan: 2)[_Project A_],
+///     [Design], [12],
+///     [Review], [3],
+///   ),
+///   table.group(
+///     header: 1,
+///     table.cell(colspan: 2)[_Project B_],
+///     [Prototype], [20],
+///     [Testing], [8],
+///     [Launch], [2],
+///   ),
+/// )
+/// ```
+#[elem(name = "group", title = "Table Group")]
+pub struct TableGroup {
+    /// The number of rows at the start of the group which make up its header.
+    ///
+    /// The header is kept together with the first row after it and repeated
+    /// whenever the group is split across pages.
+    #[default(0)]
+    pub header: usize,
+
+    /// The cells and lines within the group.
+    #[variadic]
+    pub children: Vec<TableItem>,
+}
+
 /// A horizontal line in the table.
 ///
 /// Overrides any per-cell stroke, including stroke specified through the
@@ -791,6 +894,117 @@ pub struct TableCell {
     /// unbreakable, while a cell spanning at least one `{auto}`-sized row is
     /// breakable.
     pub breakable: Smart<bool>,
+
+    /// A function computing the cell's content from the values of other
+    /// cells.
+    ///
+    /// The function receives a [sheet]($sheet) through which the values of
+    /// the table's cells can be retrieved, referenced like in a spreadsheet.
+    /// The cell then displays the value returned by the function instead of
+    /// its body, which is usually left empty. Since formulas are evaluated
+    /// once all cells are known, totals and percentages stay consistent with
+    /// the data they are computed from. Formulas may refer to other cells with
+    /// formulas, but not to themselves.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 3,
+    ///   [*Item*], [*Price*], [*Share*],
+    ///   [Rent], [800], table.cell(formula: r => {
+    ///     calc.round(r.at("B2") / r.at("B4") * 100)
+    ///     [%]
+    ///   })[],
+    ///   [Food], [400], table.cell(formula: r => {
+    ///     calc.round(r.at("B3") / r.at("B4") * 100)
+    ///     [%]
+    ///   })[],
+    ///   [*Total*], table.cell(formula: r => r.sum("B2:B3"))[], [],
+    /// )
+    /// ```
+    pub formula: Option<Func>,
+
+    /// Which cells this cell is a header for.
+    ///
+    /// - `{auto}`: Cells in the table's [header]($table.header) are headers
+    ///   for the cells below them. All other cells are data cells.
+    /// - `{none}`: The cell is a data cell.
+    /// - `{"column"}`: The cell is a header for the cells below it in the
+    ///   same columns.
+    /// - `{"row"}`: The cell is a header for the cells after it in the same
+    ///   rows.
+    /// - `{"both"}`: The cell is a header for both the cells below it and
+    ///   after it.
+    ///
+    /// The association between header and data cells does not affect how the
+    /// table looks, but is recorded in the cell's [`headers`]($table.cell.headers)
+    /// field, such that tools can tell which headers describe a data cell.
+    /// Multiple levels of headers are supported, in which case a cell is
+    /// associated with all of them.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 3,
+    ///   table.header[][*Q1*][*Q2*],
+    ///   table.cell(scope: "row")[*Sales*], [120], [140],
+    ///   table.cell(scope: "row")[*Costs*], [80], [95],
+    /// )
+    /// ```
+    pub scope: Smart<Option<TableHeaderScope>>,
+
+    /// The positions of the header cells associated with this cell, as
+    /// `(x, y)` arrays. Column headers come first, from the top down,
+    /// followed by row headers, from left to right.
+    ///
+    /// ```example
+    /// #show table.cell: it => {
+    ///   it
+    ///   if it.headers.len() > 0 {
+    ///     text(0.6em, gray, repr(it.headers))
+    ///   }
+    /// }
+    ///
+    /// #table(
+    ///   columns: 2,
+    ///   table.header[*Name*][*Age*],
+    ///   table.cell(scope: "row")[Hannes], [36],
+    /// )
+    /// ```
+    #[synthesized]
+    pub headers: Array,
+
+    /// The total amount of columns in the table. Functions identically to the
+    /// `column-count` field in [`grid.cell`]($grid.cell).
+    #[synthesized]
+    pub column_count: usize,
+
+    /// The total amount of rows in the table. Functions identically to the
+    /// `row-count` field in [`grid.cell`]($grid.cell).
+    #[synthesized]
+    pub row_count: usize,
+
+    /// Whether the cell is part of the table's [header]($table.header).
+    /// Functions identically to the `header` field in
+    /// [`grid.cell`]($grid.cell).
+    ///
+    /// ```example
+    /// #show table.cell.where(header: true): set text(white)
+    /// #show table.cell.where(header: true): set table.cell(fill: black)
+    ///
+    /// #table(
+    ///   columns: 2,
+    ///   table.header[*Name*][*Age*],
+    ///   [Hannes], [36],
+    ///   [Irma], [50],
+    /// )
+    /// ```
+    #[synthesized]
+    pub header: bool,
+
+    /// Whether the cell is part of the table's [footer]($table.footer).
+    /// Functions identically to the `footer` field in
+    /// [`grid.cell`]($grid.cell).
+    #[synthesized]
+    pub footer: bool,
 }
 
 cast! {
@@ -897,6 +1111,82 @@ impl ResolvableCell for Packed<TableCell> {
     fn span(&self) -> Span {
         Packed::span(self)
     }
+
+    fn finalize_cell(
+        cell: &mut Cell,
+        column_count: usize,
+        row_count: usize,
+        header: bool,
+        footer: bool,
+    ) {
+        if let Some(cell) = cell.body.to_packed_mut::<TableCell>() {
+            cell.push_column_count(column_count);
+            cell.push_row_count(row_count);
+            cell.push_header(header);
+            cell.push_footer(footer);
+        }
+    }
+
+    fn cell_formula(cell: &Cell, styles: StyleChain) -> Option<Func> {
+        cell.body.to_packed::<TableCell>()?.formula(styles)
+    }
+
+    fn cell_body<'a>(cell: &'a Cell) -> Option<&'a Content> {
+        cell.body.to_packed::<TableCell>().map(|cell| cell.body())
+    }
+
+    fn set_cell_body(cell: &mut Cell, body: Content) {
+        if let Some(cell) = cell.body.to_packed_mut::<TableCell>() {
+            cell.push_body(body);
+        }
+    }
+
+    fn cell_scope(
+        cell: &Cell,
+        header: bool,
+        styles: StyleChain,
+    ) -> Option<TableHeaderScope> {
+        match cell.body.to_packed::<TableCell>()?.scope(styles) {
+            Smart::Auto => header.then_some(TableHeaderScope::Column),
+            Smart::Custom(scope) => scope,
+        }
+    }
+
+    fn set_cell_headers(
+        cell: &mut Cell,
+        scope: Option<TableHeaderScope>,
+        headers: &[Axes<usize>],
+    ) {
+        if let Some(cell) = cell.body.to_packed_mut::<TableCell>() {
+            cell.push_scope(Smart::Custom(scope));
+            cell.push_headers(
+                headers.iter().map(|pos| array![pos.x, pos.y].into_value()).collect(),
+            );
+        }
+    }
+}
+
+/// Which cells a header cell of a table applies to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum TableHeaderScope {
+    /// The cell is a header for the cells after it in the same rows.
+    Row,
+    /// The cell is a header for the cells below it in the same columns.
+    Column,
+    /// The cell is a header for the cells after and below it.
+    Both,
+}
+
+impl TableHeaderScope {
+    /// Whether the cell is a header for the cells after it in its rows.
+    pub fn applies_to_rows(self) -> bool {
+        matches!(self, Self::Row | Self::Both)
+    }
+
+    /// Whether the cell is a header for the cells below it in its columns.
+    pub fn applies_to_columns(self) -> bool {
+        matches!(self, Self::Column | Self::Both)
+    }
 }
 
 impl Show for Packed<TableCell> {
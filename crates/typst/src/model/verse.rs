@@ -0,0 +1,231 @@
+use std::num::NonZeroUsize;
+
+use comemo::Track;
+use smallvec::smallvec;
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, Content, Context, NativeElement, Packed, Show, Smart, StyleChain,
+};
+use crate::layout::{
+    Alignment, Em, Fr, GridCell, GridChild, GridElem, GridItem, Length, Sizing, Spacing,
+    TrackSizings, VElem,
+};
+use crate::model::{Numbering, ParElem, ParbreakElem};
+use crate::text::{LinebreakElem, SpaceElem, TextElem};
+
+/// A poem or another piece of verse.
+///
+/// Verse doesn't fit well into paragraphs: Its lines are broken where the
+/// author decided and not where the text runs out of space. This element lays
+/// out each line of its body on its own. A line that is too long for the
+/// available width wraps with a hanging indent, such that it can still be told
+/// apart from the next line.
+///
+/// Lines are separated by [line breaks]($linebreak) and stanzas by blank lines.
+/// In a string body, each newline starts a new line.
+///
+/// # Example
+/// ```example
+/// #verse(
+///   numbering: "1",
+///   step: 2,
+///   attribution: [William Blake],
+/// )[
+///   Tyger Tyger, burning bright, \
+///   In the forests of the night; \
+///   What immortal hand or eye, \
+///   Could frame thy fearful symmetry?
+///
+///   In what distant deeps or skies, \
+///   Burnt the fire of thine eyes?
+/// ]
+/// ```
+#[elem(Show)]
+pub struct VerseElem {
+    /// The indent of the continuation lines of a line that is too long and
+    /// wraps.
+    ///
+    /// ```example
+    /// #set page(width: 120pt)
+    /// #verse(indent: 3em)[
+    ///   Shall I compare thee to a summer's day?
+    /// ]
+    /// ```
+    #[default(Em::new(2.0).into())]
+    pub indent: Length,
+
+    /// The spacing between stanzas.
+    #[default(Em::new(1.2).into())]
+    pub spacing: Length,
+
+    /// How to [number]($numbering) the lines of the verse.
+    ///
+    /// If this is `{none}`, the lines are not numbered. Otherwise, every
+    /// [`step`]($verse.step)-th line is numbered, counting across stanzas.
+    /// The numbers are placed at the end of the lines.
+    #[borrowed]
+    pub numbering: Option<Numbering>,
+
+    /// Which lines to number: With a step of `{5}`, the fifth, tenth, and
+    /// so on are numbered.
+    #[default(NonZeroUsize::new(5).unwrap())]
+    pub step: NonZeroUsize,
+
+    /// The space between the lines and their numbers.
+    #[default(Em::new(1.0).into())]
+    pub number_gap: Length,
+
+    /// An attribution for the verse, for example its author. It is aligned
+    /// to the end of the line below the verse.
+    pub attribution: Option<Content>,
+
+    /// The verse.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for Packed<VerseElem> {
+    #[typst_macros::time(name = "verse", span = self.span())]
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let span = self.span();
+        let numbering = self.numbering(styles);
+        let step = self.step(styles).get();
+        let leading = ParElem::leading_in(styles);
+        let spacing = self.spacing(styles);
+
+        // Each line is a row of a grid, optionally with its number in a
+        // second column. The gutters separate lines and stanzas.
+        let mut cells = vec![];
+        let mut gutters = vec![];
+        let mut number = 0;
+        for (i, stanza) in split_stanzas(self.body()).into_iter().enumerate() {
+            if i > 0 {
+                gutters.pop();
+                gutters.push(Sizing::Rel(spacing.into()));
+            }
+
+            for line in stanza {
+                number += 1;
+                gutters.push(Sizing::Rel(leading.into()));
+                cells.push(line);
+
+                if let Some(numbering) = numbering {
+                    let mut content = Content::empty();
+                    if number % step == 0 {
+                        let context = Context::new(None, Some(styles));
+                        content = numbering
+                            .apply(engine, context.track(), &[number])?
+                            .display();
+                    }
+                    cells.push(content);
+                }
+            }
+        }
+        gutters.pop();
+
+        let mut columns = smallvec![Sizing::Fr(Fr::one())];
+        if numbering.is_some() {
+            columns.push(Sizing::Auto);
+        }
+
+        let cells = cells
+            .into_iter()
+            .enumerate()
+            .map(|(i, body)| {
+                let mut cell = GridCell::new(body);
+                if numbering.is_some() && i % 2 == 1 {
+                    cell.push_align(Smart::Custom(Alignment::END));
+                }
+                GridChild::Item(GridItem::Cell(Packed::new(cell).spanned(span)))
+            })
+            .collect();
+
+        let mut realized = GridElem::new(cells)
+            .with_columns(TrackSizings(columns))
+            .with_column_gutter(TrackSizings(smallvec![self.number_gap(styles).into()]))
+            .with_row_gutter(TrackSizings(gutters.into()))
+            .pack()
+            .spanned(span)
+            .styled(ParElem::set_hanging_indent(self.indent(styles)))
+            .styled(ParElem::set_justify(false));
+
+        if let Some(attribution) = self.attribution(styles) {
+            // Bring the attribution a bit closer to the verse, like the
+            // attribution of a quote.
+            let weak_v = VElem::weak(Spacing::Rel(Em::new(0.9).into())).pack();
+            let seq = [TextElem::packed('—'), SpaceElem::new().pack(), attribution];
+            realized += weak_v + Content::sequence(seq).aligned(Alignment::END);
+        }
+
+        Ok(realized)
+    }
+}
+
+/// Splits the body of a verse into its stanzas and their lines.
+fn split_stanzas(body: &Content) -> Vec<Vec<Content>> {
+    let mut splitter = Splitter { stanzas: vec![vec![]], line: vec![] };
+    body.sequence_recursive_for_each(&mut |child| {
+        if child.is::<LinebreakElem>() {
+            splitter.break_line();
+        } else if child.is::<ParbreakElem>() {
+            splitter.break_stanza();
+        } else if let Some(text) = child.to_packed::<TextElem>() {
+            for (i, part) in text.text().split('\n').enumerate() {
+                if i > 0 {
+                    splitter.break_line();
+                }
+                if !part.is_empty() {
+                    splitter.line.push(TextElem::packed(part));
+                }
+            }
+        } else {
+            splitter.line.push(child.clone());
+        }
+    });
+
+    splitter.break_stanza();
+    splitter.stanzas.pop();
+    splitter.stanzas
+}
+
+/// Collects the lines of a verse into stanzas.
+struct Splitter {
+    stanzas: Vec<Vec<Content>>,
+    line: Vec<Content>,
+}
+
+impl Splitter {
+    /// Finishes the current line. An empty line ends the stanza.
+    fn break_line(&mut self) {
+        let is_space = |content: &Content| {
+            content.is::<SpaceElem>()
+                || content
+                    .to_packed::<TextElem>()
+                    .is_some_and(|text| text.text().trim().is_empty())
+        };
+
+        let line = std::mem::take(&mut self.line);
+        let start = line.iter().position(|c| !is_space(c));
+        let end = line.iter().rposition(|c| !is_space(c));
+        match start.zip(end) {
+            Some((start, end)) => self
+                .stanzas
+                .last_mut()
+                .unwrap()
+                .push(Content::sequence(line[start..=end].iter().cloned())),
+            None => self.break_stanza(),
+        }
+    }
+
+    /// Finishes the current stanza.
+    fn break_stanza(&mut self) {
+        if !self.line.is_empty() {
+            self.break_line();
+        }
+        if !self.stanzas.last().unwrap().is_empty() {
+            self.stanzas.push(vec![]);
+        }
+    }
+}
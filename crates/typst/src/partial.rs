@@ -0,0 +1,164 @@
+//! Compilation of a labelled part of a document.
+
+use std::ops::Range;
+
+use crate::compile::compile_with;
+use crate::diag::{bail, SourceResult, Warned};
+use crate::engine::Budget;
+use crate::foundations::{
+    Content, Label, NativeElement, Repr, Selector, SequenceElem, StyleChain, StyledElem,
+    Styles, Value,
+};
+use crate::introspection::{Counter, CounterKey, CounterState, CounterUpdate};
+use crate::model::{Document, HeadingElem};
+use crate::syntax::Span;
+use crate::World;
+
+/// Compile only the part of the main source file that is marked with the
+/// given label into a document.
+///
+/// A labelled heading selects the heading and everything up to the next
+/// heading of the same or a higher level. Any other labelled element selects
+/// just that element, even if it is nested in another one. The part keeps the
+/// styles of the set and show rules that apply to it, but not the properties
+/// of the elements it is nested in, and the rest of the document is neither
+/// laid out nor introspected. Heading numbers are approximated by counting the
+/// numbered headings at the top level before a section, while all other
+/// counters, including the page counter, start from scratch.
+///
+/// This is meant for editors that offer a fast preview of the section that
+/// is being edited.
+#[typst_macros::time]
+pub fn compile_partial(
+    world: &dyn World,
+    label: Label,
+) -> Warned<SourceResult<Document>> {
+    compile_with(world, Budget::default(), Some(label))
+}
+
+/// Extract the part of the content that is marked with the label, keeping the
+/// styles around it, and prepend an approximation of the heading counter.
+pub(crate) fn extract_partial(
+    content: &Content,
+    label: Label,
+    styles: StyleChain,
+) -> SourceResult<Content> {
+    let mut leaves = vec![];
+    collect_leaves(content, &Styles::new(), &mut leaves);
+
+    let Some(start) = leaves.iter().position(|(leaf, _)| leaf.label() == Some(label))
+    else {
+        // A label that is nested within other content selects just the
+        // labelled element, with the styles of the styled content around it.
+        return match find_nested(content, label, &Styles::new()) {
+            Some((elem, local)) => Ok(elem.styled_with_map(local)),
+            None => bail!(
+                Span::detached(),
+                "label `{}` does not exist in the document",
+                label.repr()
+            ),
+        };
+    };
+
+    let heading = |(leaf, local): &(Content, Styles)| {
+        let heading = leaf.to_packed::<HeadingElem>()?;
+        let styles = styles.chain(local);
+        Some((heading.resolve_level(styles), heading.numbering(styles).is_some()))
+    };
+
+    let end = match heading(&leaves[start]) {
+        Some((level, _)) => leaves[start + 1..]
+            .iter()
+            .position(|leaf| heading(leaf).is_some_and(|(l, _)| l <= level))
+            .map_or(leaves.len(), |i| start + 1 + i),
+        None => start + 1,
+    };
+
+    let key = CounterKey::Selector(Selector::Elem(HeadingElem::elem(), None));
+    let mut state = CounterState::init(&key);
+    for (level, _) in leaves[..start].iter().filter_map(heading).filter(|h| h.1) {
+        state.step(level, 1);
+    }
+
+    let update = Counter::new(key).update(Span::detached(), CounterUpdate::Set(state));
+    let part = retain_leaves(content, &(start..end), &mut 0);
+    Ok(update + part)
+}
+
+/// Collect the leaves of sequences and styled content, along with the styles
+/// that apply to each of them.
+fn collect_leaves(
+    content: &Content,
+    outer: &Styles,
+    leaves: &mut Vec<(Content, Styles)>,
+) {
+    if let Some(sequence) = content.to_packed::<SequenceElem>() {
+        for child in &sequence.children {
+            collect_leaves(child, outer, leaves);
+        }
+    } else if let Some(styled) = content.to_packed::<StyledElem>() {
+        let mut local = styled.styles.clone();
+        local.apply(outer.clone());
+        collect_leaves(&styled.child, &local, leaves);
+    } else {
+        leaves.push((content.clone(), outer.clone()));
+    }
+}
+
+/// Find the first element with the label in the content and its fields, along
+/// with the styles of the styled content around it.
+fn find_nested(
+    content: &Content,
+    label: Label,
+    outer: &Styles,
+) -> Option<(Content, Styles)> {
+    if content.label() == Some(label) {
+        return Some((content.clone(), outer.clone()));
+    }
+
+    if let Some(styled) = content.to_packed::<StyledElem>() {
+        let mut local = styled.styles.clone();
+        local.apply(outer.clone());
+        return find_nested(&styled.child, label, &local);
+    }
+
+    /// Search a field's value for the labelled element.
+    fn find_in_value(
+        value: Value,
+        label: Label,
+        outer: &Styles,
+    ) -> Option<(Content, Styles)> {
+        match value {
+            Value::Content(content) => find_nested(&content, label, outer),
+            Value::Array(array) => {
+                array.into_iter().find_map(|value| find_in_value(value, label, outer))
+            }
+            _ => None,
+        }
+    }
+
+    content
+        .fields()
+        .into_iter()
+        .find_map(|(_, value)| find_in_value(value, label, outer))
+}
+
+/// Rebuild the content with only the leaves in the range, counting leaves in
+/// the same order as [`collect_leaves`].
+fn retain_leaves(content: &Content, range: &Range<usize>, i: &mut usize) -> Content {
+    if let Some(sequence) = content.to_packed::<SequenceElem>() {
+        Content::sequence(
+            sequence.children.iter().map(|child| retain_leaves(child, range, i)),
+        )
+    } else if let Some(styled) = content.to_packed::<StyledElem>() {
+        retain_leaves(&styled.child, range, i).styled_with_map(styled.styles.clone())
+    } else {
+        let keep = range.contains(i);
+        *i += 1;
+        if keep {
+            content.clone()
+        } else {
+            Content::empty()
+        }
+    }
+}
@@ -14,27 +14,31 @@ use once_cell::unsync::Lazy;
 
 pub use self::arenas::Arenas;
 pub use self::behaviour::{Behave, BehavedBuilder, Behaviour, StyleVec};
-pub use self::process::process;
+pub use self::process::{process, FirstMatches};
 
 use std::mem;
+use std::num::NonZeroUsize;
+use std::ptr;
 
 use crate::diag::{bail, SourceResult};
 use crate::engine::{Engine, Route};
 use crate::foundations::{
-    Content, NativeElement, Packed, SequenceElem, Smart, StyleChain, StyledElem, Styles,
+    Chainable, Content, NativeElement, Packed, SequenceElem, Smart, Style, StyleChain,
+    StyledElem, Styles,
 };
 use crate::introspection::{Locator, SplitLocator, TagElem};
 use crate::layout::{
-    AlignElem, BlockElem, BoxElem, ColbreakElem, FlowElem, FlushElem, HElem, InlineElem,
-    PageElem, PagebreakElem, Parity, PlaceElem, VElem,
+    AlignElem, AnchorElem, BlockElem, BoxElem, ColbreakElem, FlowElem, FlushElem, HElem,
+    InlineElem, LeaderElem, PageElem, PageTarget, PagebreakElem, PlaceElem, VElem,
 };
 use crate::math::{EquationElem, LayoutMath};
 use crate::model::{
-    CiteElem, CiteGroup, DocumentElem, EnumElem, EnumItem, ListElem, ListItem, ParElem,
-    ParbreakElem, TermItem, TermsElem,
+    ApplyElem, BlankElem, CiteElem, CiteGroup, DocumentElem, EnumElem, EnumItem,
+    HeadingElem, ListElem, ListItem, ParElem, ParbreakElem, TermItem, TermsElem,
 };
 use crate::syntax::Span;
 use crate::text::{LinebreakElem, SmartQuoteElem, SpaceElem, TextElem};
+use crate::utils::LazyHash;
 
 /// Realize into a `DocumentElem`, an element that is capable of root-level
 /// layout.
@@ -85,6 +89,10 @@ struct Builder<'a, 'v, 't> {
     list: ListBuilder<'a>,
     /// The current citation grouping state.
     cites: CiteGroupBuilder<'a>,
+    /// Which `first-in` selectors already had a match.
+    firsts: FirstMatches,
+    /// The rules applied to sections of the content.
+    sections: SectionBuilder<'a>,
 }
 
 impl<'a, 'v, 't> Builder<'a, 'v, 't> {
@@ -103,6 +111,8 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
             par: ParBuilder::default(),
             list: ListBuilder::default(),
             cites: CiteGroupBuilder::default(),
+            firsts: FirstMatches::default(),
+            sections: SectionBuilder::default(),
         }
     }
 
@@ -110,7 +120,7 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
     fn accept(
         &mut self,
         mut content: &'a Content,
-        styles: StyleChain<'a>,
+        mut styles: StyleChain<'a>,
     ) -> SourceResult<()> {
         // Implicitly wrap math content in an equation if needed
         if content.can::<dyn LayoutMath>() && !content.is::<EquationElem>() {
@@ -119,9 +129,18 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
                 .store(EquationElem::new(content.clone()).pack().spanned(content.span()));
         }
 
+        // Apply the rules of the sections that the content is part of.
+        if !self.sections.rules.is_empty() {
+            if content.is::<HeadingElem>() && !content.is_prepared() {
+                self.heading(content, styles)?;
+            }
+            styles = self.sections.restyle(self.arenas, styles);
+        }
+
         // Styled elements and sequences can (at least currently) also have
         // labels, so this needs to happen before they are handled.
-        if let Some(realized) = process(self.engine, &mut self.locator, content, styles)?
+        if let Some(realized) =
+            process(self.engine, &mut self.locator, &mut self.firsts, content, styles)?
         {
             self.engine.route.increase();
             if !self.engine.route.within(Route::MAX_SHOW_RULE_DEPTH) {
@@ -146,6 +165,13 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
             return Ok(());
         }
 
+        if let Some(apply) = content.to_packed::<ApplyElem>() {
+            if !apply.rules.is_empty() {
+                self.sections.rules.push(apply);
+            }
+            return Ok(());
+        }
+
         // Try to merge `content` with an element under construction
 
         if self.cites.accept(content, styles) {
@@ -198,6 +224,14 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
         styled: &'a StyledElem,
         styles: StyleChain<'a>,
     ) -> SourceResult<()> {
+        // Evaluate the properties of contextual set rules with the styles they
+        // are applied to, so that they take effect like ordinary properties.
+        if styled.styles.is_contextual() {
+            let local = styled.styles.resolve_contextual(self.engine, None, styles)?;
+            let resolved = StyledElem::new(styled.child.clone(), local).pack();
+            return self.accept(self.arenas.store(resolved), styles);
+        }
+
         let stored = self.arenas.store(styles);
         let styles = stored.chain(&styled.styles);
         self.interrupt_style(&styled.styles, None)?;
@@ -206,6 +240,35 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
         Ok(())
     }
 
+    /// Closes the sections that end at the heading and opens the ones that
+    /// start at it.
+    fn heading(&mut self, content: &Content, styles: StyleChain<'a>) -> SourceResult<()> {
+        let Some(heading) = content.to_packed::<HeadingElem>() else { return Ok(()) };
+        let level = heading.resolve_level(styles);
+        while let Some(&(_, rules)) =
+            self.sections.open.last().filter(|(open, _)| *open >= level)
+        {
+            self.sections.open.pop();
+            self.interrupt_style(rules, Some(styles))?;
+        }
+
+        // Match against the heading with its level resolved, as show rules
+        // see it after synthesis.
+        let mut target = heading.clone();
+        target.push_level(Smart::Custom(level));
+        let target = target.pack();
+
+        for i in 0..self.sections.rules.len() {
+            let apply = self.sections.rules[i];
+            if apply.target.matches(&target, Some(styles)) {
+                self.sections.open.push((level, &apply.rules));
+                self.interrupt_style(&apply.rules, None)?;
+            }
+        }
+
+        Ok(())
+    }
+
     fn interrupt_style(
         &mut self,
         local: &Styles,
@@ -306,14 +369,69 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
     }
 }
 
+/// Tracks the sections that rules were [applied][ApplyElem] to.
+#[derive(Default)]
+struct SectionBuilder<'a> {
+    /// The rules encountered so far.
+    rules: Vec<&'a Packed<ApplyElem>>,
+    /// The currently open sections, with the levels of their headings and the
+    /// rules applied to them.
+    open: Vec<(NonZeroUsize, &'a Styles)>,
+}
+
+impl<'a> SectionBuilder<'a> {
+    /// Adjusts the style chain such that it contains the rules of exactly the
+    /// open sections.
+    ///
+    /// The rules of closed sections are removed from the chain since content
+    /// styled while their section was open (e.g. by a set rule in the section)
+    /// can extend beyond the section's end.
+    fn restyle(&self, arenas: &'a Arenas<'a>, styles: StyleChain<'a>) -> StyleChain<'a> {
+        let is_open = |link: &[LazyHash<Style>]| {
+            self.open.iter().any(|(_, rules)| ptr::eq(link, rules.as_slice()))
+        };
+        let is_closed = |link: &[LazyHash<Style>]| {
+            !is_open(link)
+                && self.rules.iter().any(|apply| ptr::eq(link, apply.rules.as_slice()))
+        };
+
+        let missing: Vec<_> = self
+            .open
+            .iter()
+            .map(|(_, rules)| rules.as_slice())
+            .filter(|rules| !styles.links().any(|link| ptr::eq(link, *rules)))
+            .collect();
+
+        if missing.is_empty() && !styles.links().any(is_closed) {
+            return styles;
+        }
+
+        // Only rebuild the part of the chain up to the outermost closed
+        // section, so that the rest is still shared with other content.
+        let mut chain = styles;
+        let mut popped = vec![];
+        while chain.links().any(is_closed) {
+            popped.extend(chain.links().next());
+            chain.pop();
+        }
+
+        let kept = popped.into_iter().rev().filter(|link| !is_closed(link));
+        for link in kept.chain(missing) {
+            chain = Chainable::chain(link, arenas.store(chain));
+        }
+        chain
+    }
+}
+
 /// Builds a [document][DocumentElem] from pagebreaks and pages.
 struct DocBuilder<'a> {
     /// The page runs built so far.
     pages: BehavedBuilder<'a>,
     /// Whether to keep a following page even if it is empty.
     keep_next: bool,
-    /// Whether the next page should be cleared to an even or odd number.
-    clear_next: Option<Parity>,
+    /// Which kind of page the next page should be cleared to and a number
+    /// that the count of pages before it should be a multiple of.
+    clear_next: (Option<PageTarget>, Option<NonZeroUsize>),
 }
 
 impl<'a> DocBuilder<'a> {
@@ -331,14 +449,16 @@ impl<'a> DocBuilder<'a> {
     ) -> bool {
         if let Some(pagebreak) = content.to_packed::<PagebreakElem>() {
             self.keep_next = !pagebreak.weak(styles);
-            self.clear_next = pagebreak.to(styles);
+            self.clear_next = (pagebreak.to(styles), pagebreak.to_multiple_of(styles));
             return true;
         }
 
         if let Some(page) = content.to_packed::<PageElem>() {
-            let elem = if let Some(clear_to) = self.clear_next.take() {
+            let elem = if self.clear_next != (None, None) {
+                let (clear_to, multiple_of) = mem::take(&mut self.clear_next);
                 let mut page = page.clone();
-                page.push_clear_to(Some(clear_to));
+                page.push_clear_to(clear_to);
+                page.push_clear_to_multiple_of(multiple_of);
                 arenas.store(page.pack())
             } else {
                 content
@@ -365,7 +485,7 @@ impl Default for DocBuilder<'_> {
         Self {
             pages: BehavedBuilder::new(),
             keep_next: true,
-            clear_next: None,
+            clear_next: (None, None),
         }
     }
 }
@@ -402,6 +522,7 @@ impl<'a> FlowBuilder<'a> {
         }
 
         if content.is::<ColbreakElem>()
+            || content.is::<AnchorElem>()
             || content.is::<TagElem>()
             || content.is::<PlaceElem>()
             || content.is::<FlushElem>()
@@ -474,6 +595,8 @@ impl<'a> ParBuilder<'a> {
             || content.is::<SmartQuoteElem>()
             || content.is::<InlineElem>()
             || content.is::<BoxElem>()
+            || content.is::<LeaderElem>()
+            || content.is::<BlankElem>()
         {
             self.0.push(content, styles);
             return true;
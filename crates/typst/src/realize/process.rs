@@ -1,12 +1,13 @@
 use std::cell::OnceCell;
+use std::collections::HashMap;
 
 use comemo::{Track, Tracked};
 
 use crate::diag::SourceResult;
 use crate::engine::Engine;
 use crate::foundations::{
-    Content, Context, Packed, Recipe, RecipeIndex, Regex, Selector, Show, ShowSet, Style,
-    StyleChain, Styles, Synthesize, Transformation,
+    AncestryElem, Content, Context, Packed, Recipe, RecipeIndex, Regex, Selector, Show,
+    ShowSet, Style, StyleChain, Styles, Synthesize, Transformation,
 };
 use crate::introspection::{Locatable, SplitLocator, Tag, TagElem};
 use crate::text::TextElem;
@@ -31,16 +32,67 @@ enum ShowStep<'a> {
     Builtin,
 }
 
+/// Remembers which `first-in` recipes already had a match within their
+/// ancestor during one realization.
+///
+/// Ancestors are identified by instance rather than by value, so that equal
+/// ancestors at different places in the document are told apart.
+#[derive(Debug, Default)]
+pub struct FirstMatches(HashMap<RecipeIndex, Vec<Content>>);
+
+impl FirstMatches {
+    /// Whether the target is the first match of the recipe's selector within
+    /// its ancestor, if it is a `first-in` selector. Records the match.
+    fn admit(
+        &mut self,
+        recipe: &Recipe,
+        index: impl FnOnce() -> RecipeIndex,
+        target: &Content,
+        styles: StyleChain,
+    ) -> bool {
+        let Some(Selector::FirstIn { ancestor, .. }) = &recipe.selector else {
+            return true;
+        };
+
+        let Some(parent) = ancestor.nearest_ancestor(target, styles) else {
+            return true;
+        };
+
+        let seen = self.0.entry(index()).or_default();
+        if seen.iter().any(|elem| elem.is_same(&parent)) {
+            return false;
+        }
+
+        seen.push(parent);
+        true
+    }
+}
+
 /// Processes the given `target` element when encountering it during realization.
 pub fn process(
     engine: &mut Engine,
     locator: &mut SplitLocator,
+    firsts: &mut FirstMatches,
     target: &Content,
     styles: StyleChain,
 ) -> SourceResult<Option<Content>> {
-    let Some(Verdict { prepared, mut map, step }) = verdict(engine, target, styles)
+    // If a structural selector depends on the target, its output is marked
+    // as being nested in it.
+    let tracked = styles
+        .entries()
+        .filter_map(Style::recipe)
+        .filter_map(|recipe| recipe.selector.as_ref())
+        .any(|selector| selector.tracks(target, styles))
+        && !AncestryElem::ancestors_in(styles).contains(target);
+
+    let Some(Verdict { prepared, mut map, step }) =
+        verdict(engine, firsts, target, styles)
     else {
-        return Ok(None);
+        return Ok(tracked.then(|| {
+            target
+                .clone()
+                .styled(AncestryElem::set_ancestors(vec![target.clone()]))
+        }));
     };
 
     // Create a fresh copy that we can mutate.
@@ -51,6 +103,12 @@ pub fn process(
     let mut tag = None;
     if !prepared {
         tag = prepare(engine, locator, &mut target, &mut map, styles)?;
+    } else if map.is_contextual() {
+        map = map.resolve_contextual(engine, target.location(), styles)?;
+    }
+
+    if tracked {
+        map.set(AncestryElem::set_ancestors(vec![target.clone()]));
     }
 
     // Apply a step, if there is one.
@@ -79,6 +137,7 @@ pub fn process(
 /// proceed with the styling.
 fn verdict<'a>(
     engine: &mut Engine,
+    firsts: &mut FirstMatches,
     target: &'a Content,
     styles: StyleChain<'a>,
 ) -> Option<Verdict<'a>> {
@@ -108,7 +167,7 @@ fn verdict<'a>(
     for entry in styles.entries() {
         let recipe = match entry {
             Style::Recipe(recipe) => recipe,
-            Style::Property(_) => continue,
+            Style::Property(_) | Style::Contextual(_) => continue,
             Style::Revocation(index) => {
                 revoked.insert(index.0);
                 continue;
@@ -116,7 +175,14 @@ fn verdict<'a>(
         };
 
         // We're not interested in recipes that don't match.
-        if !recipe.applicable(target, styles) {
+        let index = || {
+            let depth =
+                *depth.get_or_init(|| styles.entries().filter_map(Style::recipe).count());
+            RecipeIndex(depth - r)
+        };
+        if !recipe.applicable(target, styles)
+            || !firsts.admit(recipe, index, target, styles)
+        {
             r += 1;
             continue;
         }
@@ -210,6 +276,12 @@ fn prepare(
         map.apply(show_settable.show_set(styles));
     }
 
+    // Evaluate contextual properties of show-set rules in the context of the
+    // element, now that it has its location.
+    if map.is_contextual() {
+        *map = map.resolve_contextual(engine, target.location(), styles)?;
+    }
+
     // If necessary, generated "synthesized" fields (which are derived from
     // other fields or queries). Do this after show-set so that show-set styles
     // are respected.
@@ -277,7 +349,7 @@ fn show_regex(
     let make = |s: &str| {
         let mut fresh = target.clone();
         fresh.push_text(s.into());
-        fresh.pack()
+        fresh
     };
 
     let mut result = vec![];
@@ -285,20 +357,22 @@ fn show_regex(
 
     let text = target.text();
 
-    for m in regex.find_iter(target.text()) {
+    for captures in regex.captures_iter(target.text()) {
+        let m = captures.get(0).unwrap();
         let start = m.start();
         if cursor < start {
-            result.push(make(&text[cursor..start]));
+            result.push(make(&text[cursor..start]).pack());
         }
 
-        let piece = make(m.as_str());
-        let transformed = recipe.apply(engine, context, piece)?;
+        let mut piece = make(m.as_str());
+        piece.push_matched(regex.details(&captures, text));
+        let transformed = recipe.apply(engine, context, piece.pack())?;
         result.push(transformed);
         cursor = m.end();
     }
 
     if cursor < text.len() {
-        result.push(make(&text[cursor..]));
+        result.push(make(&text[cursor..]).pack());
     }
 
     // In contrast to normal elements, which are guarded individually, for text
@@ -0,0 +1,105 @@
+//! Layout of content outside of a document.
+
+use comemo::{Track, Tracked, Validate};
+
+use crate::compile::{deduplicate, sort_by_span, warn_divergences, DEFAULT_ITERATIONS};
+use crate::diag::{SourceResult, Warned};
+use crate::engine::{Budget, Engine, Route, Sink, Traced};
+use crate::foundations::{Content, StyleChain};
+use crate::introspection::{Introspector, Locator};
+use crate::layout::{Abs, Axes, Binding, Frame, Page, Regions, Size};
+use crate::World;
+
+/// Lay out a piece of content into a single, tightly cropped frame.
+///
+/// Unlike [`compile`], this doesn't produce a document: There are no pages and
+/// no margins. The content is laid out with the styles of the world's library
+/// into a region of unbounded size and the frame is just as large as the
+/// content. This is meant for tooling that generates many small previews, for
+/// example of equations. The frame can be rasterized with `typst-render` or
+/// turned into an SVG with `typst-svg`.
+///
+/// Introspections within the content are resolved like in a full compilation.
+#[typst_macros::time]
+pub fn layout_snippet(
+    world: &dyn World,
+    content: &Content,
+) -> Warned<SourceResult<Frame>> {
+    let mut sink = Sink::new();
+    let output = layout_snippet_inner(world.track(), content, &mut sink)
+        .map_err(deduplicate)
+        .map_err(sort_by_span);
+    let logs = sort_by_span(sink.logs());
+    Warned {
+        output,
+        warnings: sort_by_span(sink.warnings()),
+        logs,
+    }
+}
+
+/// Relayout a snippet until introspection converges.
+pub(crate) fn layout_snippet_inner(
+    world: Tracked<dyn World + '_>,
+    content: &Content,
+    sink: &mut Sink,
+) -> SourceResult<Frame> {
+    let library = world.library();
+    let styles = StyleChain::new(&library.styles);
+    let traced = Traced::default();
+    let budget = Budget::default();
+    let pod = Regions::one(Size::splat(Abs::inf()), Axes::splat(false));
+
+    let logged = sink.logged();
+    let mut iter = 0;
+    let mut introspector = Introspector::default();
+    let frame = loop {
+        // Clear delayed errors and messages logged by the previous attempt.
+        sink.delayed();
+        sink.truncate_logs(logged);
+
+        let constraint = <Introspector as Validate>::Constraint::new();
+        let mut engine = Engine {
+            world,
+            introspector: introspector.track_with(&constraint),
+            traced: traced.track(),
+            budget: budget.track(),
+            sink: sink.track_mut(),
+            route: Route::default(),
+        };
+
+        // The introspector works on pages, so the frame is wrapped in one.
+        let frame = content
+            .layout(&mut engine, Locator::root(), styles, pod)?
+            .into_frame();
+        let page = Page {
+            frame,
+            numbering: None,
+            number: 1,
+            spine: Binding::Left,
+            trim: Abs::zero(),
+            bleed: Abs::zero(),
+        };
+        let previous = (iter + 1 >= DEFAULT_ITERATIONS).then(|| introspector.clone());
+        introspector.rebuild(std::slice::from_ref(&page));
+        iter += 1;
+
+        if introspector.validate(&constraint) {
+            break page.frame;
+        }
+
+        if let Some(previous) = previous {
+            warn_divergences(world, sink, DEFAULT_ITERATIONS, &previous, &introspector);
+            break page.frame;
+        }
+    };
+
+    sink.deduplicate_logs(logged);
+
+    // Promote delayed errors.
+    let delayed = sink.delayed();
+    if !delayed.is_empty() {
+        return Err(delayed);
+    }
+
+    Ok(frame)
+}
@@ -0,0 +1,113 @@
+//! Running the tests defined in a source file.
+
+use comemo::{Track, Tracked};
+use ecow::EcoString;
+
+use crate::compile::{deduplicate, sort_by_span};
+use crate::diag::{SourceResult, Warned};
+use crate::engine::{Budget, Engine, Route, Sink, Traced};
+use crate::foundations::{
+    Context, NativeElement, Packed, Selector, StyleChain, TestCaseElem, Value,
+};
+use crate::introspection::Introspector;
+use crate::layout::Frame;
+use crate::snippet::layout_snippet_inner;
+use crate::syntax::Span;
+use crate::World;
+
+/// Run the tests defined in the main source file.
+///
+/// The main file is evaluated and the
+/// [`test-case`](crate::foundations::TestCaseElem) elements at its top level
+/// are collected. Each test's function is then called, and only tests that
+/// ask for it are laid out into a frame like with [`layout_snippet`](crate::layout_snippet). This is
+/// meant for package authors who want to check their code without producing a
+/// document.
+///
+/// - Returns `Ok(results)` with one result per test if the file could be
+///   evaluated, even if some of the tests failed.
+/// - Returns `Err(errors)` if evaluating the file itself failed.
+#[typst_macros::time]
+pub fn test(world: &dyn World) -> Warned<SourceResult<Vec<TestResult>>> {
+    let mut sink = Sink::new();
+    let output = test_inner(world.track(), &mut sink)
+        .map_err(deduplicate)
+        .map_err(sort_by_span);
+    let logs = sort_by_span(sink.logs());
+    Warned {
+        output,
+        warnings: sort_by_span(sink.warnings()),
+        logs,
+    }
+}
+
+/// The result of running a single test.
+#[derive(Debug, Clone)]
+pub struct TestResult {
+    /// The name of the test.
+    pub name: EcoString,
+    /// The location of the test in the source.
+    pub span: Span,
+    /// The frame the test was laid out into if it asked for layout, or the
+    /// errors it failed with.
+    pub output: SourceResult<Option<Frame>>,
+}
+
+/// Evaluate the main source file and run its tests.
+fn test_inner(
+    world: Tracked<dyn World + '_>,
+    sink: &mut Sink,
+) -> SourceResult<Vec<TestResult>> {
+    let library = world.library();
+    let styles = StyleChain::new(&library.styles);
+    let content = crate::eval::eval(
+        world,
+        Traced::default().track(),
+        sink.track_mut(),
+        Route::default().track(),
+        &world.main(),
+    )?
+    .content();
+
+    Ok(content
+        .query(Selector::Elem(TestCaseElem::elem(), None))
+        .iter()
+        .filter_map(|elem| elem.to_packed::<TestCaseElem>())
+        .map(|elem| TestResult {
+            name: elem.name.clone(),
+            span: elem.span(),
+            output: run_test(world, elem, styles, sink)
+                .map_err(deduplicate)
+                .map_err(sort_by_span),
+        })
+        .collect())
+}
+
+/// Call a test's function and lay out what it returns if requested.
+fn run_test(
+    world: Tracked<dyn World + '_>,
+    test: &Packed<TestCaseElem>,
+    styles: StyleChain,
+    sink: &mut Sink,
+) -> SourceResult<Option<Frame>> {
+    let introspector = Introspector::default();
+    let traced = Traced::default();
+    let budget = Budget::default();
+    let mut engine = Engine {
+        world,
+        introspector: introspector.track(),
+        traced: traced.track(),
+        budget: budget.track(),
+        sink: sink.track_mut(),
+        route: Route::default(),
+    };
+
+    let value = test
+        .body
+        .call::<[Value; 0]>(&mut engine, Context::none().track(), [])?;
+    if !test.layout(styles) {
+        return Ok(None);
+    }
+
+    layout_snippet_inner(world, &value.display(), sink).map(Some)
+}
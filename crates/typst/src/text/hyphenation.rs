@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::StrResult;
+use crate::foundations::{cast, Str};
+
+/// User-provided hyphenation patterns in the format of Liang's algorithm, as
+/// used by TeX.
+///
+/// The patterns are given as their source text with one or more patterns per
+/// line, like `{.ach4 a1b 2b1l}`. Text after a `%` is a comment.
+#[derive(Clone)]
+pub struct HyphenationPatterns(Arc<Repr>);
+
+/// The internal representation of hyphenation patterns.
+struct Repr {
+    /// The source text the patterns were parsed from.
+    source: EcoString,
+    /// Maps from the letters of each pattern to the levels between them.
+    patterns: HashMap<String, Vec<u8>>,
+    /// The length of the longest pattern in letters.
+    longest: usize,
+}
+
+impl HyphenationPatterns {
+    /// The minimum number of letters before a hyphenation opportunity.
+    const LEFT_MIN: usize = 2;
+
+    /// The minimum number of letters after a hyphenation opportunity.
+    const RIGHT_MIN: usize = 2;
+
+    /// Parse patterns from their source text.
+    pub fn parse(source: EcoString) -> StrResult<Self> {
+        let mut patterns = HashMap::new();
+        let mut longest = 0;
+
+        for line in source.lines() {
+            let line = line.split('%').next().unwrap_or_default();
+            for pattern in line.split_whitespace() {
+                let mut letters = String::new();
+                let mut levels = vec![0];
+                for c in pattern.chars() {
+                    if let Some(level) = c.to_digit(10) {
+                        *levels.last_mut().unwrap() = level as u8;
+                    } else if c.is_alphabetic() || c == '.' || c == '\'' {
+                        letters.push(lowercase(c));
+                        levels.push(0);
+                    } else {
+                        return Err(eco_format!(
+                            "invalid hyphenation pattern `{pattern}`"
+                        ));
+                    }
+                }
+
+                longest = longest.max(levels.len() - 1);
+                patterns.insert(letters, levels);
+            }
+        }
+
+        if patterns.is_empty() {
+            return Err("hyphenation patterns must not be empty".into());
+        }
+
+        Ok(Self(Arc::new(Repr { source, patterns, longest })))
+    }
+
+    /// The source text of the patterns.
+    pub fn source(&self) -> &EcoString {
+        &self.0.source
+    }
+
+    /// Split a word into syllables at its hyphenation opportunities.
+    pub fn hyphenate<'s>(&self, word: &'s str) -> Vec<&'s str> {
+        let chars: Vec<char> = std::iter::once('.')
+            .chain(word.chars().map(lowercase))
+            .chain(std::iter::once('.'))
+            .collect();
+
+        // Find all patterns in the word and keep the highest level between
+        // each pair of letters.
+        let mut levels = vec![0; chars.len() + 1];
+        let mut key = String::new();
+        for start in 0..chars.len() {
+            key.clear();
+            for end in start + 1..=chars.len().min(start + self.0.longest) {
+                key.push(chars[end - 1]);
+                let Some(pattern) = self.0.patterns.get(&key) else { continue };
+                for (i, &level) in pattern.iter().enumerate() {
+                    levels[start + i] = levels[start + i].max(level);
+                }
+            }
+        }
+
+        // Odd levels permit a break. The level before the n-th letter of the
+        // word is at index n + 1 because of the leading dot.
+        let len = chars.len() - 2;
+        let mut syllables = vec![];
+        let mut last = 0;
+        for (n, (offset, _)) in word.char_indices().enumerate() {
+            if n >= Self::LEFT_MIN && n + Self::RIGHT_MIN <= len && levels[n + 1] % 2 == 1
+            {
+                syllables.push(&word[last..offset]);
+                last = offset;
+            }
+        }
+
+        syllables.push(&word[last..]);
+        syllables
+    }
+}
+
+impl Debug for HyphenationPatterns {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "HyphenationPatterns(..)")
+    }
+}
+
+impl Hash for HyphenationPatterns {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.source.hash(state);
+    }
+}
+
+impl PartialEq for HyphenationPatterns {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.source == other.0.source
+    }
+}
+
+cast! {
+    HyphenationPatterns,
+    self => self.source().clone().into_value(),
+    v: Str => Self::parse(v.into())?,
+}
+
+/// Split a word into syllables according to a hyphenation exception like
+/// `ta-ble-au`, ignoring case.
+///
+/// Returns `None` if the exception is for a different word.
+pub(crate) fn hyphenate_exception<'s>(
+    word: &'s str,
+    exception: &str,
+) -> Option<Vec<&'s str>> {
+    let mut rest = word.char_indices().peekable();
+    let mut splits = vec![];
+    for c in exception.chars() {
+        if c == '-' {
+            splits.push(rest.peek().map_or(word.len(), |&(i, _)| i));
+        } else {
+            let (_, w) = rest.next()?;
+            if lowercase(w) != lowercase(c) {
+                return None;
+            }
+        }
+    }
+
+    if rest.next().is_some() {
+        return None;
+    }
+
+    let mut syllables = vec![];
+    let mut last = 0;
+    for split in splits {
+        if split > last && split < word.len() {
+            syllables.push(&word[last..split]);
+            last = split;
+        }
+    }
+
+    syllables.push(&word[last..]);
+    Some(syllables)
+}
+
+/// Lowercase a letter for matching it against patterns and exceptions.
+///
+/// Letters whose lowercase form consists of multiple characters are kept as
+/// they are, such that each letter of a word corresponds to exactly one letter
+/// of a pattern.
+fn lowercase(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(l), None) => l,
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hyphenate_with_patterns() {
+        let patterns = HyphenationPatterns::parse("% Comment\n1ba 1ca".into()).unwrap();
+        assert_eq!(patterns.hyphenate("abacab"), ["aba", "cab"]);
+        assert_eq!(patterns.hyphenate("Abacaba"), ["Aba", "ca", "ba"]);
+        assert!(HyphenationPatterns::parse("a-b".into()).is_err());
+    }
+
+    #[test]
+    fn test_hyphenate_with_multi_char_lowercase() {
+        let patterns = HyphenationPatterns::parse("1İ".into()).unwrap();
+        assert_eq!(patterns.hyphenate("abİcd"), ["ab", "İcd"]);
+        assert_eq!(hyphenate_exception("İbe", "İ-be"), Some(vec!["İ", "be"]));
+    }
+
+    #[test]
+    fn test_hyphenate_exception() {
+        assert_eq!(
+            hyphenate_exception("Tableau", "ta-ble-au"),
+            Some(vec!["Ta", "ble", "au"])
+        );
+        assert_eq!(hyphenate_exception("tables", "ta-ble-au"), None);
+        assert_eq!(hyphenate_exception("table", "ta-ble-au"), None);
+    }
+}
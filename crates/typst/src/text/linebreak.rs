@@ -1,5 +1,8 @@
-use crate::foundations::{elem, Packed};
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, Packed, Show, StyleChain};
 use crate::realize::{Behave, Behaviour};
+use crate::text::TextElem;
 
 /// Inserts a line break.
 ///
@@ -42,3 +45,37 @@ impl Behave for Packed<LinebreakElem> {
         Behaviour::Destructive
     }
 }
+
+/// Prevents line breaks within its content.
+///
+/// Lines are neither broken at spaces nor at hyphenation opportunities inside
+/// of the content. This is useful for product names, code identifiers, and
+/// dates that should be read as one unit. Unlike content in a [box], it remains
+/// part of the surrounding text, so its spaces still stretch when the paragraph
+/// is justified. Explicit [line breaks]($linebreak) within the content are
+/// kept.
+///
+/// If the content is wider than a line, it starts on a new line and overflows
+/// that line's end.
+///
+/// # Example
+/// ```example
+/// #set page(width: 140pt)
+/// The release is planned for
+/// #nobreak[24 December 2024]
+/// and will ship with
+/// #nobreak[Typst Web App] support.
+/// ```
+#[elem(title = "No Break", Show)]
+pub struct NobreakElem {
+    /// The content that should not be broken across lines.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for Packed<NobreakElem> {
+    #[typst_macros::time(name = "nobreak", span = self.span())]
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(self.body().clone().styled(TextElem::set_nobreak(true)))
+    }
+}
@@ -3,6 +3,7 @@
 mod case;
 mod deco;
 mod font;
+mod hyphenation;
 mod item;
 mod lang;
 mod linebreak;
@@ -14,10 +15,12 @@ mod shift;
 mod smallcaps_;
 mod smartquote;
 mod space;
+mod warichu;
 
 pub use self::case::*;
 pub use self::deco::*;
 pub use self::font::*;
+pub use self::hyphenation::*;
 pub use self::item::*;
 pub use self::lang::*;
 pub use self::linebreak::*;
@@ -27,13 +30,16 @@ pub use self::shift::*;
 pub use self::smallcaps_::*;
 pub use self::smartquote::*;
 pub use self::space::*;
+pub use self::warichu::*;
 
 use std::fmt::{self, Debug, Formatter};
+use std::str::FromStr;
 
 use ecow::{eco_format, EcoString};
 use rustybuzz::Feature;
 use smallvec::SmallVec;
 use ttf_parser::{Rect, Tag};
+use unicode_script::Script;
 
 use crate::diag::{bail, warning, HintedStrResult, SourceResult};
 use crate::engine::Engine;
@@ -59,6 +65,7 @@ pub(super) fn define(global: &mut Scope) {
     global.category(TEXT);
     global.define_elem::<TextElem>();
     global.define_elem::<LinebreakElem>();
+    global.define_elem::<NobreakElem>();
     global.define_elem::<SmartQuoteElem>();
     global.define_elem::<SubElem>();
     global.define_elem::<SuperElem>();
@@ -68,6 +75,7 @@ pub(super) fn define(global: &mut Scope) {
     global.define_elem::<HighlightElem>();
     global.define_elem::<SmallcapsElem>();
     global.define_elem::<RawElem>();
+    global.define_elem::<WarichuElem>();
     global.define_func::<lower>();
     global.define_func::<upper>();
     global.define_func::<lorem>();
@@ -164,9 +172,30 @@ pub struct TextElem {
     /// #set text(fallback: false)
     /// هذا عربي
     /// ```
-    #[default(true)]
+    ///
+    /// Instead of a boolean, you can also pass a dictionary that maps scripts
+    /// or languages to fallback families. For text in one of the scripts or
+    /// languages, the given families are tried after the primary font list
+    /// and before the last resort fallback, in the order of the dictionary.
+    /// This way, mixed-script documents don't need to rely on the order of one
+    /// global font list. The keys are lowercase script names like `han`,
+    /// `arabic`, or `cyrillic`, or [language codes]($text.lang) like `ja`.
+    /// The families for a language apply to text in any script in that
+    /// language, so they are also tried for, say, Latin letters in Japanese
+    /// text that the primary font list lacks. Last resort fallback stays
+    /// enabled unless the dictionary maps `last-resort` to `{false}`.
+    ///
+    /// ```example
+    /// #set text(fallback: (
+    ///   han: "Noto Serif CJK SC",
+    ///   arabic: "Noto Sans Arabic",
+    /// ))
+    /// Typst 排版 هذا عربي
+    /// ```
+    #[default(FontFallback::default())]
+    #[borrowed]
     #[ghost]
-    pub fallback: bool,
+    pub fallback: FontFallback,
 
     /// The desired font style.
     ///
@@ -313,6 +342,40 @@ pub struct TextElem {
     #[ghost]
     pub cjk_latin_spacing: Smart<Option<Never>>,
 
+    /// Configures the layout of Chinese and Japanese text.
+    ///
+    /// This property is a dictionary with the following keys, each of which
+    /// is optional:
+    ///
+    /// - `kinsoku`: Whether to enforce the line breaking rules of Japanese
+    ///   and Chinese typesetting (_kinsoku shori_) in addition to those of
+    ///   Unicode. Closing brackets, full stops, commas, iteration marks, small
+    ///   kana, and the prolonged sound mark then never start a line and
+    ///   opening brackets never end one. Defaults to `{false}`, which only
+    ///   applies the rules of Unicode.
+    /// - `compress`: Whether to compress consecutive full-width punctuation
+    ///   marks, like a closing bracket followed by a full stop, to half
+    ///   their width. Defaults to `{true}`.
+    /// - `spacing`: The spacing between CJK and Latin characters. Can be
+    ///   `{auto}` for a quarter of an em, `{none}`, or a length. The spacing
+    ///   can shrink to half its size in justified text. When not set, the
+    ///   [`cjk-latin-spacing`]($text.cjk-latin-spacing) property applies.
+    ///
+    /// Values are updated key by key, so that setting one key keeps the
+    /// others.
+    ///
+    /// ```example
+    /// #set text(lang: "ja", font: "Noto Serif CJK JP")
+    /// #set text(cjk: (spacing: 0.5em))
+    /// 第4章ではAPIを紹介する。
+    ///
+    /// #set text(cjk: (compress: false))
+    /// 「こんにちは」。
+    /// ```
+    #[fold]
+    #[ghost]
+    pub cjk: Cjk,
+
     /// An amount to shift the text baseline by.
     ///
     /// ```example
@@ -485,6 +548,84 @@ pub struct TextElem {
     #[ghost]
     pub hyphenate: Hyphenate,
 
+    /// Words with fixed hyphenation opportunities, marked with hyphens.
+    ///
+    /// For these words, the given opportunities take precedence over the
+    /// hyphenation patterns of the text language. This way, you can teach
+    /// Typst to correctly hyphenate domain-specific vocabulary, names, or
+    /// words the patterns get wrong. Words are matched regardless of case.
+    /// A word without hyphens is never hyphenated.
+    ///
+    /// ```example
+    /// #set page(width: 60pt)
+    /// #set par(justify: true)
+    /// #set text(hyphenation-exceptions: ("ta-ble-au", "Zu-cker"))
+    /// A tableau of Zucker.
+    /// ```
+    #[ghost]
+    pub hyphenation_exceptions: Vec<EcoString>,
+
+    /// Custom hyphenation patterns to use instead of the built-in ones.
+    ///
+    /// The patterns are given as a string in the format of Liang's algorithm
+    /// as used by TeX, with whitespace-separated patterns like `{"1ba"}` and
+    /// comments starting with `%`. Typically, you load them from a file with
+    /// [`read`]. The `hyph-*.pat.txt` files of the
+    /// [hyph-utf8](https://github.com/hyphenation/tex-hyphen) project have
+    /// this format. With custom patterns, you can hyphenate languages that
+    /// Typst doesn't support out of the box.
+    ///
+    /// When set to `{none}`, the built-in patterns for the
+    /// [text language]($text.lang) are used.
+    ///
+    /// ```typ
+    /// #set text(
+    ///   lang: "la",
+    ///   hyphenation-patterns: read("hyph-la.pat.txt"),
+    /// )
+    /// ```
+    #[ghost]
+    pub hyphenation_patterns: Option<HyphenationPatterns>,
+
+    /// Whether to repeat a hyphen at the start of the next line when a line
+    /// breaks at an explicit hyphen, as in "arco-/-da-velha".
+    ///
+    /// When `{auto}`, the hyphen is repeated if the conventions of the
+    /// [text language]($text.lang) ask for it. This is the case in Croatian,
+    /// Czech, Hungarian, Lower Sorbian, Polish, Portuguese, and Slovak, as
+    /// well as in Spanish if the next line doesn't start with a capital
+    /// letter.
+    ///
+    /// ```example
+    /// #set page(width: 80pt)
+    /// #set text(lang: "hu")
+    /// Egy nagyon hosszú kelet-európai szó.
+    ///
+    /// #set text(repeat-hyphen: false)
+    /// Egy nagyon hosszú kelet-európai szó.
+    /// ```
+    #[ghost]
+    pub repeat_hyphen: Smart<bool>,
+
+    /// Whether a line may end with a word of a single letter.
+    ///
+    /// In some languages, like Czech, Polish, and Slovak, one-letter
+    /// prepositions and conjunctions should not be stranded at the end of a
+    /// line but move to the next line together with the following word. Set
+    /// this to `{false}` to follow this convention.
+    ///
+    /// ```example
+    /// #set page(width: 90pt)
+    /// #set text(lang: "cs")
+    /// Šli jsme spolu v lese a v horách.
+    ///
+    /// #set text(single-letter-breaks: false)
+    /// Šli jsme spolu v lese a v horách.
+    /// ```
+    #[ghost]
+    #[default(true)]
+    pub single_letter_breaks: bool,
+
     /// The "cost" of various choices when laying out text. A higher cost means
     /// the layout engine will make the choice less often. Costs are specified
     /// as a ratio of the default cost, so `50%` will make text layout twice as
@@ -495,6 +636,7 @@ pub struct TextElem {
     /// - `runt`: ending a paragraph with a line with a single word
     /// - `widow`: leaving a single line of paragraph on the next page
     /// - `orphan`: leaving single line of paragraph on the previous page
+    /// - `ladder`: ending two consecutive lines with a hyphen or dash
     ///
     /// Hyphenation is generally avoided by placing the whole word on the next
     /// line, so a higher hyphenation cost can result in awkward justification
@@ -597,6 +739,18 @@ pub struct TextElem {
     #[ghost]
     pub ligatures: bool,
 
+    /// Whether contextual ligatures are active. Setting this to `{false}`
+    /// disables the OpenType `clig` font feature, while keeping the standard
+    /// ligatures enabled by [`ligatures`]($text.ligatures).
+    ///
+    /// Contextual ligatures are formed depending on the surrounding
+    /// characters. Some fonts, particularly those for programming, use them
+    /// to join sequences like `->` or `!=`, which may not be desired in all
+    /// places.
+    #[default(true)]
+    #[ghost]
+    pub contextual_ligatures: bool,
+
     /// Whether ligatures that should be used sparingly are active. Setting this
     /// to `{true}` enables the OpenType `dlig` font feature.
     #[default(false)]
@@ -664,6 +818,23 @@ pub struct TextElem {
     #[ghost]
     pub fractions: bool,
 
+    /// Whether to set acronyms in small capitals. Setting this to `{true}`
+    /// enables the OpenType `c2sc` font feature for every run of two or more
+    /// uppercase letters and spaces the letters of the run slightly apart.
+    ///
+    /// Like with [`smallcaps`]($smallcaps), the font must support the feature
+    /// for this to have a visible effect.
+    ///
+    /// ```example
+    /// #set text(font: "Libertinus Serif")
+    /// The NASA and ESA missions. \
+    /// #set text(acronyms: true)
+    /// The NASA and ESA missions.
+    /// ```
+    #[default(false)]
+    #[ghost]
+    pub acronyms: bool,
+
     /// Raw OpenType features to apply.
     ///
     /// - If given an array of strings, sets the features identified by the
@@ -689,6 +860,31 @@ pub struct TextElem {
     #[required]
     pub text: EcoString,
 
+    /// Details about the match, if the text was matched by a
+    /// [regex show rule]($styling/#show-rules).
+    ///
+    /// This is a dictionary with the following keys:
+    /// - `start`: The start offset of the match in the surrounding text.
+    /// - `end`: The end offset of the match in the surrounding text.
+    /// - `text`: The text that was matched.
+    /// - `captures`: An array of the texts of the capture groups, with
+    ///   `{none}` for groups that didn't participate in the match.
+    /// - `named`: A dictionary of the texts of the named capture groups.
+    /// - `before`: The surrounding text before the match.
+    /// - `after`: The surrounding text after the match.
+    ///
+    /// ```example
+    /// #show regex("(\d+)\s*(kg|m)"): it => {
+    ///   let (num, unit) = it.matched.captures
+    ///   [#num#sym.space.thin#unit]
+    /// }
+    ///
+    /// The rope is 12m long and
+    /// weighs 3 kg.
+    /// ```
+    #[synthesized]
+    pub matched: Dict,
+
     /// The offset of the text in the text syntax node referenced by this
     /// element's span.
     #[internal]
@@ -724,6 +920,12 @@ pub struct TextElem {
     #[default(false)]
     #[ghost]
     pub smallcaps: bool,
+
+    /// Whether line breaks are forbidden within the text.
+    #[internal]
+    #[default(false)]
+    #[ghost]
+    pub nobreak: bool,
 }
 
 impl TextElem {
@@ -814,8 +1016,138 @@ cast! {
     values: Array => Self(values.into_iter().map(|v| v.cast()).collect::<HintedStrResult<_>>()?),
 }
 
+/// Which fonts to fall back to when the primary font list contains no match.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct FontFallback {
+    /// Whether to try the built-in fallback families and, as a last resort,
+    /// all available fonts.
+    pub last_resort: bool,
+    /// Fallback families for text in specific scripts or languages.
+    pub families: Vec<(FallbackKey, FontList)>,
+}
+
+impl Default for FontFallback {
+    fn default() -> Self {
+        Self { last_resort: true, families: vec![] }
+    }
+}
+
+cast! {
+    FontFallback,
+    self => if self.families.is_empty() {
+        self.last_resort.into_value()
+    } else {
+        let mut dict = self
+            .families
+            .into_iter()
+            .map(|(key, list)| (key.as_str().into(), list.into_value()))
+            .collect::<Dict>();
+        if !self.last_resort {
+            dict.insert("last-resort".into(), false.into_value());
+        }
+        dict.into_value()
+    },
+    v: bool => Self { last_resort: v, families: vec![] },
+    mut v: Dict => Self {
+        last_resort: v
+            .take("last-resort")
+            .ok()
+            .map(|v| v.cast())
+            .transpose()?
+            .unwrap_or(true),
+        families: v
+            .into_iter()
+            .map(|(key, value)| Ok((key.as_str().parse()?, value.cast()?)))
+            .collect::<HintedStrResult<_>>()?,
+    },
+}
+
+/// The scripts or language that a fallback family applies to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FallbackKey {
+    /// Text in the given script.
+    Script(Script),
+    /// Text in the given language.
+    Lang(Lang),
+}
+
+impl FallbackKey {
+    /// The scripts that fallback families can be configured for, by name.
+    const SCRIPTS: &'static [(&'static str, Script)] = &[
+        ("arabic", Script::Arabic),
+        ("armenian", Script::Armenian),
+        ("bengali", Script::Bengali),
+        ("cyrillic", Script::Cyrillic),
+        ("devanagari", Script::Devanagari),
+        ("ethiopic", Script::Ethiopic),
+        ("georgian", Script::Georgian),
+        ("greek", Script::Greek),
+        ("gujarati", Script::Gujarati),
+        ("gurmukhi", Script::Gurmukhi),
+        ("han", Script::Han),
+        ("hangul", Script::Hangul),
+        ("hebrew", Script::Hebrew),
+        ("hiragana", Script::Hiragana),
+        ("kannada", Script::Kannada),
+        ("katakana", Script::Katakana),
+        ("khmer", Script::Khmer),
+        ("lao", Script::Lao),
+        ("latin", Script::Latin),
+        ("malayalam", Script::Malayalam),
+        ("myanmar", Script::Myanmar),
+        ("sinhala", Script::Sinhala),
+        ("tamil", Script::Tamil),
+        ("telugu", Script::Telugu),
+        ("thai", Script::Thai),
+        ("tibetan", Script::Tibetan),
+    ];
+
+    /// Whether the fallback applies to text in the script and language.
+    fn matches(self, script: Script, lang: Lang) -> bool {
+        match self {
+            Self::Script(s) => s == script,
+            Self::Lang(l) => l == lang,
+        }
+    }
+
+    /// The key's name.
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Script(script) => Self::SCRIPTS
+                .iter()
+                .find(|(_, s)| s == script)
+                .map_or("", |(name, _)| name),
+            Self::Lang(lang) => lang.as_str(),
+        }
+    }
+}
+
+impl FromStr for FallbackKey {
+    type Err = EcoString;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        if let Some(&(_, script)) = Self::SCRIPTS.iter().find(|(name, _)| *name == key) {
+            return Ok(Self::Script(script));
+        }
+
+        Lang::from_str(key)
+            .map(Self::Lang)
+            .map_err(|_| eco_format!("unknown script or language `{key}`"))
+    }
+}
+
 /// Resolve a prioritized iterator over the font families.
 pub(crate) fn families(styles: StyleChain) -> impl Iterator<Item = &str> + Clone {
+    script_families(styles, Script::Unknown)
+}
+
+/// Resolve a prioritized iterator over the font families for text in the given
+/// script, including the fallback families for the script and the text's
+/// language.
+pub(crate) fn script_families(
+    styles: StyleChain,
+    script: Script,
+) -> impl Iterator<Item = &str> + Clone {
     const FALLBACKS: &[&str] = &[
         "linux libertine",
         "twitter color emoji",
@@ -824,10 +1156,19 @@ pub(crate) fn families(styles: StyleChain) -> impl Iterator<Item = &str> + Clone
         "segoe ui emoji",
     ];
 
-    let tail = if TextElem::fallback_in(styles) { FALLBACKS } else { &[] };
+    let fallback = TextElem::fallback_in(styles);
+    let lang = TextElem::lang_in(styles);
+    let specific = fallback
+        .families
+        .iter()
+        .filter(move |(key, _)| key.matches(script, lang))
+        .flat_map(|(_, list)| list.into_iter().map(|family| family.as_str()));
+
+    let tail = if fallback.last_resort { FALLBACKS } else { &[] };
     TextElem::font_in(styles)
         .into_iter()
         .map(|family| family.as_str())
+        .chain(specific)
         .chain(tail.iter().copied())
 }
 
@@ -1177,6 +1518,8 @@ pub(crate) fn features(styles: StyleChain) -> Vec<Feature> {
     if !TextElem::ligatures_in(styles) {
         feat(b"liga", 0);
         feat(b"clig", 0);
+    } else if !TextElem::contextual_ligatures_in(styles) {
+        feat(b"clig", 0);
     }
 
     if TextElem::discretionary_ligatures_in(styles) {
@@ -1234,6 +1577,66 @@ impl Fold for WeightDelta {
     }
 }
 
+/// Configuration for the layout of Chinese and Japanese text.
+///
+/// Configurations are updated (prioritizing the later value) when folded.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub struct Cjk {
+    kinsoku: Option<bool>,
+    compress: Option<bool>,
+    spacing: Option<Smart<Option<Length>>>,
+}
+
+impl Cjk {
+    /// Whether to enforce the additional line breaking rules.
+    #[must_use]
+    pub fn kinsoku(&self) -> bool {
+        self.kinsoku.unwrap_or(false)
+    }
+
+    /// Whether to compress consecutive full-width punctuation.
+    #[must_use]
+    pub fn compress(&self) -> bool {
+        self.compress.unwrap_or(true)
+    }
+
+    /// The spacing between CJK and Latin characters, if it was configured.
+    #[must_use]
+    pub fn spacing(&self) -> Option<Smart<Option<Length>>> {
+        self.spacing
+    }
+}
+
+impl Fold for Cjk {
+    #[inline]
+    fn fold(self, outer: Self) -> Self {
+        Self {
+            kinsoku: self.kinsoku.or(outer.kinsoku),
+            compress: self.compress.or(outer.compress),
+            spacing: self.spacing.or(outer.spacing),
+        }
+    }
+}
+
+cast! {
+    Cjk,
+    self => dict![
+        "kinsoku" => self.kinsoku(),
+        "compress" => self.compress(),
+        "spacing" => self.spacing().unwrap_or(Smart::Auto),
+    ].into_value(),
+    mut v: Dict => {
+        let ret = Self {
+            kinsoku: v.take("kinsoku").ok().map(|v| v.cast()).transpose()?,
+            compress: v.take("compress").ok().map(|v| v.cast()).transpose()?,
+            spacing: v.take("spacing").ok().map(|v| v.cast()).transpose()?,
+        };
+        v.finish(&["kinsoku", "compress", "spacing"])?;
+        ret
+    },
+}
+
 /// Costs for various layout decisions.
 ///
 /// Costs are updated (prioritizing the later value) when folded.
@@ -1244,6 +1647,7 @@ pub struct Costs {
     runt: Option<Ratio>,
     widow: Option<Ratio>,
     orphan: Option<Ratio>,
+    ladder: Option<Ratio>,
 }
 
 impl Costs {
@@ -1266,6 +1670,11 @@ impl Costs {
     pub fn orphan(&self) -> Ratio {
         self.orphan.unwrap_or(Ratio::one())
     }
+
+    #[must_use]
+    pub fn ladder(&self) -> Ratio {
+        self.ladder.unwrap_or(Ratio::one())
+    }
 }
 
 impl Fold for Costs {
@@ -1276,6 +1685,7 @@ impl Fold for Costs {
             runt: self.runt.or(outer.runt),
             widow: self.widow.or(outer.widow),
             orphan: self.orphan.or(outer.orphan),
+            ladder: self.ladder.or(outer.ladder),
         }
     }
 }
@@ -1287,6 +1697,7 @@ cast! {
         "runt" => self.runt(),
         "widow" => self.widow(),
         "orphan" => self.orphan(),
+        "ladder" => self.ladder(),
     ].into_value(),
     mut v: Dict => {
         let ret = Self {
@@ -1294,8 +1705,9 @@ cast! {
             runt: v.take("runt").ok().map(|v| v.cast()).transpose()?,
             widow: v.take("widow").ok().map(|v| v.cast()).transpose()?,
             orphan: v.take("orphan").ok().map(|v| v.cast()).transpose()?,
+            ladder: v.take("ladder").ok().map(|v| v.cast()).transpose()?,
         };
-        v.finish(&["hyphenation", "runt", "widow", "orphan"])?;
+        v.finish(&["hyphenation", "runt", "widow", "orphan", "ladder"])?;
         ret
     },
 }
@@ -31,6 +31,9 @@ use crate::text::TextElem;
 /// #show smallcaps: set text(font: "Latin Modern Roman Caps")
 /// ```
 ///
+/// To set acronyms in small capitals automatically, enable the
+/// [`acronyms`]($text.acronyms) property of the text function instead.
+///
 /// In the future, this function will support synthesizing smallcaps from normal
 /// letters, but this is not yet implemented.
 #[elem(title = "Small Capitals", Show)]
@@ -0,0 +1,130 @@
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, NativeElement, Packed, Show, StyleChain};
+use crate::introspection::Locator;
+use crate::layout::{
+    Abs, Axes, Em, Frame, InlineElem, InlineItem, Length, Ratio, Regions, Size,
+};
+use crate::model::{first_baseline, FirstLineIndent, ParElem};
+use crate::text::{TextElem, TextSize};
+use crate::utils::Numeric;
+
+/// An inline note set in two smaller lines (warichū).
+///
+/// In Japanese typography, short annotations are often set within the line
+/// of the running text: The note's text is set smaller and split into two
+/// lines that are stacked on top of each other, so that they together take
+/// up about the height of one line. The note is read from the upper line to
+/// the lower one.
+///
+/// A note that doesn't fit onto the line is broken into several segments,
+/// each of which is set in two lines as wide as the paragraph and read
+/// before the next one. Since the segments are set before the paragraph is
+/// broken into lines, the first one doesn't start in the space that remains
+/// on the current line, but on a line of its own.
+///
+/// # Example
+/// ```example
+/// #set text(lang: "ja", font: "Noto Serif CJK JP")
+/// 吾輩は猫である#warichu[夏目漱石の小説]。名前はまだ無い。
+/// ```
+#[elem(Show)]
+pub struct WarichuElem {
+    /// The size of the note's text relative to the surrounding text.
+    #[default(Ratio::new(0.5))]
+    pub size: Ratio,
+
+    /// The gap between the two lines of the note.
+    #[resolve]
+    #[default(Em::new(0.0).into())]
+    pub gap: Length,
+
+    /// The note's content.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for Packed<WarichuElem> {
+    #[typst_macros::time(name = "warichu", span = self.span())]
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(InlineElem::layouter(self.clone(), layout_warichu)
+            .pack()
+            .spanned(self.span()))
+    }
+}
+
+/// Layout a warichū into one or more segments of two lines each.
+///
+/// Each segment sits on the baseline of its lower line.
+fn layout_warichu(
+    elem: &Packed<WarichuElem>,
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    region: Size,
+) -> SourceResult<Vec<InlineItem>> {
+    let size = elem.size(styles).of(TextElem::size_in(styles));
+    let gap = elem.gap(styles);
+    let body = elem
+        .body()
+        .clone()
+        .styled(TextElem::set_size(TextSize(size.into())))
+        .styled(ParElem::set_leading(gap.into()))
+        .styled(ParElem::set_justify(false))
+        .styled(ParElem::set_hanging_indent(Abs::zero().into()))
+        .styled(ParElem::set_first_line_indent(FirstLineIndent::default()));
+
+    let layout = |engine: &mut Engine, locator: Locator, regions: Regions| {
+        body.layout(engine, locator, styles, regions)
+    };
+
+    // Measure the note when it is set in a single line.
+    let unbounded = Size::new(Abs::inf(), Abs::inf());
+    let single =
+        layout(engine, locator.relayout(), Regions::one(unbounded, Axes::splat(false)))?
+            .into_frame();
+    if single.width().is_zero() {
+        return Ok(vec![]);
+    }
+
+    // The height of a segment of two lines and the descent of its lower line.
+    let descent = single.height() - first_baseline(&single).unwrap_or(single.height());
+    let height = 2.0 * single.height() + gap;
+
+    // If the note fits onto one line, split it into two lines of about half
+    // its width. Since the lines can't be broken just anywhere, they may need
+    // to be a bit wider.
+    let mut width = single.width() / 2.0;
+    if width < region.x {
+        let step = size / 2.0;
+        let frame = loop {
+            let pod = Regions::one(Size::new(width, Abs::inf()), Axes::splat(false));
+            let frame = layout(engine, locator.relayout(), pod)?.into_frame();
+            if height.fits(frame.height()) || width >= region.x {
+                break frame;
+            }
+            width = (width + step).min(region.x);
+        };
+
+        if height.fits(frame.height()) {
+            let pod = Regions::one(Size::new(width, Abs::inf()), Axes::splat(false));
+            let mut frame = layout(engine, locator, pod)?.into_frame();
+            frame.set_baseline(frame.height() - descent);
+            return Ok(vec![InlineItem::Frame(frame)]);
+        }
+    }
+
+    // Otherwise, break the note into segments of two lines as wide as the
+    // line. How much of the current line remains is only known once the
+    // paragraph is broken into lines, so the first segment takes a full line,
+    // too.
+    let pod = Regions::repeat(Size::new(region.x, height), Axes::splat(false));
+    Ok(layout(engine, locator, pod)?
+        .into_iter()
+        .filter(|frame| !frame.is_empty())
+        .map(|mut frame: Frame| {
+            frame.set_baseline(frame.height() - descent);
+            InlineItem::Frame(frame)
+        })
+        .collect())
+}
@@ -21,13 +21,13 @@ use crate::foundations::{
 };
 use crate::introspection::Locator;
 use crate::layout::{
-    Abs, Axes, BlockElem, FixedAlignment, Frame, FrameItem, Length, Point, Region, Rel,
-    Size,
+    Abs, Axes, BlockElem, BoxBaseline, BoxElem, Em, FixedAlignment, Frame, FrameItem,
+    Length, Point, Region, Rel, Size,
 };
 use crate::loading::Readable;
 use crate::model::Figurable;
 use crate::syntax::{Span, Spanned};
-use crate::text::{families, LocalName};
+use crate::text::{families, variant, LocalName};
 use crate::utils::LazyHash;
 use crate::visualize::Path;
 use crate::World;
@@ -97,9 +97,31 @@ pub struct ImageElem {
     /// ```
     #[default(ImageFit::Cover)]
     pub fit: ImageFit,
+
+    /// Whether to place the image inline with the surrounding text, with its
+    /// bottom edge at the descender of the current font.
+    ///
+    /// This way, an image with a height of `{1em}` spans roughly from the
+    /// lowest to the highest point of the surrounding letters, like an emoji.
+    /// Since the placement depends on the font size, the image stays aligned
+    /// with the text when the font size changes.
+    ///
+    /// ```example
+    /// #let icon = image.with(
+    ///   "tiger.jpg",
+    ///   height: 1em,
+    ///   baseline-align: true,
+    /// )
+    ///
+    /// A tiger #icon() in the text. \
+    /// #text(18pt)[A bigger #icon() tiger.]
+    /// ```
+    #[default(false)]
+    pub baseline_align: bool,
 }
 
 #[scope]
+#[allow(clippy::too_many_arguments)]
 impl ImageElem {
     /// Decode a raster or vector graphic from bytes or a string.
     ///
@@ -134,6 +156,9 @@ impl ImageElem {
         /// How the image should adjust itself to a given area.
         #[named]
         fit: Option<ImageFit>,
+        /// Whether to place the image inline with the surrounding text.
+        #[named]
+        baseline_align: Option<bool>,
     ) -> StrResult<Content> {
         let mut elem = ImageElem::new(EcoString::new(), data);
         if let Some(format) = format {
@@ -151,16 +176,32 @@ impl ImageElem {
         if let Some(fit) = fit {
             elem.push_fit(fit);
         }
+        if let Some(baseline_align) = baseline_align {
+            elem.push_baseline_align(baseline_align);
+        }
         Ok(elem.pack().spanned(span))
     }
 }
 
 impl Show for Packed<ImageElem> {
-    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
-        Ok(BlockElem::single_layouter(self.clone(), layout_image)
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let realized = BlockElem::single_layouter(self.clone(), layout_image)
             .with_width(self.width(styles))
             .with_height(self.height(styles))
             .pack()
+            .spanned(self.span());
+
+        if !self.baseline_align(styles) {
+            return Ok(realized);
+        }
+
+        // Lower the image's bottom edge to the font's descender. The shift is
+        // given in em, so that it scales with the font size.
+        let shift = Length::from(-descender(engine, styles));
+        Ok(BoxElem::new()
+            .with_body(Some(realized))
+            .with_baseline(BoxBaseline::Shift(shift.into()))
+            .pack()
             .spanned(self.span()))
     }
 }
@@ -171,6 +212,19 @@ impl LocalName for Packed<ImageElem> {
 
 impl Figurable for Packed<ImageElem> {}
 
+/// The descender of the first available font family.
+fn descender(engine: &Engine, styles: StyleChain) -> Em {
+    let world = engine.world;
+    families(styles)
+        .find_map(|family| {
+            world
+                .book()
+                .select(family, variant(styles))
+                .and_then(|id| world.font(id))
+        })
+        .map_or(Em::zero(), |font| font.metrics().descender)
+}
+
 /// Layout the image.
 #[typst_macros::time(span = elem.span())]
 fn layout_image(
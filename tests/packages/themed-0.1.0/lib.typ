@@ -0,0 +1,3 @@
+#import "themes.typ"
+#let helper(x) = x * 2
+#let double(x) = helper(x)
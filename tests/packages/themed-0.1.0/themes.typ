@@ -0,0 +1,2 @@
+#let dark = (fill: black)
+#let light = (fill: white)
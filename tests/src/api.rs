@@ -0,0 +1,266 @@
+//! Tests for the compiler's entry points, which can't be expressed as test
+//! files in the suite.
+
+mod world;
+
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
+use ecow::EcoString;
+use typst::diag::{FileResult, SourceResult};
+use typst::foundations::{Bytes, Datetime, Label};
+use typst::layout::{Abs, Frame, FrameItem, PageRanges, Point, Size};
+use typst::model::Incomplete;
+use typst::syntax::{FileId, Source, Span};
+use typst::text::{Font, FontBook};
+use typst::utils::LazyHash;
+use typst::visualize::{Color, Geometry};
+use typst::{Library, LibraryBuilder, World};
+
+use crate::world::TestWorld;
+
+/// Create a world for a test with the given source text.
+fn world(text: &str) -> TestWorld {
+    TestWorld::new(Source::detached(text))
+}
+
+/// A test world with a customized library.
+struct LibraryWorld {
+    base: TestWorld,
+    library: LazyHash<Library>,
+}
+
+impl LibraryWorld {
+    /// Create a new world with the library built by the builder.
+    fn new(text: &str, builder: LibraryBuilder) -> Self {
+        Self {
+            base: world(text),
+            library: LazyHash::new(builder.build()),
+        }
+    }
+}
+
+impl World for LibraryWorld {
+    fn library(&self) -> &LazyHash<Library> {
+        &self.library
+    }
+
+    fn book(&self) -> &LazyHash<FontBook> {
+        self.base.book()
+    }
+
+    fn main(&self) -> Source {
+        self.base.main()
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        self.base.source(id)
+    }
+
+    fn file(&self, id: FileId) -> FileResult<Bytes> {
+        self.base.file(id)
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        self.base.font(index)
+    }
+
+    fn today(&self, offset: Option<i64>) -> Option<Datetime> {
+        self.base.today(offset)
+    }
+}
+
+/// Count the items in a frame and its groups that match the predicate.
+fn count(frame: &Frame, predicate: fn(&FrameItem) -> bool) -> usize {
+    frame
+        .items()
+        .map(|(_, item)| match item {
+            FrameItem::Group(group) => count(&group.frame, predicate),
+            item => predicate(item) as usize,
+        })
+        .sum()
+}
+
+/// Whether a frame item is a shape.
+fn is_shape(item: &FrameItem) -> bool {
+    matches!(item, FrameItem::Shape(..))
+}
+
+/// The text of all text items in a frame and its groups.
+fn texts(frame: &Frame) -> Vec<EcoString> {
+    frame
+        .items()
+        .flat_map(|(_, item)| match item {
+            FrameItem::Group(group) => texts(&group.frame),
+            FrameItem::Text(text) => vec![text.text.clone()],
+            _ => vec![],
+        })
+        .collect()
+}
+
+/// Compile the part with the given label and return the text of its pages.
+fn compile_part(text: &str, label: &str) -> SourceResult<Vec<EcoString>> {
+    let document = typst::compile_partial(&world(text), Label::new(label)).output?;
+    Ok(document.pages.iter().flat_map(|page| texts(&page.frame)).collect())
+}
+
+#[test]
+fn test_compile_within_budget() {
+    let world = world("#for i in range(3) [#pagebreak(weak: true) Page]");
+
+    let document = typst::compile_within(&world, Duration::from_secs(3600))
+        .output
+        .unwrap();
+    assert_eq!(document.incomplete, None);
+    assert_eq!(document.pages.len(), 3);
+
+    let document = typst::compile_within(&world, Duration::ZERO).output.unwrap();
+    assert_eq!(document.incomplete, Some(Incomplete::Truncated));
+    assert!(document.pages.len() < 3);
+}
+
+#[test]
+fn test_layout_document_iter_streams_page_run() {
+    let world =
+        world("#set page(height: 40pt)\n#for i in range(8) [#block(height: 20pt)[#i]]");
+
+    let mut iter = typst::layout_document_iter(&world);
+    let (index, page) = iter.next().unwrap().unwrap();
+    assert_eq!(index, 0);
+    assert_eq!(texts(&page.frame), ["0"]);
+    assert!(iter.pages().len() < 8);
+
+    let rest: Vec<_> = iter.by_ref().map(|result| result.unwrap().0).collect();
+    assert_eq!(rest, (1..8).collect::<Vec<_>>());
+
+    let document = typst::compile(&world).output.unwrap();
+    assert_eq!(iter.pages().len(), document.pages.len());
+    for (page, expected) in iter.pages().iter().zip(&document.pages) {
+        assert_eq!(texts(&page.frame), texts(&expected.frame));
+    }
+}
+
+#[test]
+fn test_layout_document_iter_updates_yielded_pages() {
+    let world = world(
+        "#set page(height: 40pt)\n\
+         #context counter(page).final().first()\n\
+         #for i in range(4) [#block(height: 20pt)]",
+    );
+
+    let mut first = vec![];
+    for result in typst::layout_document_iter(&world) {
+        let (index, page) = result.unwrap();
+        if index == 0 {
+            first.push(texts(&page.frame));
+        }
+    }
+
+    assert!(first.len() > 1);
+    assert_eq!(first.last().unwrap(), &["5"]);
+}
+
+#[test]
+fn test_layout_debug_overlay() {
+    let compile_shapes = |builder: LibraryBuilder| {
+        let world = LibraryWorld::new("#lorem(5)", builder);
+        let document = typst::compile(&world).output.unwrap();
+        count(&document.pages[0].frame, is_shape)
+    };
+
+    assert_eq!(compile_shapes(Library::builder()), 0);
+    assert!(compile_shapes(Library::builder().with_layout_debug(true)) > 0);
+}
+
+#[test]
+fn test_page_hook_stamps_every_page() {
+    let builder = Library::builder().with_page_hook(|page| {
+        let stamp = Geometry::Rect(Size::splat(Abs::pt(5.0))).filled(Color::BLACK.into());
+        page.frame
+            .push(Point::zero(), FrameItem::Shape(stamp, Span::detached()));
+    });
+
+    let world = LibraryWorld::new("A #pagebreak() B", builder);
+    let document = typst::compile(&world).output.unwrap();
+    assert_eq!(document.pages.len(), 2);
+    for page in &document.pages {
+        assert_eq!(count(&page.frame, is_shape), 1);
+        assert_eq!(count(&page.frame, |item| matches!(item, FrameItem::Text(_))), 1);
+        assert!(matches!(page.frame.items().last(), Some((_, FrameItem::Shape(..)))));
+    }
+}
+
+#[test]
+fn test_compile_partial_section() {
+    let text = "= One\nA\n= Two <two>\nB\n== Sub\nC\n= Three\nD";
+    assert_eq!(compile_part(text, "two").unwrap(), ["Two", "B", "Sub", "C"]);
+}
+
+#[test]
+fn test_compile_partial_heading_numbers() {
+    let text = "#set heading(numbering: \"1.1\")\n\
+                = One\n= Two\n== Sub\n= Three <three>\nA";
+    assert_eq!(compile_part(text, "three").unwrap(), ["3", "Three", "A"]);
+}
+
+#[test]
+fn test_compile_partial_nested() {
+    let world = world("#set text(fill: red)\n#block[A #strong[B]<b> C]");
+    let document = typst::compile_partial(&world, Label::new("b")).output.unwrap();
+    let frame = &document.pages[0].frame;
+    assert_eq!(texts(frame), ["B"]);
+    assert_eq!(
+        count(frame, |item| matches!(
+            item,
+            FrameItem::Text(text) if text.fill == Color::RED.into()
+        )),
+        1
+    );
+}
+
+#[test]
+fn test_compile_partial_missing_label() {
+    let errors = compile_part("= One <one>", "two").unwrap_err();
+    assert_eq!(errors[0].message, "label `<two>` does not exist in the document");
+}
+
+#[test]
+fn test_run_tests() {
+    let world = world(
+        "#test-case(\"passes\", () => assert.eq(1 + 1, 2))\n\
+         #test-case(\"fails\", () => assert.eq(1 + 1, 3))\n\
+         #test-case(\"lays out\", layout: true, () => rect())",
+    );
+
+    let results = typst::test(&world).output.unwrap();
+    let names: Vec<_> = results.iter().map(|result| result.name.as_str()).collect();
+    assert_eq!(names, ["passes", "fails", "lays out"]);
+    assert!(matches!(results[0].output, Ok(None)));
+    assert!(results[1].output.is_err());
+    assert!(matches!(&results[2].output, Ok(Some(frame)) if !frame.is_empty()));
+
+    let unclosed = self::world("#test-case(\"unclosed\"");
+    assert!(typst::test(&unclosed).output.is_err());
+}
+
+#[test]
+fn test_document_plain_text() {
+    let world = world("A B\n\nC #pagebreak() D #pagebreak() E");
+    let document = typst::compile(&world).output.unwrap();
+    assert_eq!(document.plain_text(None), "A B\nC\n\u{c}D\n\u{c}E\n");
+
+    let pages = PageRanges::new(vec![NonZeroUsize::new(2)..=None]);
+    assert_eq!(document.plain_text(Some(&pages)), "D\n\u{c}E\n");
+}
+
+#[test]
+fn test_redact_removes_text_and_links() {
+    let world = world("#link(\"https://typst.org/\")[#redact[typst.org]]");
+    let document = typst::compile(&world).output.unwrap();
+    let frame = &document.pages[0].frame;
+    assert!(count(frame, is_shape) > 0);
+    assert_eq!(
+        count(frame, |item| matches!(item, FrameItem::Text(_) | FrameItem::Link(..))),
+        0
+    );
+}
@@ -74,6 +74,7 @@ pub struct Note {
 pub enum NoteKind {
     Error,
     Warning,
+    Info,
     Hint,
 }
 
@@ -84,6 +85,7 @@ impl FromStr for NoteKind {
         Ok(match s {
             "Error" => Self::Error,
             "Warning" => Self::Warning,
+            "Info" => Self::Info,
             "Hint" => Self::Hint,
             _ => return Err(()),
         })
@@ -95,6 +97,7 @@ impl Display for NoteKind {
         f.pad(match self {
             Self::Error => "Error",
             Self::Warning => "Warning",
+            Self::Info => "Info",
             Self::Hint => "Hint",
         })
     }
@@ -4,7 +4,7 @@ use std::path::Path;
 
 use ecow::eco_vec;
 use tiny_skia as sk;
-use typst::diag::{SourceDiagnostic, Warned};
+use typst::diag::{Severity, SourceDiagnostic, Warned};
 use typst::foundations::Smart;
 use typst::layout::{Abs, Frame, FrameItem, Page, Transform};
 use typst::model::Document;
@@ -79,7 +79,7 @@ impl<'a> Runner<'a> {
             log!(into: self.result.infos, "tree: {:#?}", self.test.source.root());
         }
 
-        let Warned { output, warnings } = typst::compile(&self.world);
+        let Warned { output, warnings, logs } = typst::compile(&self.world);
         let (doc, errors) = match output {
             Ok(doc) => (Some(doc), eco_vec![]),
             Err(errors) => (None, errors),
@@ -99,6 +99,14 @@ impl<'a> Runner<'a> {
             self.check_diagnostic(NoteKind::Warning, warning);
         }
 
+        for entry in &logs {
+            let kind = match entry.severity {
+                Severity::Info => NoteKind::Info,
+                _ => NoteKind::Warning,
+            };
+            self.check_diagnostic(kind, entry);
+        }
+
         self.handle_not_emitted();
         self.handle_not_annotated();
 
@@ -7,9 +7,10 @@ use std::sync::OnceLock;
 
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
-use typst::diag::{bail, FileError, FileResult, StrResult};
+use typst::diag::{bail, FileError, FileResult, PackageError, PackageResult, StrResult};
 use typst::foundations::{func, Bytes, Datetime, NoneValue, Repr, Smart, Value};
 use typst::layout::{Abs, Margin, PageElem};
+use typst::syntax::package::{BoundedPackageSpec, PackageVersion};
 use typst::syntax::{FileId, Source};
 use typst::text::{Font, FontBook, TextElem, TextSize};
 use typst::utils::LazyHash;
@@ -66,6 +67,23 @@ impl World for TestWorld {
     fn today(&self, _: Option<i64>) -> Option<Datetime> {
         Some(Datetime::from_ymd(1970, 1, 1).unwrap())
     }
+
+    fn resolve_package(
+        &self,
+        spec: &BoundedPackageSpec,
+    ) -> PackageResult<PackageVersion> {
+        let prefix = format!("{}-", spec.name);
+        fs::read_dir(packages_dir())
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                entry.file_name().to_str()?.strip_prefix(&prefix)?.parse().ok()
+            })
+            .filter(|version| spec.matches(version))
+            .max()
+            .ok_or_else(|| PackageError::NoMatchingVersion(spec.clone()))
+    }
 }
 
 impl TestWorld {
@@ -141,10 +159,15 @@ impl FileSlot {
     }
 }
 
+/// The directory with the packages available to the tests.
+fn packages_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("packages")
+}
+
 /// The file system path for a file ID.
 fn system_path(id: FileId) -> FileResult<PathBuf> {
-    let root: PathBuf = match id.package() {
-        Some(spec) => format!("tests/packages/{}-{}", spec.name, spec.version).into(),
+    let root = match id.package() {
+        Some(spec) => packages_dir().join(format!("{}-{}", spec.name, spec.version)),
         None => PathBuf::new(),
     };
 
@@ -23,6 +23,30 @@
 // Error: 2-55 equality assertion failed: 10 and 12 are not equal
 #assert.eq(10, 12, message: "10 and 12 are not equal")
 
+--- assert-eq-fail-array-diff ---
+// Test that the first differing element is pointed out.
+// Error: 2-37 equality assertion failed: value (1, (2, 3)) was not equal to (1, (2, 4))
+// Hint: 2-37 the values differ at `.at(1).at(1)`: 3 was not equal to 4
+#assert.eq((1, (2, 3)), (1, (2, 4)))
+
+--- assert-eq-fail-dict-missing-key ---
+// Error: 2-33 equality assertion failed: value (a: 1) was not equal to (a: 1, b: 2)
+// Hint: 2-33 the values differ: key "b" is missing in the first value
+#assert.eq((a: 1), (a: 1, b: 2))
+
+--- assert-eq-fail-array-length ---
+// Error: 2-30 equality assertion failed: value (1, 2) was not equal to (1, 2, 3)
+// Hint: 2-30 the values differ: lengths 2 and 3 are not equal
+#assert.eq((1, 2), (1, 2, 3))
+
+--- assert-eq-fail-tolerance ---
+// Error: 2-38 equality assertion failed: value 1.0 was not equal to 1.1
+#assert.eq(1.0, 1.1, tolerance: 0.01)
+
+--- assert-eq-tolerance ---
+#assert.eq(0.1 + 0.2, 0.3, tolerance: 1e-9)
+#assert.eq((x: 1, y: (0.5, 2)), (x: 1.0000001, y: (0.5, 2)), tolerance: 1e-6)
+
 --- assert-ne-fail ---
 // Test failing assertions.
 // Error: 2-19 inequality assertion failed: value 11 was equal to 11
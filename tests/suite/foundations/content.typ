@@ -130,3 +130,27 @@
 --- content-try-to-access-internal-field ---
 // Error: 9-15 hide does not have field "hidden"
 #hide[].hidden
+
+--- content-children ---
+#let body = [Intro *bold*]
+#test(body.children().map(c => c.func()), (text, [ ].func(), strong))
+#test([= Intro].children(), ([Intro],))
+#test([*Hi*].children(), ([Hi],))
+#test([Hi].children(), ())
+#test(list[A][B].children().map(c => c.func()), (list.item, list.item))
+
+--- content-find ---
+#let doc = [
+  Preface.
+  = Introduction
+  Some *bold* and *strong* words.
+  = Conclusion <end>
+]
+#test(doc.find(heading).body, [Introduction])
+#test(doc.find(<end>).func(), heading)
+#test(doc.find(heading.where(depth: 1)).body, [Introduction])
+#test(doc.find(heading.where(level: 1)), none)
+#test(doc.find(table), none)
+#test(doc.find-all(heading).len(), 2)
+#test(doc.find-all(strong).map(s => s.body), ([bold], [strong]))
+#test(doc.find-all(math.equation), ())
@@ -37,6 +37,55 @@
 // Error: 11-12 variables from outside the context expression are read-only and cannot be modified
 #context (i = 1)
 
+--- context-set-rule ---
+// Contextual properties of show-set rules are evaluated for each element
+// separately.
+#show heading: set text(fill: context {
+  if here().page() == 1 { green } else { red }
+})
+= First
+#pagebreak()
+= Second
+
+--- context-set-rule-inherited ---
+// Contextual properties are inherited and can be read back.
+#set text(size: 8pt)
+#set text(size: context text.size * 2)
+#context test(text.size, 16pt)
+#box[#context test(text.size, 16pt)]
+#[
+  #set text(size: context text.size / 4)
+  #context test(text.size, 4pt)
+]
+#context test(text.size, 16pt)
+
+--- context-set-rule-inherited-visual ---
+// The contextual fill applies to nested content like an ordinary one.
+#set text(fill: context if text.size > 10pt { blue } else { red })
+Blue #text(8pt)[blue, as the fill is computed outside] \
+#[
+  #set text(8pt)
+  #set text(fill: context if text.size > 10pt { blue } else { red })
+  Red #strong[and red]
+]
+
+--- context-set-rule-here ---
+// Error: 28-34 can only be used when context is known
+// Hint: 28-34 try wrapping this in a `context` expression
+// Hint: 28-34 the `context` expression should wrap everything that depends on this function
+#set text(fill: context if here().page() == 1 { red } else { blue })
+Text
+
+--- context-set-rule-content-property ---
+// Properties that accept content receive the context expression as content.
+#set page(header: context [Page #here().page()])
+#box()
+
+--- context-set-rule-invalid ---
+// Error: 17-30 expected length, found string
+#set text(size: context "big")
+Hello
+
 --- context-compatibility-locate ---
 #let s = state("x", 0)
 #let compute(expr) = [
@@ -0,0 +1,23 @@
+--- log-info ---
+// Info: 2-44 rendering chapter 3 of 10
+#log.info("rendering chapter", 3, "of", 10)
+
+--- log-warn-hint ---
+// Warning: 2-66 no title given
+// Hint: 2-66 pass a title to the template
+#log.warn("no title given", hint: "pass a title to the template")
+
+--- log-repr ---
+// Info: 2-36 values: (1, 2) 1.5pt
+#log.info("values:", (1, 2), 1.5pt)
+
+--- log-in-context ---
+// Test that messages from layout iterations are not repeated.
+// Info: 10-41 page 1
+#context log.info("page", here().page())
+
+--- log-repeated ---
+// Test that messages logged repeatedly during evaluation are all reported.
+// Info: 22-38 same
+// Info: 22-38 same
+#for i in range(2) { log.info("same") }
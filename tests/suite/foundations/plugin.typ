@@ -45,3 +45,8 @@
 
 // Error: 2-27 plugin tried to write out of bounds: pointer 0x40000000 is out of bounds for write of length 3
 #p.write_oob(bytes("xyz"))
+
+--- plugin-capabilities ---
+#test(sys.capabilities.plugins, true)
+#test(sys.capabilities.network, true)
+#test(sys.capabilities.read-roots, ("/",))
@@ -0,0 +1,21 @@
+// Test test cases.
+
+--- test-case-invisible ---
+// Test cases are not part of the document's output.
+#test-case("adds", () => assert.eq(1 + 1, 2))
+
+--- test-case-fields ---
+#let case = test-case("renders", layout: true, () => rect())
+#test(case.name, "renders")
+#test(case.layout, true)
+// Like other settable fields, the layout flag is only known when it is given.
+#test(test-case("checks", () => none).has("layout"), false)
+#test(test-case("checks", layout: false, () => none).layout, false)
+
+--- test-case-not-run ---
+// A failing test doesn't fail the document.
+#test-case("fails", () => panic("not run"))
+
+--- test-case-missing-body ---
+// Error: 2-20 missing argument: body
+#test-case("empty")
@@ -104,3 +104,44 @@ At Beta, it was #context {
 #block(foo())
 #block(foo())
 #foo()
+
+--- counter-no-convergence ---
+// Warning: layout did not converge within 5 attempts
+// Hint: check if any states or queries are updating themselves
+#let c = counter("c")
+// Warning: 10-41 counter("c") did not converge
+// Hint: 10-41 its value changed from (4,) to (5,) in the last attempt
+#context c.update(c.final().first() + 1)
+
+--- counter-final-display ---
+#let c = counter("c")
+#c.update(3)
+#context test(c.final-display(), "5")
+#context test(c.final-display("I"), "V")
+#c.update(5)
+
+--- counter-display-of ---
+#context test(counter(page).display-of(), "1 of 1")
+#let c = counter("c")
+#c.update(2)
+#context test(c.display-of("i", separator: "/"), "ii/iii")
+#c.step()
+
+--- counter-display-of-lang ---
+#set text(lang: "de")
+#let c = counter("c")
+#c.step()
+#context test(c.display-of(), "1 von 2")
+#c.step()
+
+--- counter-display-of-numbering-function ---
+#let c = counter("c")
+#c.update(2)
+#context test(c.display-of(n => str(2 * n)), "4 of 4")
+#context test(type(c.display-of(n => [#n])), content)
+
+--- counter-final-display-no-context ---
+// Error: 2-30 can only be used when context is known
+// Hint: 2-30 try wrapping this in a `context` expression
+// Hint: 2-30 the `context` expression should wrap everything that depends on this function
+#counter("c").final-display()
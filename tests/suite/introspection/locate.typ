@@ -37,3 +37,15 @@
 
 // Error: 10-25 selector matches multiple elements
 #context locate(heading)
+
+--- locate-id ---
+= Introduction <intro>
+= Methods
+#context {
+  let id = locate(<intro>).id()
+  test(type(id), str)
+  test(id.len(), 32)
+  assert(id.match(regex("^[0-9a-f]+$")) != none)
+  test(id, query(heading).first().location().id())
+  assert(id != query(heading).last().location().id())
+}
@@ -52,9 +52,37 @@ Was: #context {
 // Warning: layout did not converge within 5 attempts
 // Hint: check if any states or queries are updating themselves
 #let s = state("s", 1)
+// Warning: 10-33 state("s") did not converge
+// Hint: 10-33 its value changed from 5 to 6 in the last attempt
 #context s.update(s.final() + 1)
 #context s.get()
 
+--- state-no-convergence-iterations ---
+// The number of attempts can be configured.
+// Warning: layout did not converge within 2 attempts
+// Hint: check if any states or queries are updating themselves
+#set document(iterations: 2)
+#let s = state("s", 1)
+// Warning: 10-33 state("s") did not converge
+// Hint: 10-33 its value changed from 2 to 3 in the last attempt
+#context s.update(s.final() + 1)
+
+--- state-no-convergence-many-iterations ---
+// More attempts than there are named timing scopes.
+// Warning: layout did not converge within 8 attempts
+// Hint: check if any states or queries are updating themselves
+#set document(iterations: 8)
+#let s = state("s", 1)
+// Warning: 10-33 state("s") did not converge
+// Hint: 10-33 its value changed from 8 to 9 in the last attempt
+#context s.update(s.final() + 1)
+
+--- state-convergence-many-iterations ---
+// A state that needs more than five attempts to settle.
+#set document(iterations: 10)
+#let s = state("s", 1)
+#context s.update(calc.min(s.final() + 1, 8))
+
 --- state-at-no-context ---
 // Test `state.at` outside of context.
 // Error: 2-26 can only be used when context is known
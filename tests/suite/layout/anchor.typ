@@ -0,0 +1,53 @@
+--- anchor-columns ---
+// Anchors with the same key are aligned across columns.
+#set page(columns: 2, height: 120pt)
+A
+#anchor("one")
+B <a>
+#colbreak()
+C \ D \ E
+#anchor("one")
+F <b>
+
+#context test(locate(<a>).position().y, locate(<b>).position().y)
+
+--- anchor-sequence ---
+// Later anchors are aligned after the earlier ones have been.
+#set page(columns: 2, height: 200pt)
+A \ B
+#anchor("one")
+C
+#anchor("two")
+D <a>
+#colbreak()
+E
+#anchor("one")
+F \ G \ H
+#anchor("two")
+I <b>
+
+#context test(locate(<a>).position().y, locate(<b>).position().y)
+
+--- anchor-grid ---
+// Anchors also align content in grid cells.
+#grid(
+  columns: 2,
+  gutter: 5pt,
+  [A #anchor("x") B <a>],
+  [C \ D #anchor("x") E <b>],
+)
+
+#context test(locate(<a>).position().y, locate(<b>).position().y)
+
+--- anchor-alone ---
+// An anchor without siblings doesn't insert any space.
+#set page(columns: 2, height: 120pt)
+A
+#anchor("alone")
+B <a>
+#colbreak()
+A
+
+B <b>
+
+#context test(locate(<a>).position().y, locate(<b>).position().y)
@@ -111,6 +111,78 @@ This is a normal page. Very normal.
 #colbreak()
 In flow.
 
+--- colbreak-weak ---
+// A weak column break at the top of a column is skipped.
+#set page(height: 60pt, columns: 2)
+#colbreak(weak: true)
+A <a>
+#colbreak()
+#colbreak(weak: true)
+B <b>
+
+#context {
+  let a = locate(<a>).position()
+  let b = locate(<b>).position()
+  test(a.page, 1)
+  test(b.page, 1)
+  assert(a.x < b.x)
+}
+
+--- colbreak-break-to-next-page ---
+// Breaking to the next page skips the remaining columns.
+#set page(height: 60pt, columns: 3)
+A <a>
+#colbreak(break-to: "next-page")
+B <b>
+#colbreak()
+#colbreak(weak: true, break-to: "next-page")
+C <c>
+
+#context {
+  test(locate(<a>).page(), 1)
+  test(locate(<b>).page(), 2)
+  test(locate(<c>).page(), 3)
+  test(locate(<c>).position().x, locate(<b>).position().x)
+}
+
+--- columns-balance ---
+// Test that content in the last region is balanced across the columns.
+#let items = for i in range(6) {
+  block(height: 10pt, spacing: 0pt, breakable: false)[#metadata(i) <item>]
+}
+#hide(place(block(height: 100pt, columns(3, balance: true, items))))
+
+#context {
+  let xs = query(<item>).map(it => it.location().position().x)
+  test(xs.at(0), xs.at(1))
+  test(xs.at(2), xs.at(3))
+  test(xs.at(4), xs.at(5))
+  assert(xs.at(1) < xs.at(2) and xs.at(3) < xs.at(4))
+}
+
+--- columns-balance-unbalanced ---
+// Without balancing, the first column is filled first.
+#let items = for i in range(6) {
+  block(height: 10pt, spacing: 0pt, breakable: false)[#metadata(i) <item>]
+}
+#hide(place(block(height: 100pt, columns(3, items))))
+
+#context test(query(<item>).map(it => it.location().position().x).dedup().len(), 1)
+
+--- columns-balance-tolerance ---
+// Test that balanced columns use the smallest height at which everything fits.
+#let items = for i in range(5) {
+  block(height: 10pt, spacing: 0pt, breakable: false)[#metadata(i) <item>]
+}
+#hide(place(block(height: 100pt, columns(3, balance: true, tolerance: 0.5pt, items))))
+
+#context {
+  let xs = query(<item>).map(it => it.location().position().x)
+  test(xs.dedup().len(), 3)
+  test(xs.at(0), xs.at(1))
+  test(xs.at(2), xs.at(3))
+}
+
 --- issue-columns-heading ---
 // The well-known columns bug.
 #set page(height: 70pt)
@@ -122,3 +194,7 @@ Hallo
   = B
   Text
 ]
+
+--- columns-balance-tolerance-zero ---
+// Error: 39-42 tolerance must be positive
+#columns(2, balance: true, tolerance: 0pt)[A]
@@ -121,6 +121,65 @@ Paragraph
 #show bibliography: none
 #bibliography("/assets/bib/works.bib")
 
+--- block-break-markers ---
+// Content is added around each break of a block across columns.
+#hide(place(block(height: 20pt, columns(3, block(
+  before-break: [#metadata("before") <marker>],
+  after-break: [#metadata("after") <marker>],
+  [A \ B \ C \ D \ E \ F],
+)))))
+
+#context {
+  let markers = query(<marker>)
+  test(markers.map(it => it.value), ("before", "after", "before", "after"))
+  let (before, after) = markers.slice(0, 2).map(it => it.location().position())
+  assert(before.x < after.x)
+  assert(before.y > after.y)
+}
+
+--- block-break-markers-unbreakable ---
+// Unbreakable blocks don't get any content around breaks.
+#hide(place(block(height: 20pt, columns(3, block(
+  breakable: false,
+  before-break: [#metadata("before") <marker>],
+  [A \ B \ C \ D \ E \ F],
+)))))
+
+#context test(query(<marker>), ())
+
+--- block-sticky-header ---
+// A sticky header is shown at the top of every region of a block.
+#hide(place(block(height: 20pt, columns(3, block(
+  sticky-header: [#metadata("header") <marker>],
+  after-break: [#metadata("after") <marker>],
+  [A \ B \ C \ D \ E \ F],
+)))))
+
+#context {
+  let markers = query(<marker>)
+  test(markers.map(it => it.value), ("header", "header", "after", "header", "after"))
+  let (first, header, after) = markers.slice(0, 3).map(it => it.location().position())
+  test(first.y, header.y)
+  test(header.x, after.x)
+  assert(first.x < header.x)
+}
+
+--- block-keep-with-next ---
+// A block moves to the next page along with the block it is kept with.
+#set page(height: 60pt)
+#v(20pt)
+#block(keep-with-next: true)[A #metadata(none) <kept>]
+#block(breakable: false, height: 20pt)
+#context test(locate(<kept>).page(), 2)
+
+--- block-keep-with-next-breakable ---
+// A block is kept with a breakable block that can't start on the page.
+#set page(height: 60pt)
+#v(25pt)
+#block(keep-with-next: true)[A #metadata(none) <kept>]
+#block(spacing: 0pt)[B \ C \ D]
+#context test(locate(<kept>).page(), 2)
+
 --- box-clip-rect ---
 // Test box clipping with a rectangle
 Hello #box(width: 1em, height: 1em, clip: false)[#rect(width: 3em, height: 3em, fill: red)]
@@ -186,6 +245,66 @@ First!
   image("/assets/images/rhino.png", width: 30pt)
 )
 
+--- box-overflow-scale ---
+// Oversize content is scaled down to fit, fitting content is left alone.
+#context {
+  let natural = measure[Overflowing text]
+  let scaled = measure(box(width: 40pt, overflow: "scale")[Overflowing text])
+  test(scaled.width, 40pt)
+  test(scaled.height, natural.height * (40pt / natural.width))
+  test(measure(box(overflow: "scale")[Fits]), measure[Fits])
+}
+
+--- box-overflow-ellipsis ---
+// Ellipsized content stays on one line within the box.
+#context {
+  let single = measure(box[A])
+  let cut = measure(box(width: 40pt, overflow: "ellipsis")[A line that is too long])
+  test(cut.width, 40pt)
+  test(cut.height, single.height)
+}
+
+--- box-overflow-ellipsis-shape ---
+// A shape that crosses the cut is dropped and the ellipsis follows the text
+// before it.
+#set page(width: 120pt)
+#box(width: 60pt, stroke: 0.5pt, overflow: "ellipsis")[
+  AB #box(width: 40pt, height: 6pt, fill: red) CD
+]
+
+--- block-overflow ---
+#set page(width: 120pt)
+#set block(width: 60pt, stroke: 0.5pt, inset: 2pt)
+#block(height: 14pt, overflow: "clip")[A paragraph that wraps onto lines]
+#block(overflow: "ellipsis")[Overflowing text]
+#block(overflow: "scale")[Overflowing text]
+
+--- block-overflow-unbreakable ---
+// An ellipsized block is moved to the next page as a whole.
+#set page(height: 60pt)
+#v(30pt)
+#block(overflow: "ellipsis", height: 20pt, width: 100%, fill: aqua)[Overflowing]
+
+--- box-overflow-invalid ---
+// Error: 29-37 expected "visible", "clip", "ellipsis", or "scale"
+#box(width: 10pt, overflow: "hidden")[A]
+
+--- box-baseline-first ---
+// The first line of the box sits on the surrounding baseline.
+A #metadata(none) <out>
+#box(baseline: "first", inset: 2pt)[B #metadata(none) <in> \ C]
+#context test(locate(<in>).position().y, locate(<out>).position().y)
+
+--- box-baseline-last ---
+// The last line of the box sits on the surrounding baseline.
+A #metadata(none) <out>
+#box(baseline: "last", inset: 2pt)[B \ C #metadata(none) <in>]
+#context test(locate(<in>).position().y, locate(<out>).position().y)
+
+--- box-baseline-invalid ---
+// Error: 16-24 expected "first", "last", or relative length
+#box(baseline: "middle")[A]
+
 --- container-layoutable-child ---
 // Test box/block sizing with directly layoutable child.
 //
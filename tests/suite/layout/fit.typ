@@ -0,0 +1,30 @@
+--- fit-max-size ---
+// Content that fits at the maximum size is not shrunk.
+#context test(
+  measure(width: 100pt, fit[Hi]),
+  measure(text(24pt)[Hi]),
+)
+
+--- fit-shrink ---
+// Long content is shrunk until it fits.
+#context {
+  let size = measure(width: 60pt, height: 20pt, fit[A rather long title])
+  assert(size.width <= 60pt)
+  assert(size.height <= 20pt)
+  assert(size.height > 10pt)
+}
+
+--- fit-min-size ---
+// Content that doesn't fit at the minimum size overflows.
+#context test(
+  measure(height: 5pt, fit(min-size: 8pt)[Overflowing]),
+  measure(height: 5pt, text(8pt)[Overflowing]),
+)
+
+--- fit-unbreakable ---
+// A word that cannot be broken is shrunk to fit horizontally.
+#context {
+  let size = measure(width: 40pt, fit(min-size: 4pt)[Unbreakable])
+  assert(size.height < measure(text(10pt)[Unbreakable]).height)
+  assert(size.height > measure(text(7pt)[Unbreakable]).height)
+}
@@ -0,0 +1,18 @@
+--- frozen-size ---
+// Frozen content takes up the size of its content.
+#context test(
+  measure(frozen(rect(width: 20pt, height: 10pt))),
+  (width: 20pt, height: 10pt),
+)
+
+--- frozen-width ---
+// Frozen content is laid out with the available width.
+#context test(
+  measure(frozen(rect(width: 100%, height: 10pt)), width: 80pt),
+  (width: 80pt, height: 10pt),
+)
+
+--- frozen-introspection ---
+// The content of a frozen element is not visible to introspection.
+#frozen[#metadata("inside") <inside>]
+#context test(query(<inside>), ())
@@ -127,6 +127,40 @@
   )
 }
 
+--- grid-cell-show-structure ---
+// Cells know the grid's dimensions and whether they are part of the header or
+// footer.
+#show grid.cell: it => [#metadata(it.fields()) <cell>]
+#grid(
+  columns: 2,
+  grid.header[A][B],
+  [C], [D],
+  [E],
+  grid.footer[F][G],
+)
+
+#context {
+  let cells = query(<cell>).map(it => it.value)
+  test(cells.len(), 8)
+  test(cells.map(it => (it.column-count, it.row-count)).dedup(), ((2, 4),))
+  test(cells.filter(it => it.header).map(it => it.body), ([A], [B]))
+  test(cells.filter(it => it.footer).map(it => it.body), ([F], [G]))
+  test(cells.find(it => it.x == 1 and it.y == 2).body, [])
+}
+
+--- grid-cell-show-where-header ---
+// Select header cells in show rules.
+#show table.cell.where(header: true): it => [#metadata(it.body) <header>]
+#show table.cell.where(header: false): none
+#table(
+  columns: 2,
+  stroke: none,
+  table.header[A][B],
+  [C], [D],
+)
+
+#context test(query(<header>).map(it => it.value), ([A], [B]))
+
 --- table-cell-in-grid ---
 // Error: 7-19 cannot use `table.cell` as a grid cell
 // Hint: 7-19 use `grid.cell` instead
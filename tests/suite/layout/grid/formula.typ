@@ -0,0 +1,53 @@
+--- grid-cell-formula-sum ---
+// Formulas sum up numeric cells and skip anything else.
+#hide(place(grid(
+  [1], [2], [3.5], [-4], [Text], [],
+  grid.cell(formula: r => [#metadata(r.sum("A1:A6")) <total>])[],
+)))
+
+#context test(query(<total>).first().value, 2.5)
+
+--- grid-cell-formula-dependencies ---
+// Formulas can use the results of other formulas, even later ones.
+#hide(place(table(
+  columns: 2,
+  [10], table.cell(formula: r => [#metadata(r.at("A1") / r.at("A3")) <share>])[],
+  [30], table.cell(formula: r => [#metadata(r.at("A2") / r.at("A3")) <share>])[],
+  table.cell(formula: r => r.sum("A1:A2"))[], [],
+)))
+
+#context test(query(<share>).map(it => it.value), (0.25, 0.75))
+
+--- grid-cell-formula-chain ---
+// Each formula is evaluated only once, even if it is referenced many times.
+#let double(n) = grid.cell(formula: r => r.at("A" + str(n - 1)) * 2)[]
+#hide(place(grid(
+  [1],
+  ..range(2, 40).map(double),
+  grid.cell(formula: r => [#metadata(r.at("A39")) <last>])[],
+)))
+
+#context test(query(<last>).first().value, calc.pow(2, 38))
+
+--- grid-cell-formula-values ---
+// Ranges are clamped to the grid and read row by row.
+#hide(place(grid(
+  columns: 2,
+  [a], [1],
+  [b], [2],
+  grid.cell(colspan: 2, formula: r => [#metadata(r.values("A1:Z2")) <values>])[],
+)))
+
+#context test(query(<values>).first().value, ("a", 1, "b", 2))
+
+--- grid-cell-formula-cyclic ---
+// Error: 70-80 cell formula has a cyclic reference to cell A1
+#grid(grid.cell(formula: r => r.at("B1"))[], grid.cell(formula: r => r.at("A1"))[], columns: 2)
+
+--- grid-cell-formula-invalid-reference ---
+// Error: 31-41 invalid cell reference: "1A"
+#grid(grid.cell(formula: r => r.at("1A"))[])
+
+--- grid-cell-formula-out-of-bounds ---
+// Error: 31-41 cell C1 does not exist
+#grid(grid.cell(formula: r => r.at("C1"))[])
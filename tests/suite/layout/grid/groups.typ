@@ -0,0 +1,61 @@
+--- grid-group-kept-together ---
+// A group which doesn't fit into the rest of the region moves as a whole.
+#let mark(name) = [#metadata(name) <mark>]
+#hide(place(block(height: 35pt, columns(3, grid(
+  rows: 10pt,
+  [A], [B],
+  grid.group(mark("first"), mark("second")),
+)))))
+
+#context {
+  let (first, second) = query(<mark>).map(it => it.location().position())
+  test(first.x, second.x)
+  assert(first.y < second.y)
+}
+
+--- grid-group-header-repeated ---
+// A group which doesn't fit into a full region is split and its header
+// is repeated.
+#let mark(name) = [#metadata(name) <mark>]
+#hide(place(block(height: 35pt, columns(3, table(
+  rows: 10pt,
+  inset: 0pt,
+  stroke: none,
+  table.group(
+    header: 1,
+    mark("head"),
+    mark(1), mark(2), mark(3), mark(4),
+  ),
+)))))
+
+#context {
+  let marks = query(<mark>)
+  test(marks.map(it => it.value), ("head", 1, 2, "head", 3, 4))
+  let (head, _, _, repeated, three, _) = marks.map(it => it.location().position())
+  assert(head.x < repeated.x)
+  test(head.y, repeated.y)
+  assert(repeated.y < three.y)
+}
+
+--- grid-group-header-too-long ---
+// Error: 7-33 row group header cannot have more rows than the group
+// Hint: 7-33 try reducing the amount of header rows
+#grid(grid.group(header: 2, [a]))
+
+--- grid-group-header-rowspan ---
+// Error: 7-64 row group header would conflict with a cell spanning the rows after it
+// Hint: 7-64 try reducing that cell's rowspan or increasing the amount of header rows
+#grid(grid.group(header: 1, grid.cell(rowspan: 2)[a], [b], [c]))
+
+--- grid-group-in-header ---
+// Error: 21-34 cannot place a table row group within another group, header or footer
+#table(table.header(table.group()))
+
+--- grid-group-header-conflict ---
+// Error: 43-81 row group must not have common rows with the header or footer
+#table(columns: 2, table.header[Heading], table.group(table.cell(x: 1, y: 0)[a]))
+
+--- grid-group-in-table ---
+// Error: 8-20 cannot use `grid.group` as a table row group
+// Hint: 8-20 use `table.group` instead
+#table(grid.group())
@@ -368,3 +368,64 @@
     [b]
   )
 )
+
+--- table-header-scope-column ---
+// Data cells are associated with all levels of column headers above them.
+#show table.cell: it => [#metadata(it.fields()) <cell>]
+#table(
+  columns: 3,
+  stroke: none,
+  table.header(
+    table.cell(colspan: 2)[Sizes], [],
+    [S], [M], [L],
+  ),
+  [1], [2], [3],
+)
+
+#context {
+  let cells = query(<cell>).map(it => it.value)
+  let at(x, y) = cells.find(it => it.x == x and it.y == y)
+  test(at(0, 0).scope, "column")
+  test(at(0, 0).headers, ())
+  test(at(1, 1).headers, ((0, 0),))
+  test(at(2, 1).headers, ((2, 0),))
+  test(at(0, 2).scope, none)
+  test(at(0, 2).headers, ((0, 0), (0, 1)))
+  test(at(1, 2).headers, ((0, 0), (1, 1)))
+  test(at(2, 2).headers, ((2, 0), (2, 1)))
+}
+
+--- table-header-scope-row ---
+// Row headers apply to the cells after them in the same rows.
+#show table.cell: it => [#metadata(it.fields()) <cell>]
+#table(
+  columns: 3,
+  stroke: none,
+  table.header[][Q1][Q2],
+  table.cell(scope: "row", rowspan: 2)[Sales], [1], [2],
+  [3], [4],
+  table.cell(scope: "both")[Costs], table.cell(scope: none)[5], [6],
+)
+
+#context {
+  let cells = query(<cell>).map(it => it.value)
+  let at(x, y) = cells.find(it => it.x == x and it.y == y)
+  test(at(0, 1).scope, "row")
+  test(at(0, 1).headers, ((0, 0),))
+  test(at(1, 1).headers, ((1, 0), (0, 1)))
+  test(at(2, 2).headers, ((2, 0), (0, 1)))
+  test(at(1, 3).scope, none)
+  test(at(1, 3).headers, ((1, 0), (0, 3)))
+  test(at(0, 3).headers, ((0, 0),))
+}
+
+--- grid-header-scope-none ---
+// Grid cells are never associated with headers.
+#show grid.cell: it => [#metadata(it.fields()) <cell>]
+#grid(
+  columns: 2,
+  grid.header[A][B],
+  [C], [D],
+)
+
+#context assert(query(<cell>).map(it => it.value).all(it => "headers" not in it))
@@ -24,6 +24,50 @@
 
 中文，中ab文a中，文ab中文
 
+--- text-cjk-spacing-custom ---
+#set text(lang: "zh", font: "Noto Serif CJK SC")
+
+// There are four gaps between CJK and Latin characters.
+#let spacing(..args) = {
+  let width(..args) = measure(text(..args)[中12文ab中]).width
+  calc.round((width(..args) - width(cjk: (spacing: none))).pt(), digits: 2)
+}
+
+#context test(spacing(cjk: (spacing: 0.5em)), 20.0)
+#context test(spacing(cjk-latin-spacing: none), 0.0)
+
+// The `cjk` group takes precedence over `cjk-latin-spacing`.
+#context test(spacing(cjk-latin-spacing: none, cjk: (spacing: auto)), 10.0)
+
+--- text-cjk-unexpected-key ---
+// Error: 16-33 unexpected key "kinsyoku", valid keys are "kinsoku", "compress", and "spacing"
+#set text(cjk: (kinsyoku: false))
+
+--- cjk-kinsoku ---
+// With kinsoku, a full-width tilde must not start a line. Unicode alone
+// allows breaking before it.
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+
+// Whether the text breaks in a block that is slightly too narrow for it.
+#let breaks(body, ..args) = {
+  let body = text(..args, body)
+  let width = measure(body).width - 1pt
+  measure(block(width: width, body)).height > measure(body).height
+}
+
+#context {
+  assert(breaks[あ～])
+  assert(not breaks(cjk: (kinsoku: true))[あ～])
+  assert(breaks(cjk: (kinsoku: true))[ああ])
+}
+
+--- cjk-punctuation-compression-disabled ---
+#set text(lang: "zh", region: "CN", font: "Noto Serif CJK SC")
+#context {
+  let width(compress) = measure(text(cjk: (compress: compress))[《书名〈章节〉》]).width
+  assert(width(true) < width(false))
+}
+
 --- cjk-punctuation-adjustment-1 ---
 #set page(width: 15em)
 
@@ -148,12 +148,88 @@ el objetivo de protegerle de las patrullas de milicianos.
 #set text(costs: (hyphenation: auto))
 
 --- costs-invalid-key ---
-// Error: 18-52 unexpected key "invalid-key", valid keys are "hyphenation", "runt", "widow", and "orphan"
+// Error: 18-52 unexpected key "invalid-key", valid keys are "hyphenation", "runt", "widow", "orphan", and "ladder"
 #set text(costs: (hyphenation: 1%, invalid-key: 3%))
 
 --- costs-access ---
 #set text(costs: (hyphenation: 1%, runt: 2%))
 #set text(costs: (widow: 3%))
 #context {
-  assert.eq(text.costs, (hyphenation: 1%, runt: 2%, widow: 3%, orphan: 100%))
+  assert.eq(text.costs, (hyphenation: 1%, runt: 2%, widow: 3%, orphan: 100%, ladder: 100%))
+}
+
+--- hyphenate-exceptions ---
+#let height(body, ..args) = measure(block(width: 20pt, text(..args, body))).height
+#context {
+  let exceptions = ("xxx-xxx", "hyphenation")
+  let hyphenated(body) = height(
+    hyphenate: true,
+    hyphenation-exceptions: exceptions,
+    body,
+  )
+
+  // Words that the exceptions split are hyphenated, regardless of case.
+  assert(hyphenated[xxxxxx] > height[xxxxxx])
+  assert(hyphenated[xxxXXX] > height[xxxXXX])
+
+  // Words listed without hyphens are not hyphenated at all.
+  assert(height(hyphenate: true)[hyphenation] > height[hyphenation])
+  assert.eq(hyphenated[hyphenation], height[hyphenation])
+}
+
+--- hyphenate-patterns ---
+#let height(body, ..args) = measure(block(width: 25pt, text(..args, body))).height
+#context {
+  let hyphenated(body) = height(
+    hyphenate: true,
+    hyphenation-patterns: "1ba 1ca",
+    body,
+  )
+
+  assert(hyphenated[abacaba] > height[abacaba])
+
+  // Custom patterns replace the built-in ones.
+  assert.eq(hyphenated[hyphenation], height[hyphenation])
+}
+
+--- hyphenate-patterns-invalid ---
+// Error: 33-42 invalid hyphenation pattern `a-b`
+#set text(hyphenation-patterns: "1ba a-b")
+
+--- hyphenate-repeat-hyphen ---
+// A repeated hyphen moves the text after it to the right.
+#let word(key, ..args) = place(block(
+  width: 40pt,
+  text(..args)[kelet-e#metadata(key)<mark>urópai],
+))
+
+#hide({
+  word("default", lang: "en")
+  word("enabled", lang: "en", repeat-hyphen: true)
+  word("auto", lang: "hu")
+  word("disabled", lang: "pt", repeat-hyphen: false)
+})
+
+#context {
+  let x = query(<mark>).map(it => (it.value, it.location().position().x)).to-dict()
+  assert(x.enabled > x.default)
+  test(x.auto, x.enabled)
+  test(x.disabled, x.default)
+}
+
+--- linebreak-single-letter ---
+// Without single-letter breaks, the preposition moves to the next line.
+#let words(key, ..args) = context place(block(
+  width: measure[Šli jsme spolu v].width + 1pt,
+  text(lang: "cs", ..args)[Šli jsme spolu v#metadata(key)<mark> lese.],
+))
+
+#hide({
+  words("default")
+  words("forbidden", single-letter-breaks: false)
+})
+
+#context {
+  let y = query(<mark>).map(it => (it.value, it.location().position().y)).to-dict()
+  assert(y.forbidden > y.default)
 }
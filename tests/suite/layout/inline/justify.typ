@@ -167,3 +167,29 @@ int main() {
 // an underfull first line.
 #set par(hanging-indent: 2.5cm, justify: true)
 #lorem(5)
+
+--- justify-expansion ---
+// Glyphs in justified lines are scaled, which moves the words.
+#let body(expansion) = block(width: 140pt, {
+  set par(justify: true, expansion: expansion)
+  [Lorem#metadata(none)<word> ipsum dolor sit amet, consectetur adipiscing elit.]
+})
+#hide(place(body(0%)))
+#hide(place(body(2%)))
+#context {
+  let (plain, expanded) = query(<word>).map(it => it.location().position().x)
+  assert.ne(plain, expanded)
+}
+
+--- justify-expansion-ragged ---
+// Ragged lines are never expanded.
+#let body(expansion) = block(width: 140pt, {
+  set par(expansion: expansion)
+  [Lorem#metadata(none)<word> ipsum dolor sit amet, consectetur adipiscing elit.]
+})
+#hide(place(body(0%)))
+#hide(place(body(5%)))
+#context {
+  let (plain, expanded) = query(<word>).map(it => it.location().position().x)
+  test(plain, expanded)
+}
@@ -107,3 +107,37 @@ For info see #link("https://myhost.tld").
 #set page(width: 50pt, height: auto)
 #h(99%) 🏳️‍🌈
 🏳️‍🌈
+
+--- nobreak ---
+// Test that spans are kept on one line.
+#set text(hyphenate: true)
+#hide(place[
+  The conference takes place on
+  #nobreak[24#metadata(none)<a> December 20#metadata(none)<b>24] in the
+  #nobreak[Extra#metadata(none)<c>ordinarily Large Audi#metadata(none)<d>torium]
+  downtown.
+])
+#context {
+  let y(label) = locate(label).position().y
+  test(y(<a>), y(<b>))
+  test(y(<c>), y(<d>))
+}
+
+--- nobreak-overflow ---
+// Test that a span wider than the line overflows it.
+#hide(place(block(width: 60pt)[
+  Visit the
+  #nobreak[I#metadata(none)<a>nternational Typesetting Conferenc#metadata(none)<b>e]
+  today.
+]))
+#context {
+  let a = locate(<a>).position()
+  let b = locate(<b>).position()
+  test(a.y, b.y)
+  assert(b.x - a.x > 60pt)
+}
+
+--- nobreak-linebreak ---
+// Test that explicit line breaks are still honored.
+#hide(place(nobreak[First#metadata(none)<a> line \ second#metadata(none)<b> line]))
+#context assert(locate(<b>).position().y > locate(<a>).position().y)
@@ -15,6 +15,12 @@ a vs #text(alternates: true)[a] \
 // Test ligatures.
 fi vs. #text(ligatures: false)[No fi]
 
+--- text-contextual-ligatures ---
+// Disabling contextual ligatures keeps the standard ones.
+#set text(contextual-ligatures: false)
+#context test(measure[fi].width, measure(text(contextual-ligatures: true)[fi]).width)
+#context assert(measure[fi].width < measure(text(ligatures: false)[fi]).width)
+
 --- text-number-type ---
 // Test number type.
 #set text(number-type: "old-style")
@@ -0,0 +1,34 @@
+--- keep-moves-group ---
+// A group that doesn't fit into the rest of the page moves to the next one.
+#set page(height: 60pt)
+#v(25pt)
+#keep[
+  A #metadata(none) <first>
+
+  B #metadata(none) <last>
+]
+#context test(locate(<first>).page(), 2)
+#context test(locate(<last>).page(), 2)
+
+--- keep-fits ---
+// A group that fits stays where it is.
+#set page(height: 60pt)
+#keep[
+  A #metadata(none) <first>
+
+  B #metadata(none) <last>
+]
+#context test(locate(<first>).page(), 1)
+#context test(locate(<last>).page(), 1)
+
+--- keep-too-tall ---
+// A group that is taller than a full page is broken after all.
+#set page(height: 60pt)
+#v(25pt)
+#keep[
+  A #metadata(none) <first>
+  #v(40pt)
+  B #metadata(none) <last>
+]
+#context test(locate(<first>).page(), 1)
+#context test(locate(<last>).page(), 2)
@@ -92,3 +92,31 @@
   table(columns: 5, u(17), it, u(1), it, u(5))
   [#size.width] // 17pt
 }
+
+--- measure-glyphs ---
+// Test `measure-glyphs`.
+#context {
+  let clusters = measure-glyphs("AVA", size: 20pt)
+  test(clusters.map(it => it.text), ("A", "V", "A"))
+  test(clusters.at(1).x, clusters.at(0).advance)
+  assert(clusters.at(0).kerning < 0pt)
+  test(
+    clusters.map(it => it.advance).sum(),
+    measure(text(20pt)[AVA]).width,
+  )
+}
+
+--- measure-glyphs-ligature ---
+// A ligature forms a single cluster.
+#context test(measure-glyphs("fi").map(it => it.text), ("fi",))
+
+--- measure-glyphs-font ---
+// The font and size default to the ones from the context.
+#set text(font: "New Computer Modern", size: 12pt)
+#context {
+  let (cluster,) = measure-glyphs("x")
+  test(cluster.font, "New Computer Modern")
+  test(cluster, measure-glyphs("x", font: "New Computer Modern", size: 12pt).first())
+  assert(cluster.ascender > 0pt)
+  assert(cluster.descender < 0pt)
+}
@@ -68,6 +68,55 @@
 #text(15pt, font: "Roboto", fill: white, smallcaps[Typst])
 #page(width: 40pt, fill: none, margin: (top: 10pt, rest: auto))[Hi]
 
+--- page-spread ---
+// The spread is split at the spine. With left binding, the first page is a
+// right-hand page and shows the right half.
+#set page(
+  width: 100pt,
+  height: 40pt,
+  spread: place(dx: 130pt, [#metadata(none) <spread>]),
+)
+#context test(
+  query(<spread>).map(m => m.location().position().x),
+  (30pt, 130pt, 30pt),
+)
+#pagebreak()
+#pagebreak()
+
+--- page-spread-binding-right ---
+#set page(
+  width: 100pt,
+  height: 40pt,
+  binding: right,
+  spread: place(dx: 130pt, [#metadata(none) <spread>]),
+)
+#context test(
+  query(<spread>).map(m => m.location().position().x),
+  (130pt, 30pt),
+)
+#pagebreak()
+
+--- page-spread-overlays ---
+// The spread's header and footer span both pages and its foreground is placed
+// in front of the pages' foregrounds.
+#set page(
+  width: 100pt,
+  height: 60pt,
+  margin: 15pt,
+  foreground: place(center + horizon, square(size: 10pt, fill: blue)),
+  spread: (
+    background: rect(width: 100%, height: 100%, fill: aqua),
+    foreground: place(center + horizon, circle(radius: 15pt, fill: red)),
+    header: align(center)[Spread header],
+    footer: align(center)[Spread footer],
+  ),
+)
+#context test(page.spread.keys(), ("background", "foreground", "header", "footer"))
+#pagebreak()
+Left
+#pagebreak()
+Right
+
 --- page-margin-uniform ---
 // Set all margins at once.
 #[
@@ -266,3 +315,344 @@ Hi
 #set page(fill: gray)
 text
 #pagebreak()
+
+--- page-baseline-grid ---
+// The baselines of lines are snapped to the grid.
+#set page(height: 20pt, margin: 0pt, baseline-grid: 9pt)
+#set text(size: 4pt)
+#hide[
+  #box(width: 10pt, height: 4pt)#metadata(none) <first>
+
+  #box(width: 10pt, height: 4pt)#metadata(none) <second>
+]
+
+#context test(
+  (locate(<first>).position().y, locate(<second>).position().y),
+  (9pt, 18pt),
+)
+
+--- page-baseline-grid-columns ---
+// Lines register across columns.
+#set page(height: 20pt, margin: 0pt, columns: 2, baseline-grid: 9pt)
+#set text(size: 4pt)
+#hide[
+  #box(width: 10pt, height: 4pt)#metadata(none) <first>
+  #colbreak()
+  #box(width: 10pt, height: 2pt)#metadata(none) <second>
+]
+
+#context test(
+  (locate(<first>).position().y, locate(<second>).position().y),
+  (9pt, 9pt),
+)
+
+--- page-frames ---
+// The content threads through the frames and onto the next page.
+#set page(width: 100pt, height: 60pt, margin: 0pt, frames: (
+  a: (x: 60%, y: 0pt, width: 40%, height: 20pt),
+  b: (x: 0%, y: 30pt, width: 40%, height: 30pt),
+))
+#set par(leading: 2pt)
+#let mark(name) = [#metadata(none)#label(name)]
+A#mark("a") \
+B#mark("b") \
+C#mark("c") \
+D#mark("d") \
+E#mark("e") \
+F#mark("f")
+#context {
+  let (a, c, f) = ("a", "c", "f").map(n => locate(label(n)).position())
+  assert(a.page == 1 and a.x > 60pt and a.y < 20pt)
+  assert(c.page == 1 and c.x < 40pt and c.y > 30pt)
+  assert(f.page == 2 and f.x > 60pt)
+}
+
+--- page-frames-width ---
+// Error: 2-116 all frames of a page must have the same width
+// Hint: 2-116 the frames' positions and heights may differ
+#page(frames: (a: (x: 0pt, y: 0pt, width: 20pt, height: 20pt), b: (x: 0pt, y: 30pt, width: 30pt, height: 20pt)))[A]
+
+--- page-bleed ---
+#set page(width: 100pt, height: 100pt, margin: 10pt, bleed: 5pt)
+#context test(here().position(), (page: 1, x: 15pt, y: 15pt))
+#context test(page.width, 100pt)
+
+--- page-bleed-place-outside ---
+#set page(width: 100pt, height: 100pt, margin: 10pt, bleed: 5pt)
+#place(dx: -13pt, dy: -13pt, context test(here().position(), (page: 1, x: 2pt, y: 2pt)))
+
+--- page-bleed-negative ---
+// Error: 18-22 bleed must not be negative
+#set page(bleed: -5pt)
+
+--- page-marks-invalid-key ---
+// Error: 18-40 unexpected key "bleed", valid keys are "crop" and "registration"
+#set page(marks: (crop: true, bleed: 1))
+
+--- page-marks-invalid-value ---
+// Error: 18-31 expected boolean, found string
+#set page(marks: (crop: "yes"))
+
+--- page-single-turned ---
+#set page(width: 100pt, height: 60pt, margin: 10pt)
+#page.single(rect(width: 100%, height: 100%)[Turned])
+
+--- page-single-turned-landscape ---
+#set page(width: 100pt, height: 60pt, margin: 10pt, flipped: true)
+#page.single(rect(width: 100%, height: 100%)[Turned])
+
+--- page-single-sized ---
+// Giving a size replaces turning.
+#set page(width: 100pt, height: 60pt, margin: 10pt)
+#page.single(width: 50pt, rect(width: 100%, height: 100%)[Sized])
+
+--- page-single-keeps-settings ---
+#set page(width: 100pt, height: 60pt, margin: (x: 10pt, y: 5pt))
+#page.single(rect(width: 100%, height: 100%)[Turned])
+
+--- page-numbering-scope ---
+// Pages with nothing but metadata would be merged, so each page holds an
+// empty box.
+#set page(height: 40pt, numbering: "i", numbering-scope: "front")
+#box() <a>
+#pagebreak()
+#box() <b>
+#set page(numbering: "1", numbering-scope: "main")
+#box() <c>
+#pagebreak()
+#box()
+#context test(counter(page).get(), (2,))
+#set page(numbering: "i", numbering-scope: "front")
+#box()
+#context {
+  test(counter(page).get(), (3,))
+  test(counter(page).at(<a>), (1,))
+  test(counter(page).at(<b>), (2,))
+  test(counter(page).at(<c>), (1,))
+  test(counter(page).final(), (3,))
+}
+
+--- page-numbering-scope-update ---
+// Manual updates apply to the current scope only.
+#set page(height: 40pt, numbering-scope: "front")
+#counter(page).update(5)
+#box()
+#set page(numbering-scope: "main")
+#box()
+#context test(counter(page).get(), (1,))
+#set page(numbering-scope: "front")
+#box()
+#context test(counter(page).get(), (6,))
+
+--- page-numbering-scope-heading ---
+#set page(height: 80pt, numbering: "i", numbering-scope: "front")
+#box()
+#set page(numbering: "1", numbering-scope: "main")
+= Intro
+#context {
+  let loc = query(heading).first().location()
+  test(loc.page(), 2)
+  test(counter(page).at(loc), (1,))
+  test(loc.page-numbering(), "1")
+}
+
+--- page-side ---
+#set page(height: 40pt)
+#context test(page.side(), "recto")
+#pagebreak()
+#context test(page.side(), "verso")
+#pagebreak()
+#context test(page.side(), "recto")
+
+--- page-side-binding ---
+#set page(height: 40pt, binding: right)
+#context test(page.side(), "verso")
+#pagebreak()
+#context test(page.side(), "recto")
+
+--- page-side-rtl ---
+#set page(height: 40pt)
+#set text(dir: rtl)
+#context test(page.side(), "verso")
+
+--- page-parity ---
+#set page(height: 40pt, numbering: "1")
+#counter(page).update(4)
+#context test(page.parity(), "odd")
+#pagebreak()
+#context test(page.parity(), "even")
+
+--- page-side-no-context ---
+// Error: 2-13 can only be used when context is known
+// Hint: 2-13 try wrapping this in a `context` expression
+// Hint: 2-13 the `context` expression should wrap everything that depends on this function
+#page.side()
+
+--- page-header-slots ---
+#let probe(x) = box(width: 20pt, context test(here().position().x, x))
+#set page(
+  height: 60pt,
+  header: (left: probe(10pt), center: probe(50pt), right: probe(90pt)),
+  footer: (right: probe(90pt)),
+)
+#box()
+
+--- page-header-slots-mirrored ---
+#let probe(recto, verso) = box(width: 20pt, context {
+  test(here().position().x, if page.side() == "recto" { recto } else { verso })
+})
+#set page(
+  height: 60pt,
+  header: (inside: probe(10pt, 90pt), outside: probe(90pt, 10pt)),
+)
+#box()
+#pagebreak()
+#box()
+#pagebreak()
+#box()
+
+--- page-binding-offset ---
+#let probe(recto, verso) = context {
+  test(here().position().x, if page.side() == "recto" { recto } else { verso })
+}
+#set page(
+  width: 100pt,
+  height: 60pt,
+  margin: (inside: 10pt, outside: 30pt),
+  binding-offset: 10pt,
+)
+#probe(20pt, 30pt)
+#pagebreak()
+#probe(20pt, 30pt)
+
+--- page-header-slots-mixed ---
+// Error: 19-44 cannot combine `left` or `right` with `inside` or `outside`
+#set page(header: (left: [A], outside: [B]))
+
+--- page-header-slots-invalid-key ---
+// Error: 19-29 unexpected key "top", valid keys are "left", "center", "right", "inside", and "outside"
+#set page(header: (top: [A]))
+
+--- page-query ---
+#set page(height: 60pt, header: context {
+  let entries = page.query(metadata).map(m => m.value)
+  test(entries, ((), ("a", "b"), ("c",), ()).at(here().page()))
+})
+#box(metadata("a"))
+#box(metadata("b"))
+#pagebreak()
+#box(metadata("c"))
+#pagebreak()
+#box()
+
+--- page-query-body ---
+#set page(height: 60pt)
+= A
+#context test(page.query(heading).map(h => h.body), ([A], [B]))
+= B
+#pagebreak()
+= C
+#context test(page.query(heading).map(h => h.body), ([C],))
+
+--- page-query-no-context ---
+// Error: 2-21 can only be used when context is known
+// Hint: 2-21 try wrapping this in a `context` expression
+// Hint: 2-21 the `context` expression should wrap everything that depends on this function
+#page.query(heading)
+
+--- page-contains ---
+#set page(height: 60pt, header: context {
+  test(page.contains(heading.where(level: 1)), here().page() in (1, 3))
+  test(page.contains(heading), here().page() != 4)
+})
+= Chapter
+#pagebreak()
+== Section
+#pagebreak()
+= Chapter
+#pagebreak()
+#box()
+
+--- page-watermark ---
+// The watermark is centered on the page, regardless of the margins.
+#set page(
+  width: 100pt,
+  height: 100pt,
+  margin: (left: 40pt, rest: 10pt),
+  watermark: watermark(
+    angle: 0deg,
+    block(width: 20pt, height: 10pt)[#metadata(none) <mark>],
+  ),
+)
+
+#box()
+#context test(locate(<mark>).position(), (page: 1, x: 40pt, y: 45pt))
+
+--- page-watermark-tile ---
+// Only one repetition of a tiled watermark is visible to introspection.
+#set page(
+  width: 100pt,
+  height: 100pt,
+  watermark: watermark(tile: true, above: true)[DRAFT #metadata(none) <mark>],
+)
+
+#box()
+#context test(query(<mark>).len(), 1)
+
+--- page-watermark-content ---
+// Plain content becomes a watermark with the default settings.
+#set page(height: 60pt, watermark: [CONFIDENTIAL #metadata(none) <mark>])
+#box()
+#context test(query(<mark>).len(), 1)
+
+--- page-diagnose-block-overflow ---
+#set page(height: 100pt, margin: 10pt, diagnose: true)
+// Warning: 2-40 block overflows its region by 20pt
+// Hint: 2-40 the block is 100pt tall but only 80pt are available
+// Hint: 2-40 consider making the block breakable or reducing its size
+#block(height: 100pt, breakable: false)
+
+--- page-diagnose-block-moved ---
+#set page(height: 100pt, margin: 10pt, diagnose: true)
+#set block(spacing: 0pt)
+#block(height: 10pt)
+// Warning: 2-39 block does not fit into the remaining space of its region
+// Hint: 2-39 this leaves 70pt of the region empty
+// Hint: 2-39 consider making the block breakable or moving it elsewhere
+#block(height: 75pt, breakable: false)
+
+--- page-diagnose-disabled ---
+#set page(height: 20pt, margin: 5pt)
+#hide(block(height: 20pt, breakable: false))
+
+--- page-border ---
+#set page(width: 100pt, height: 80pt, margin: 20pt, border: 0.5pt + blue)
+#context test(page.border, 0.5pt + blue)
+Bordered.
+
+--- page-border-two-sided ---
+#set page(
+  width: 100pt,
+  height: 80pt,
+  margin: 20pt,
+  border: (
+    stroke: 0.5pt,
+    outside: 2pt,
+    inset: 6pt,
+    corner: polygon((0pt, 0pt), (8pt, 0pt), (0pt, 8pt), fill: black),
+  ),
+)
+
+#context {
+  let (corner, ..border) = page.border
+  test(border, (y: stroke(0.5pt), inside: stroke(0.5pt), outside: stroke(2pt), inset: 6pt))
+  test(corner.func(), polygon)
+}
+
+Right page
+#pagebreak()
+Left page
+
+--- page-border-inside-and-left ---
+// Error: 19-43 `inside` and `outside` are mutually exclusive with `left` and `right`
+#set page(border: (inside: 1pt, left: 1pt))
@@ -113,6 +113,43 @@ Second
 
 Third
 
+--- pagebreak-to-recto ---
+#set page(height: 40pt)
+First
+#pagebreak(to: "recto")
+#context test(here().page(), 3)
+#pagebreak(to: "verso")
+#context test(here().page(), 4)
+
+--- pagebreak-to-recto-binding ---
+// The binding of the next page is honored.
+#set page(height: 40pt)
+First
+#pagebreak(to: "recto")
+#set page(binding: right)
+#context test(here().page(), 2)
+
+--- pagebreak-to-multiple-of ---
+#set page(height: 40pt)
+First
+#pagebreak(to-multiple-of: 4)
+#context test(here().page(), 5)
+#pagebreak(to-multiple-of: 4)
+#context test(here().page(), 9)
+#pagebreak(to-multiple-of: 2)
+#context test(here().page(), 11)
+
+--- pagebreak-to-multiple-of-and-parity ---
+// The parity takes precedence.
+#set page(height: 40pt)
+First
+#pagebreak(to-multiple-of: 2, to: "even")
+#context test(here().page(), 4)
+
+--- pagebreak-to-invalid ---
+// Error: 16-22 expected "even", "odd", "recto", "verso", or none
+#pagebreak(to: "left")
+
 --- issue-2134-pagebreak-bibliography ---
 // Test weak pagebreak before bibliography.
 #pagebreak(weak: true)
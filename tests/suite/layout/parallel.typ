@@ -0,0 +1,97 @@
+// Test parallel streams.
+
+--- parallel-facing ---
+// Corresponding paragraphs start at the same position of facing pages.
+#set page(height: 80pt)
+Intro.
+#parallel[
+  #metadata(none) <l1> Gallia est omnis divisa in partes tres.
+
+  #metadata(none) <l2> Hi omnes.
+][
+  #metadata(none) <r1> All Gaul is divided into three parts, quarum unam
+  incolunt Belgae, aliam Aquitani.
+
+  #metadata(none) <r2> All these differ from each other in language.
+]
+#context {
+  let (l1, l2, r1, r2) = (<l1>, <l2>, <r1>, <r2>).map(l => locate(l).position())
+  test((l1.page, r1.page), (1, 2))
+  test(l1.y, r1.y)
+  test((l2.page, r2.page), (3, 4))
+  test(l2.y, r2.y)
+}
+
+--- parallel-uneven ---
+// A stream with more paragraphs continues on its own.
+#set page(height: 80pt)
+#parallel[
+  A
+
+  B
+
+  #metadata(none) <c> C
+][
+  #metadata(none) <a> A
+]
+#context test(locate(<c>).position().page, 1)
+#context test(locate(<a>).position().page, 2)
+
+--- parallel-side-by-side ---
+// Without further regions, the streams are placed side by side.
+#box(height: 60pt, width: 100%, parallel(gutter: 10pt)[
+  #metadata(none) <l1> A
+
+  #metadata(none) <l2> B
+][
+  #metadata(none) <r1> One two three four five six.
+
+  #metadata(none) <r2> Seven
+])
+#context {
+  let (l1, l2, r1, r2) = (<l1>, <l2>, <r1>, <r2>).map(l => locate(l).position())
+  test(l1.y, r1.y)
+  test(l2.y, r2.y)
+  test(r1.x - l1.x, 55pt)
+}
+
+--- parallel-alternating ---
+// The chunks alternate in a single flow.
+#parallel(mode: "alternating")[
+  #metadata(none) <l1> A
+
+  #metadata(none) <l2> B
+][
+  #metadata(none) <r1> One
+
+  #metadata(none) <r2> Two
+]
+#context {
+  let (l1, l2, r1, r2) = (<l1>, <l2>, <r1>, <r2>).map(l => locate(l).position().y)
+  assert(l1 < r1 and r1 < l2 and l2 < r2)
+}
+
+--- parallel-sync-anchors ---
+// Only anchors that occur in both streams break them.
+#set page(height: 100pt)
+#parallel(sync: "anchors")[
+  A
+
+  #anchor("b")
+  #metadata(none) <l> B
+][
+  One
+
+  Two
+
+  #anchor("x")
+  Three
+
+  #anchor("b")
+  #metadata(none) <r> Four
+]
+#context {
+  let (l, r) = (locate(<l>).position(), locate(<r>).position())
+  test((l.page, r.page), (1, 2))
+  test(l.y, r.y)
+}
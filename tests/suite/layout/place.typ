@@ -100,6 +100,172 @@ Second
 )
 #lorem(20)
 
+--- place-float-parent-scope ---
+// A parent-scoped float spans all columns and the columns flow around it.
+#set page(height: 20pt, margin: (x: 10pt, y: 0pt), columns: 2)
+#set columns(gutter: 0pt)
+#show: hide
+#let item(i) = block(height: 5pt, spacing: 0pt, breakable: false)[#metadata(i) <item>]
+#item(0)
+#place(
+  top,
+  float: true,
+  scope: "parent",
+  clearance: 0pt,
+  block(width: 100%, height: 10pt)[#metadata(none) <float>],
+)
+#for i in range(1, 4) { item(i) }
+
+#context {
+  let float = locate(<float>).position()
+  test((float.page, float.x, float.y), (1, 10pt, 0pt))
+  test(query(<float>).first().location().page(), 1)
+  let items = query(<item>).map(it => it.location().position())
+  test(items.map(it => it.page).dedup(), (1,))
+  test(items.map(it => (it.x, it.y)), (
+    (10pt, 10pt), (10pt, 15pt), (60pt, 10pt), (60pt, 15pt),
+  ))
+}
+
+--- place-float-parent-scope-container ---
+// Parent-scoped floats also span the columns of a container.
+#let item(i) = block(height: 5pt, spacing: 0pt, breakable: false)[#metadata(i) <item>]
+#hide(place(block(width: 100pt, height: 20pt, columns(2, gutter: 0pt)[
+  #item(0)
+  #place(
+    bottom,
+    float: true,
+    scope: "parent",
+    clearance: 0pt,
+    block(width: 100%, height: 10pt)[#metadata(none) <float>],
+  )
+  #for i in range(1, 4) { item(i) }
+])))
+
+#context {
+  let float = locate(<float>).position()
+  test((float.x, float.y), (10pt, 20pt))
+  let items = query(<item>).map(it => it.location().position())
+  test(items.map(it => (it.x, it.y)), (
+    (10pt, 10pt), (10pt, 15pt), (60pt, 10pt), (60pt, 15pt),
+  ))
+}
+
+--- place-float-parent-scope-rtl ---
+// Parent-scoped floats are positioned relative to the parent in RTL columns.
+#set page(height: 20pt, margin: (x: 10pt, y: 0pt), columns: 2)
+#set columns(gutter: 0pt)
+#set text(dir: rtl)
+#show: hide
+#place(
+  top + left,
+  float: true,
+  scope: "parent",
+  clearance: 0pt,
+  block(width: 20%, height: 10pt)[#metadata(none) <float>],
+)
+#block(height: 5pt)[#metadata(none) <item>]
+
+#context {
+  let float = locate(<float>).position()
+  test((float.x, float.y), (10pt, 0pt))
+  test(locate(<item>).position().y, 10pt)
+}
+
+--- place-float-parent-scope-not-floating ---
+// Error: 2-31 parent-scoped placement is only available for floating placement
+// Hint: 2-31 you can enable floating placement with `place(float: true, ..)`
+#place(scope: "parent")[Hello]
+
+--- place-wrap-auto ---
+// Lines beside the placed element wrap around its bounding box.
+#hide(place(block(width: 100pt, height: 60pt)[
+  #set par(leading: 2pt)
+  #place(top + right, wrap: auto, clearance: 0pt, box(width: 50pt, height: 20pt))
+  #metadata(none) <origin>
+  #for i in range(5) [#box(width: 40pt, height: 8pt)#metadata(i) <end> ]
+]))
+
+#context {
+  let origin = locate(<origin>).position()
+  let ends = query(<end>).map(it => {
+    let pos = it.location().position()
+    (pos.x - origin.x, pos.y - origin.y)
+  })
+  test(ends, ((40pt, 8pt), (40pt, 18pt), (40pt, 28pt), (82.5pt, 28pt), (40pt, 38pt)))
+}
+
+--- place-wrap-polygon ---
+// Lines wrap around a triangle, getting wider further down.
+#hide(place(block(width: 100pt, height: 60pt)[
+  #set par(leading: 2pt)
+  #place(
+    top + right,
+    wrap: ((0pt, 0pt), (100%, 0pt), (100%, 100%)),
+    clearance: 0pt,
+    box(width: 50pt, height: 40pt),
+  )
+  #metadata(none) <origin>
+  #for i in range(6) [#box(width: 40pt, height: 8pt)#metadata(i) <end> ]
+]))
+
+#context {
+  let origin = locate(<origin>).position()
+  let ends = query(<end>).map(it => {
+    let pos = it.location().position()
+    (pos.x - origin.x, pos.y - origin.y)
+  })
+  test(ends.at(3), (40pt, 38pt))
+  test(ends.at(4), (82.5pt, 38pt))
+}
+
+--- place-wrap-below ---
+// Lines which don't fit beside the placed element move below it.
+#hide(place(block(width: 100pt, height: 60pt)[
+  #set par(leading: 2pt)
+  #place(top + left, wrap: auto, clearance: 2pt, box(width: 70pt, height: 20pt))
+  #metadata(none) <origin>
+  #for i in range(2) [#box(width: 40pt, height: 8pt)#metadata(i) <end> ]
+]))
+
+#context {
+  let origin = locate(<origin>).position()
+  let ends = query(<end>).map(it => {
+    let pos = it.location().position()
+    (pos.x - origin.x, pos.y - origin.y)
+  })
+  test(ends, ((40pt, 30pt), (82.5pt, 30pt)))
+}
+
+--- place-wrap-float ---
+// A float that text wraps around is placed at the current position.
+#hide(place(block(width: 100pt, height: 60pt)[
+  #set par(leading: 2pt)
+  #set block(spacing: 12pt)
+  #metadata(none) <origin>
+  #box(width: 40pt, height: 8pt)
+
+  #place(
+    top + right,
+    float: true,
+    wrap: auto,
+    clearance: 0pt,
+    [#metadata(none) <float>#box(width: 50pt, height: 20pt)],
+  )
+  #for i in range(4) [#box(width: 40pt, height: 8pt)#metadata(i) <end> ]
+]))
+
+#context {
+  let origin = locate(<origin>).position()
+  let float = locate(<float>).position()
+  test((float.x - origin.x, float.y - origin.y), (50pt, 20pt))
+  let ends = query(<end>).map(it => {
+    let pos = it.location().position()
+    (pos.x - origin.x, pos.y - origin.y)
+  })
+  test(ends, ((40pt, 28pt), (40pt, 38pt), (40pt, 48pt), (82.5pt, 48pt)))
+}
+
 --- place-float-figure ---
 // LARGE
 #set page(height: 250pt, width: 150pt)
@@ -251,3 +417,50 @@ Start.
 )
 
 #lorem(20)
+
+--- place-order-auto ---
+// In-flow metadata is boxed to give it a paragraph of its own.
+#box(metadata("a"))
+#place(bottom, metadata("b"))
+#box(metadata("c"))
+#context test(query(metadata).map(m => m.value), ("a", "b", "c"))
+
+--- place-order-start ---
+#box(metadata("a"))
+#place(bottom, order: "start", metadata("b"))
+#box(metadata("c"))
+#context test(query(metadata).map(m => m.value), ("b", "a", "c"))
+
+--- place-order-end ---
+#place(top, order: "end", metadata("a"))
+#place(top, float: true, order: "end", metadata("b"))
+#box(metadata("c"))
+#context test(query(metadata).map(m => m.value), ("c", "a", "b"))
+
+--- place-order-invalid ---
+// Error: 15-21 expected "start", "end", or auto
+#place(order: "left")[]
+
+--- place-on ---
+#set page(
+  height: 40pt,
+  background: {
+    place(on: "recto", horizon + right, dx: -10pt)[Recto]
+    place(on: "verso", horizon + left, dx: 10pt)[Verso]
+  },
+)
+#box()
+#pagebreak()
+#box()
+#pagebreak()
+#box()
+
+--- place-on-float ---
+// Dropped floats take up no space.
+#set page(height: 60pt)
+#place(top, float: true, on: "verso", rect(height: 20pt))
+The text starts at the top.
+
+--- place-on-invalid ---
+// Error: 12-18 expected "recto", "verso", or none
+#place(on: "left")[]
@@ -0,0 +1,37 @@
+// Test the `redact` function.
+
+--- redact-text ---
+// Redacted text still takes up its space.
+#context {
+  test(measure[A #redact[secret] B], measure[A secret B])
+  test(measure(redact(fill: gray)[A]), measure[A])
+}
+
+--- redact-paragraph ---
+#context test(
+  measure(block(width: 100pt, redact(lorem(12)))),
+  measure(block(width: 100pt, lorem(12))),
+)
+
+--- redact-size ---
+#context {
+  let body = [Some *secret* text]
+  test(measure(redact(body)), measure(body))
+  test(measure(redact(rect(width: 30pt))).width, 30pt)
+}
+
+--- redact-link ---
+#context test(
+  measure(link("https://typst.org/")[Visit #redact[typst.org]]),
+  measure(link("https://typst.org/")[Visit typst.org]),
+)
+
+--- redact-math ---
+#context test(measure($x + #redact($y^2$) = z$), measure($x + y^2 = z$))
+
+--- redact-counter ---
+// Elements in redacted content are still counted.
+#set heading(numbering: "1.")
+#hide(place(redact[= Hidden]))
+#hide(place[= Visible])
+#context test(counter(heading).get(), (2,))
@@ -42,3 +42,22 @@ A#box(width: 1fr, repeat(rect(width: 6em, height: 0.7em)))B
 // Error: 2:2-2:13 repeat with no size restrictions
 #set page(width: auto)
 #repeat(".")
+
+--- leader-fills-line ---
+// A leader takes up the remaining space of its line.
+#context test(measure(width: 80pt)[A #leader() B].width, 80pt)
+
+--- leader-unbounded ---
+// Without bounds, a leader behaves like a fractionally sized box.
+#context test(
+  measure[A #leader() B].width,
+  measure[A #box(width: 1fr) B].width,
+)
+
+--- leader-fill ---
+#test(leader(fill: [-]).fill, [-])
+#test(leader(align: false).align, false)
+
+--- leader-many-instances ---
+// A tiny leader in a wide region is limited to a thousand instances.
+#context test(measure(width: 100000pt)[#leader(fill: box(width: 0.01pt))].width, 100000pt)
@@ -0,0 +1,63 @@
+--- sideways-size ---
+// Sideways content takes up the full region.
+#context test(
+  measure(width: 60pt, height: 100pt, sideways[Hi]),
+  (width: 60pt, height: 100pt),
+)
+
+--- sideways-counterclockwise ---
+// By default, the top of the content faces the binding on the left.
+#hide(place(block(width: 60pt, height: 100pt)[
+  #metadata(none) <origin>
+  #sideways[#metadata(none) <start>]
+]))
+
+#context {
+  let origin = locate(<origin>).position()
+  let start = locate(<start>).position()
+  test(start.x, origin.x)
+  test(start.y, origin.y + 100pt)
+}
+
+--- sideways-clockwise ---
+#hide(place(block(width: 60pt, height: 100pt)[
+  #metadata(none) <origin>
+  #sideways(clockwise: true)[#metadata(none) <start>]
+]))
+
+#context {
+  let origin = locate(<origin>).position()
+  let start = locate(<start>).position()
+  test(start.x, origin.x + 60pt)
+  test(start.y, origin.y)
+}
+
+--- sideways-binding-right ---
+// With a binding on the right, the content is turned clockwise.
+#set page(binding: right)
+#hide(place(block(width: 60pt, height: 100pt)[
+  #metadata(none) <origin>
+  #sideways[#metadata(none) <start>]
+]))
+
+#context {
+  let origin = locate(<origin>).position()
+  test(locate(<start>).position().x, origin.x + 60pt)
+}
+
+--- sideways-next-region ---
+// Sideways content moves on to the next region if there is content before it.
+#hide(place(block(width: 100pt, height: 60pt)[
+  #metadata(none) <origin>
+  #columns(2, gutter: 0pt)[
+    Before
+    #sideways[#metadata(none) <start>]
+  ]
+]))
+
+#context {
+  let origin = locate(<origin>).position()
+  let start = locate(<start>).position()
+  test(start.x, origin.x + 50pt)
+  test(start.y, origin.y + 60pt)
+}
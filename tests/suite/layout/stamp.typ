@@ -0,0 +1,17 @@
+--- stamp-size ---
+// A stamp takes up the size of its content.
+#context test(
+  measure(stamp(rect(width: 20pt, height: 10pt))),
+  (width: 20pt, height: 10pt),
+)
+
+--- stamp-reuse ---
+// Each use of a stamp takes up the same space.
+#let mark = stamp(box(width: 15pt, height: 5pt))
+#context test(measure[#mark#mark].width, 30pt)
+
+--- stamp-introspection ---
+// The content of a stamp is not visible to introspection.
+#let mark = stamp[#metadata("inside") <inside>]
+#hide(place[#mark #mark])
+#context test(query(<inside>), ())
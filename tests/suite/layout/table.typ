@@ -332,3 +332,16 @@ Hello
   rows: 16pt,
   ..range(6).map(str).flatten(),
 )
+
+--- table-number-figures ---
+#hide(place(table(context {
+  test(text.number-type, auto)
+  test(text.number-width, auto)
+})))
+#set table(tabular-figures: true)
+#hide(place(table(context {
+  test(text.number-type, "lining")
+  test(text.number-width, "tabular")
+})))
+#show table: set text(number-width: "proportional")
+#hide(place(table(context test(text.number-width, "proportional"))))
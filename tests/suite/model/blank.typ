@@ -0,0 +1,38 @@
+// Test blanks.
+
+--- blank-fills-line ---
+// A blank takes up the remaining space of its line.
+#context test(measure(width: 80pt)[A #blank() B].width, 80pt)
+#context test(
+  measure(width: 80pt)[A #blank() B #blank(width: 2fr)].width,
+  80pt,
+)
+
+--- blank-width ---
+#context test(measure[A #blank(width: 30pt) B].width, measure[A #box(width: 30pt) B].width)
+#context test(
+  measure[#blank(width: auto, answer: [Paris])].width,
+  measure[Paris].width,
+)
+
+--- blank-baseline ---
+// The line of a blank doesn't make its line taller, but a caption does.
+#context test(measure[A #blank(width: 20pt) B].height, measure[A B].height)
+#context test(measure[A #blank(width: 20pt, answer: [x]) B].height, measure[A B].height)
+#context assert(measure[A #blank(caption: [Name]) B].height > measure[A B].height)
+
+--- blank-reveal ---
+// Revealing the answers doesn't change the layout.
+#let exam = [The capital of France is #blank(width: auto, answer: [Paris]).]
+#context test(
+  measure(exam),
+  measure({
+    set blank(reveal: true)
+    exam
+  }),
+)
+
+--- blank-rule ---
+#test(blank(rule: "dotted").rule, stroke(dash: "dotted"))
+#test(blank(rule: 1pt + red).rule, 1pt + red)
+#test(blank(rule: none).rule, none)
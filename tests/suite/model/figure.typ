@@ -175,9 +175,59 @@ We can clearly see that @fig-cylinder and
 #set figure.caption(position: top)
 
 --- figure-caption-position-bad ---
-// Error: 31-38 expected `top` or `bottom`, found horizon
+// Error: 31-38 cannot convert this alignment to a side
 #set figure.caption(position: horizon)
 
+--- figure-caption-position-side ---
+// Captions on the left or right are placed beside the body.
+#set page(width: 200pt)
+#figure(
+  rect(width: 40pt, height: 20pt),
+  caption: figure.caption(position: left)[Left],
+)
+#figure(
+  rect(width: 40pt, height: 20pt),
+  caption: figure.caption(position: right)[Right],
+)
+
+--- figure-caption-short ---
+// The short caption is shown in the outline.
+#outline(target: figure)
+#figure(
+  rect(width: 40pt, height: 10pt),
+  caption: figure.caption(short: [Short], [A long caption]),
+)
+
+--- figure-caption-continued ---
+// A repeated caption is shown where a breakable figure breaks.
+#set page(width: 240pt)
+#show figure: set block(breakable: true)
+#block(height: 50pt, columns(3, figure(
+  kind: image,
+  [A #colbreak() B #colbreak() C],
+  caption: figure.caption(position: top, repeat: true)[Letters],
+)))
+
+--- figure-caption-continued-bottom ---
+// A caption at the bottom is repeated before each break.
+#set page(width: 240pt)
+#show figure: set block(breakable: true)
+#block(height: 50pt, columns(3, figure(
+  kind: image,
+  [A #colbreak() B #colbreak() C],
+  caption: figure.caption(repeat: true)[Letters],
+)))
+
+--- figure-caption-continued-no-repeat ---
+// Captions are not repeated by default.
+#set page(width: 240pt)
+#show figure: set block(breakable: true)
+#block(height: 50pt, columns(3, figure(
+  kind: image,
+  [A #colbreak() B #colbreak() C],
+  caption: figure.caption(position: top)[Letters],
+)))
+
 --- figure-localization-fr ---
 // Test French
 #set text(lang: "fr")
@@ -130,6 +130,108 @@ Definition #footnote[Hi]<fn>
 Real #footnote[...]<fn> \
 Ref @fn
 
+--- footnote-entry-continued ---
+// Test the markers of an entry that is split across pages.
+#set page(height: 100pt)
+#set footnote.entry(
+  continued: [#metadata("continued") <marker>],
+  continuation: [#metadata("continuation") <marker>],
+)
+
+#v(3em)
+A long note #footnote(lorem(14))
+
+#context test(
+  query(<marker>).map(it => (it.value, it.location().page())),
+  (("continued", 1), ("continuation", 2)),
+)
+
+--- footnote-entry-continued-unsplit ---
+// Test that an entry that isn't split has no markers.
+#set page(height: 100pt)
+#set footnote.entry(
+  continued: [#metadata("continued") <marker>],
+  continuation: [#metadata("continuation") <marker>],
+)
+
+A short note #footnote[Short.]
+
+#context test(query(<marker>), ())
+
+--- footnote-entry-scope-page ---
+// Test that page-scoped entries are placed below all columns.
+#set page(columns: 2, height: 120pt)
+#set footnote.entry(scope: "page")
+
+Left#footnote[One. #metadata(none) <one>]
+#colbreak()
+Right <right> #footnote[Two. #metadata(none) <two>]
+
+#context {
+  let one = locate(<one>).position()
+  let two = locate(<two>).position()
+  test(one.page, 1)
+  test(two.page, 1)
+  assert(one.y < two.y)
+  assert(locate(<right>).position().x > one.x)
+}
+
+--- footnote-entry-scope-column ---
+// Test that column-scoped entries are placed below their column.
+#set page(columns: 2, height: 120pt)
+
+Left#footnote[One. #metadata(none) <one>]
+#colbreak()
+Right#footnote[Two. #metadata(none) <two>]
+
+#context {
+  let one = locate(<one>).position()
+  let two = locate(<two>).position()
+  test(one.y, two.y)
+  assert(one.x < two.x)
+}
+
+--- footnote-stream ---
+// Each note stream is numbered on its own and listed in its own area.
+#set page(height: 140pt)
+A#footnote[#metadata(none)<main>Main.]
+B#footnote(stream: "tr")[#metadata(none)<tr>Translator.]
+C#footnote(stream: "app")[#metadata(none)<app>App.]
+D#footnote(stream: "app")[App.] <app-two>
+E#footnote[Main.]
+#context {
+  let y(label) = locate(label).position().y
+  assert(y(<main>) < y(<app>) and y(<app>) < y(<tr>))
+  test(counter(footnote).final(), (2,))
+  test(counter(footnote.where(stream: "app")).final(), (2,))
+  test(counter(footnote.where(stream: "app")).at(<app-two>), (2,))
+  test(counter(footnote.where(stream: "tr")).final(), (1,))
+}
+
+--- footnote-stream-pages ---
+// The counter of a stream has a footnote's number at the footnote, on every
+// page.
+#set page(height: 80pt)
+A#footnote(stream: "app")[One.] <one>
+#pagebreak()
+B#footnote[Main.] <main>
+C#footnote(stream: "app")[Two.] <two>
+#pagebreak()
+D#footnote(stream: "app")[Three.] <three>
+#context {
+  let app = counter(footnote.where(stream: "app"))
+  test(app.at(<one>), (1,))
+  test(app.at(<two>), (2,))
+  test(app.at(<three>), (3,))
+  test(counter(footnote).at(<main>), (1,))
+  test(counter(footnote).at(<three>), (1,))
+}
+
+--- footnote-stream-numbering ---
+#show footnote.where(stream: "app"): set footnote(numbering: "a")
+A#footnote(stream: "app")[B] <note>
+#context test(query(<note>).first().numbering, "a")
+
 --- footnote-ref-call ---
 // Footnote call with label
 #footnote(<fn>)
@@ -0,0 +1,29 @@
+// Test interlinear glosses.
+
+--- gloss-align ---
+// Each word is aligned with its annotation.
+#gloss(
+  [Der Hund schläft],
+  [the dog sleeps],
+)
+
+--- gloss-wrap ---
+// A word and its annotation wrap as a unit.
+#set page(width: 110pt)
+#gloss(
+  ("aaaa", "bbbb", "cccc"),
+  ("x", "y", "z"),
+  [The words.],
+)
+
+--- gloss-numbering ---
+#set gloss(supplement: [Ex.])
+#gloss([a b], [c d]) <one>
+#gloss(numbering: none, [a], [b])
+#gloss([a], [b]) <two>
+@one, @two
+#context test(counter(gloss).at(<two>), (2,))
+
+--- gloss-unnumbered ---
+#set gloss(numbering: none)
+#gloss([a], [b])
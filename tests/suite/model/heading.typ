@@ -60,6 +60,16 @@ multiline.
 = Fake level 2
 == Fake level 3
 
+--- heading-keep-lines-after ---
+#set page(height: 100pt)
+#set heading(keep-lines-after: 3)
+#lorem(14)
+
+= Moved <moved>
+#lorem(12)
+
+#context test(locate(<moved>).page(), 2)
+
 --- heading-offset-and-level ---
 // Passing level directly still overrides all other set values
 #set heading(numbering: "1.1", offset: 1)
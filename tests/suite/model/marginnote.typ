@@ -0,0 +1,42 @@
+// Test margin notes.
+
+--- marginnote-side ---
+#set page(height: 40pt, margin: (x: 40pt, y: 10pt))
+#set marginnote(clearance: 5pt)
+A#marginnote[#metadata(none)<right>R]
+B#marginnote(side: "left")[#metadata(none)<left>L]
+#context test(locate(<right>).position().x, 85pt)
+#context test(locate(<left>).position().x, 5pt)
+
+--- marginnote-baseline ---
+// The baselines of the note and its line are aligned.
+#set page(margin: (right: 50pt))
+A#metadata(none)<line>#marginnote[B#metadata(none)<note>]
+#context test(locate(<note>).position().y, locate(<line>).position().y)
+
+--- marginnote-collision ---
+// A note that would overlap with the previous one is moved down.
+#set page(height: 40pt, margin: (right: 50pt))
+#set marginnote(gap: 4pt)
+A#marginnote[#metadata(none)<a>X]#marginnote[#metadata(none)<b>Y]
+#context {
+  let a = locate(<a>).position().y
+  let b = locate(<b>).position().y
+  test(b - a, measure[X].height + 4pt)
+}
+
+--- marginnote-two-sided ---
+// The outside margin alternates between right and left pages.
+#set page(height: 40pt, margin: (inside: 10pt, outside: 40pt, y: 10pt))
+#set marginnote(clearance: 5pt)
+A#marginnote[#metadata(none)<one>1]
+#pagebreak()
+B#marginnote[#metadata(none)<two>2]
+#context test(locate(<one>).position().x, 85pt)
+#context test(locate(<two>).position().x, 5pt)
+
+--- marginnote-inside-right-bound ---
+#set page(height: 40pt, binding: right, margin: (x: 40pt, y: 10pt))
+#set marginnote(side: "inside", clearance: 5pt)
+A#marginnote[#metadata(none)<note>N]
+#context test(locate(<note>).position().x, 85pt)
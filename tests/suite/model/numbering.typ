@@ -112,4 +112,34 @@
 
 --- numbering-double-circled-number ---
 #assert.eq(numbering("⓵", 1), "⓵")
-#assert.eq(numbering("⓵", 10), "⓾")
\ No newline at end of file
+#assert.eq(numbering("⓵", 10), "⓾")
+
+--- numbering-devanagari ---
+#assert.eq(numbering("१", 1475), "१४७५")
+#assert.eq(numbering("१.१", 1, 20), "१.२०")
+
+--- numbering-thai ---
+#assert.eq(numbering("๑", 1475), "๑๔๗๕")
+
+--- numbering-chinese-traditional ---
+#set text(lang: "zh", region: "TW")
+#context assert.eq(numbering("一", 20002), "二萬零二")
+#context assert.eq(numbering("壹", 2), "貳")
+#set text(region: "HK")
+#context assert.eq(numbering("一", 20002), "二萬零二")
+#set text(region: "CN")
+#context assert.eq(numbering("一", 20002), "二万零二")
+#set text(lang: "en", region: "TW")
+#context assert.eq(numbering("一", 20002), "二万零二")
+
+--- numbering-ordinal-word ---
+#assert.eq(numbering("first", 2), "second")
+#assert.eq(numbering("First.", 3), "Third.")
+#assert.eq(numbering("first", 13), "13")
+#assert.eq(numbering("1.first", 1, 1), "1.first")
+
+--- numbering-ordinal-word-lang ---
+#set text(lang: "de")
+#context assert.eq(numbering("First", 2), "Zweitens")
+#set text(lang: "da")
+#context assert.eq(numbering("first", 2), "second")
@@ -57,6 +57,35 @@ starts a paragraph, also with indent.
 
 ثم يصبح النص رطبًا وقابل للطرق ويبدو المستند رائعًا.
 
+--- par-first-line-indent-all ---
+#set par(first-line-indent: (amount: 12pt, all: true))
+#hide(place[
+  A#metadata(none)<first> first paragraph is indented, too.
+
+  A#metadata(none)<second>nd so is the second one.
+
+  = Headings
+  A#metadata(none)<heading> paragraph after a heading as well.
+
+  #block[A#metadata(none)<block>nd the first one in a block.]
+])
+
+// The measured paragraph is indented, too.
+#context for label in (<first>, <second>, <heading>, <block>) {
+  test(locate(label).position().x, 10pt + measure[A].width)
+}
+
+--- par-first-line-indent-dict ---
+#set par(first-line-indent: (amount: 1em))
+#context test(par.first-line-indent, 1em)
+
+#set par(first-line-indent: (amount: 1em, all: true))
+#context test(par.first-line-indent, (amount: 1em, all: true))
+
+--- par-first-line-indent-dict-unexpected-key ---
+// Error: 29-51 unexpected key "size", valid keys are "amount" and "all"
+#set par(first-line-indent: (amount: 1em, size: 2))
+
 --- par-spacing-and-first-line-indent ---
 // This is madness.
 #set par(first-line-indent: 12pt)
@@ -78,3 +107,218 @@ Welcome \ here. Does this work well?
 #set text(dir: rtl)
 لآن وقد أظلم الليل وبدأت النجوم
 تنضخ وجه الطبيعة التي أعْيَتْ من طول ما انبعثت في النهار
+
+--- par-tolerance ---
+// Avoiding loose lines leads to more hyphenation and thus
+// a longer last line.
+#set page(width: 100pt)
+#set par(justify: true)
+#set text(hyphenate: true)
+#lorem(30)#metadata(none)<loose>
+
+#set par(tolerance: 50%)
+#lorem(30)#metadata(none)<tight>
+
+#context assert(locate(<loose>).position().x < locate(<tight>).position().x)
+
+--- par-last-line-fill ---
+#set par(justify: true)
+#set text(5.5pt)
+#lorem(28)#metadata(none)<short>
+
+#set par(last-line-fill: 75%)
+#lorem(28)#metadata(none)<long>
+
+#context assert(locate(<short>).position().x < locate(<long>).position().x)
+
+--- par-last-line-min ---
+#set par(justify: true)
+#lorem(30)#metadata(none)<runt>
+
+#set par(last-line-min: 2em)
+#lorem(30)#metadata(none)<no-runt>
+
+#context {
+  let runt = locate(<runt>).position().x - 10pt
+  let no-runt = locate(<no-runt>).position().x - 10pt
+  assert(runt < 2em.to-absolute())
+  assert(no-runt >= 2em.to-absolute())
+}
+
+--- par-tab-stops ---
+#set page(width: 200pt, margin: 0pt)
+#set par(tab-stops: (50pt, (pos: 120pt, align: right), (pos: 160pt, align: ".")))
+A#"\t"#metadata(none)<left>B#"\t"CC#metadata(none)<right>#"\t"12#metadata(none)<dec>.5
+
+#context {
+  test(locate(<left>).position().x, 50pt)
+  test(locate(<right>).position().x, 120pt)
+  test(locate(<dec>).position().x, 160pt)
+}
+
+
+--- par-tab-stops-hanging-indent ---
+// Test that tab stops are measured from the paragraph's start on the first
+// line and on the indented lines alike.
+#set par(hanging-indent: 20pt, tab-stops: (50pt,))
+#hide(place[
+  A#"\t"#metadata(none)<first>B \
+  C#"\t"#metadata(none)<second>D
+])
+
+#context {
+  test(locate(<first>).position().x, 60pt)
+  test(locate(<second>).position().x, 60pt)
+}
+
+--- par-line-numbering ---
+#set page(margin: (left: 30pt, rest: 10pt))
+#set par.line-numbering(numbering: n => [#metadata(n)<num>#n])
+A \
+B \
+C
+#context test(query(<num>).map(m => m.value), (1, 2, 3))
+
+--- par-line-numbering-disabled ---
+#set page(margin: (left: 30pt, rest: 10pt))
+#set par.line-numbering(numbering: n => [#metadata(n)<num>#n])
+A
+
+#[
+  #set par.line-numbering(numbering: none)
+  B
+]
+
+C \
+D
+#context test(query(<num>).map(m => m.value), (1, 2, 3))
+
+--- par-line-numbering-scope-page ---
+#set page(height: 50pt, margin: (left: 30pt, rest: 10pt))
+#set text(costs: (orphan: 0%, widow: 0%))
+#set par.line-numbering(
+  numbering: n => [#metadata(n)<num>#n],
+  scope: "page",
+)
+A \
+B \
+C \
+D \
+E
+#context test(query(<num>).map(m => m.value), (1, 2, 1, 2, 1))
+
+--- par-line-numbering-scope-section ---
+#set page(margin: (left: 30pt, rest: 10pt))
+#set par.line-numbering(
+  numbering: n => [#metadata(n)<num>#n],
+  scope: "section",
+)
+A \
+B
+= Heading
+C
+#context test(query(<num>).map(m => m.value), (1, 2, 1))
+
+--- par-line-numbering-side ---
+#set page(margin: (x: 30pt, y: 10pt))
+#set par.line-numbering(
+  numbering: n => [#metadata(n)<num>#n],
+  side: "right",
+  clearance: 5pt,
+)
+A
+#context test(locate(<num>).position().x, 95pt)
+
+--- par-line-numbering-two-sided ---
+#set page(height: 40pt, margin: (inside: 30pt, outside: 10pt, y: 10pt))
+#set par.line-numbering(
+  numbering: n => box(width: 4pt)[#metadata(n)<num>#n],
+  side: "outside",
+  clearance: 5pt,
+)
+A
+#pagebreak()
+B
+#context test(
+  query(<num>).map(m => m.location().position().x),
+  (115pt, 1pt),
+)
+
+--- par-line-numbering-math ---
+// Text in math is not numbered.
+#set page(margin: (left: 30pt, rest: 10pt))
+#set par.line-numbering(numbering: n => [#metadata(n)<num>#n])
+A $"text"$
+#context test(query(<num>).len(), 1)
+
+--- par-shape ---
+// Test that a stepped paragraph needs more lines than a plain one.
+#context {
+  let plain = measure(block(width: 150pt, lorem(30))).height
+  let stepped = measure(block(width: 150pt, {
+    set par(shape: (i, width) => if i < 3 { (left: 40%) } else { (:) })
+    lorem(30)
+  })).height
+  assert(stepped > plain)
+}
+
+--- par-shape-triangle ---
+// Test that indents on both sides add up and are relative to the width.
+#context {
+  let body(shape) = block(width: 150pt, {
+    set par(justify: true, shape: shape)
+    lorem(25)
+  })
+  let plain = measure(body(none)).height
+  let half = measure(body((i, width) => (left: 25%, right: 25%))).height
+  let narrow = measure(body((i, width) => (left: 50%))).height
+  let triangle = measure(body((i, width) => {
+    let indent = calc.min(i * 8pt, width / 3)
+    (left: indent, right: indent)
+  })).height
+  assert(half > plain)
+  assert.eq(half, narrow)
+  assert(triangle > plain)
+}
+
+--- par-diagnose-loose ---
+#set page(width: 100pt)
+#set par(justify: true, diagnose: true)
+// Warning: 1-9 line is too loose
+// Hint: 1-9 its spaces had to be stretched beyond their limit
+// Hint: 1-9 consider rewording the paragraph or enabling hyphenation
+Hi there #linebreak(justify: true)
+and more.
+
+--- par-diagnose-tight ---
+#set page(width: 100pt)
+#set par(diagnose: true)
+#set text(hyphenate: false)
+// Warning: 1-44 line is too tight
+// Hint: 1-44 it does not fit even with its spaces shrunk as far as possible
+// Hint: 1-44 consider rewording the paragraph or enabling hyphenation
+Donaudampfschifffahrtsgesellschaftskapitaen
+
+--- par-diagnose-river ---
+#set par(diagnose: true)
+// Warning: 1-10 spaces form a river through the paragraph
+// Hint: 1-10 consider rewording the paragraph or adjusting the hyphenation
+aaaa bbbb\
+aaaa bbbb\
+aaaa bbbb
+
+--- par-diagnose-hyphen-ladder ---
+#set page(width: 30pt, margin: 0pt)
+#set par(diagnose: true)
+// Warning: 1-20 three consecutive lines end with a hyphen
+// Hint: 1-20 consider rewording the paragraph or raising the ladder cost
+aaaa-bbbb-cccc-dddd
+
+--- par-diagnose-disabled ---
+#set par(justify: true)
+#hide(place(block(width: 80pt)[
+Hi there #linebreak(justify: true)
+aaaa bbbb\
+aaaa bbbb\
+aaaa bbbb
+]))
@@ -0,0 +1,29 @@
+// Test sidenotes.
+
+--- sidenote-numbering ---
+// Sidenotes and footnotes share a counter.
+#set page(margin: (right: 50pt))
+A#footnote[F]
+B#sidenote[S] <side>
+C#footnote[G] <g>
+#context test(counter(footnote).at(<side>), (1,))
+#context test(counter(footnote).at(<g>), (3,))
+
+--- sidenote-margin ---
+// A sidenote is placed into the margin at the height of its line.
+#set page(height: 60pt, margin: (right: 40pt, rest: 10pt))
+#set marginnote(clearance: 5pt)
+A#metadata(none)<line>#sidenote[#metadata(none)<note>N]
+#context {
+  // The note's body follows its number.
+  let x = locate(<note>).position().x
+  assert(85pt < x and x < 95pt)
+}
+#context test(locate(<note>).position().y, locate(<line>).position().y)
+
+--- sidenote-collision ---
+// A sidenote that would overlap with a previous note is moved down.
+#set page(height: 40pt, margin: (right: 50pt))
+#set marginnote(gap: 4pt)
+A#sidenote[#metadata(none)<a>X]#sidenote[#metadata(none)<b>Y]
+#context assert(locate(<b>).position().y > locate(<a>).position().y)
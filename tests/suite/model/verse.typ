@@ -0,0 +1,59 @@
+// Test verse.
+
+--- verse-wrap ---
+// Long lines wrap with a hanging indent.
+#hide(place(verse(indent: 1em)[
+  A#metadata(none)<a> line that is long enough to wrap \
+  A#metadata(none)<b>
+]))
+#context {
+  let a = locate(<a>).position()
+  let b = locate(<b>).position()
+  test(a.x, b.x)
+  assert(b.y - a.y > 2 * measure[A].height)
+}
+
+--- verse-stanzas ---
+// Lines are separated by leading and stanzas by the stanza spacing.
+#set par(leading: 5pt)
+#hide(place(verse(spacing: 20pt)[
+  A#metadata(none)<a> \
+  B#metadata(none)<b>
+
+  C#metadata(none)<c>
+]))
+#context {
+  let y(label) = locate(label).position().y
+  test(y(<c>) - y(<b>) - (y(<b>) - y(<a>)), 15pt)
+}
+
+--- verse-string ---
+// In a string, newlines separate lines and blank lines stanzas.
+#context test(
+  measure(verse("One\nTwo\n\nThree")),
+  measure(verse[One \ Two
+
+    Three
+  ]),
+)
+
+--- verse-numbering ---
+// Every step-th line is numbered, counting across stanzas.
+#hide(place(verse(numbering: n => [#metadata(n)<number>], step: 2)[
+  A \ B \ C
+
+  D \ E
+]))
+#context test(query(<number>).map(it => it.value), (2, 4))
+
+--- verse-attribution ---
+// The attribution is aligned to the end below the verse.
+#hide(place(verse(attribution: [Anonymous#metadata(none)<attribution>])[
+  Roses are red, \
+  violets are blue.#metadata(none)<last>
+]))
+#context {
+  let attribution = locate(<attribution>).position()
+  test(attribution.x, 110pt)
+  assert(attribution.y > locate(<last>).position().y)
+}
@@ -221,3 +221,25 @@
 #let x = 1
 #let c = [#(x) => (1, 2)]
 #test(c.children.last(), [(1, 2)]))
+
+--- closure-deprecated ---
+#let add(x, y) = x + y
+#let plus = add.deprecated("use `add` instead", since: "0.3.0")
+
+// Warning: 7-17 function is deprecated since 0.3.0
+// Hint: 7-17 use `add` instead
+#test(plus(1, 2), 3)
+
+--- closure-deprecated-once-per-call-site ---
+#let old = calc.abs.deprecated("use `calc.abs` instead")
+// Warning: 30-37 function is deprecated
+// Hint: 30-37 use `calc.abs` instead
+#for i in range(3) { let _ = old(-i) }
+
+--- closure-deprecated-once-per-function ---
+// A deprecated function is only reported at its first use.
+#let old = calc.abs.deprecated("use `calc.abs` instead")
+// Warning: 10-17 function is deprecated
+// Hint: 10-17 use `calc.abs` instead
+#let _ = old(-1)
+#let _ = old(-2) + old(-3)
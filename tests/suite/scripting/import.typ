@@ -321,6 +321,26 @@ This is never reached.
 #import "@test/adder:0.1.0": add
 #test(add(2, 8), 10)
 
+--- import-from-package-version-bound ---
+// Test import with a version bound.
+#import "@test/adder:0.1": add
+#test(add(2, 8), 10)
+
+--- import-from-package-version-bound-major ---
+#import "@test/adder:0" as adder
+#test(adder.add(1, 1), 2)
+
+--- import-from-package-exports ---
+// Test that only the exported API of a package is imported.
+#import "@test/themed:0.1.0" as t: *
+#test(double(2), 4)
+#test(dark.fill, black)
+#test(t.themes.light.fill, white)
+
+--- import-from-package-exports-internal ---
+// Error: 31-37 unresolved import
+#import "@test/themed:0.1.0": helper
+
 --- import-from-package-required-compiler-version ---
 // Test too high required compiler version.
 // Error: 9-29 package requires typst 1.0.0 or newer (current version is VERSION)
@@ -354,8 +374,8 @@ This is never reached.
 // Error: 9-23 package specification is missing version
 #import "@test/mypkg:": *
 
---- import-from-package-version-missing-minor ---
-// Error: 9-24 version number is missing minor version
+--- import-from-package-version-bound-unmatched-1 ---
+// Error: 9-24 no matching package version found (searched for @test/mypkg:0)
 #import "@test/mypkg:0": *
 
 --- import-from-package-version-major-invalid-1 ---
@@ -366,8 +386,8 @@ This is never reached.
 // Error: 9-29 `-3` is not a valid major version
 #import "@test/mypkg:-3.0.0": *
 
---- import-from-package-version-missing-patch-1 ---
-// Error: 9-26 version number is missing patch version
+--- import-from-package-version-bound-unmatched-2 ---
+// Error: 9-26 no matching package version found (searched for @test/mypkg:0.3)
 #import "@test/mypkg:0.3": *
 
 --- import-from-package-version-missing-patch-2 ---
@@ -0,0 +1,60 @@
+// Test applying rules to sections.
+
+--- apply-section ---
+#apply(<b>, set text(red))
+
+= A
+#context test(text.fill, black)
+
+= B <b>
+#context test(text.fill, red)
+
+== B.1
+#context test(text.fill, red)
+
+= C
+#context test(text.fill, black)
+
+--- apply-section-set-rule-inside ---
+// Set rules in the section take precedence, and the applied rules end with
+// the section even if such a set rule spans beyond it.
+#apply(<b>, set text(red, weight: "bold"))
+
+= B <b>
+#set text(blue)
+#context test((text.fill, text.weight), (blue, "bold"))
+
+= C
+#context test((text.fill, text.weight), (blue, "regular"))
+
+--- apply-section-where ---
+#apply(heading.where(level: 2), set text(green))
+
+= A
+#context test(text.fill, black)
+
+== A.1
+#context test(text.fill, green)
+
+== A.2
+#context test(text.fill, green)
+
+= B
+#context test(text.fill, black)
+
+--- apply-section-show-rule ---
+#apply(<b>, show heading: set text(green))
+
+= A
+= B <b>
+== Green
+= C
+
+--- apply-show-rule-without-selector ---
+// Error: 13-27 show rules without a selector cannot be passed as arguments
+#apply(<a>, show: it => it)
+
+--- apply-rule-arg-other-func ---
+// Only `apply` takes rules as arguments.
+// Error: 7-20 set is only allowed directly in code and content blocks
+#text(set text(red))[A]
@@ -94,3 +94,32 @@ A #box(rect(fill: yellow, inset: 5pt, rect())) B
 // when there are recursive show rules.
 #show enum: set text(blue)
 #enum(numbering: "(a)", [A], enum[B])
+
+--- styles-get ---
+#set text(14pt)
+#set par(leading: 1em)
+#set line(stroke: 0.5em + red)
+#context {
+  test(par.leading, 1em)
+  test(styles.get(par, "leading"), 14pt)
+  test(styles.get(text, "size"), 14pt)
+  test(styles.get(line, "stroke"), 7pt + red)
+  test(styles.get(block, "above"), auto)
+}
+
+--- styles-get-default ---
+#context test(styles.get(heading, "numbering"), none)
+#set text(size: 20pt)
+#context test(styles.get(list, "indent"), 0pt)
+#set list(indent: 1em)
+#context test(styles.get(list, "indent"), 20pt)
+
+--- styles-get-unknown-property ---
+// Error: 10-32 par does not have a settable property "foo"
+#context styles.get(par, "foo")
+
+--- styles-get-no-context ---
+// Error: 2-28 can only be used when context is known
+// Hint: 2-28 try wrapping this in a `context` expression
+// Hint: 2-28 the `context` expression should wrap everything that depends on this function
+#styles.get(par, "leading")
@@ -131,3 +131,35 @@ Heya
 #show "Heya": set text(red)
 #show   "yaho": set text(weight: "bold")
 Heyaho
+
+--- show-text-regex-captures ---
+#show regex("(\d+)\s*(kg|m)"): it => {
+  let (num, unit) = it.matched.captures
+  [#metadata((num, unit))<unit>#num#unit]
+}
+The rope is 12m long and weighs 3 kg.
+#context test(query(<unit>).map(m => m.value), (("12", "m"), ("3", "kg")))
+
+--- show-text-regex-match-details ---
+#let details = state("details", ())
+#show regex("v(?<major>\d)(\.(\d))?"): it => {
+  details.update(list => list + (it.matched,))
+  it
+}
+Use v1 or v2.3 now.
+#context {
+  let (a, b) = details.final()
+  test(a.text, "v1")
+  test(a.captures, ("1", none, none))
+  test(a.named, (major: "1"))
+  test(a.before, "Use ")
+  test(b.start, 10)
+  test(b.end, 14)
+  test(b.after, " now.")
+}
+
+--- show-text-matched-field-plain ---
+#show "A": it => assert(it.has("matched"))
+#show "B": it => test(it.matched.text, "B")
+A B
+#test(text[C].has("matched"), false)
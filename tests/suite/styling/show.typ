@@ -260,3 +260,54 @@ I am *strong*, I am _emphasized_, and I am #[special<special>].
 
 = Hello
 *strong*
+
+--- show-selector-within ---
+#show heading.within(figure): set text(red)
+= Outside
+#figure([= Inside], caption: [Caption])
+
+--- show-selector-within-nested ---
+#show strong.within(emph): set text(red)
+*A* _*B* and #box[*C*]_
+
+--- show-selector-first-in ---
+#show par.first-in(block): it => text(red, it)
+
+#block[
+  First.
+
+  Second.
+]
+
+#block[
+  Third.
+
+  #block[Fourth.]
+]
+
+--- show-selector-first-in-equal-ancestors ---
+// Equal ancestors are still distinct.
+#show strong.first-in(emph): set text(red)
+#for _ in range(2) [_*A* *B*_ ]
+
+--- show-selector-at-depth ---
+#show list.item.at-depth(3): set text(red)
+- A
+  - B
+    - C
+  - D
+    - E
+- F
+
+--- show-selector-at-depth-repr ---
+#test(repr(list.item.at-depth(2)), "item.at-depth(2)")
+#test(repr(heading.within(figure)), "heading.within(figure)")
+#test(repr(par.first-in(block)), "par.first-in(block)")
+
+--- show-selector-structural-not-locatable ---
+// Error: 16-38 structural selectors are not locatable
+#context query(heading.within(figure))
+
+--- show-selector-within-not-element ---
+// Error: 7-30 `within()` can only be called on element functions
+#show (x => x).within(figure): none
@@ -76,3 +76,33 @@ I
 // Warning: 23-56 unknown font family: non-existing-fonts
 #let var = text(font: ("list-of", "non-existing-fonts"))[don't]
 #var
+
+--- text-fallback-per-script ---
+// Script-specific fallback families apply to text in their script only.
+#let font(body) = measure-glyphs(body).first().font
+#set text(fallback: (
+  han: "Noto Serif CJK SC",
+  arabic: "Noto Sans Arabic",
+  last-resort: false,
+))
+#context test(font("排版"), "Noto Serif CJK SC")
+#context test(font("عربي"), "Noto Sans Arabic")
+#set text(fallback: (arabic: "Noto Serif CJK SC", last-resort: false))
+#context assert.ne(font("排版"), "Noto Serif CJK SC")
+
+--- text-fallback-per-language ---
+#let font(body) = measure-glyphs(body).first().font
+#set text(fallback: (zh: "Noto Serif CJK SC", last-resort: false))
+#context assert.ne(font("中文"), "Noto Serif CJK SC")
+#set text(lang: "zh")
+#context test(font("中文"), "Noto Serif CJK SC")
+
+--- text-fallback-repr ---
+#set text(fallback: (han: "Noto Serif CJK SC"))
+#context test(text.fallback, (han: "noto serif cjk sc"))
+#set text(fallback: (han: "Noto Serif CJK SC", last-resort: false))
+#context test(text.fallback, (han: "noto serif cjk sc", last-resort: false))
+
+--- text-fallback-unknown-key ---
+// Error: 21-35 unknown script or language `klingon`
+#set text(fallback: (klingon: "A"))
@@ -10,3 +10,12 @@
 
 #show smallcaps: set text(fill: red)
 #smallcaps[Smallcaps]
+
+--- smallcaps-acronyms ---
+// Runs of two or more capitals become small capitals.
+#set text(acronyms: true)
+#context assert(measure[NASA].width < measure(text(acronyms: false)[NASA]).width)
+
+// Single capitals stay untouched.
+#context test(measure[Nasa].width, measure(text(acronyms: false)[Nasa]).width)
+#context test(measure[I].width, measure(text(acronyms: false)[I]).width)
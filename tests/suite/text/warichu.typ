@@ -0,0 +1,32 @@
+// Test inline two-line notes.
+
+--- warichu-basic ---
+// Test that a note is set in two lines of about half its width.
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+#context {
+  let single = measure(text(size: 0.5em)[夏目漱石の小説])
+  let note = measure(warichu[夏目漱石の小説])
+  assert(note.width < single.width * 0.75)
+  assert(note.height > single.height * 1.5)
+}
+
+--- warichu-breaking ---
+// Test that a note that doesn't fit onto the line is broken into segments.
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+#context {
+  let height(note) = measure(block(width: 60pt)[吾輩は猫である#warichu(note)。]).height
+  let short = height[小説]
+  let long = height[明治三十八年に雑誌「ホトトギス」に発表された夏目漱石の最初の長編小説]
+  assert(long > 2 * short)
+}
+
+--- warichu-size-and-gap ---
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+#context {
+  let small = measure(warichu[夏目漱石の小説])
+  let large = measure(warichu(size: 60%, gap: 0.1em)[夏目漱石の小説])
+  assert(large.height > small.height)
+}
+
+--- warichu-empty ---
+#context assert.eq(measure[A#warichu[]B], measure[AB])
@@ -45,6 +45,14 @@ Stuff
 // Test baseline.
 A #box(image("/assets/images/tiger.jpg", height: 1cm, width: 80%)) B
 
+--- image-baseline-align ---
+// A baseline-aligned image is inline and extends below the baseline.
+#let svg = `<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10"></svg>`
+#let icon = image.decode(svg.text, height: 1em, baseline-align: true)
+A #icon B #metadata(none) <b>
+#context assert(locate(<b>).position().y < 20pt)
+#context test(measure[A #icon B].height, 10pt)
+
 --- image-svg-complex ---
 // Test advanced SVG features.
 #image("/assets/images/pattern.svg")